@@ -0,0 +1,62 @@
+// Measures the effective throughput of segmented SDO uploads, client and server in memory.
+//
+// cargo bench --bench sdo_throughput
+
+use canopen_rs::id::NodeId;
+use canopen_rs::od::{AccessType, Object, ObjectDictionary, Value, Variable};
+use canopen_rs::sdo::{SdoServer, SdoUpload, SdoUploadStep};
+
+const INDEX: u16 = 0x2000;
+const SIZES: [usize; 3] = [0x400, 0x1_0000, 0x10_0000];
+const TOTAL_SIZE: usize = 0x400_0000;
+
+// Each segment carries 7 bytes in a request and a response of 111 bits each (11-bit ID,
+// 8 data bytes, no stuff bits).
+const BITS_PER_SEGMENT: f64 = 2.0 * 111.0;
+const BIT_RATE: f64 = 1_000_000.0;
+
+fn upload(server: &mut SdoServer, od: &mut ObjectDictionary, node_id: NodeId) -> usize {
+    let mut upload = SdoUpload::new(node_id, INDEX, 0);
+    let mut request = upload.start();
+    loop {
+        let response = server.on_request(od, request.data()).unwrap();
+        match upload.on_response(response.data()).unwrap() {
+            SdoUploadStep::Send(next) => request = next,
+            SdoUploadStep::Done(data) => return data.len(),
+        }
+    }
+}
+
+fn main() {
+    let node_id = 1.try_into().unwrap();
+    println!(
+        "bus limit at 1 Mbit/s: {:.0} bytes/s",
+        7.0 * BIT_RATE / BITS_PER_SEGMENT
+    );
+    for size in SIZES {
+        let mut od = ObjectDictionary::new();
+        od.insert(Object::new_var(
+            INDEX,
+            Variable::new(
+                "Domain",
+                AccessType::ReadOnly,
+                Value::Domain((0..size).map(|i| i as u8).collect()),
+            ),
+        ));
+        let mut server = SdoServer::new(node_id);
+        let iterations = TOTAL_SIZE / size;
+        let started_at = std::time::Instant::now();
+        let mut transferred = 0;
+        for _ in 0..iterations {
+            transferred += upload(&mut server, &mut od, node_id);
+        }
+        let elapsed = started_at.elapsed();
+        assert_eq!(transferred, size * iterations);
+        println!(
+            "{:>8} bytes x {:>5}: {:>12.0} bytes/s",
+            size,
+            iterations,
+            transferred as f64 / elapsed.as_secs_f64()
+        );
+    }
+}
@@ -0,0 +1,39 @@
+use socketcan::{BlockingCan, CanSocket, EmbeddedFrame, Socket};
+
+use canopen_rs::cia402::{DriveStateMachine, State, StatusWord};
+use canopen_rs::frame::CanOpenFrame;
+use canopen_rs::pdo_layout::PdoField;
+
+const INTERFACE_NAME: &str = "can0";
+const NODE_ID: u8 = 1;
+
+fn main() {
+    let node_id = NODE_ID.try_into().unwrap();
+    let mut sock = CanSocket::open(INTERFACE_NAME).unwrap();
+    let mut drive = DriveStateMachine::new(State::OperationEnabled);
+
+    loop {
+        sock.transmit(&CanOpenFrame::from(StatusWord::new_sdo_read_frame(node_id)).into())
+            .unwrap();
+        let frame = sock.receive().unwrap();
+        // Expedited upload response: the statusword is in bytes 4-5
+        let status_word = StatusWord::read(&frame.data()[4..6]);
+        let control_word = drive.on_status_word(status_word);
+        println!(
+            "statusword: {:#06X} ({:?})",
+            status_word.bits(),
+            drive.state()
+        );
+        if drive.is_target_reached() {
+            break;
+        }
+        // No controlword while the drive changes state by itself
+        if let Some(control_word) = control_word {
+            println!("controlword: {:#06X}", control_word.bits());
+            sock.transmit(&CanOpenFrame::from(control_word.new_sdo_write_frame(node_id)).into())
+                .unwrap();
+            sock.receive().unwrap();
+        }
+        std::thread::sleep(std::time::Duration::from_millis(10));
+    }
+}
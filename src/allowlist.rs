@@ -0,0 +1,144 @@
+use crate::error::{Error, Result};
+use crate::id::{CommunicationObject, NodeId};
+
+// Guards upper layers against frames of node ids that are not part of the configured network,
+// e.g. a misconfigured device or foreign traffic. Check every received frame with `check`
+// before dispatching it.
+//
+// Frames that carry no node id (NMT node control, SYNC, TIME, LSS) always pass. In strict mode,
+// frames of unexpected nodes are rejected with `Error::UnexpectedNodeId`; otherwise they are
+// only counted, e.g. to audit a bus before enabling strict mode.
+#[derive(Clone, Debug, PartialEq)]
+pub struct NodeAllowlist {
+    allowed: std::collections::BTreeSet<u8>,
+    strict: bool,
+    rejected: std::collections::BTreeMap<u8, u64>,
+}
+
+impl NodeAllowlist {
+    pub fn new<I: IntoIterator<Item = NodeId>>(node_ids: I) -> Self {
+        Self {
+            allowed: node_ids
+                .into_iter()
+                .map(|node_id| node_id.as_raw())
+                .collect(),
+            strict: true,
+            rejected: std::collections::BTreeMap::new(),
+        }
+    }
+
+    pub fn with_strict(self, strict: bool) -> Self {
+        Self { strict, ..self }
+    }
+
+    pub fn is_strict(&self) -> bool {
+        self.strict
+    }
+
+    pub fn allow(&mut self, node_id: NodeId) {
+        self.allowed.insert(node_id.as_raw());
+    }
+
+    pub fn disallow(&mut self, node_id: NodeId) {
+        self.allowed.remove(&node_id.as_raw());
+    }
+
+    pub fn is_allowed(&self, node_id: NodeId) -> bool {
+        self.allowed.contains(&node_id.as_raw())
+    }
+
+    // Only fails in strict mode. Frames of unexpected nodes are counted in both modes.
+    pub fn check(&mut self, cob: CommunicationObject) -> Result<()> {
+        let Some(node_id) = cob.node_id() else {
+            return Ok(());
+        };
+        if self.is_allowed(node_id) {
+            return Ok(());
+        }
+        *self.rejected.entry(node_id.as_raw()).or_default() += 1;
+        if self.strict {
+            Err(Error::UnexpectedNodeId(node_id))
+        } else {
+            Ok(())
+        }
+    }
+
+    // Number of frames of `node_id` that were not expected
+    pub fn rejected(&self, node_id: NodeId) -> u64 {
+        self.rejected
+            .get(&node_id.as_raw())
+            .copied()
+            .unwrap_or_default()
+    }
+
+    pub fn rejected_total(&self) -> u64 {
+        self.rejected.values().sum()
+    }
+
+    // The unexpected nodes seen on the bus and their frame counts
+    pub fn unexpected_nodes(&self) -> std::vec::Vec<(NodeId, u64)> {
+        self.rejected
+            .iter()
+            .map(|(raw_id, count)| ((*raw_id).try_into().unwrap(), *count))
+            .collect()
+    }
+
+    pub fn clear(&mut self) {
+        self.rejected.clear();
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_strict() {
+        let node_id: NodeId = 2.try_into().unwrap();
+        let foreign_id: NodeId = 9.try_into().unwrap();
+        let mut allowlist = NodeAllowlist::new([node_id]);
+        assert!(allowlist.is_strict());
+        assert_eq!(
+            allowlist.check(CommunicationObject::TxPdo1(node_id)),
+            Ok(())
+        );
+        assert_eq!(allowlist.check(CommunicationObject::Sync), Ok(()));
+        assert_eq!(allowlist.check(CommunicationObject::TxLss), Ok(()));
+        assert_eq!(
+            allowlist.check(CommunicationObject::NmtNodeMonitoring(foreign_id)),
+            Err(Error::UnexpectedNodeId(foreign_id))
+        );
+        assert_eq!(
+            allowlist.check(CommunicationObject::Emergency(foreign_id)),
+            Err(Error::UnexpectedNodeId(foreign_id))
+        );
+        assert_eq!(allowlist.rejected(foreign_id), 2);
+        assert_eq!(allowlist.rejected(node_id), 0);
+        assert_eq!(allowlist.unexpected_nodes(), vec![(foreign_id, 2)]);
+
+        allowlist.allow(foreign_id);
+        assert_eq!(
+            allowlist.check(CommunicationObject::TxSdo(foreign_id)),
+            Ok(())
+        );
+        allowlist.disallow(node_id);
+        assert!(!allowlist.is_allowed(node_id));
+        assert!(allowlist
+            .check(CommunicationObject::TxPdo1(node_id))
+            .is_err());
+        assert_eq!(allowlist.rejected_total(), 3);
+        allowlist.clear();
+        assert_eq!(allowlist.rejected_total(), 0);
+    }
+
+    #[test]
+    fn test_not_strict() {
+        let foreign_id: NodeId = 9.try_into().unwrap();
+        let mut allowlist = NodeAllowlist::new([2.try_into().unwrap()]).with_strict(false);
+        assert_eq!(
+            allowlist.check(CommunicationObject::TxPdo1(foreign_id)),
+            Ok(())
+        );
+        assert_eq!(allowlist.rejected(foreign_id), 1);
+    }
+}
@@ -0,0 +1,251 @@
+use crate::error::{Error, Result};
+use crate::frame::{Direction, RawFrame};
+
+// A line of a candump log (`candump -l`), e.g. `(1436509052.249713) can0 123#DEADBEEF`
+#[derive(Clone, Debug, PartialEq)]
+pub struct CandumpEntry {
+    // Since the Unix epoch
+    pub timestamp: std::time::Duration,
+    pub interface_name: String,
+    pub frame: RawFrame,
+    // From the optional `T`/`R` marker of recent can-utils, relative to the recording process
+    pub direction: Option<Direction>,
+}
+
+impl CandumpEntry {
+    pub fn parse(line: &str) -> Result<Self> {
+        let failed = || Error::ParseFailed {
+            input: line.to_owned(),
+            data_type: "candump log line".to_owned(),
+        };
+        let mut fields = line.split_whitespace();
+        let timestamp = fields
+            .next()
+            .and_then(|field| field.strip_prefix('(')?.strip_suffix(')'))
+            .and_then(|field| {
+                let (seconds, micros) = field.split_once('.')?;
+                if micros.len() != 6 {
+                    return None;
+                }
+                Some(
+                    std::time::Duration::from_secs(seconds.parse().ok()?)
+                        + std::time::Duration::from_micros(micros.parse().ok()?),
+                )
+            })
+            .ok_or_else(failed)?;
+        let interface_name = fields.next().ok_or_else(failed)?.to_owned();
+        let (id, data) = fields
+            .next()
+            .and_then(|field| field.split_once('#'))
+            .ok_or_else(failed)?;
+        let direction = match fields.next() {
+            None => None,
+            Some("T") => Some(Direction::Tx),
+            Some("R") => Some(Direction::Rx),
+            Some(_) => return Err(failed()),
+        };
+        if fields.next().is_some() || id.len() != 3 {
+            return Err(failed());
+        }
+        let id = u16::from_str_radix(id, 16).map_err(|_| failed())?;
+        let frame = if let Some(length) = data.strip_prefix('R') {
+            let length = if length.is_empty() {
+                0
+            } else {
+                length.parse().map_err(|_| failed())?
+            };
+            RawFrame::new_remote(id, length)?
+        } else {
+            if !data.is_ascii() || data.len() % 2 != 0 {
+                return Err(failed());
+            }
+            let bytes = (0..data.len())
+                .step_by(2)
+                .map(|i| u8::from_str_radix(&data[i..i + 2], 16))
+                .collect::<std::result::Result<std::vec::Vec<_>, _>>()
+                .map_err(|_| failed())?;
+            RawFrame::new(id, &bytes)?
+        };
+        Ok(Self {
+            timestamp,
+            interface_name,
+            frame,
+            direction,
+        })
+    }
+}
+
+impl std::fmt::Display for CandumpEntry {
+    fn fmt(&self, f: &mut std::fmt::Formatter) -> std::fmt::Result {
+        write!(
+            f,
+            "({}.{:06}) {} {}",
+            self.timestamp.as_secs(),
+            self.timestamp.subsec_micros(),
+            self.interface_name,
+            self.frame
+        )?;
+        match self.direction {
+            Some(Direction::Tx) => write!(f, " T"),
+            Some(Direction::Rx) => write!(f, " R"),
+            None => Ok(()),
+        }
+    }
+}
+
+// Records the frames transmitted and received by the caller in a candump log, which
+// `canplayer` can replay. Older can-utils do not accept the direction markers, so they are
+// only written if enabled.
+#[derive(Debug)]
+pub struct CandumpRecorder<W> {
+    writer: W,
+    interface_name: String,
+    direction_markers: bool,
+    frames: u64,
+}
+
+impl<W: std::io::Write> CandumpRecorder<W> {
+    pub fn new(writer: W, interface_name: &str) -> Self {
+        Self {
+            writer,
+            interface_name: interface_name.to_owned(),
+            direction_markers: false,
+            frames: 0,
+        }
+    }
+
+    pub fn with_direction_markers(self, direction_markers: bool) -> Self {
+        Self {
+            direction_markers,
+            ..self
+        }
+    }
+
+    // `Direction::Tx` for the frames transmitted by the caller
+    pub fn record(
+        &mut self,
+        frame: &RawFrame,
+        direction: Direction,
+        timestamp: std::time::SystemTime,
+    ) -> Result<()> {
+        let entry = CandumpEntry {
+            timestamp: timestamp
+                .duration_since(std::time::UNIX_EPOCH)
+                .map_err(|_| Error::TimeOutOfRange)?,
+            interface_name: self.interface_name.clone(),
+            frame: *frame,
+            direction: self.direction_markers.then_some(direction),
+        };
+        writeln!(self.writer, "{entry}").map_err(record_failed)?;
+        self.frames += 1;
+        Ok(())
+    }
+
+    pub fn frames(&self) -> u64 {
+        self.frames
+    }
+
+    pub fn flush(&mut self) -> Result<()> {
+        self.writer.flush().map_err(record_failed)
+    }
+
+    pub fn into_inner(self) -> W {
+        self.writer
+    }
+}
+
+fn record_failed(error: std::io::Error) -> Error {
+    Error::RecordFailed(error.to_string())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    use std::time::Duration;
+
+    fn timestamp() -> std::time::SystemTime {
+        std::time::UNIX_EPOCH + Duration::from_micros(1_436_509_052_249_713)
+    }
+
+    #[test]
+    fn test_record() {
+        let mut recorder = CandumpRecorder::new(std::vec::Vec::new(), "can0");
+        recorder
+            .record(
+                &RawFrame::new(0x603, &[0x40, 0x17, 0x10, 0x00, 0, 0, 0, 0]).unwrap(),
+                Direction::Tx,
+                timestamp(),
+            )
+            .unwrap();
+        recorder
+            .record(
+                &RawFrame::new_remote(0x703, 1).unwrap(),
+                Direction::Tx,
+                timestamp() + Duration::from_millis(1),
+            )
+            .unwrap();
+        recorder
+            .record(
+                &RawFrame::new(0x080, &[]).unwrap(),
+                Direction::Rx,
+                timestamp(),
+            )
+            .unwrap();
+        assert_eq!(recorder.frames(), 3);
+        assert_eq!(
+            String::from_utf8(recorder.into_inner()).unwrap(),
+            "(1436509052.249713) can0 603#4017100000000000\n\
+             (1436509052.250713) can0 703#R1\n\
+             (1436509052.249713) can0 080#\n"
+        );
+
+        let mut recorder =
+            CandumpRecorder::new(std::vec::Vec::new(), "vcan0").with_direction_markers(true);
+        recorder
+            .record(
+                &RawFrame::new(0x183, &[0x01]).unwrap(),
+                Direction::Rx,
+                timestamp(),
+            )
+            .unwrap();
+        assert_eq!(
+            String::from_utf8(recorder.into_inner()).unwrap(),
+            "(1436509052.249713) vcan0 183#01 R\n"
+        );
+    }
+
+    #[test]
+    fn test_parse() {
+        for line in [
+            "(1436509052.249713) can0 603#4017100000000000",
+            "(1436509052.000001) can0 703#R1 T",
+            "(0.000000) vcan0 080#",
+            "(1436509052.249713) can0 703#R",
+        ] {
+            assert_eq!(CandumpEntry::parse(line).unwrap().to_string(), line);
+        }
+        let entry = CandumpEntry::parse("(1436509052.249713) can0 183#0102 R").unwrap();
+        assert_eq!(
+            entry,
+            CandumpEntry {
+                timestamp: Duration::from_micros(1_436_509_052_249_713),
+                interface_name: "can0".to_owned(),
+                frame: RawFrame::new(0x183, &[0x01, 0x02]).unwrap(),
+                direction: Some(Direction::Rx),
+            }
+        );
+        for line in [
+            "",
+            "1436509052.249713 can0 183#01",
+            "(1436509052.2) can0 183#01",
+            "(1436509052.249713) can0",
+            "(1436509052.249713) can0 183#012",
+            "(1436509052.249713) can0 12345678#01",
+            "(1436509052.249713) can0 183#01 X",
+            "(1436509052.249713) can0 183#010203040506070809",
+        ] {
+            assert!(CandumpEntry::parse(line).is_err(), "{line}");
+        }
+    }
+}
@@ -0,0 +1,537 @@
+use crate::frame::SdoFrame;
+use crate::id::NodeId;
+use crate::pdo_layout::PdoField;
+
+mod motion;
+pub use motion::{ModeOfOperation, MotionStep, ProfileMove, Target};
+
+macro_rules! flags {
+    ($name:ident) => {
+        impl $name {
+            pub const fn from_bits(bits: u16) -> Self {
+                Self(bits)
+            }
+
+            pub const fn bits(&self) -> u16 {
+                self.0
+            }
+
+            pub const fn empty() -> Self {
+                Self(0)
+            }
+
+            pub const fn contains(&self, other: Self) -> bool {
+                self.0 & other.0 == other.0
+            }
+
+            pub fn insert(&mut self, other: Self) {
+                self.0 |= other.0;
+            }
+
+            pub fn remove(&mut self, other: Self) {
+                self.0 &= !other.0;
+            }
+
+            pub fn set(&mut self, other: Self, value: bool) {
+                if value {
+                    self.insert(other)
+                } else {
+                    self.remove(other)
+                }
+            }
+        }
+
+        impl std::ops::BitOr for $name {
+            type Output = Self;
+
+            fn bitor(self, other: Self) -> Self {
+                Self(self.0 | other.0)
+            }
+        }
+
+        impl std::ops::BitAnd for $name {
+            type Output = Self;
+
+            fn bitand(self, other: Self) -> Self {
+                Self(self.0 & other.0)
+            }
+        }
+
+        impl std::ops::Not for $name {
+            type Output = Self;
+
+            fn not(self) -> Self {
+                Self(!self.0)
+            }
+        }
+
+        impl From<u16> for $name {
+            fn from(bits: u16) -> Self {
+                Self(bits)
+            }
+        }
+
+        impl From<$name> for u16 {
+            fn from(flags: $name) -> Self {
+                flags.0
+            }
+        }
+
+        impl PdoField for $name {
+            const SIZE: usize = 2;
+
+            fn write(&self, bytes: &mut [u8]) {
+                self.0.write(bytes)
+            }
+
+            fn read(bytes: &[u8]) -> Self {
+                Self(u16::read(bytes))
+            }
+        }
+    };
+}
+
+// States of the drive power state machine (cf. CiA 402)
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum State {
+    NotReadyToSwitchOn,
+    SwitchOnDisabled,
+    ReadyToSwitchOn,
+    SwitchedOn,
+    OperationEnabled,
+    QuickStopActive,
+    FaultReactionActive,
+    Fault,
+}
+
+// Statusword (0x6041)
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq)]
+pub struct StatusWord(u16);
+
+flags!(StatusWord);
+
+impl StatusWord {
+    pub const INDEX: u16 = 0x6041;
+
+    pub const READY_TO_SWITCH_ON: Self = Self(1 << 0);
+    pub const SWITCHED_ON: Self = Self(1 << 1);
+    pub const OPERATION_ENABLED: Self = Self(1 << 2);
+    pub const FAULT: Self = Self(1 << 3);
+    pub const VOLTAGE_ENABLED: Self = Self(1 << 4);
+    pub const QUICK_STOP: Self = Self(1 << 5);
+    pub const SWITCH_ON_DISABLED: Self = Self(1 << 6);
+    pub const WARNING: Self = Self(1 << 7);
+    pub const REMOTE: Self = Self(1 << 9);
+    pub const TARGET_REACHED: Self = Self(1 << 10);
+    pub const INTERNAL_LIMIT_ACTIVE: Self = Self(1 << 11);
+    // Bits 12 and 13 depend on the mode of operation.
+    pub const SET_POINT_ACKNOWLEDGE: Self = Self(1 << 12);
+    pub const FOLLOWING_ERROR: Self = Self(1 << 13);
+
+    // Decodes the state from bits 0-3, 5 and 6. Returns `None` for undefined combinations.
+    pub fn state(&self) -> Option<State> {
+        match (self.0 & 0x4F, self.0 & 0x6F) {
+            (0x00, _) => Some(State::NotReadyToSwitchOn),
+            (0x40, _) => Some(State::SwitchOnDisabled),
+            (_, 0x21) => Some(State::ReadyToSwitchOn),
+            (_, 0x23) => Some(State::SwitchedOn),
+            (_, 0x27) => Some(State::OperationEnabled),
+            (_, 0x07) => Some(State::QuickStopActive),
+            (0x0F, _) => Some(State::FaultReactionActive),
+            (0x08, _) => Some(State::Fault),
+            _ => None,
+        }
+    }
+
+    pub fn new_sdo_read_frame(node_id: NodeId) -> SdoFrame {
+        SdoFrame::new_sdo_read_frame(node_id, Self::INDEX, 0)
+    }
+}
+
+// Controlword (0x6040)
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq)]
+pub struct ControlWord(u16);
+
+flags!(ControlWord);
+
+impl ControlWord {
+    pub const INDEX: u16 = 0x6040;
+
+    pub const SWITCH_ON: Self = Self(1 << 0);
+    pub const ENABLE_VOLTAGE: Self = Self(1 << 1);
+    pub const QUICK_STOP: Self = Self(1 << 2);
+    pub const ENABLE_OPERATION: Self = Self(1 << 3);
+    pub const FAULT_RESET: Self = Self(1 << 7);
+    pub const HALT: Self = Self(1 << 8);
+    // Bits 4-6 depend on the mode of operation (here profile position).
+    pub const NEW_SET_POINT: Self = Self(1 << 4);
+    pub const CHANGE_SET_IMMEDIATELY: Self = Self(1 << 5);
+    pub const RELATIVE: Self = Self(1 << 6);
+
+    // Bits 0-3 and 7 select the command. The others (e.g. operation mode specific bits) are
+    // kept.
+    const COMMAND_MASK: u16 = 0x008F;
+
+    pub fn command(&self) -> Option<Command> {
+        Command::from_bits(self.0 & Self::COMMAND_MASK)
+    }
+
+    pub fn with_command(&self, command: Command) -> Self {
+        Self((self.0 & !Self::COMMAND_MASK) | command.bits())
+    }
+
+    pub fn new_sdo_write_frame(&self, node_id: NodeId) -> SdoFrame {
+        SdoFrame::new_sdo_write_frame(node_id, Self::INDEX, 0, self.0.to_le_bytes().into())
+    }
+}
+
+impl From<Command> for ControlWord {
+    fn from(command: Command) -> Self {
+        Self(command.bits())
+    }
+}
+
+// Device control commands (cf. CiA 402)
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum Command {
+    Shutdown,
+    SwitchOn,
+    DisableVoltage,
+    QuickStop,
+    DisableOperation,
+    // Also switches on when in ReadyToSwitchOn
+    EnableOperation,
+    FaultReset,
+}
+
+impl Command {
+    pub fn bits(&self) -> u16 {
+        match self {
+            Self::Shutdown => 0x06,
+            Self::SwitchOn | Self::DisableOperation => 0x07,
+            Self::DisableVoltage => 0x00,
+            Self::QuickStop => 0x02,
+            Self::EnableOperation => 0x0F,
+            Self::FaultReset => 0x80,
+        }
+    }
+
+    // `SwitchOn` and `DisableOperation` share their bits; `SwitchOn` is returned for both.
+    fn from_bits(bits: u16) -> Option<Self> {
+        if bits & 0x80 != 0 {
+            return Some(Self::FaultReset);
+        }
+        match bits & 0x0F {
+            0x0F => Some(Self::EnableOperation),
+            0x07 => Some(Self::SwitchOn),
+            0x06 | 0x0E => Some(Self::Shutdown),
+            0x02 | 0x0A => Some(Self::QuickStop),
+            bits if bits & 0x02 == 0 => Some(Self::DisableVoltage),
+            _ => None,
+        }
+    }
+}
+
+impl State {
+    // The next command to send to get closer to OperationEnabled, if any
+    pub fn command_towards_operation_enabled(&self) -> Option<Command> {
+        self.command_towards(Self::OperationEnabled)
+    }
+
+    // The next command to send to get closer to `target`, if any. A fault is reset first.
+    // QuickStopActive can only be reached from OperationEnabled, and is left by disabling the
+    // voltage.
+    pub fn command_towards(&self, target: Self) -> Option<Command> {
+        if *self == target {
+            return None;
+        }
+        match (self, target) {
+            (Self::NotReadyToSwitchOn | Self::FaultReactionActive, _) | (_, Self::Fault) => None,
+            (_, Self::NotReadyToSwitchOn | Self::FaultReactionActive) => None,
+            (Self::Fault, _) => Some(Command::FaultReset),
+            (Self::OperationEnabled, Self::QuickStopActive) => Some(Command::QuickStop),
+            (_, Self::QuickStopActive) => None,
+            (Self::QuickStopActive, _) | (_, Self::SwitchOnDisabled) => {
+                Some(Command::DisableVoltage)
+            }
+            (Self::SwitchOnDisabled, _) => Some(Command::Shutdown),
+            (Self::ReadyToSwitchOn, _) => Some(Command::SwitchOn),
+            (Self::SwitchedOn, Self::ReadyToSwitchOn) => Some(Command::Shutdown),
+            (Self::SwitchedOn, _) => Some(Command::EnableOperation),
+            (Self::OperationEnabled, Self::ReadyToSwitchOn) => Some(Command::Shutdown),
+            (Self::OperationEnabled, _) => Some(Command::DisableOperation),
+        }
+    }
+}
+
+// Drives a device to a target state of the power state machine. Feed it the statuswords read
+// by SDO or received by TPDO; it returns the controlwords to write by SDO, and `control_word`
+// is the value to map in a cyclic RPDO. Bits outside the command (e.g. halt or operation mode
+// specific bits) are kept.
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub struct DriveStateMachine {
+    target: State,
+    state: Option<State>,
+    control_word: ControlWord,
+}
+
+impl DriveStateMachine {
+    pub fn new(target: State) -> Self {
+        Self {
+            target,
+            state: None,
+            control_word: ControlWord::empty(),
+        }
+    }
+
+    pub fn target(&self) -> State {
+        self.target
+    }
+
+    pub fn set_target(&mut self, target: State) {
+        self.target = target;
+    }
+
+    // The state decoded from the last statusword, if valid
+    pub fn state(&self) -> Option<State> {
+        self.state
+    }
+
+    pub fn is_target_reached(&self) -> bool {
+        self.state == Some(self.target)
+    }
+
+    pub fn control_word(&self) -> ControlWord {
+        self.control_word
+    }
+
+    pub fn set_control_word(&mut self, control_word: ControlWord) {
+        self.control_word = control_word;
+    }
+
+    // Returns the controlword to write for the next transition, if any.
+    pub fn on_status_word(&mut self, status_word: StatusWord) -> Option<ControlWord> {
+        self.state = status_word.state();
+        let command = match self.state?.command_towards(self.target) {
+            // The fault is reset on the rising edge of the bit.
+            Some(Command::FaultReset) if self.control_word.contains(ControlWord::FAULT_RESET) => {
+                Command::DisableVoltage
+            }
+            Some(command) => command,
+            None if self.control_word.contains(ControlWord::FAULT_RESET) => {
+                let mut control_word = self.control_word;
+                control_word.remove(ControlWord::FAULT_RESET);
+                self.control_word = control_word;
+                return Some(control_word);
+            }
+            None => return None,
+        };
+        self.control_word = self.control_word.with_command(command);
+        Some(self.control_word)
+    }
+
+    pub fn on_sdo_status_word(
+        &mut self,
+        node_id: NodeId,
+        status_word: StatusWord,
+    ) -> Option<SdoFrame> {
+        self.on_status_word(status_word)
+            .map(|control_word| control_word.new_sdo_write_frame(node_id))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_status_word_state() {
+        let cases = [
+            (0x0000, Some(State::NotReadyToSwitchOn)),
+            (0x0250, Some(State::SwitchOnDisabled)),
+            (0x0231, Some(State::ReadyToSwitchOn)),
+            (0x0233, Some(State::SwitchedOn)),
+            (0x0637, Some(State::OperationEnabled)),
+            (0x0217, Some(State::QuickStopActive)),
+            (0x021F, Some(State::FaultReactionActive)),
+            (0x0218, Some(State::Fault)),
+            (0x0001, None),
+        ];
+        for (bits, state) in cases {
+            assert_eq!(StatusWord::from_bits(bits).state(), state, "{bits:#06X}");
+        }
+    }
+
+    #[test]
+    fn test_status_word_flags() {
+        let status_word = StatusWord::from(0x0637);
+        assert!(status_word.contains(StatusWord::TARGET_REACHED | StatusWord::REMOTE));
+        assert!(!status_word.contains(StatusWord::FAULT));
+        assert_eq!(
+            status_word & StatusWord::VOLTAGE_ENABLED,
+            StatusWord::VOLTAGE_ENABLED
+        );
+        let mut status_word = StatusWord::empty();
+        status_word.set(StatusWord::WARNING, true);
+        status_word.insert(StatusWord::FAULT);
+        status_word.remove(StatusWord::WARNING);
+        assert_eq!(u16::from(status_word), 0x0008);
+    }
+
+    #[test]
+    fn test_control_word() {
+        let control_word = ControlWord::from(Command::EnableOperation) | ControlWord::HALT;
+        assert_eq!(control_word.bits(), 0x010F);
+        assert_eq!(control_word.command(), Some(Command::EnableOperation));
+        let control_word = control_word.with_command(Command::Shutdown);
+        assert_eq!(control_word.bits(), 0x0106);
+        assert_eq!(control_word.command(), Some(Command::Shutdown));
+        assert_eq!(
+            ControlWord::from(Command::FaultReset).command(),
+            Some(Command::FaultReset)
+        );
+        assert_eq!(
+            ControlWord::from(Command::QuickStop).command(),
+            Some(Command::QuickStop)
+        );
+        assert_eq!(
+            ControlWord::from(Command::DisableVoltage).command(),
+            Some(Command::DisableVoltage)
+        );
+        assert_eq!(ControlWord::from_bits(0x0003).command(), None);
+        assert_eq!(
+            control_word.new_sdo_write_frame(1.try_into().unwrap()),
+            SdoFrame::new_sdo_write_frame(1.try_into().unwrap(), 0x6040, 0, vec![0x06, 0x01])
+        );
+    }
+
+    #[test]
+    fn test_command_towards_operation_enabled() {
+        let mut state = State::Fault;
+        let mut commands = vec![];
+        while let Some(command) = state.command_towards_operation_enabled() {
+            commands.push(command);
+            state = match command {
+                Command::FaultReset => State::SwitchOnDisabled,
+                Command::Shutdown => State::ReadyToSwitchOn,
+                Command::SwitchOn => State::SwitchedOn,
+                Command::EnableOperation => State::OperationEnabled,
+                _ => unreachable!(),
+            };
+        }
+        assert_eq!(
+            commands,
+            vec![
+                Command::FaultReset,
+                Command::Shutdown,
+                Command::SwitchOn,
+                Command::EnableOperation
+            ]
+        );
+    }
+
+    #[test]
+    fn test_pdo_field() {
+        let mut bytes = [0x00; 2];
+        StatusWord::from_bits(0x0637).write(&mut bytes);
+        assert_eq!(bytes, [0x37, 0x06]);
+        assert_eq!(
+            ControlWord::read(&[0x0F, 0x00]),
+            Command::EnableOperation.into()
+        );
+    }
+
+    #[test]
+    fn test_command_towards() {
+        assert_eq!(
+            State::OperationEnabled.command_towards(State::SwitchedOn),
+            Some(Command::DisableOperation)
+        );
+        assert_eq!(
+            State::OperationEnabled.command_towards(State::QuickStopActive),
+            Some(Command::QuickStop)
+        );
+        assert_eq!(
+            State::SwitchedOn.command_towards(State::SwitchOnDisabled),
+            Some(Command::DisableVoltage)
+        );
+        assert_eq!(
+            State::SwitchedOn.command_towards(State::ReadyToSwitchOn),
+            Some(Command::Shutdown)
+        );
+        assert_eq!(
+            State::QuickStopActive.command_towards(State::SwitchedOn),
+            Some(Command::DisableVoltage)
+        );
+        assert_eq!(
+            State::Fault.command_towards(State::SwitchOnDisabled),
+            Some(Command::FaultReset)
+        );
+        assert_eq!(
+            State::SwitchedOn.command_towards(State::QuickStopActive),
+            None
+        );
+        assert_eq!(State::SwitchedOn.command_towards(State::Fault), None);
+        assert_eq!(
+            State::FaultReactionActive.command_towards(State::OperationEnabled),
+            None
+        );
+    }
+
+    #[test]
+    fn test_drive_state_machine() {
+        let mut drive = DriveStateMachine::new(State::OperationEnabled);
+        drive.set_control_word(ControlWord::HALT);
+        // A fault reset left set by someone else is cleared first.
+        drive.set_control_word(drive.control_word() | ControlWord::FAULT_RESET);
+        assert_eq!(
+            drive.on_status_word(0x0218.into()).map(|word| word.bits()),
+            Some(0x0100)
+        );
+        assert_eq!(
+            drive.on_status_word(0x0218.into()).map(|word| word.bits()),
+            Some(0x0180)
+        );
+        assert_eq!(
+            drive.on_status_word(0x0250.into()).map(|word| word.bits()),
+            Some(0x0106)
+        );
+        assert_eq!(
+            drive.on_status_word(0x0231.into()).map(|word| word.bits()),
+            Some(0x0107)
+        );
+        assert_eq!(
+            drive.on_status_word(0x0233.into()).map(|word| word.bits()),
+            Some(0x010F)
+        );
+        assert_eq!(drive.on_status_word(0x0637.into()), None);
+        assert!(drive.is_target_reached());
+        assert_eq!(drive.on_status_word(0x0001.into()), None);
+        assert_eq!(drive.state(), None);
+
+        drive.set_target(State::SwitchOnDisabled);
+        assert_eq!(
+            drive.on_sdo_status_word(1.try_into().unwrap(), 0x0637.into()),
+            Some(SdoFrame::new_sdo_write_frame(
+                1.try_into().unwrap(),
+                0x6040,
+                0,
+                vec![0x00, 0x01]
+            ))
+        );
+    }
+
+    #[test]
+    fn test_fault_reset_to_switch_on_disabled() {
+        let mut drive = DriveStateMachine::new(State::SwitchOnDisabled);
+        assert_eq!(
+            drive.on_status_word(0x0008.into()),
+            Some(Command::FaultReset.into())
+        );
+        assert_eq!(
+            drive.on_status_word(0x0040.into()),
+            Some(ControlWord::empty())
+        );
+        assert_eq!(drive.on_status_word(0x0040.into()), None);
+    }
+}
@@ -0,0 +1,343 @@
+use super::{ControlWord, StatusWord};
+use crate::error::{Error, Result};
+use crate::frame::SdoFrame;
+use crate::id::NodeId;
+
+// Modes of operation (0x6060) and modes of operation display (0x6061) (cf. CiA 402)
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum ModeOfOperation {
+    ProfilePosition,
+    Velocity,
+    ProfileVelocity,
+    ProfileTorque,
+    Homing,
+    InterpolatedPosition,
+    CyclicSynchronousPosition,
+    CyclicSynchronousVelocity,
+    CyclicSynchronousTorque,
+    // Manufacturer specific (negative) or reserved values
+    Other(i8),
+}
+
+impl ModeOfOperation {
+    pub const INDEX: u16 = 0x6060;
+    pub const DISPLAY_INDEX: u16 = 0x6061;
+
+    pub fn from_i8(value: i8) -> Self {
+        match value {
+            1 => Self::ProfilePosition,
+            2 => Self::Velocity,
+            3 => Self::ProfileVelocity,
+            4 => Self::ProfileTorque,
+            6 => Self::Homing,
+            7 => Self::InterpolatedPosition,
+            8 => Self::CyclicSynchronousPosition,
+            9 => Self::CyclicSynchronousVelocity,
+            10 => Self::CyclicSynchronousTorque,
+            value => Self::Other(value),
+        }
+    }
+
+    pub fn as_i8(&self) -> i8 {
+        match self {
+            Self::ProfilePosition => 1,
+            Self::Velocity => 2,
+            Self::ProfileVelocity => 3,
+            Self::ProfileTorque => 4,
+            Self::Homing => 6,
+            Self::InterpolatedPosition => 7,
+            Self::CyclicSynchronousPosition => 8,
+            Self::CyclicSynchronousVelocity => 9,
+            Self::CyclicSynchronousTorque => 10,
+            Self::Other(value) => *value,
+        }
+    }
+
+    pub fn new_sdo_write_frame(&self, node_id: NodeId) -> SdoFrame {
+        SdoFrame::new_sdo_write_frame(node_id, Self::INDEX, 0, vec![self.as_i8() as u8])
+    }
+
+    // Reads the mode of operation display, i.e. the mode actually in use
+    pub fn new_sdo_read_frame(node_id: NodeId) -> SdoFrame {
+        SdoFrame::new_sdo_read_frame(node_id, Self::DISPLAY_INDEX, 0)
+    }
+}
+
+impl From<i8> for ModeOfOperation {
+    fn from(value: i8) -> Self {
+        Self::from_i8(value)
+    }
+}
+
+impl From<ModeOfOperation> for i8 {
+    fn from(mode: ModeOfOperation) -> Self {
+        mode.as_i8()
+    }
+}
+
+// Target of a profile mode, in the units configured on the drive
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum Target {
+    // Target position (0x607A)
+    Position(i32),
+    // Target velocity (0x60FF)
+    Velocity(i32),
+    // Target torque (0x6071)
+    Torque(i16),
+}
+
+impl Target {
+    pub fn index(&self) -> u16 {
+        match self {
+            Self::Position(_) => 0x607A,
+            Self::Velocity(_) => 0x60FF,
+            Self::Torque(_) => 0x6071,
+        }
+    }
+
+    // The profile mode the target is used in
+    pub fn mode(&self) -> ModeOfOperation {
+        match self {
+            Self::Position(_) => ModeOfOperation::ProfilePosition,
+            Self::Velocity(_) => ModeOfOperation::ProfileVelocity,
+            Self::Torque(_) => ModeOfOperation::ProfileTorque,
+        }
+    }
+
+    pub fn new_sdo_write_frame(&self, node_id: NodeId) -> SdoFrame {
+        let data = match self {
+            Self::Position(value) | Self::Velocity(value) => value.to_le_bytes().into(),
+            Self::Torque(value) => value.to_le_bytes().into(),
+        };
+        SdoFrame::new_sdo_write_frame(node_id, self.index(), 0, data)
+    }
+}
+
+#[derive(Debug, PartialEq)]
+pub enum MotionStep {
+    // Write this frame and keep feeding statuswords
+    Write(SdoFrame),
+    // Nothing to do until the next statusword
+    Wait,
+    // The target is reached.
+    Done,
+}
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+enum Phase {
+    Idle,
+    SetPointAcknowledge,
+    TargetReached,
+    Done,
+}
+
+// A move to a target in the matching profile mode, for a drive already in OperationEnabled
+// (cf. `DriveStateMachine`). `start` returns the frames to write, then each statusword read by
+// SDO or received by TPDO is fed to `on_status_word` until it returns `Done`.
+//
+// In profile position mode the new set point is handed over with the set-point acknowledge
+// handshake (controlword bit 4, statusword bit 12). In the velocity and torque modes, writing
+// the target is enough.
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub struct ProfileMove {
+    node_id: NodeId,
+    target: Target,
+    control_word: ControlWord,
+    phase: Phase,
+}
+
+impl ProfileMove {
+    // `control_word` is the one the drive was enabled with, e.g.
+    // `DriveStateMachine::control_word`.
+    pub fn new(node_id: NodeId, target: Target, control_word: ControlWord) -> Self {
+        Self {
+            node_id,
+            target,
+            control_word,
+            phase: Phase::Idle,
+        }
+    }
+
+    // Position relative to the current target rather than absolute
+    pub fn relative(mut self, relative: bool) -> Self {
+        self.control_word.set(ControlWord::RELATIVE, relative);
+        self
+    }
+
+    // Interrupt the move in progress rather than queuing the new set point after it
+    pub fn immediate(mut self, immediate: bool) -> Self {
+        self.control_word
+            .set(ControlWord::CHANGE_SET_IMMEDIATELY, immediate);
+        self
+    }
+
+    pub fn target(&self) -> Target {
+        self.target
+    }
+
+    // The controlword to map in a cyclic RPDO instead of writing it by SDO
+    pub fn control_word(&self) -> ControlWord {
+        self.control_word
+    }
+
+    pub fn is_done(&self) -> bool {
+        self.phase == Phase::Done
+    }
+
+    // The mode of operation, the target and, in profile position mode, the controlword with a
+    // new set point, to write in order
+    pub fn start(&mut self) -> std::vec::Vec<SdoFrame> {
+        let mut frames = vec![
+            self.target.mode().new_sdo_write_frame(self.node_id),
+            self.target.new_sdo_write_frame(self.node_id),
+        ];
+        if let Target::Position(_) = self.target {
+            self.control_word.insert(ControlWord::NEW_SET_POINT);
+            frames.push(self.control_word.new_sdo_write_frame(self.node_id));
+            self.phase = Phase::SetPointAcknowledge;
+        } else {
+            self.phase = Phase::TargetReached;
+        }
+        frames
+    }
+
+    // Fails on a fault or, in profile position mode, a following error.
+    pub fn on_status_word(&mut self, status_word: StatusWord) -> Result<MotionStep> {
+        let following_error = matches!(self.target, Target::Position(_))
+            && status_word.contains(StatusWord::FOLLOWING_ERROR);
+        if status_word.contains(StatusWord::FAULT) || following_error {
+            self.phase = Phase::Idle;
+            return Err(Error::MotionFailed(status_word.bits()));
+        }
+        match self.phase {
+            Phase::Idle => Ok(MotionStep::Wait),
+            Phase::SetPointAcknowledge => {
+                if !status_word.contains(StatusWord::SET_POINT_ACKNOWLEDGE) {
+                    return Ok(MotionStep::Wait);
+                }
+                self.control_word.remove(ControlWord::NEW_SET_POINT);
+                self.phase = Phase::TargetReached;
+                Ok(MotionStep::Write(
+                    self.control_word.new_sdo_write_frame(self.node_id),
+                ))
+            }
+            Phase::TargetReached => {
+                if !status_word.contains(StatusWord::TARGET_REACHED) {
+                    return Ok(MotionStep::Wait);
+                }
+                self.phase = Phase::Done;
+                Ok(MotionStep::Done)
+            }
+            Phase::Done => Ok(MotionStep::Done),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    use crate::cia402::Command;
+
+    #[test]
+    fn test_mode_of_operation() {
+        for value in -1..=10 {
+            assert_eq!(ModeOfOperation::from_i8(value).as_i8(), value);
+        }
+        assert_eq!(ModeOfOperation::from(-3), ModeOfOperation::Other(-3));
+        assert_eq!(
+            ModeOfOperation::CyclicSynchronousPosition.new_sdo_write_frame(1.try_into().unwrap()),
+            SdoFrame::new_sdo_write_frame(1.try_into().unwrap(), 0x6060, 0, vec![0x08])
+        );
+        assert_eq!(
+            ModeOfOperation::new_sdo_read_frame(1.try_into().unwrap()),
+            SdoFrame::new_sdo_read_frame(1.try_into().unwrap(), 0x6061, 0)
+        );
+    }
+
+    #[test]
+    fn test_target() {
+        let node_id: NodeId = 1.try_into().unwrap();
+        assert_eq!(
+            Target::Position(-2).new_sdo_write_frame(node_id),
+            SdoFrame::new_sdo_write_frame(node_id, 0x607A, 0, vec![0xFE, 0xFF, 0xFF, 0xFF])
+        );
+        assert_eq!(
+            Target::Velocity(1000).new_sdo_write_frame(node_id),
+            SdoFrame::new_sdo_write_frame(node_id, 0x60FF, 0, vec![0xE8, 0x03, 0x00, 0x00])
+        );
+        assert_eq!(
+            Target::Torque(500).new_sdo_write_frame(node_id),
+            SdoFrame::new_sdo_write_frame(node_id, 0x6071, 0, vec![0xF4, 0x01])
+        );
+        assert_eq!(Target::Torque(0).mode(), ModeOfOperation::ProfileTorque);
+    }
+
+    #[test]
+    fn test_profile_position() {
+        let node_id: NodeId = 1.try_into().unwrap();
+        let mut motion = ProfileMove::new(
+            node_id,
+            Target::Position(10_000),
+            Command::EnableOperation.into(),
+        )
+        .immediate(true);
+        assert_eq!(
+            motion.start(),
+            vec![
+                SdoFrame::new_sdo_write_frame(node_id, 0x6060, 0, vec![0x01]),
+                SdoFrame::new_sdo_write_frame(node_id, 0x607A, 0, vec![0x10, 0x27, 0x00, 0x00]),
+                SdoFrame::new_sdo_write_frame(node_id, 0x6040, 0, vec![0x3F, 0x00]),
+            ]
+        );
+        assert_eq!(motion.on_status_word(0x0237.into()), Ok(MotionStep::Wait));
+        assert_eq!(
+            motion.on_status_word(0x1237.into()),
+            Ok(MotionStep::Write(SdoFrame::new_sdo_write_frame(
+                node_id,
+                0x6040,
+                0,
+                vec![0x2F, 0x00]
+            )))
+        );
+        assert_eq!(motion.on_status_word(0x0237.into()), Ok(MotionStep::Wait));
+        assert_eq!(motion.on_status_word(0x0637.into()), Ok(MotionStep::Done));
+        assert!(motion.is_done());
+        assert_eq!(motion.control_word().bits(), 0x002F);
+    }
+
+    #[test]
+    fn test_profile_velocity() {
+        let node_id: NodeId = 1.try_into().unwrap();
+        let mut motion = ProfileMove::new(
+            node_id,
+            Target::Velocity(100),
+            Command::EnableOperation.into(),
+        );
+        assert_eq!(motion.start().len(), 2);
+        assert_eq!(motion.on_status_word(0x0237.into()), Ok(MotionStep::Wait));
+        assert_eq!(motion.on_status_word(0x0637.into()), Ok(MotionStep::Done));
+    }
+
+    #[test]
+    fn test_motion_failed() {
+        let node_id: NodeId = 1.try_into().unwrap();
+        let mut motion = ProfileMove::new(
+            node_id,
+            Target::Position(0),
+            Command::EnableOperation.into(),
+        )
+        .relative(true);
+        motion.start();
+        assert_eq!(motion.control_word().bits(), 0x005F);
+        assert_eq!(
+            motion.on_status_word(0x2237.into()),
+            Err(Error::MotionFailed(0x2237))
+        );
+        assert_eq!(
+            motion.on_status_word(0x0218.into()),
+            Err(Error::MotionFailed(0x0218))
+        );
+        assert!(!motion.is_done());
+    }
+}
@@ -0,0 +1,300 @@
+use crate::error::{Error, Result};
+use crate::frame::SdoFrame;
+use crate::id::NodeId;
+use crate::pdo_layout::PdoField;
+
+// Like `vendor_objects!`, for the objects of the encoder profile (cf. CiA 406)
+macro_rules! encoder_objects {
+    (
+        $(
+            $(#[$meta:meta])*
+            $object:ident: $index:literal, $sub_index:literal, $t:ty, $access:ident;
+        )*
+    ) => {
+        $(
+            $(#[$meta])*
+            #[derive(Clone, Copy, Debug, PartialEq)]
+            pub struct $object;
+
+            impl $object {
+                pub const INDEX: u16 = $index;
+                pub const SUB_INDEX: u8 = $sub_index;
+                pub const NAME: &'static str = concat!("cia406::", stringify!($object));
+
+                pub fn from_bytes(bytes: &[u8]) -> Result<$t> {
+                    if bytes.len() != <$t as PdoField>::SIZE {
+                        return Err(Error::InvalidDataLength {
+                            length: bytes.len(),
+                            data_type: Self::NAME.to_owned(),
+                        });
+                    }
+                    Ok(<$t as PdoField>::read(bytes))
+                }
+            }
+
+            crate::vendor_objects!(@access $access, $object, $t);
+        )*
+    };
+}
+
+encoder_objects! {
+    // Code sequence, scaling and diagnostic control (cf. `OperatingParameters`)
+    OperatingParameters: 0x6000, 0, u16, rw;
+    MeasuringUnitsPerRevolution: 0x6001, 0, u32, rw;
+    TotalMeasuringRange: 0x6002, 0, u32, rw;
+    // Sets the position value to this value at the current position.
+    PresetValue: 0x6003, 0, u32, rw;
+    PositionValue: 0x6004, 0, u32, ro;
+    // Speed value of the first channel, in the units of the speed configuration
+    SpeedValue: 0x6030, 1, i16, ro;
+    // Interval of the position TPDO in ms (0 disables it)
+    CyclicTimer: 0x6200, 0, u16, rw;
+    // Operating parameters in use
+    OperatingStatus: 0x6500, 0, u16, ro;
+    // Physical measuring steps per revolution
+    SingleturnResolution: 0x6501, 0, u32, ro;
+    NumberOfDistinguishableRevolutions: 0x6502, 0, u16, ro;
+}
+
+impl OperatingParameters {
+    // Bits of the value
+    pub const CODE_SEQUENCE_CCW: u16 = 1 << 0;
+    pub const COMMISSIONING_DIAGNOSTIC: u16 = 1 << 1;
+    pub const SCALING: u16 = 1 << 2;
+}
+
+// Resolution of an absolute encoder, read from 0x6501 and 0x6502 (physical) or configured in
+// 0x6001 and 0x6002 (scaled)
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub struct Resolution {
+    pub steps_per_revolution: u32,
+    pub total_steps: u32,
+}
+
+impl Resolution {
+    pub fn new(steps_per_revolution: u32, total_steps: u32) -> Self {
+        Self {
+            steps_per_revolution,
+            total_steps,
+        }
+    }
+
+    // From the singleturn resolution and the number of distinguishable revolutions
+    pub fn from_physical(singleturn_resolution: u32, revolutions: u16) -> Self {
+        Self::new(
+            singleturn_resolution,
+            singleturn_resolution.saturating_mul(revolutions.into()),
+        )
+    }
+
+    pub fn revolutions(&self) -> u32 {
+        self.total_steps
+            .checked_div(self.steps_per_revolution)
+            .unwrap_or(0)
+    }
+
+    // The position value in revolutions
+    pub fn to_revolutions(&self, position_value: u32) -> f64 {
+        if self.steps_per_revolution == 0 {
+            return 0.0;
+        }
+        position_value as f64 / self.steps_per_revolution as f64
+    }
+
+    // Reads the physical resolution
+    pub fn new_sdo_read_frames(node_id: NodeId) -> [SdoFrame; 2] {
+        [
+            SingleturnResolution::new_sdo_read_frame(node_id),
+            NumberOfDistinguishableRevolutions::new_sdo_read_frame(node_id),
+        ]
+    }
+
+    // Configures the scaling, which takes effect once the scaling bit of the operating
+    // parameters is set
+    pub fn new_sdo_write_frames(&self, node_id: NodeId) -> [SdoFrame; 2] {
+        [
+            MeasuringUnitsPerRevolution::new_sdo_write_frame(node_id, self.steps_per_revolution),
+            TotalMeasuringRange::new_sdo_write_frame(node_id, self.total_steps),
+        ]
+    }
+}
+
+// CAMs of a channel (cf. CiA 406): up to 8 position windows, each switching a bit of the CAM
+// state register. Channels and CAMs are numbered from 1 to 8.
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub struct CamChannel(u8);
+
+impl CamChannel {
+    pub const STATE_REGISTER_INDEX: u16 = 0x6300;
+    pub const ENABLE_REGISTER_INDEX: u16 = 0x6301;
+    pub const POLARITY_REGISTER_INDEX: u16 = 0x6302;
+    // Per channel, from the index of the first one. The sub-index is the CAM number.
+    pub const LOW_LIMIT_INDEX: u16 = 0x6310;
+    pub const HIGH_LIMIT_INDEX: u16 = 0x6320;
+    pub const HYSTERESIS_INDEX: u16 = 0x6330;
+
+    pub const MAX: u8 = 8;
+
+    pub fn new(channel: u8) -> Result<Self> {
+        if !(1..=Self::MAX).contains(&channel) {
+            return Err(Error::InvalidCamNumber(channel));
+        }
+        Ok(Self(channel))
+    }
+
+    pub fn number(&self) -> u8 {
+        self.0
+    }
+
+    // The CAM state register holds one bit per CAM, set when the position is in its window.
+    pub fn new_state_read_frame(&self, node_id: NodeId) -> SdoFrame {
+        SdoFrame::new_sdo_read_frame(node_id, Self::STATE_REGISTER_INDEX, self.0)
+    }
+
+    // Enables the CAMs whose bits are set in `cams`.
+    pub fn new_enable_write_frame(&self, node_id: NodeId, cams: u8) -> SdoFrame {
+        SdoFrame::new_sdo_write_frame(node_id, Self::ENABLE_REGISTER_INDEX, self.0, vec![cams])
+    }
+
+    // Inverts the state bits of the CAMs whose bits are set in `cams`.
+    pub fn new_polarity_write_frame(&self, node_id: NodeId, cams: u8) -> SdoFrame {
+        SdoFrame::new_sdo_write_frame(node_id, Self::POLARITY_REGISTER_INDEX, self.0, vec![cams])
+    }
+
+    // Writes the window of `cam`, in position units, with its hysteresis.
+    pub fn new_cam_write_frames(
+        &self,
+        node_id: NodeId,
+        cam: u8,
+        low_limit: u32,
+        high_limit: u32,
+        hysteresis: u16,
+    ) -> Result<[SdoFrame; 3]> {
+        if !(1..=Self::MAX).contains(&cam) {
+            return Err(Error::InvalidCamNumber(cam));
+        }
+        let offset = (self.0 - 1) as u16;
+        Ok([
+            SdoFrame::new_sdo_write_frame(
+                node_id,
+                Self::LOW_LIMIT_INDEX + offset,
+                cam,
+                low_limit.to_le_bytes().into(),
+            ),
+            SdoFrame::new_sdo_write_frame(
+                node_id,
+                Self::HIGH_LIMIT_INDEX + offset,
+                cam,
+                high_limit.to_le_bytes().into(),
+            ),
+            SdoFrame::new_sdo_write_frame(
+                node_id,
+                Self::HYSTERESIS_INDEX + offset,
+                cam,
+                hysteresis.to_le_bytes().into(),
+            ),
+        ])
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    use crate::frame::ConvertibleFrame;
+
+    fn node_id() -> NodeId {
+        3.try_into().unwrap()
+    }
+
+    #[test]
+    fn test_objects() {
+        assert_eq!(
+            PositionValue::new_sdo_read_frame(node_id()).frame_data(),
+            &[0x40, 0x04, 0x60, 0x00, 0x00, 0x00, 0x00, 0x00]
+        );
+        assert_eq!(
+            PresetValue::new_sdo_write_frame(node_id(), 0x1234).frame_data(),
+            &[0x23, 0x03, 0x60, 0x00, 0x34, 0x12, 0x00, 0x00]
+        );
+        assert_eq!(
+            OperatingParameters::new_sdo_write_frame(
+                node_id(),
+                OperatingParameters::CODE_SEQUENCE_CCW | OperatingParameters::SCALING
+            )
+            .frame_data(),
+            &[0x2B, 0x00, 0x60, 0x00, 0x05, 0x00, 0x00, 0x00]
+        );
+        assert_eq!(
+            PositionValue::from_bytes(&[0x00, 0x10, 0x00, 0x00]),
+            Ok(0x1000)
+        );
+        assert_eq!(
+            SpeedValue::from_bytes(&[0x00]),
+            Err(Error::InvalidDataLength {
+                length: 1,
+                data_type: "cia406::SpeedValue".to_owned()
+            })
+        );
+    }
+
+    #[test]
+    fn test_resolution() {
+        let resolution = Resolution::from_physical(4096, 16);
+        assert_eq!(resolution.total_steps, 65536);
+        assert_eq!(resolution.revolutions(), 16);
+        assert_eq!(resolution.to_revolutions(6144), 1.5);
+        assert_eq!(Resolution::new(0, 0).revolutions(), 0);
+        let [units, range] = resolution.new_sdo_write_frames(node_id());
+        assert_eq!(
+            units.frame_data(),
+            &[0x23, 0x01, 0x60, 0x00, 0x00, 0x10, 0x00, 0x00]
+        );
+        assert_eq!(
+            range.frame_data(),
+            &[0x23, 0x02, 0x60, 0x00, 0x00, 0x00, 0x01, 0x00]
+        );
+        let [singleturn, revolutions] = Resolution::new_sdo_read_frames(node_id());
+        assert_eq!(
+            singleturn.frame_data(),
+            &[0x40, 0x01, 0x65, 0x00, 0x00, 0x00, 0x00, 0x00]
+        );
+        assert_eq!(
+            revolutions.frame_data(),
+            &[0x40, 0x02, 0x65, 0x00, 0x00, 0x00, 0x00, 0x00]
+        );
+    }
+
+    #[test]
+    fn test_cam_channel() {
+        assert_eq!(CamChannel::new(0), Err(Error::InvalidCamNumber(0)));
+        assert_eq!(CamChannel::new(9), Err(Error::InvalidCamNumber(9)));
+        let channel = CamChannel::new(2).unwrap();
+        assert_eq!(
+            channel.new_state_read_frame(node_id()).frame_data(),
+            &[0x40, 0x00, 0x63, 0x02, 0x00, 0x00, 0x00, 0x00]
+        );
+        assert_eq!(
+            channel
+                .new_enable_write_frame(node_id(), 0b0101)
+                .frame_data(),
+            &[0x2F, 0x01, 0x63, 0x02, 0x05, 0x00, 0x00, 0x00]
+        );
+        let [low, high, hysteresis] = channel
+            .new_cam_write_frames(node_id(), 3, 100, 200, 5)
+            .unwrap();
+        assert_eq!(
+            low.frame_data(),
+            &[0x23, 0x11, 0x63, 0x03, 0x64, 0x00, 0x00, 0x00]
+        );
+        assert_eq!(
+            high.frame_data(),
+            &[0x23, 0x21, 0x63, 0x03, 0xC8, 0x00, 0x00, 0x00]
+        );
+        assert_eq!(
+            hysteresis.frame_data(),
+            &[0x2B, 0x31, 0x63, 0x03, 0x05, 0x00, 0x00, 0x00]
+        );
+        assert!(channel.new_cam_write_frames(node_id(), 9, 0, 0, 0).is_err());
+    }
+}
@@ -0,0 +1,261 @@
+use crate::id::CommunicationObject;
+
+// A bus problem observed by the caller, e.g. from the error frames of the CAN controller
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum BusError {
+    // A single error (error frame, lost arbitration excluded)
+    ErrorFrame,
+    ErrorPassive,
+    BusOff,
+}
+
+// What may be transmitted. Each level restricts the previous one.
+#[derive(Clone, Copy, Debug, PartialEq, Eq, PartialOrd, Ord)]
+pub enum ContainmentLevel {
+    Normal,
+    // Service traffic (SDO, LSS polling, logging) is stopped.
+    Degraded,
+    // Only supervision frames (NMT, heartbeat/node guarding, EMCY) are transmitted.
+    Contained,
+}
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum ContainmentEvent {
+    Escalated {
+        from: ContainmentLevel,
+        to: ContainmentLevel,
+    },
+    Relaxed {
+        from: ContainmentLevel,
+        to: ContainmentLevel,
+    },
+}
+
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub struct ContainmentPolicy {
+    // Errors within `window` that degrade the bus
+    pub error_threshold: u32,
+    pub window: std::time::Duration,
+    // Time without errors before relaxing by one level
+    pub recovery_time: std::time::Duration,
+}
+
+impl Default for ContainmentPolicy {
+    fn default() -> Self {
+        Self {
+            error_threshold: 10,
+            window: std::time::Duration::from_secs(1),
+            recovery_time: std::time::Duration::from_secs(5),
+        }
+    }
+}
+
+// Shared containment of a sick bus, so that subsystems stop transmitting together instead of
+// each retrying on its own. Feed it the bus errors with `on_error`, call `poll` periodically
+// to relax after recovery, and check `allows` before transmitting.
+#[derive(Clone, Debug)]
+pub struct BusContainment {
+    policy: ContainmentPolicy,
+    level: ContainmentLevel,
+    errors: std::collections::VecDeque<std::time::Instant>,
+    last_error_at: Option<std::time::Instant>,
+    // Escalations since the bus was last normal
+    escalations: u32,
+}
+
+impl BusContainment {
+    pub fn new(policy: ContainmentPolicy) -> Self {
+        Self {
+            policy,
+            level: ContainmentLevel::Normal,
+            errors: std::collections::VecDeque::new(),
+            last_error_at: None,
+            escalations: 0,
+        }
+    }
+
+    pub fn level(&self) -> ContainmentLevel {
+        self.level
+    }
+
+    pub fn escalations(&self) -> u32 {
+        self.escalations
+    }
+
+    pub fn allows(&self, cob: CommunicationObject) -> bool {
+        let required = match cob {
+            CommunicationObject::NmtNodeControl
+            | CommunicationObject::NmtNodeMonitoring(_)
+            | CommunicationObject::Emergency(_) => ContainmentLevel::Contained,
+            CommunicationObject::RxSdo(_)
+            | CommunicationObject::TxSdo(_)
+            | CommunicationObject::TxLss
+            | CommunicationObject::RxLss => ContainmentLevel::Normal,
+            _ => ContainmentLevel::Degraded,
+        };
+        self.level <= required
+    }
+
+    pub fn on_error(
+        &mut self,
+        error: BusError,
+        now: std::time::Instant,
+    ) -> Option<ContainmentEvent> {
+        self.last_error_at = Some(now);
+        let level = match error {
+            BusError::BusOff => ContainmentLevel::Contained,
+            BusError::ErrorPassive => ContainmentLevel::Degraded,
+            BusError::ErrorFrame => {
+                self.errors.push_back(now);
+                while self.errors.front().is_some_and(|error_at| {
+                    now.saturating_duration_since(*error_at) > self.policy.window
+                }) {
+                    self.errors.pop_front();
+                }
+                if self.errors.len() < self.policy.error_threshold as usize {
+                    return None;
+                }
+                self.errors.clear();
+                // A storm that goes on escalates further.
+                match self.level {
+                    ContainmentLevel::Normal => ContainmentLevel::Degraded,
+                    _ => ContainmentLevel::Contained,
+                }
+            }
+        };
+        if level <= self.level {
+            return None;
+        }
+        let from = std::mem::replace(&mut self.level, level);
+        self.escalations += 1;
+        Some(ContainmentEvent::Escalated { from, to: level })
+    }
+
+    pub fn poll(&mut self, now: std::time::Instant) -> Option<ContainmentEvent> {
+        let last_error_at = self.last_error_at?;
+        if self.level == ContainmentLevel::Normal
+            || now.saturating_duration_since(last_error_at) < self.policy.recovery_time
+        {
+            return None;
+        }
+        let from = self.level;
+        self.level = match from {
+            ContainmentLevel::Contained => ContainmentLevel::Degraded,
+            _ => ContainmentLevel::Normal,
+        };
+        if self.level == ContainmentLevel::Normal {
+            self.escalations = 0;
+        }
+        // The next step down needs another quiet period.
+        self.last_error_at = Some(now);
+        Some(ContainmentEvent::Relaxed {
+            from,
+            to: self.level,
+        })
+    }
+}
+
+impl Default for BusContainment {
+    fn default() -> Self {
+        Self::new(ContainmentPolicy::default())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    use std::time::Duration;
+
+    fn policy() -> ContainmentPolicy {
+        ContainmentPolicy {
+            error_threshold: 3,
+            window: Duration::from_millis(100),
+            recovery_time: Duration::from_secs(1),
+        }
+    }
+
+    #[test]
+    fn test_allows() {
+        let node_id = 1.try_into().unwrap();
+        let cobs = [
+            CommunicationObject::NmtNodeMonitoring(node_id),
+            CommunicationObject::Sync,
+            CommunicationObject::TxPdo1(node_id),
+            CommunicationObject::RxSdo(node_id),
+        ];
+        let mut containment = BusContainment::new(policy());
+        let now = std::time::Instant::now();
+        let allowed = |containment: &BusContainment| cobs.map(|cob| containment.allows(cob));
+        assert_eq!(allowed(&containment), [true, true, true, true]);
+        containment.on_error(BusError::ErrorPassive, now);
+        assert_eq!(allowed(&containment), [true, true, true, false]);
+        containment.on_error(BusError::BusOff, now);
+        assert_eq!(allowed(&containment), [true, false, false, false]);
+    }
+
+    #[test]
+    fn test_error_storm() {
+        let now = std::time::Instant::now();
+        let mut containment = BusContainment::new(policy());
+        assert_eq!(containment.on_error(BusError::ErrorFrame, now), None);
+        assert_eq!(
+            containment.on_error(BusError::ErrorFrame, now + Duration::from_millis(50)),
+            None
+        );
+        // The first error is out of the window.
+        assert_eq!(
+            containment.on_error(BusError::ErrorFrame, now + Duration::from_millis(101)),
+            None
+        );
+        assert_eq!(
+            containment.on_error(BusError::ErrorFrame, now + Duration::from_millis(102)),
+            Some(ContainmentEvent::Escalated {
+                from: ContainmentLevel::Normal,
+                to: ContainmentLevel::Degraded
+            })
+        );
+        for _ in 0..2 {
+            containment.on_error(BusError::ErrorFrame, now + Duration::from_millis(103));
+        }
+        assert_eq!(
+            containment.on_error(BusError::ErrorFrame, now + Duration::from_millis(104)),
+            Some(ContainmentEvent::Escalated {
+                from: ContainmentLevel::Degraded,
+                to: ContainmentLevel::Contained
+            })
+        );
+        assert_eq!(containment.escalations(), 2);
+        assert_eq!(
+            containment.on_error(BusError::ErrorPassive, now + Duration::from_millis(104)),
+            None
+        );
+    }
+
+    #[test]
+    fn test_recovery() {
+        let now = std::time::Instant::now();
+        let mut containment = BusContainment::new(policy());
+        assert_eq!(containment.poll(now), None);
+        containment.on_error(BusError::BusOff, now);
+        assert_eq!(containment.poll(now + Duration::from_millis(999)), None);
+        assert_eq!(
+            containment.poll(now + Duration::from_secs(1)),
+            Some(ContainmentEvent::Relaxed {
+                from: ContainmentLevel::Contained,
+                to: ContainmentLevel::Degraded
+            })
+        );
+        assert_eq!(containment.poll(now + Duration::from_millis(1500)), None);
+        assert_eq!(
+            containment.poll(now + Duration::from_secs(2)),
+            Some(ContainmentEvent::Relaxed {
+                from: ContainmentLevel::Degraded,
+                to: ContainmentLevel::Normal
+            })
+        );
+        assert_eq!(containment.level(), ContainmentLevel::Normal);
+        assert_eq!(containment.escalations(), 0);
+        assert_eq!(containment.poll(now + Duration::from_secs(10)), None);
+    }
+}
@@ -0,0 +1,861 @@
+use crate::cancel::CancellationToken;
+use crate::error::{Error, Result};
+use crate::id::NodeId;
+use crate::object::ConfigurationDateTime;
+use crate::od::{AccessType, DataType, Value};
+use crate::sdo::{SdoDownload, SdoDownloadStep, SdoRequest};
+
+// A sub-object (or a VAR object, with sub-index 0) described in a DCF
+#[derive(Clone, Debug, PartialEq)]
+pub struct DcfEntry {
+    pub index: u16,
+    pub sub_index: u8,
+    pub name: std::string::String,
+    pub data_type: DataType,
+    pub access: AccessType,
+    pub default_value: Option<std::string::String>,
+    pub parameter_value: Option<std::string::String>,
+}
+
+impl DcfEntry {
+    // Evaluates `ParameterValue`, resolving `$NODEID` with `node_id`.
+    pub fn value(&self, node_id: NodeId) -> Result<Option<Value>> {
+        self.parameter_value
+            .as_deref()
+            .map(|value| parse_value(self.data_type, value, node_id))
+            .transpose()
+    }
+}
+
+// Device configuration file (cf. CiA 306). Only the parts needed to configure a node are kept:
+// the commissioned node ID and bit rate, and the VAR entries.
+#[derive(Clone, Debug, Default, PartialEq)]
+pub struct Dcf {
+    pub node_id: Option<NodeId>,
+    // kbit/s
+    pub baud_rate: Option<u32>,
+    pub entries: std::vec::Vec<DcfEntry>,
+}
+
+impl Dcf {
+    pub fn parse(text: &str) -> Result<Self> {
+        let mut dcf = Self::default();
+        let mut section: Option<(std::string::String, usize)> = None;
+        let mut keys = std::collections::BTreeMap::new();
+        for (number, line) in text.lines().enumerate() {
+            let line = line.trim();
+            if line.is_empty() || line.starts_with(';') {
+                continue;
+            }
+            if let Some(name) = line.strip_prefix('[') {
+                let name = name.strip_suffix(']').ok_or_else(|| Error::InvalidDcf {
+                    line: number + 1,
+                    message: "unterminated section name".to_owned(),
+                })?;
+                if let Some((name, line)) = section.take() {
+                    dcf.add_section(&name, line, &keys)?;
+                }
+                section = Some((name.trim().to_owned(), number + 1));
+                keys.clear();
+                continue;
+            }
+            let (key, value) = line.split_once('=').ok_or_else(|| Error::InvalidDcf {
+                line: number + 1,
+                message: "expected key=value".to_owned(),
+            })?;
+            keys.insert(key.trim().to_ascii_lowercase(), value.trim().to_owned());
+        }
+        if let Some((name, line)) = section {
+            dcf.add_section(&name, line, &keys)?;
+        }
+        Ok(dcf)
+    }
+
+    pub fn entry(&self, index: u16, sub_index: u8) -> Option<&DcfEntry> {
+        self.entries
+            .iter()
+            .find(|entry| entry.index == index && entry.sub_index == sub_index)
+    }
+
+    fn add_section(
+        &mut self,
+        name: &str,
+        line: usize,
+        keys: &std::collections::BTreeMap<std::string::String, std::string::String>,
+    ) -> Result<()> {
+        let invalid = |message: &str| Error::InvalidDcf {
+            line,
+            message: message.to_owned(),
+        };
+        // CiA 306 spells it "DeviceComissioning".
+        if name.eq_ignore_ascii_case("DeviceComissioning") {
+            if let Some(node_id) = keys.get("nodeid") {
+                let node_id = parse_integer(node_id).ok_or_else(|| invalid("invalid NodeID"))?;
+                self.node_id = Some(
+                    u8::try_from(node_id)
+                        .map_err(|_| invalid("invalid NodeID"))?
+                        .try_into()?,
+                );
+            }
+            if let Some(baud_rate) = keys.get("baudrate") {
+                self.baud_rate = Some(
+                    parse_integer(baud_rate)
+                        .and_then(|baud_rate| u32::try_from(baud_rate).ok())
+                        .ok_or_else(|| invalid("invalid Baudrate"))?,
+                );
+            }
+            return Ok(());
+        }
+        let Some((index, sub_index)) = parse_section_name(name) else {
+            return Ok(());
+        };
+        // ARRAY and RECORD sections only describe their sub-objects.
+        if let Some(object_type) = keys.get("objecttype") {
+            if parse_integer(object_type) != Some(7) {
+                return Ok(());
+            }
+        }
+        let data_type = keys
+            .get("datatype")
+            .and_then(|data_type| parse_integer(data_type))
+            .and_then(|data_type| u16::try_from(data_type).ok())
+            .ok_or_else(|| invalid("missing or invalid DataType"))?;
+        let access = match keys
+            .get("accesstype")
+            .map(|access| access.to_ascii_lowercase())
+        {
+            Some(access) => match access.as_str() {
+                "ro" => AccessType::ReadOnly,
+                "wo" => AccessType::WriteOnly,
+                "rw" | "rwr" | "rww" => AccessType::ReadWrite,
+                "const" => AccessType::Constant,
+                _ => return Err(invalid("invalid AccessType")),
+            },
+            None => return Err(invalid("missing AccessType")),
+        };
+        self.entries.push(DcfEntry {
+            index,
+            sub_index,
+            name: keys.get("parametername").cloned().unwrap_or_default(),
+            data_type: DataType::from_u16(data_type)?,
+            access,
+            default_value: keys.get("defaultvalue").cloned(),
+            parameter_value: keys.get("parametervalue").cloned(),
+        });
+        Ok(())
+    }
+}
+
+// "1018" or "1018sub1"
+fn parse_section_name(name: &str) -> Option<(u16, u8)> {
+    let lower = name.to_ascii_lowercase();
+    let (index, sub_index) = match lower.split_once("sub") {
+        Some((index, sub_index)) => (index, u8::from_str_radix(sub_index, 16).ok()?),
+        None => (lower.as_str(), 0),
+    };
+    if index.len() != 4 {
+        return None;
+    }
+    Some((u16::from_str_radix(index, 16).ok()?, sub_index))
+}
+
+fn parse_integer(s: &str) -> Option<i128> {
+    let s = s.trim();
+    let (negative, s) = match s.strip_prefix('-') {
+        Some(s) => (true, s),
+        None => (false, s),
+    };
+    let value = match s.strip_prefix("0x").or_else(|| s.strip_prefix("0X")) {
+        Some(hex) => i128::from_str_radix(hex, 16).ok()?,
+        None => s.parse().ok()?,
+    };
+    Some(if negative { -value } else { value })
+}
+
+// Integer values may be sums such as "$NODEID+0x180".
+fn parse_integer_expression(s: &str, node_id: NodeId) -> Option<i128> {
+    s.split('+').try_fold(0, |sum: i128, term| {
+        let term = term.trim();
+        let value = if term.eq_ignore_ascii_case("$NODEID") {
+            node_id.as_raw().into()
+        } else {
+            parse_integer(term)?
+        };
+        sum.checked_add(value)
+    })
+}
+
+fn parse_value(data_type: DataType, s: &str, node_id: NodeId) -> Result<Value> {
+    let failed = || Error::ParseFailed {
+        input: s.to_owned(),
+        data_type: format!("{:?}", data_type),
+    };
+    let integer = || parse_integer_expression(s, node_id).ok_or_else(failed);
+    Ok(match data_type {
+        DataType::Boolean => Value::Boolean(match integer()? {
+            0 => false,
+            1 => true,
+            _ => return Err(failed()),
+        }),
+        DataType::Integer8 => Value::Integer8(integer()?.try_into().map_err(|_| failed())?),
+        DataType::Integer16 => Value::Integer16(integer()?.try_into().map_err(|_| failed())?),
+        DataType::Integer32 => Value::Integer32(integer()?.try_into().map_err(|_| failed())?),
+        DataType::Integer64 => Value::Integer64(integer()?.try_into().map_err(|_| failed())?),
+        DataType::Unsigned8 => Value::Unsigned8(integer()?.try_into().map_err(|_| failed())?),
+        DataType::Unsigned16 => Value::Unsigned16(integer()?.try_into().map_err(|_| failed())?),
+        DataType::Unsigned32 => Value::Unsigned32(integer()?.try_into().map_err(|_| failed())?),
+        DataType::Unsigned64 => Value::Unsigned64(integer()?.try_into().map_err(|_| failed())?),
+        DataType::Real32 => Value::Real32(s.trim().parse().map_err(|_| failed())?),
+        DataType::Real64 => Value::Real64(s.trim().parse().map_err(|_| failed())?),
+        DataType::VisibleString => Value::VisibleString(s.to_owned()),
+        DataType::OctetString => {
+            let hex = s.trim();
+            if !hex.len().is_multiple_of(2) {
+                return Err(failed());
+            }
+            Value::OctetString(
+                (0..hex.len())
+                    .step_by(2)
+                    .map(|i| u8::from_str_radix(hex.get(i..i + 2)?, 16).ok())
+                    .collect::<Option<_>>()
+                    .ok_or_else(failed)?,
+            )
+        }
+        DataType::Domain => return Err(failed()),
+    })
+}
+
+#[derive(Debug, PartialEq)]
+pub enum DcfSkipReason {
+    NotWritable,
+    InvalidValue(Error),
+}
+
+#[derive(Debug, Default, PartialEq)]
+pub struct DcfDownloadReport {
+    pub written: std::vec::Vec<(u16, u8)>,
+    pub skipped: std::vec::Vec<(u16, u8, DcfSkipReason)>,
+    // Entries the node refused, e.g. with an SDO abort
+    pub rejected: std::vec::Vec<(u16, u8, Error)>,
+}
+
+// An entry to write: index, sub-index and data
+type DcfValue = (u16, u8, std::vec::Vec<u8>);
+
+#[derive(Debug, PartialEq)]
+pub enum DcfDownloadStep {
+    Send(SdoRequest),
+    Done(DcfDownloadReport),
+    // The cancellation token was cancelled. Send the abort of the entry being written, if any;
+    // it is reported as rejected with `Error::Cancelled`, and the remaining entries are not
+    // written.
+    Cancelled(Option<SdoRequest>, DcfDownloadReport),
+}
+
+// Downloads the `ParameterValue`s of a DCF to a node by SDO, in file order. A rejected entry
+// does not stop the download.
+#[derive(Debug)]
+pub struct DcfDownload {
+    node_id: NodeId,
+    pending: std::collections::VecDeque<DcfValue>,
+    current: Option<(u16, u8, SdoDownload)>,
+    report: DcfDownloadReport,
+    signature_object: Option<(u16, u8, ConfigurationSignature)>,
+    date_time: Option<ConfigurationDateTime>,
+    cancellation: Option<CancellationToken>,
+}
+
+impl DcfDownload {
+    pub fn new(dcf: &Dcf, node_id: NodeId) -> Self {
+        let (pending, skipped) = writable_values(dcf, node_id);
+        Self {
+            node_id,
+            pending: pending.into(),
+            current: None,
+            report: DcfDownloadReport {
+                skipped,
+                ..Default::default()
+            },
+            signature_object: None,
+            date_time: None,
+            cancellation: None,
+        }
+    }
+
+    // Once every entry has been written, writes the signature of the configuration to
+    // `index`:`sub_index` (an UNSIGNED32 object, e.g. manufacturer specific), so that it can
+    // later be verified without reading back each entry. It is not written if the node rejected
+    // an entry.
+    pub fn with_signature_object(self, index: u16, sub_index: u8) -> Self {
+        let signature = ConfigurationSignature::new(self.pending.iter());
+        Self {
+            signature_object: Some((index, sub_index, signature)),
+            ..self
+        }
+    }
+
+    // Once every entry (and the signature, if any) has been written, stamps the configuration
+    // date and time (0x1020:01 and 0x1020:02, cf. CiA 302), e.g. with
+    // `ConfigurationDateTime::now()`. Comparing the stamp read back after a boot with the
+    // expected one tells whether the node needs reconfiguring. It is not written if the node
+    // rejected an entry.
+    pub fn with_date_time(self, date_time: ConfigurationDateTime) -> Self {
+        Self {
+            date_time: Some(date_time),
+            ..self
+        }
+    }
+
+    // The token is checked between entries and between the segments of an entry.
+    pub fn with_cancellation(self, cancellation: CancellationToken) -> Self {
+        Self {
+            cancellation: Some(cancellation),
+            ..self
+        }
+    }
+
+    pub fn start(&mut self) -> DcfDownloadStep {
+        self.next_entry()
+    }
+
+    // Feeds the data of a frame received on the TxSDO COB of the node.
+    pub fn on_response(&mut self, bytes: &[u8]) -> DcfDownloadStep {
+        let cancelled = self.is_cancelled();
+        let Some((index, sub_index, download)) = self.current.as_mut() else {
+            return DcfDownloadStep::Done(std::mem::take(&mut self.report));
+        };
+        match download.on_response(bytes) {
+            Ok(SdoDownloadStep::Send(_)) if cancelled => {
+                let abort = download.abort_request();
+                self.report
+                    .rejected
+                    .push((*index, *sub_index, Error::Cancelled));
+                self.current = None;
+                self.pending.clear();
+                return DcfDownloadStep::Cancelled(Some(abort), std::mem::take(&mut self.report));
+            }
+            Ok(SdoDownloadStep::Send(request)) => return DcfDownloadStep::Send(request),
+            Ok(SdoDownloadStep::Done) => self.report.written.push((*index, *sub_index)),
+            Err(error) => self.report.rejected.push((*index, *sub_index, error)),
+        }
+        self.next_entry()
+    }
+
+    // Records the current entry as rejected with `error` (e.g. `Error::SdoTimeout`) and moves
+    // on to the next one.
+    pub fn on_error(&mut self, error: Error) -> DcfDownloadStep {
+        if let Some((index, sub_index, _)) = self.current.take() {
+            self.report.rejected.push((index, sub_index, error));
+        }
+        self.next_entry()
+    }
+
+    fn is_cancelled(&self) -> bool {
+        self.cancellation
+            .as_ref()
+            .is_some_and(CancellationToken::is_cancelled)
+    }
+
+    fn next_entry(&mut self) -> DcfDownloadStep {
+        if self.is_cancelled() {
+            self.current = None;
+            self.pending.clear();
+            return DcfDownloadStep::Cancelled(None, std::mem::take(&mut self.report));
+        }
+        if self.pending.is_empty() && self.report.rejected.is_empty() {
+            if let Some((index, sub_index, signature)) = self.signature_object.take() {
+                self.pending
+                    .push_back((index, sub_index, signature.value().to_le_bytes().into()));
+            } else if let Some(date_time) = self.date_time.take() {
+                self.pending.push_back((
+                    ConfigurationDateTime::INDEX,
+                    ConfigurationDateTime::DATE_SUB_INDEX,
+                    date_time.date.to_le_bytes().into(),
+                ));
+                self.pending.push_back((
+                    ConfigurationDateTime::INDEX,
+                    ConfigurationDateTime::TIME_SUB_INDEX,
+                    date_time.time.to_le_bytes().into(),
+                ));
+            }
+        }
+        match self.pending.pop_front() {
+            Some((index, sub_index, data)) => {
+                let mut download = SdoDownload::new(self.node_id, index, sub_index, data);
+                let request = download.start();
+                self.current = Some((index, sub_index, download));
+                DcfDownloadStep::Send(request)
+            }
+            None => {
+                self.current = None;
+                DcfDownloadStep::Done(std::mem::take(&mut self.report))
+            }
+        }
+    }
+}
+
+// The values of the entries that a `DcfDownload` writes, in file order, and the skipped entries
+fn writable_values(
+    dcf: &Dcf,
+    node_id: NodeId,
+) -> (
+    std::vec::Vec<DcfValue>,
+    std::vec::Vec<(u16, u8, DcfSkipReason)>,
+) {
+    let mut values = std::vec::Vec::new();
+    let mut skipped = std::vec::Vec::new();
+    for entry in &dcf.entries {
+        let value = match entry.value(node_id) {
+            Ok(Some(value)) => value,
+            Ok(None) => continue,
+            Err(error) => {
+                skipped.push((
+                    entry.index,
+                    entry.sub_index,
+                    DcfSkipReason::InvalidValue(error),
+                ));
+                continue;
+            }
+        };
+        if !entry.access.is_writable() {
+            skipped.push((entry.index, entry.sub_index, DcfSkipReason::NotWritable));
+            continue;
+        }
+        values.push((entry.index, entry.sub_index, value.to_bytes()));
+    }
+    (values, skipped)
+}
+
+// CRC-32 of the values a DCF configures on a node (with `$NODEID` resolved), to check that the
+// node still carries the configuration by reading a single object. It covers the index,
+// sub-index and data of each written entry, in file order.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub struct ConfigurationSignature(u32);
+
+impl ConfigurationSignature {
+    fn new<'a>(values: impl Iterator<Item = &'a DcfValue>) -> Self {
+        let mut crc = !0;
+        for (index, sub_index, data) in values {
+            crc = crc32_update(crc, &index.to_le_bytes());
+            crc = crc32_update(crc, &[*sub_index]);
+            crc = crc32_update(crc, &(data.len() as u32).to_le_bytes());
+            crc = crc32_update(crc, data);
+        }
+        Self(!crc)
+    }
+
+    pub fn from_dcf(dcf: &Dcf, node_id: NodeId) -> Self {
+        Self::new(writable_values(dcf, node_id).0.iter())
+    }
+
+    pub fn from_value(value: u32) -> Self {
+        Self(value)
+    }
+
+    pub fn value(&self) -> u32 {
+        self.0
+    }
+}
+
+// Signatures of the configurations applied to each node, for a host that keeps them instead of
+// (or in addition to) storing them on the nodes
+#[derive(Clone, Debug, Default, PartialEq)]
+pub struct ConfigurationSignatures {
+    nodes: std::collections::BTreeMap<u8, ConfigurationSignature>,
+}
+
+impl ConfigurationSignatures {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    pub fn insert(&mut self, node_id: NodeId, signature: ConfigurationSignature) {
+        self.nodes.insert(node_id.as_raw(), signature);
+    }
+
+    pub fn remove(&mut self, node_id: NodeId) -> Option<ConfigurationSignature> {
+        self.nodes.remove(&node_id.as_raw())
+    }
+
+    pub fn get(&self, node_id: NodeId) -> Option<ConfigurationSignature> {
+        self.nodes.get(&node_id.as_raw()).copied()
+    }
+
+    // False if the node has no recorded signature
+    pub fn verify(&self, node_id: NodeId, signature: ConfigurationSignature) -> bool {
+        self.get(node_id) == Some(signature)
+    }
+}
+
+// CRC-32 (IEEE 802.3, reflected polynomial 0xEDB88320), without the initial and final inversion
+fn crc32_update(crc: u32, data: &[u8]) -> u32 {
+    data.iter().fold(crc, |crc, byte| {
+        (0..8).fold(crc ^ (*byte as u32), |crc, _| {
+            if crc & 1 != 0 {
+                (crc >> 1) ^ 0xEDB8_8320
+            } else {
+                crc >> 1
+            }
+        })
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    use crate::sdo::SdoAbortCode;
+
+    const DCF: &str = "
+[FileInfo]
+FileName=test.dcf
+
+[DeviceComissioning]
+NodeID=0x05
+Baudrate=500
+
+[1000]
+ParameterName=Device type
+ObjectType=0x7
+DataType=0x0007
+AccessType=ro
+DefaultValue=0x00020192
+ParameterValue=0x00020192
+
+[1017]
+ParameterName=Producer heartbeat time
+ObjectType=0x7
+DataType=0x0006
+AccessType=rw
+DefaultValue=0
+ParameterValue=1000
+
+[1800]
+ParameterName=TPDO1 communication parameter
+ObjectType=0x9
+SubNumber=2
+
+[1800sub1]
+ParameterName=COB-ID
+ObjectType=0x7
+DataType=0x0007
+AccessType=rw
+DefaultValue=$NODEID+0x180
+ParameterValue=$NODEID+0x180
+
+[1800sub2]
+ParameterName=Transmission type
+ObjectType=0x7
+DataType=0x0005
+AccessType=rw
+ParameterValue=0x1FF
+
+[1008]
+ParameterName=Device name
+DataType=0x0009
+AccessType=const
+DefaultValue=Motor
+";
+
+    fn node_id() -> NodeId {
+        5.try_into().unwrap()
+    }
+
+    #[test]
+    fn test_parse() {
+        let dcf = Dcf::parse(DCF).unwrap();
+        assert_eq!(dcf.node_id, Some(node_id()));
+        assert_eq!(dcf.baud_rate, Some(500));
+        assert_eq!(dcf.entries.len(), 5);
+        assert_eq!(
+            dcf.entry(0x1800, 1),
+            Some(&DcfEntry {
+                index: 0x1800,
+                sub_index: 1,
+                name: "COB-ID".to_owned(),
+                data_type: DataType::Unsigned32,
+                access: AccessType::ReadWrite,
+                default_value: Some("$NODEID+0x180".to_owned()),
+                parameter_value: Some("$NODEID+0x180".to_owned()),
+            })
+        );
+        assert!(dcf.entry(0x1800, 0).is_none());
+        assert_eq!(dcf.entry(0x1008, 0).unwrap().parameter_value, None);
+    }
+
+    #[test]
+    fn test_parse_invalid() {
+        assert_eq!(
+            Dcf::parse("[1000\nDataType=0x0007"),
+            Err(Error::InvalidDcf {
+                line: 1,
+                message: "unterminated section name".to_owned()
+            })
+        );
+        assert_eq!(
+            Dcf::parse("[1000]\nDataType"),
+            Err(Error::InvalidDcf {
+                line: 2,
+                message: "expected key=value".to_owned()
+            })
+        );
+        assert_eq!(
+            Dcf::parse("\n[1000]\nDataType=0x0007\nAccessType=xx"),
+            Err(Error::InvalidDcf {
+                line: 2,
+                message: "invalid AccessType".to_owned()
+            })
+        );
+    }
+
+    #[test]
+    fn test_value() {
+        let dcf = Dcf::parse(DCF).unwrap();
+        assert_eq!(
+            dcf.entry(0x1800, 1).unwrap().value(node_id()),
+            Ok(Some(Value::Unsigned32(0x185)))
+        );
+        assert_eq!(
+            dcf.entry(0x1017, 0).unwrap().value(node_id()),
+            Ok(Some(Value::Unsigned16(1000)))
+        );
+        assert!(dcf.entry(0x1800, 2).unwrap().value(node_id()).is_err());
+        assert_eq!(
+            parse_value(DataType::Integer16, "-0x10", node_id()),
+            Ok(Value::Integer16(-16))
+        );
+        assert_eq!(
+            parse_value(DataType::OctetString, "01AB", node_id()),
+            Ok(Value::OctetString(vec![0x01, 0xAB]))
+        );
+    }
+
+    #[test]
+    fn test_download() {
+        let dcf = Dcf::parse(DCF).unwrap();
+        let mut download = DcfDownload::new(&dcf, node_id());
+
+        let DcfDownloadStep::Send(request) = download.start() else {
+            panic!("expected a request");
+        };
+        assert_eq!(
+            request.data(),
+            &[0x2B, 0x17, 0x10, 0x00, 0xE8, 0x03, 0x00, 0x00]
+        );
+        let DcfDownloadStep::Send(request) =
+            download.on_response(&[0x60, 0x17, 0x10, 0x00, 0x00, 0x00, 0x00, 0x00])
+        else {
+            panic!("expected a request");
+        };
+        assert_eq!(
+            request.data(),
+            &[0x23, 0x00, 0x18, 0x01, 0x85, 0x01, 0x00, 0x00]
+        );
+        assert_eq!(
+            download.on_response(&[0x80, 0x00, 0x18, 0x01, 0x30, 0x00, 0x09, 0x06]),
+            DcfDownloadStep::Done(DcfDownloadReport {
+                written: vec![(0x1017, 0)],
+                skipped: vec![
+                    (0x1000, 0, DcfSkipReason::NotWritable),
+                    (
+                        0x1800,
+                        2,
+                        DcfSkipReason::InvalidValue(Error::ParseFailed {
+                            input: "0x1FF".to_owned(),
+                            data_type: "Unsigned8".to_owned()
+                        })
+                    ),
+                ],
+                rejected: vec![(0x1800, 1, Error::SdoAborted(SdoAbortCode::InvalidValue))],
+            })
+        );
+    }
+
+    #[test]
+    fn test_download_on_error() {
+        let dcf = Dcf::parse(DCF).unwrap();
+        let mut download = DcfDownload::new(&dcf, node_id());
+        download.start();
+        let timeout = || Error::SdoTimeout {
+            node_id: node_id(),
+            index: 0x1017,
+            sub_index: 0,
+        };
+        assert!(matches!(
+            download.on_error(timeout()),
+            DcfDownloadStep::Send(_)
+        ));
+        let DcfDownloadStep::Done(report) = download.on_error(Error::NotImplemented) else {
+            panic!("expected the report");
+        };
+        assert!(report.written.is_empty());
+        assert_eq!(
+            report.rejected,
+            vec![(0x1017, 0, timeout()), (0x1800, 1, Error::NotImplemented)]
+        );
+    }
+
+    #[test]
+    fn test_download_cancelled() {
+        let dcf = Dcf::parse(DCF).unwrap();
+        let cancellation = CancellationToken::new();
+        let mut download =
+            DcfDownload::new(&dcf, node_id()).with_cancellation(cancellation.clone());
+        download.start();
+        cancellation.cancel();
+        // Stopped between entries: 0x1800:01 is not written
+        let DcfDownloadStep::Cancelled(None, report) =
+            download.on_response(&[0x60, 0x17, 0x10, 0x00, 0x00, 0x00, 0x00, 0x00])
+        else {
+            panic!("expected the report");
+        };
+        assert_eq!(report.written, vec![(0x1017, 0)]);
+        assert!(report.rejected.is_empty());
+        assert!(matches!(
+            download.on_response(&[0x60, 0x00, 0x18, 0x01, 0x00, 0x00, 0x00, 0x00]),
+            DcfDownloadStep::Done(_)
+        ));
+
+        // A segmented entry is aborted
+        let dcf =
+            Dcf::parse("[2000]\nDataType=0x0009\nAccessType=rw\nParameterValue=Motor controller")
+                .unwrap();
+        let cancellation = CancellationToken::new();
+        let mut download =
+            DcfDownload::new(&dcf, node_id()).with_cancellation(cancellation.clone());
+        let DcfDownloadStep::Send(request) = download.start() else {
+            panic!("expected a request");
+        };
+        assert_eq!(request.data()[..4], [0x21, 0x00, 0x20, 0x00]);
+        assert!(matches!(
+            download.on_response(&[0x60, 0x00, 0x20, 0x00, 0x00, 0x00, 0x00, 0x00]),
+            DcfDownloadStep::Send(_)
+        ));
+        cancellation.cancel();
+        let DcfDownloadStep::Cancelled(Some(abort), report) =
+            download.on_response(&[0x20, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00])
+        else {
+            panic!("expected an abort");
+        };
+        assert_eq!(
+            abort.data(),
+            &[0x80, 0x00, 0x20, 0x00, 0x00, 0x00, 0x00, 0x08]
+        );
+        assert_eq!(report.rejected, vec![(0x2000, 0, Error::Cancelled)]);
+    }
+
+    #[test]
+    fn test_crc32() {
+        assert_eq!(!crc32_update(!0, b"123456789"), 0xCBF4_3926);
+    }
+
+    #[test]
+    fn test_signature() {
+        let dcf = Dcf::parse(DCF).unwrap();
+        let signature = ConfigurationSignature::from_dcf(&dcf, node_id());
+        assert_ne!(
+            signature,
+            ConfigurationSignature::from_dcf(&dcf, 6.try_into().unwrap())
+        );
+        let mut changed = dcf.clone();
+        changed.entries[1].parameter_value = Some("500".to_owned());
+        assert_ne!(
+            signature,
+            ConfigurationSignature::from_dcf(&changed, node_id())
+        );
+        assert_eq!(
+            ConfigurationSignature::from_value(signature.value()),
+            signature
+        );
+
+        let mut download = DcfDownload::new(&dcf, node_id()).with_signature_object(0x2F00, 1);
+        download.start();
+        download.on_response(&[0x60, 0x17, 0x10, 0x00, 0x00, 0x00, 0x00, 0x00]);
+        let DcfDownloadStep::Send(request) =
+            download.on_response(&[0x60, 0x00, 0x18, 0x01, 0x00, 0x00, 0x00, 0x00])
+        else {
+            panic!("expected the signature");
+        };
+        let mut expected = [0x23, 0x00, 0x2F, 0x01, 0x00, 0x00, 0x00, 0x00];
+        expected[4..].copy_from_slice(&signature.value().to_le_bytes());
+        assert_eq!(request.data(), &expected);
+        let DcfDownloadStep::Done(report) =
+            download.on_response(&[0x60, 0x00, 0x2F, 0x01, 0x00, 0x00, 0x00, 0x00])
+        else {
+            panic!("expected the report");
+        };
+        assert_eq!(report.written, vec![(0x1017, 0), (0x1800, 1), (0x2F00, 1)]);
+
+        // Not written after a rejected entry
+        let mut download = DcfDownload::new(&dcf, node_id()).with_signature_object(0x2F00, 1);
+        download.start();
+        download.on_response(&[0x60, 0x17, 0x10, 0x00, 0x00, 0x00, 0x00, 0x00]);
+        assert!(matches!(
+            download.on_response(&[0x80, 0x00, 0x18, 0x01, 0x30, 0x00, 0x09, 0x06]),
+            DcfDownloadStep::Done(_)
+        ));
+
+        let mut signatures = ConfigurationSignatures::new();
+        assert!(!signatures.verify(node_id(), signature));
+        signatures.insert(node_id(), signature);
+        assert!(signatures.verify(node_id(), signature));
+        assert!(!signatures.verify(node_id(), ConfigurationSignature::from_value(0)));
+        assert_eq!(signatures.remove(node_id()), Some(signature));
+    }
+
+    #[test]
+    fn test_date_time() {
+        let dcf = Dcf::parse(DCF).unwrap();
+        let date_time = ConfigurationDateTime::new(14865, 3_600_000);
+        let mut download = DcfDownload::new(&dcf, node_id())
+            .with_signature_object(0x2F00, 1)
+            .with_date_time(date_time);
+        download.start();
+        download.on_response(&[0x60, 0x17, 0x10, 0x00, 0x00, 0x00, 0x00, 0x00]);
+        download.on_response(&[0x60, 0x00, 0x18, 0x01, 0x00, 0x00, 0x00, 0x00]);
+        let DcfDownloadStep::Send(request) =
+            download.on_response(&[0x60, 0x00, 0x2F, 0x01, 0x00, 0x00, 0x00, 0x00])
+        else {
+            panic!("expected the date");
+        };
+        assert_eq!(
+            request.data(),
+            &[0x23, 0x20, 0x10, 0x01, 0x11, 0x3A, 0x00, 0x00]
+        );
+        let DcfDownloadStep::Send(request) =
+            download.on_response(&[0x60, 0x20, 0x10, 0x01, 0x00, 0x00, 0x00, 0x00])
+        else {
+            panic!("expected the time");
+        };
+        assert_eq!(
+            request.data(),
+            &[0x23, 0x20, 0x10, 0x02, 0x80, 0xEE, 0x36, 0x00]
+        );
+        let DcfDownloadStep::Done(report) =
+            download.on_response(&[0x60, 0x20, 0x10, 0x02, 0x00, 0x00, 0x00, 0x00])
+        else {
+            panic!("expected the report");
+        };
+        assert_eq!(
+            report.written,
+            vec![
+                (0x1017, 0),
+                (0x1800, 1),
+                (0x2F00, 1),
+                (0x1020, 1),
+                (0x1020, 2)
+            ]
+        );
+
+        // Not written after a rejected entry
+        let mut download = DcfDownload::new(&dcf, node_id()).with_date_time(date_time);
+        download.start();
+        download.on_response(&[0x60, 0x17, 0x10, 0x00, 0x00, 0x00, 0x00, 0x00]);
+        let DcfDownloadStep::Done(report) =
+            download.on_response(&[0x80, 0x00, 0x18, 0x01, 0x30, 0x00, 0x09, 0x06])
+        else {
+            panic!("expected the report");
+        };
+        assert_eq!(report.written, vec![(0x1017, 0)]);
+    }
+}
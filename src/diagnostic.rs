@@ -0,0 +1,305 @@
+use socketcan::{CanFrame, CanSocket, EmbeddedFrame, Socket, StandardId};
+
+use crate::error::{Error, Result};
+
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub struct DiagnosticOptions {
+    // COB-ID of the probe frames. Unless the interface is virtual, they are transmitted on the
+    // bus, so it should not be used by any device.
+    pub probe_id: u16,
+    pub probes: u32,
+    // Time to wait for each probe to loop back
+    pub timeout: std::time::Duration,
+    // Time spent listening to the traffic on the bus
+    pub observation: std::time::Duration,
+    // Configured bitrate (bit/s), to check the observed bus load against
+    pub bitrate: Option<u32>,
+}
+
+impl Default for DiagnosticOptions {
+    fn default() -> Self {
+        Self {
+            probe_id: 0x7FF,
+            probes: 10,
+            timeout: std::time::Duration::from_millis(100),
+            observation: std::time::Duration::from_secs(1),
+            bitrate: None,
+        }
+    }
+}
+
+#[derive(Clone, Debug, Default, PartialEq)]
+pub struct DiagnosticReport {
+    pub interface_name: String,
+    pub probes_sent: u32,
+    // Round-trip latency through the kernel of each probe received back
+    pub round_trips: std::vec::Vec<std::time::Duration>,
+    pub frames_observed: u64,
+    pub error_frames: u64,
+    // Lower bound of the bits on the bus during the observation (bit stuffing excluded)
+    pub bits_observed: u64,
+    pub observation: std::time::Duration,
+    pub bitrate: Option<u32>,
+}
+
+impl DiagnosticReport {
+    pub fn probes_received(&self) -> u32 {
+        self.round_trips.len() as u32
+    }
+
+    pub fn min_round_trip(&self) -> Option<std::time::Duration> {
+        self.round_trips.iter().min().copied()
+    }
+
+    pub fn max_round_trip(&self) -> Option<std::time::Duration> {
+        self.round_trips.iter().max().copied()
+    }
+
+    pub fn mean_round_trip(&self) -> Option<std::time::Duration> {
+        let count = self.probes_received();
+        (count > 0).then(|| self.round_trips.iter().sum::<std::time::Duration>() / count)
+    }
+
+    // Ratio of the observation time the bus was busy at the configured bitrate
+    pub fn bus_load(&self) -> Option<f64> {
+        let bitrate = self.bitrate.filter(|bitrate| *bitrate > 0)?;
+        if self.observation.is_zero() {
+            return None;
+        }
+        Some(self.bits_observed as f64 / (bitrate as f64 * self.observation.as_secs_f64()))
+    }
+
+    pub fn failures(&self) -> std::vec::Vec<String> {
+        let mut failures = std::vec::Vec::new();
+        if self.probes_received() < self.probes_sent {
+            failures.push(format!(
+                "{} of {} probes did not loop back",
+                self.probes_sent - self.probes_received(),
+                self.probes_sent
+            ));
+        }
+        if self.error_frames > 0 {
+            failures.push(format!(
+                "{} error frames (bitrate mismatch or wiring problem)",
+                self.error_frames
+            ));
+        }
+        if let Some(bus_load) = self.bus_load().filter(|bus_load| *bus_load > 1.0) {
+            failures.push(format!(
+                "observed traffic exceeds the configured bitrate ({:.0}% bus load)",
+                bus_load * 100.0
+            ));
+        }
+        failures
+    }
+
+    pub fn passed(&self) -> bool {
+        self.failures().is_empty()
+    }
+}
+
+impl std::fmt::Display for DiagnosticReport {
+    fn fmt(&self, f: &mut std::fmt::Formatter) -> std::fmt::Result {
+        writeln!(
+            f,
+            "{}: {}",
+            self.interface_name,
+            if self.passed() { "PASS" } else { "FAIL" }
+        )?;
+        write!(
+            f,
+            "  loopback: {}/{} probes",
+            self.probes_received(),
+            self.probes_sent
+        )?;
+        if let (Some(min), Some(mean), Some(max)) = (
+            self.min_round_trip(),
+            self.mean_round_trip(),
+            self.max_round_trip(),
+        ) {
+            write!(f, ", round trip min/mean/max {min:?}/{mean:?}/{max:?}")?;
+        }
+        writeln!(f)?;
+        write!(
+            f,
+            "  traffic: {} frames, {} error frames in {:?}",
+            self.frames_observed, self.error_frames, self.observation
+        )?;
+        if let Some(bus_load) = self.bus_load() {
+            write!(f, ", {:.1}% bus load", bus_load * 100.0)?;
+        }
+        for failure in self.failures() {
+            write!(f, "\n  failure: {failure}")?;
+        }
+        Ok(())
+    }
+}
+
+// Checks the local interface before relying on the bus: probe frames sent on one socket must
+// be looped back to another one by the kernel, which for a real controller happens once they
+// are acknowledged on the bus. The traffic is then observed to detect error frames and a bus
+// load that the configured bitrate could not carry.
+pub fn diagnose_interface(
+    interface_name: &str,
+    options: &DiagnosticOptions,
+) -> Result<DiagnosticReport> {
+    let tx = open(interface_name)?;
+    let rx = open(interface_name)?;
+    rx.set_error_filter_accept_all()
+        .map_err(|e| query_failed(interface_name, e))?;
+    let probe_id =
+        StandardId::new(options.probe_id).ok_or(Error::InvalidCobId(options.probe_id.into()))?;
+
+    let mut report = DiagnosticReport {
+        interface_name: interface_name.to_owned(),
+        bitrate: options.bitrate,
+        ..Default::default()
+    };
+    for probe in 0..options.probes {
+        let data = probe.to_le_bytes();
+        let frame = CanFrame::new(probe_id, &data)
+            .expect("Should have failed only when the data length exceeded `CAN_MAX_DLEN`");
+        let sent_at = std::time::Instant::now();
+        tx.write_frame(&frame)
+            .map_err(|e| query_failed(interface_name, e))?;
+        report.probes_sent += 1;
+        let deadline = sent_at + options.timeout;
+        while let Some(received) = read_until(&rx, interface_name, deadline)? {
+            if received.id() == frame.id() && received.data() == data {
+                report.round_trips.push(sent_at.elapsed());
+                break;
+            }
+        }
+    }
+
+    let started_at = std::time::Instant::now();
+    while let Some(frame) = read_until(&rx, interface_name, started_at + options.observation)? {
+        match frame {
+            CanFrame::Error(_) => report.error_frames += 1,
+            frame => {
+                report.frames_observed += 1;
+                report.bits_observed +=
+                    frame_bits(frame.is_extended(), frame.is_remote_frame(), frame.dlc());
+            }
+        }
+    }
+    report.observation = started_at.elapsed();
+    Ok(report)
+}
+
+// Bits of a frame on the bus, from SOF to the interframe space, without stuffing bits
+fn frame_bits(extended: bool, remote: bool, dlc: usize) -> u64 {
+    let overhead = if extended { 67 } else { 47 };
+    let data = if remote { 0 } else { dlc.min(8) as u64 * 8 };
+    overhead + data
+}
+
+// Returns `None` once the deadline has passed.
+fn read_until(
+    socket: &CanSocket,
+    interface_name: &str,
+    deadline: std::time::Instant,
+) -> Result<Option<CanFrame>> {
+    let remaining = deadline.saturating_duration_since(std::time::Instant::now());
+    if remaining.is_zero() {
+        return Ok(None);
+    }
+    match socket.read_frame_timeout(remaining) {
+        Ok(frame) => Ok(Some(frame)),
+        Err(e)
+            if matches!(
+                e.kind(),
+                std::io::ErrorKind::TimedOut | std::io::ErrorKind::WouldBlock
+            ) =>
+        {
+            Ok(None)
+        }
+        Err(e) => Err(query_failed(interface_name, e)),
+    }
+}
+
+fn open(interface_name: &str) -> Result<CanSocket> {
+    CanSocket::open(interface_name).map_err(|e| query_failed(interface_name, e))
+}
+
+fn query_failed(interface_name: &str, error: std::io::Error) -> Error {
+    Error::InterfaceQueryFailed {
+        interface_name: interface_name.to_owned(),
+        message: error.to_string(),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    use std::time::Duration;
+
+    fn report() -> DiagnosticReport {
+        DiagnosticReport {
+            interface_name: "can0".to_owned(),
+            probes_sent: 3,
+            round_trips: vec![
+                Duration::from_micros(100),
+                Duration::from_micros(300),
+                Duration::from_micros(200),
+            ],
+            frames_observed: 1000,
+            error_frames: 0,
+            bits_observed: 111_000,
+            observation: Duration::from_secs(1),
+            bitrate: Some(500_000),
+        }
+    }
+
+    #[test]
+    fn test_frame_bits() {
+        assert_eq!(frame_bits(false, false, 8), 111);
+        assert_eq!(frame_bits(false, true, 8), 47);
+        assert_eq!(frame_bits(true, false, 0), 67);
+    }
+
+    #[test]
+    fn test_passed() {
+        let report = report();
+        assert!(report.passed());
+        assert_eq!(report.min_round_trip(), Some(Duration::from_micros(100)));
+        assert_eq!(report.mean_round_trip(), Some(Duration::from_micros(200)));
+        assert_eq!(report.max_round_trip(), Some(Duration::from_micros(300)));
+        assert_eq!(report.bus_load(), Some(0.222));
+        assert_eq!(
+            report.to_string(),
+            "can0: PASS\n  loopback: 3/3 probes, round trip min/mean/max 100µs/200µs/300µs\n  \
+             traffic: 1000 frames, 0 error frames in 1s, 22.2% bus load"
+        );
+    }
+
+    #[test]
+    fn test_failures() {
+        let report = DiagnosticReport {
+            round_trips: vec![],
+            error_frames: 2,
+            bitrate: Some(100_000),
+            ..report()
+        };
+        assert_eq!(
+            report.failures(),
+            vec![
+                "3 of 3 probes did not loop back".to_owned(),
+                "2 error frames (bitrate mismatch or wiring problem)".to_owned(),
+                "observed traffic exceeds the configured bitrate (111% bus load)".to_owned(),
+            ]
+        );
+        assert!(!report.passed());
+        assert_eq!(report.mean_round_trip(), None);
+        assert!(report.to_string().starts_with("can0: FAIL\n"));
+    }
+
+    #[test]
+    fn test_missing_interface() {
+        assert!(matches!(
+            diagnose_interface("canopen-rs-missing", &DiagnosticOptions::default()),
+            Err(Error::InterfaceQueryFailed { .. })
+        ));
+    }
+}
@@ -0,0 +1,236 @@
+use embedded_can::blocking::Can as BlockingCan;
+use embedded_can::nb::Can as NbCan;
+use embedded_can::{Frame, Id, StandardId};
+
+use crate::error::{Error, Result};
+use crate::frame::RawFrame;
+
+impl RawFrame {
+    pub fn to_embedded_frame<F: Frame>(&self) -> F {
+        let id = StandardId::new(self.id()).expect("Should have been checked by `RawFrame::new`");
+        if self.is_remote() {
+            F::new_remote(id, self.data_length().into())
+        } else {
+            F::new(id, self.data())
+        }
+        .expect("Should have failed only when the data length exceeded 8 bytes")
+    }
+
+    // Extended identifiers are not used by CANopen.
+    pub fn from_embedded_frame<F: Frame>(frame: &F) -> Result<Self> {
+        let Id::Standard(id) = frame.id() else {
+            return Err(Error::CanFdNotSupported);
+        };
+        if frame.is_remote_frame() {
+            Self::new_remote(id.as_raw(), frame.dlc() as u8)
+        } else {
+            Self::new(id.as_raw(), frame.data())
+        }
+    }
+}
+
+fn driver_failed(error: impl embedded_can::Error) -> Error {
+    Error::DriverFailed(error.kind().to_string())
+}
+
+// Exchanges raw frames through any blocking `embedded_can` driver, e.g. of an MCU peripheral
+// or a USB adapter
+#[derive(Debug)]
+pub struct EmbeddedCanInterface<C> {
+    can: C,
+}
+
+impl<C: BlockingCan> EmbeddedCanInterface<C> {
+    pub fn new(can: C) -> Self {
+        Self { can }
+    }
+
+    pub fn into_inner(self) -> C {
+        self.can
+    }
+
+    pub fn transmit(&mut self, frame: &RawFrame) -> Result<()> {
+        self.can
+            .transmit(&frame.to_embedded_frame())
+            .map_err(driver_failed)
+    }
+
+    // Fails with `Error::CanFdNotSupported` for a frame with an extended identifier, which the
+    // caller may ignore.
+    pub fn receive(&mut self) -> Result<RawFrame> {
+        let frame = self.can.receive().map_err(driver_failed)?;
+        RawFrame::from_embedded_frame(&frame)
+    }
+}
+
+// Like `EmbeddedCanInterface`, for a non-blocking driver
+#[derive(Debug)]
+pub struct NbEmbeddedCanInterface<C> {
+    can: C,
+}
+
+impl<C: NbCan> NbEmbeddedCanInterface<C> {
+    pub fn new(can: C) -> Self {
+        Self { can }
+    }
+
+    pub fn into_inner(self) -> C {
+        self.can
+    }
+
+    // The driver may replace a pending frame of lower priority, which is returned to be
+    // transmitted again.
+    pub fn transmit(&mut self, frame: &RawFrame) -> nb::Result<Option<RawFrame>, Error> {
+        match self.can.transmit(&frame.to_embedded_frame()) {
+            Ok(replaced) => replaced
+                .map(|replaced| RawFrame::from_embedded_frame(&replaced))
+                .transpose()
+                .map_err(nb::Error::Other),
+            Err(nb::Error::WouldBlock) => Err(nb::Error::WouldBlock),
+            Err(nb::Error::Other(error)) => Err(nb::Error::Other(driver_failed(error))),
+        }
+    }
+
+    pub fn receive(&mut self) -> nb::Result<RawFrame, Error> {
+        match self.can.receive() {
+            Ok(frame) => RawFrame::from_embedded_frame(&frame).map_err(nb::Error::Other),
+            Err(nb::Error::WouldBlock) => Err(nb::Error::WouldBlock),
+            Err(nb::Error::Other(error)) => Err(nb::Error::Other(driver_failed(error))),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    use embedded_can::{ErrorKind, ExtendedId};
+
+    #[derive(Clone, Debug, PartialEq)]
+    struct TestFrame {
+        id: Id,
+        remote: bool,
+        dlc: usize,
+        data: std::vec::Vec<u8>,
+    }
+
+    impl Frame for TestFrame {
+        fn new(id: impl Into<Id>, data: &[u8]) -> Option<Self> {
+            (data.len() <= 8).then(|| Self {
+                id: id.into(),
+                remote: false,
+                dlc: data.len(),
+                data: data.to_vec(),
+            })
+        }
+
+        fn new_remote(id: impl Into<Id>, dlc: usize) -> Option<Self> {
+            (dlc <= 8).then(|| Self {
+                id: id.into(),
+                remote: true,
+                dlc,
+                data: std::vec::Vec::new(),
+            })
+        }
+
+        fn is_extended(&self) -> bool {
+            matches!(self.id, Id::Extended(_))
+        }
+
+        fn is_remote_frame(&self) -> bool {
+            self.remote
+        }
+
+        fn id(&self) -> Id {
+            self.id
+        }
+
+        fn dlc(&self) -> usize {
+            self.dlc
+        }
+
+        fn data(&self) -> &[u8] {
+            &self.data
+        }
+    }
+
+    // Loops the transmitted frames back
+    #[derive(Debug, Default)]
+    struct Loopback {
+        frames: std::collections::VecDeque<TestFrame>,
+    }
+
+    impl BlockingCan for Loopback {
+        type Frame = TestFrame;
+        type Error = ErrorKind;
+
+        fn transmit(&mut self, frame: &TestFrame) -> std::result::Result<(), ErrorKind> {
+            self.frames.push_back(frame.clone());
+            Ok(())
+        }
+
+        fn receive(&mut self) -> std::result::Result<TestFrame, ErrorKind> {
+            self.frames.pop_front().ok_or(ErrorKind::Overrun)
+        }
+    }
+
+    impl NbCan for Loopback {
+        type Frame = TestFrame;
+        type Error = ErrorKind;
+
+        fn transmit(&mut self, frame: &TestFrame) -> nb::Result<Option<TestFrame>, ErrorKind> {
+            if self.frames.len() == 2 {
+                return Err(nb::Error::WouldBlock);
+            }
+            self.frames.push_back(frame.clone());
+            Ok(None)
+        }
+
+        fn receive(&mut self) -> nb::Result<TestFrame, ErrorKind> {
+            self.frames.pop_front().ok_or(nb::Error::WouldBlock)
+        }
+    }
+
+    #[test]
+    fn test_conversion() {
+        let frame = RawFrame::new(0x603, &[0x40, 0x17, 0x10, 0x00]).unwrap();
+        let embedded: TestFrame = frame.to_embedded_frame();
+        assert_eq!(embedded.id, Id::Standard(StandardId::new(0x603).unwrap()));
+        assert_eq!(RawFrame::from_embedded_frame(&embedded), Ok(frame));
+
+        let remote = RawFrame::new_remote(0x703, 1).unwrap();
+        let embedded: TestFrame = remote.to_embedded_frame();
+        assert!(embedded.remote);
+        assert_eq!(RawFrame::from_embedded_frame(&embedded), Ok(remote));
+
+        let extended = TestFrame::new(ExtendedId::new(0x1234_5678).unwrap(), &[]).unwrap();
+        assert_eq!(
+            RawFrame::from_embedded_frame(&extended),
+            Err(Error::CanFdNotSupported)
+        );
+    }
+
+    #[test]
+    fn test_blocking() {
+        let mut interface = EmbeddedCanInterface::new(Loopback::default());
+        let frame = RawFrame::new(0x000, &[0x01, 0x00]).unwrap();
+        interface.transmit(&frame).unwrap();
+        assert_eq!(interface.receive(), Ok(frame));
+        assert_eq!(
+            interface.receive(),
+            Err(Error::DriverFailed(ErrorKind::Overrun.to_string()))
+        );
+    }
+
+    #[test]
+    fn test_nb() {
+        let mut interface = NbEmbeddedCanInterface::new(Loopback::default());
+        let frame = RawFrame::new(0x080, &[]).unwrap();
+        assert_eq!(interface.transmit(&frame), Ok(None));
+        assert_eq!(interface.transmit(&frame), Ok(None));
+        assert_eq!(interface.transmit(&frame), Err(nb::Error::WouldBlock));
+        assert_eq!(interface.receive(), Ok(frame));
+        assert_eq!(interface.receive(), Ok(frame));
+        assert_eq!(interface.receive(), Err(nb::Error::WouldBlock));
+    }
+}
@@ -0,0 +1,409 @@
+use crate::frame::{CanOpenFrame, DataLengthPolicy, EmergencyFrame, RawFrame};
+use crate::id::{CommunicationObject, NodeId};
+
+// The class of an emergency error code (cf. CiA 301, emergency error codes)
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum ErrorClass {
+    // Error reset or no error
+    NoError,
+    Generic,
+    Current,
+    Voltage,
+    Temperature,
+    DeviceHardware,
+    DeviceSoftware,
+    AdditionalModules,
+    Monitoring,
+    External,
+    AdditionalFunctions,
+    DeviceSpecific,
+    // Codes reserved by CiA 301, or defined by a device profile
+    Unknown,
+}
+
+impl ErrorClass {
+    pub fn from_error_code(error_code: u16) -> Self {
+        match error_code >> 8 {
+            0x00 => Self::NoError,
+            0x10 => Self::Generic,
+            0x20..=0x23 => Self::Current,
+            0x30..=0x33 => Self::Voltage,
+            0x40..=0x42 => Self::Temperature,
+            0x50 => Self::DeviceHardware,
+            0x60..=0x63 => Self::DeviceSoftware,
+            0x70 => Self::AdditionalModules,
+            0x80..=0x82 => Self::Monitoring,
+            0x90 => Self::External,
+            0xF0 => Self::AdditionalFunctions,
+            0xFF => Self::DeviceSpecific,
+            _ => Self::Unknown,
+        }
+    }
+
+    pub fn description(&self) -> &'static str {
+        match self {
+            Self::NoError => "no error",
+            Self::Generic => "generic error",
+            Self::Current => "current",
+            Self::Voltage => "voltage",
+            Self::Temperature => "temperature",
+            Self::DeviceHardware => "device hardware",
+            Self::DeviceSoftware => "device software",
+            Self::AdditionalModules => "additional modules",
+            Self::Monitoring => "monitoring",
+            Self::External => "external error",
+            Self::AdditionalFunctions => "additional functions",
+            Self::DeviceSpecific => "device specific",
+            Self::Unknown => "unknown",
+        }
+    }
+}
+
+impl std::fmt::Display for ErrorClass {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(f, "{}", self.description())
+    }
+}
+
+// The description of an error code, as precise as CiA 301 defines it
+pub fn describe_error_code(error_code: u16) -> &'static str {
+    match error_code {
+        0x0000 => "error reset or no error",
+        0x2100 => "current, device input side",
+        0x2200 => "current inside the device",
+        0x2300 => "current, device output side",
+        0x3100 => "mains voltage",
+        0x3200 => "voltage inside the device",
+        0x3300 => "output voltage",
+        0x4100 => "ambient temperature",
+        0x4200 => "device temperature",
+        0x6100 => "internal software",
+        0x6200 => "user software",
+        0x6300 => "data set",
+        0x8100 => "communication",
+        0x8110 => "CAN overrun (objects lost)",
+        0x8120 => "CAN in error passive mode",
+        0x8130 => "life guard error or heartbeat error",
+        0x8140 => "recovered from bus off",
+        0x8150 => "CAN-ID collision",
+        0x8200 => "protocol error",
+        0x8210 => "PDO not processed due to length error",
+        0x8220 => "PDO length exceeded",
+        0x8230 => "DAM MPDO not processed, destination object not available",
+        0x8240 => "unexpected SYNC data length",
+        0x8250 => "RPDO timeout",
+        _ => ErrorClass::from_error_code(error_code).description(),
+    }
+}
+
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub struct EmergencyRecord {
+    pub frame: EmergencyFrame,
+    pub class: ErrorClass,
+    pub received_at: std::time::Instant,
+}
+
+impl EmergencyRecord {
+    pub fn node_id(&self) -> NodeId {
+        self.frame.node_id
+    }
+
+    pub fn is_reset(&self) -> bool {
+        self.class == ErrorClass::NoError
+    }
+
+    pub fn description(&self) -> &'static str {
+        describe_error_code(self.frame.error_code)
+    }
+}
+
+// Time from the reception of emergency frames to their delivery to the subscribers, as
+// measured by `EmergencyConsumer::take_emergencies`
+#[derive(Clone, Copy, Debug, Default, PartialEq)]
+pub struct EmergencyLatency {
+    count: u64,
+    last: std::time::Duration,
+    max: std::time::Duration,
+    total: std::time::Duration,
+}
+
+impl EmergencyLatency {
+    fn record(&mut self, latency: std::time::Duration) {
+        self.count += 1;
+        self.last = latency;
+        self.max = self.max.max(latency);
+        self.total += latency;
+    }
+
+    pub fn count(&self) -> u64 {
+        self.count
+    }
+
+    pub fn last(&self) -> Option<std::time::Duration> {
+        (self.count > 0).then_some(self.last)
+    }
+
+    pub fn max(&self) -> Option<std::time::Duration> {
+        (self.count > 0).then_some(self.max)
+    }
+
+    pub fn mean(&self) -> Option<std::time::Duration> {
+        (self.count > 0).then(|| self.total.div_f64(self.count as f64))
+    }
+}
+
+#[derive(Debug)]
+struct Subscriber {
+    node_id: Option<NodeId>,
+    sender: std::sync::mpsc::Sender<EmergencyRecord>,
+}
+
+// Emergency consumer (cf. CiA 301). Feed it the emergency frames received with `on_frame`. It
+// keeps the last `capacity` emergencies of each node, and forwards them to the subscribers.
+#[derive(Debug)]
+pub struct EmergencyConsumer {
+    capacity: usize,
+    histories: std::collections::BTreeMap<u8, std::collections::VecDeque<EmergencyRecord>>,
+    subscribers: std::vec::Vec<Subscriber>,
+    latency: EmergencyLatency,
+}
+
+impl EmergencyConsumer {
+    pub const DEFAULT_CAPACITY: usize = 16;
+
+    pub fn new(capacity: usize) -> Self {
+        Self {
+            capacity,
+            histories: std::collections::BTreeMap::new(),
+            subscribers: std::vec::Vec::new(),
+            latency: EmergencyLatency::default(),
+        }
+    }
+
+    // Emergencies of all nodes. The subscription ends when the receiver is dropped.
+    pub fn subscribe(&mut self) -> std::sync::mpsc::Receiver<EmergencyRecord> {
+        self.add_subscriber(None)
+    }
+
+    pub fn subscribe_node(
+        &mut self,
+        node_id: NodeId,
+    ) -> std::sync::mpsc::Receiver<EmergencyRecord> {
+        self.add_subscriber(Some(node_id))
+    }
+
+    // The emergencies of `node_id`, from the oldest to the newest
+    pub fn history(&self, node_id: NodeId) -> impl Iterator<Item = &EmergencyRecord> {
+        self.histories
+            .get(&node_id.as_raw())
+            .into_iter()
+            .flat_map(|history| history.iter())
+    }
+
+    // The last emergency of `node_id`, unless it has been reset since
+    pub fn active(&self, node_id: NodeId) -> Option<&EmergencyRecord> {
+        self.history(node_id)
+            .last()
+            .filter(|record| !record.is_reset())
+    }
+
+    pub fn clear(&mut self, node_id: NodeId) {
+        self.histories.remove(&node_id.as_raw());
+    }
+
+    pub fn on_frame(&mut self, frame: &EmergencyFrame, now: std::time::Instant) -> EmergencyRecord {
+        let record = EmergencyRecord {
+            frame: *frame,
+            class: ErrorClass::from_error_code(frame.error_code),
+            received_at: now,
+        };
+        if self.capacity > 0 {
+            let history = self.histories.entry(frame.node_id.as_raw()).or_default();
+            if history.len() == self.capacity {
+                history.pop_front();
+            }
+            history.push_back(record);
+        }
+        self.subscribers.retain(|subscriber| {
+            subscriber
+                .node_id
+                .is_some_and(|node_id| node_id != frame.node_id)
+                || subscriber.sender.send(record).is_ok()
+        });
+        record
+    }
+
+    // Fast path for a batch of frames read from the bus with their reception time: the
+    // emergencies are delivered first, so that fault reactions are not delayed by the
+    // processing of the other frames (e.g. a burst of PDOs), and removed from the batch. The
+    // other frames, including undecodable emergencies, are left in order for the normal path.
+    // The latency of each delivery is recorded in `latency()`.
+    pub fn take_emergencies(
+        &mut self,
+        frames: &mut std::vec::Vec<(std::time::Instant, RawFrame)>,
+    ) -> std::vec::Vec<EmergencyRecord> {
+        let mut records = std::vec::Vec::new();
+        frames.retain(|(received_at, frame)| {
+            if !matches!(
+                CommunicationObject::new(frame.id()),
+                Ok(CommunicationObject::Emergency(_))
+            ) {
+                return true;
+            }
+            match CanOpenFrame::decode(frame, DataLengthPolicy::Lenient) {
+                Ok(CanOpenFrame::EmergencyFrame(emergency)) => {
+                    records.push(self.on_frame(&emergency, *received_at));
+                    self.latency.record(received_at.elapsed());
+                    false
+                }
+                _ => true,
+            }
+        });
+        records
+    }
+
+    pub fn latency(&self) -> &EmergencyLatency {
+        &self.latency
+    }
+
+    fn add_subscriber(
+        &mut self,
+        node_id: Option<NodeId>,
+    ) -> std::sync::mpsc::Receiver<EmergencyRecord> {
+        let (sender, receiver) = std::sync::mpsc::channel();
+        self.subscribers.push(Subscriber { node_id, sender });
+        receiver
+    }
+}
+
+impl Default for EmergencyConsumer {
+    fn default() -> Self {
+        Self::new(Self::DEFAULT_CAPACITY)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn emergency(raw_id: u8, error_code: u16) -> EmergencyFrame {
+        EmergencyFrame::new(raw_id.try_into().unwrap(), error_code, 0x01.into())
+    }
+
+    #[test]
+    fn test_error_class() {
+        assert_eq!(ErrorClass::from_error_code(0x0000), ErrorClass::NoError);
+        assert_eq!(ErrorClass::from_error_code(0x1000), ErrorClass::Generic);
+        assert_eq!(ErrorClass::from_error_code(0x2310), ErrorClass::Current);
+        assert_eq!(ErrorClass::from_error_code(0x4210), ErrorClass::Temperature);
+        assert_eq!(ErrorClass::from_error_code(0x8130), ErrorClass::Monitoring);
+        assert_eq!(
+            ErrorClass::from_error_code(0xFF01),
+            ErrorClass::DeviceSpecific
+        );
+        assert_eq!(ErrorClass::from_error_code(0xA000), ErrorClass::Unknown);
+        assert_eq!(ErrorClass::DeviceHardware.to_string(), "device hardware");
+    }
+
+    #[test]
+    fn test_describe_error_code() {
+        assert_eq!(
+            describe_error_code(0x8130),
+            "life guard error or heartbeat error"
+        );
+        assert_eq!(describe_error_code(0x3210), "voltage");
+        assert_eq!(describe_error_code(0xFF00), "device specific");
+    }
+
+    #[test]
+    fn test_history() {
+        let now = std::time::Instant::now();
+        let node_id: NodeId = 2.try_into().unwrap();
+        let mut consumer = EmergencyConsumer::new(2);
+        assert_eq!(consumer.active(node_id), None);
+
+        let record = consumer.on_frame(&emergency(2, 0x2310), now);
+        assert_eq!(record.class, ErrorClass::Current);
+        assert_eq!(record.node_id(), node_id);
+        assert_eq!(consumer.active(node_id), Some(&record));
+        consumer.on_frame(&emergency(2, 0x4210), now);
+        consumer.on_frame(&emergency(2, 0x0000), now);
+        assert_eq!(consumer.active(node_id), None);
+        assert_eq!(
+            consumer
+                .history(node_id)
+                .map(|record| record.frame.error_code)
+                .collect::<Vec<_>>(),
+            vec![0x4210, 0x0000]
+        );
+        assert_eq!(consumer.history(3.try_into().unwrap()).count(), 0);
+
+        consumer.clear(node_id);
+        assert_eq!(consumer.history(node_id).count(), 0);
+    }
+
+    #[test]
+    fn test_subscribe() {
+        let now = std::time::Instant::now();
+        let mut consumer = EmergencyConsumer::default();
+        let all = consumer.subscribe();
+        let node = consumer.subscribe_node(3.try_into().unwrap());
+        let dropped = consumer.subscribe();
+        drop(dropped);
+
+        consumer.on_frame(&emergency(2, 0x5000), now);
+        consumer.on_frame(&emergency(3, 0x8120), now);
+        assert_eq!(
+            all.try_iter()
+                .map(|record| record.frame.error_code)
+                .collect::<Vec<_>>(),
+            vec![0x5000, 0x8120]
+        );
+        assert_eq!(
+            node.try_iter()
+                .map(|record| record.frame.error_code)
+                .collect::<Vec<_>>(),
+            vec![0x8120]
+        );
+        assert_eq!(consumer.subscribers.len(), 2);
+    }
+
+    #[test]
+    fn test_take_emergencies() {
+        let mut consumer = EmergencyConsumer::default();
+        let subscriber = consumer.subscribe();
+        assert_eq!(consumer.latency().mean(), None);
+
+        let received_at = std::time::Instant::now();
+        let pdo = RawFrame::new(0x182, &[0x01, 0x02]).unwrap();
+        let mut frames = vec![
+            (received_at, pdo),
+            (received_at, crate::frame::encode(&emergency(2, 0x2310))),
+            (received_at, pdo),
+            (received_at, RawFrame::new(0x083, &[0x00]).unwrap()),
+            (received_at, crate::frame::encode(&emergency(3, 0x4210))),
+        ];
+        let records = consumer.take_emergencies(&mut frames);
+        assert_eq!(
+            records
+                .iter()
+                .map(|record| record.frame.error_code)
+                .collect::<Vec<_>>(),
+            vec![0x2310, 0x4210]
+        );
+        assert_eq!(subscriber.try_iter().count(), 2);
+        assert_eq!(
+            frames,
+            vec![
+                (received_at, pdo),
+                (received_at, pdo),
+                (received_at, RawFrame::new(0x083, &[0x00]).unwrap()),
+            ]
+        );
+
+        let latency = consumer.latency();
+        assert_eq!(latency.count(), 2);
+        assert!(latency.last().unwrap() >= latency.mean().unwrap());
+        assert_eq!(latency.max(), latency.last());
+    }
+}
@@ -4,8 +4,10 @@
 pub enum Error {
     #[error("Invalid Node ID ({})", .0)]
     InvalidNodeId(u8),
+    #[error("Cannot parse \"{}\" as {}", .input, .data_type)]
+    ParseFailed { input: String, data_type: String },
     #[error("Invalid COB ID ({:03X})", .0)]
-    InvalidCobId(u16),
+    InvalidCobId(u32),
     #[error("Invalid NMT Command (0x{:02X})", .0)]
     InvalidNmtCommand(u8),
     #[error("Invalid NMT State(0x{:02X})", .0)]
@@ -14,10 +16,124 @@ pub enum Error {
     InvalidDataLength { length: usize, data_type: String },
     #[error("Invalid client command specifier ({})", .0)]
     InvalidClientCommandSpecifier(u8),
+    #[error("Invalid server command specifier ({})", .0)]
+    InvalidServerCommandSpecifier(u8),
+    #[error("SDO transfer aborted ({})", .0)]
+    SdoAborted(crate::sdo::SdoAbortCode),
+    #[error("SDO request to node {} timed out (0x{:04X}:{})", .node_id.as_raw(), .index, .sub_index)]
+    SdoTimeout {
+        node_id: crate::id::NodeId,
+        index: u16,
+        sub_index: u8,
+    },
+    #[error("SDO toggle bit not alternated")]
+    SdoToggleBitMismatch,
+    #[error("SDO response for another object (expected 0x{:04X}:{})", .index, .sub_index)]
+    SdoMultiplexerMismatch { index: u16, sub_index: u8 },
+    #[error("SDO CRC mismatch (0x{:04X} received, 0x{:04X} calculated)", .expected, .actual)]
+    SdoCrcMismatch { expected: u16, actual: u16 },
+    #[error("Invalid SDO block size ({})", .0)]
+    InvalidSdoBlockSize(u8),
+    #[error("Invalid SDO sequence number ({})", .0)]
+    InvalidSdoSequenceNumber(u8),
+    #[error("SDO data size mismatch ({} bytes expected, {} bytes transferred)", .expected, .actual)]
+    SdoSizeMismatch { expected: usize, actual: usize },
+    #[error("SDO data exceeds the size limit ({} bytes)", .0)]
+    SdoSizeLimitExceeded(usize),
+    #[error("Invalid string (not UTF-8)")]
+    InvalidString,
+    #[error("Invalid data type (0x{:04X})", .0)]
+    InvalidDataType(u16),
+    #[error("Data type mismatch ({:?} expected, {:?} given)", .expected, .actual)]
+    DataTypeMismatch {
+        expected: crate::od::DataType,
+        actual: crate::od::DataType,
+    },
+    #[error("Invalid number of sub-indices ({})", .0)]
+    InvalidSubIndexCount(usize),
+    #[error("Object access failed ({})", .0)]
+    ObjectAccessFailed(crate::sdo::SdoAbortCode),
+    #[error("Invalid DCF at line {} ({})", .line, .message)]
+    InvalidDcf { line: usize, message: String },
+    #[error("Invalid PDO number ({})", .0)]
+    InvalidPdoNumber(u8),
+    #[error("Invalid counter field ({} bits at bit {})", .bit_length, .bit_offset)]
+    InvalidCounterField { bit_offset: u8, bit_length: u8 },
+    #[error("COB ID {:03X} is already used by TPDO{} of node {}", .cob_id, .pdo, .node_id.as_raw())]
+    PdoCobIdCollision {
+        cob_id: u16,
+        node_id: crate::id::NodeId,
+        pdo: u16,
+    },
+    #[error("Invalid LSS command specifier (0x{:02X})", .0)]
+    InvalidLssCommandSpecifier(u8),
+    #[error("Invalid LSS mode (0x{:02X})", .0)]
+    InvalidLssMode(u8),
     #[error("CAN-FD is not supported")]
     CanFdNotSupported,
+    #[error("CAN driver failed ({})", .0)]
+    DriverFailed(String),
+    #[error("Invalid interface name ({:?})", .0)]
+    InvalidInterfaceName(String),
+    #[error("Interface {} is already leased by another process", .0)]
+    InterfaceAlreadyLeased(String),
+    #[error("Failed to lease interface {} ({:?})", .interface_name, .kind)]
+    InterfaceLeaseFailed {
+        interface_name: String,
+        kind: std::io::ErrorKind,
+    },
+    #[error("Failed to query interface {} ({})", .interface_name, .message)]
+    InterfaceQueryFailed {
+        interface_name: String,
+        message: String,
+    },
+    #[error("Failed to set or get socket option {} ({})", .option, .message)]
+    SocketOptionFailed { option: String, message: String },
+    #[error("Interface {} does not match the expected configuration ({})", .interface_name, .message)]
+    InterfaceMismatch {
+        interface_name: String,
+        message: String,
+    },
+    #[error("Undecodable frame (COB-ID 0x{:03X}, data {:02X?}): {}", .cob_id, .data, .reason)]
+    UndecodableFrame {
+        cob_id: u32,
+        data: Vec<u8>,
+        reason: Box<Error>,
+    },
+    #[error("Frame from unexpected node {}", .0.as_raw())]
+    UnexpectedNodeId(crate::id::NodeId),
+    #[error("Invalid synchronous counter overflow value ({})", .0)]
+    InvalidSyncCounterOverflow(u8),
+    #[error("Invalid CAM number ({})", .0)]
+    InvalidCamNumber(u8),
+    #[error("Motion failed (statusword 0x{:04X})", .0)]
+    MotionFailed(u16),
+    // Only produced by the soak test harness
+    #[cfg(any(test, feature = "test-util"))]
+    #[error("Soak test invariant violated at action {}: {}", .action, .message)]
+    SoakInvariantViolated { action: u64, message: String },
+    // Only produced by the mock bus
+    #[cfg(any(test, feature = "test-util"))]
+    #[error("Mock expectation failed: {}", .0)]
+    MockExpectationFailed(String),
+    #[error("Cannot record frames ({})", .0)]
+    RecordFailed(String),
+    #[error("Operation cancelled")]
+    Cancelled,
+    #[error("Time is out of the representable range")]
+    TimeOutOfRange,
     #[error("Not implemented")]
     NotImplemented,
 }
 
+impl Error {
+    /// Returns the underlying error of an `UndecodableFrame`, or the error itself otherwise.
+    pub fn reason(&self) -> &Error {
+        match self {
+            Error::UndecodableFrame { reason, .. } => reason,
+            _ => self,
+        }
+    }
+}
+
 pub type Result<T> = std::result::Result<T, Error>;
@@ -1,32 +1,184 @@
+use crate::error::{Error, Result};
 use crate::id::{CommunicationObject, NodeId};
 
 pub trait ConvertibleFrame {
     fn communication_object(&self) -> CommunicationObject;
     fn frame_data(&self) -> std::vec::Vec<u8>;
+
+    /// Writes the frame data into `buf` and returns its length.
+    ///
+    /// Frame types on the hot path (e.g. PDO and SYNC) override this to avoid allocating.
+    fn write_frame_data(&self, buf: &mut [u8; 8]) -> usize {
+        let data = self.frame_data();
+        buf[..data.len()].copy_from_slice(&data);
+        data.len()
+    }
+}
+
+#[derive(Copy, Clone, Debug, PartialEq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub enum Direction {
+    /// Transmitted by the node
+    Tx,
+    /// Received by the node
+    Rx,
+}
+
+impl std::fmt::Display for Direction {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            Self::Tx => write!(f, "Tx"),
+            Self::Rx => write!(f, "Rx"),
+        }
+    }
+}
+
+// Space-separated hex bytes, e.g. "0F 00 E8 03"
+pub(crate) fn write_bytes(f: &mut std::fmt::Formatter<'_>, bytes: &[u8]) -> std::fmt::Result {
+    for (i, byte) in bytes.iter().enumerate() {
+        if i > 0 {
+            write!(f, " ")?;
+        }
+        write!(f, "{byte:02X}")?;
+    }
+    Ok(())
+}
+
+#[derive(Copy, Clone, Debug, Default, PartialEq)]
+pub enum DataLengthPolicy {
+    /// The data length must match the size defined for the frame type.
+    #[default]
+    Strict,
+    /// Any data length covering the decoded fields is accepted and the rest is ignored.
+    Lenient,
+}
+
+impl DataLengthPolicy {
+    pub(crate) fn check(
+        &self,
+        bytes: &[u8],
+        frame_data_size: usize,
+        required_size: usize,
+        data_type: &str,
+    ) -> Result<()> {
+        let valid = match self {
+            Self::Strict => bytes.len() == frame_data_size,
+            Self::Lenient => bytes.len() >= required_size,
+        };
+        if valid {
+            Ok(())
+        } else {
+            Err(Error::InvalidDataLength {
+                length: bytes.len(),
+                data_type: data_type.to_owned(),
+            })
+        }
+    }
 }
 
 mod nmt_node_control;
 pub use nmt_node_control::{NmtCommand, NmtNodeControlAddress, NmtNodeControlFrame};
 
 mod sync;
-pub use sync::SyncFrame;
+pub use sync::{SyncCobId, SyncFrame};
 
 mod emergency;
 pub use emergency::EmergencyFrame;
 
+mod time_stamp;
+pub use time_stamp::TimeStampFrame;
+
 pub(crate) mod sdo;
 pub use sdo::SdoFrame;
 
+mod pdo;
+pub use pdo::PdoFrame;
+
+mod lss;
+pub use lss::{LssFrame, LssIdentityField, LssMode, LssRequest, LssResponse};
+
 mod nmt_node_monitoring;
 pub use nmt_node_monitoring::{NmtNodeMonitoringFrame, NmtState};
 
+mod remote;
+pub use remote::RemoteFrame;
+
+mod raw;
+pub use raw::{encode, RawFrame};
+
 #[derive(Debug, PartialEq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
 pub enum CanOpenFrame {
     NmtNodeControlFrame(NmtNodeControlFrame),
     SyncFrame(SyncFrame),
     EmergencyFrame(EmergencyFrame),
+    TimeStampFrame(TimeStampFrame),
     SdoFrame(SdoFrame),
+    PdoFrame(PdoFrame),
     NmtNodeMonitoringFrame(NmtNodeMonitoringFrame),
+    LssFrame(LssFrame),
+    RemoteFrame(RemoteFrame),
+}
+
+impl ConvertibleFrame for CanOpenFrame {
+    fn communication_object(&self) -> CommunicationObject {
+        match self {
+            Self::NmtNodeControlFrame(frame) => frame.communication_object(),
+            Self::SyncFrame(frame) => frame.communication_object(),
+            Self::EmergencyFrame(frame) => frame.communication_object(),
+            Self::TimeStampFrame(frame) => frame.communication_object(),
+            Self::SdoFrame(frame) => frame.communication_object(),
+            Self::PdoFrame(frame) => frame.communication_object(),
+            Self::NmtNodeMonitoringFrame(frame) => frame.communication_object(),
+            Self::LssFrame(frame) => frame.communication_object(),
+            Self::RemoteFrame(frame) => frame.communication_object(),
+        }
+    }
+
+    fn frame_data(&self) -> std::vec::Vec<u8> {
+        match self {
+            Self::NmtNodeControlFrame(frame) => frame.frame_data(),
+            Self::SyncFrame(frame) => frame.frame_data(),
+            Self::EmergencyFrame(frame) => frame.frame_data(),
+            Self::TimeStampFrame(frame) => frame.frame_data(),
+            Self::SdoFrame(frame) => frame.frame_data(),
+            Self::PdoFrame(frame) => frame.frame_data(),
+            Self::NmtNodeMonitoringFrame(frame) => frame.frame_data(),
+            Self::LssFrame(frame) => frame.frame_data(),
+            Self::RemoteFrame(frame) => frame.frame_data(),
+        }
+    }
+
+    fn write_frame_data(&self, buf: &mut [u8; 8]) -> usize {
+        match self {
+            Self::NmtNodeControlFrame(frame) => frame.write_frame_data(buf),
+            Self::SyncFrame(frame) => frame.write_frame_data(buf),
+            Self::EmergencyFrame(frame) => frame.write_frame_data(buf),
+            Self::TimeStampFrame(frame) => frame.write_frame_data(buf),
+            Self::SdoFrame(frame) => frame.write_frame_data(buf),
+            Self::PdoFrame(frame) => frame.write_frame_data(buf),
+            Self::NmtNodeMonitoringFrame(frame) => frame.write_frame_data(buf),
+            Self::LssFrame(frame) => frame.write_frame_data(buf),
+            Self::RemoteFrame(frame) => frame.write_frame_data(buf),
+        }
+    }
+}
+
+// One line per frame for logs and CLI tools, e.g. "SDO Rx node 3: write 0x1017:00 = 0x03E8"
+impl std::fmt::Display for CanOpenFrame {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            Self::NmtNodeControlFrame(frame) => write!(f, "{frame}"),
+            Self::SyncFrame(frame) => write!(f, "{frame}"),
+            Self::EmergencyFrame(frame) => write!(f, "{frame}"),
+            Self::TimeStampFrame(frame) => write!(f, "{frame}"),
+            Self::SdoFrame(frame) => write!(f, "{frame}"),
+            Self::PdoFrame(frame) => write!(f, "{frame}"),
+            Self::NmtNodeMonitoringFrame(frame) => write!(f, "{frame}"),
+            Self::LssFrame(frame) => write!(f, "{frame}"),
+            Self::RemoteFrame(frame) => write!(f, "{frame}"),
+        }
+    }
 }
 
 impl CanOpenFrame {
@@ -48,4 +200,281 @@ impl CanOpenFrame {
             node_id, index, sub_index, data,
         ))
     }
+
+    pub fn new_pdo_frame(
+        direction: Direction,
+        number: u8,
+        node_id: NodeId,
+        data: std::vec::Vec<u8>,
+    ) -> Result<Self> {
+        Ok(Self::PdoFrame(PdoFrame::new(
+            direction, number, node_id, data,
+        )?))
+    }
+
+    pub fn new_with_bytes(
+        cob: CommunicationObject,
+        bytes: &[u8],
+        policy: DataLengthPolicy,
+    ) -> Result<Self> {
+        match cob {
+            CommunicationObject::NmtNodeControl => {
+                Ok(NmtNodeControlFrame::new_with_bytes(bytes, policy)?.into())
+            }
+            CommunicationObject::Sync => Ok(SyncFrame::new_with_bytes(bytes, policy)?.into()),
+            CommunicationObject::Emergency(node_id) => {
+                Ok(EmergencyFrame::new_with_bytes(node_id, bytes, policy)?.into())
+            }
+            CommunicationObject::TimeStamp => {
+                Ok(TimeStampFrame::new_with_bytes(bytes, policy)?.into())
+            }
+            CommunicationObject::TxSdo(node_id) => {
+                Ok(SdoFrame::new_with_bytes(Direction::Tx, node_id, bytes, policy)?.into())
+            }
+            CommunicationObject::RxSdo(node_id) => {
+                Ok(SdoFrame::new_with_bytes(Direction::Rx, node_id, bytes, policy)?.into())
+            }
+            CommunicationObject::TxPdo1(node_id) => {
+                Ok(PdoFrame::new(Direction::Tx, 1, node_id, bytes.to_owned())?.into())
+            }
+            CommunicationObject::RxPdo1(node_id) => {
+                Ok(PdoFrame::new(Direction::Rx, 1, node_id, bytes.to_owned())?.into())
+            }
+            CommunicationObject::TxPdo2(node_id) => {
+                Ok(PdoFrame::new(Direction::Tx, 2, node_id, bytes.to_owned())?.into())
+            }
+            CommunicationObject::RxPdo2(node_id) => {
+                Ok(PdoFrame::new(Direction::Rx, 2, node_id, bytes.to_owned())?.into())
+            }
+            CommunicationObject::TxPdo3(node_id) => {
+                Ok(PdoFrame::new(Direction::Tx, 3, node_id, bytes.to_owned())?.into())
+            }
+            CommunicationObject::RxPdo3(node_id) => {
+                Ok(PdoFrame::new(Direction::Rx, 3, node_id, bytes.to_owned())?.into())
+            }
+            CommunicationObject::TxPdo4(node_id) => {
+                Ok(PdoFrame::new(Direction::Tx, 4, node_id, bytes.to_owned())?.into())
+            }
+            CommunicationObject::RxPdo4(node_id) => {
+                Ok(PdoFrame::new(Direction::Rx, 4, node_id, bytes.to_owned())?.into())
+            }
+            CommunicationObject::NmtNodeMonitoring(node_id) => {
+                Ok(NmtNodeMonitoringFrame::new_with_bytes(node_id, bytes, policy)?.into())
+            }
+            CommunicationObject::TxLss => {
+                Ok(LssFrame::new_response_with_bytes(bytes, policy)?.into())
+            }
+            CommunicationObject::RxLss => {
+                Ok(LssFrame::new_request_with_bytes(bytes, policy)?.into())
+            }
+            _ => Err(Error::NotImplemented),
+        }
+    }
+
+    pub fn new_remote_with_data_length(cob: CommunicationObject, data_length: u8) -> Result<Self> {
+        Ok(RemoteFrame::new(cob, data_length)?.into())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_data_length_policy_check() {
+        let policy = DataLengthPolicy::Strict;
+        assert!(policy.check(&[0x00; 8], 8, 3, "Frame").is_ok());
+        assert_eq!(
+            policy.check(&[0x00; 3], 8, 3, "Frame"),
+            Err(Error::InvalidDataLength {
+                length: 3,
+                data_type: "Frame".to_owned()
+            })
+        );
+        assert!(policy.check(&[0x00; 2], 1, 1, "Frame").is_err());
+
+        let policy = DataLengthPolicy::Lenient;
+        assert!(policy.check(&[0x00; 8], 8, 3, "Frame").is_ok());
+        assert!(policy.check(&[0x00; 3], 8, 3, "Frame").is_ok());
+        assert!(policy.check(&[0x00; 2], 1, 1, "Frame").is_ok());
+        assert_eq!(
+            policy.check(&[0x00; 2], 8, 3, "Frame"),
+            Err(Error::InvalidDataLength {
+                length: 2,
+                data_type: "Frame".to_owned()
+            })
+        );
+    }
+
+    #[test]
+    fn test_new_with_bytes() {
+        assert_eq!(
+            CanOpenFrame::new_with_bytes(
+                CommunicationObject::NmtNodeControl,
+                &[0x01, 0x00],
+                DataLengthPolicy::Strict
+            ),
+            Ok(CanOpenFrame::new_nmt_node_control_frame(
+                NmtCommand::Operational,
+                NmtNodeControlAddress::AllNodes
+            ))
+        );
+        assert_eq!(
+            CanOpenFrame::new_with_bytes(CommunicationObject::Sync, &[], DataLengthPolicy::Strict),
+            Ok(CanOpenFrame::SyncFrame(SyncFrame::new()))
+        );
+        assert_eq!(
+            CanOpenFrame::new_with_bytes(CommunicationObject::Sync, &[5], DataLengthPolicy::Strict),
+            Ok(CanOpenFrame::SyncFrame(SyncFrame::new_with_counter(5)))
+        );
+        assert_eq!(
+            CanOpenFrame::new_with_bytes(
+                CommunicationObject::Sync,
+                &[5, 0],
+                DataLengthPolicy::Strict
+            ),
+            Err(Error::InvalidDataLength {
+                length: 2,
+                data_type: "SyncFrame".to_owned()
+            })
+        );
+        assert_eq!(
+            CanOpenFrame::new_with_bytes(
+                CommunicationObject::Sync,
+                &[5, 0],
+                DataLengthPolicy::Lenient
+            ),
+            Ok(CanOpenFrame::SyncFrame(SyncFrame::new_with_counter(5)))
+        );
+        assert_eq!(
+            CanOpenFrame::new_with_bytes(
+                CommunicationObject::NmtNodeMonitoring(1.try_into().unwrap()),
+                &[0x05, 0x00],
+                DataLengthPolicy::Lenient
+            ),
+            Ok(CanOpenFrame::NmtNodeMonitoringFrame(
+                NmtNodeMonitoringFrame::new(1.try_into().unwrap(), NmtState::Operational)
+            ))
+        );
+        assert_eq!(
+            CanOpenFrame::new_with_bytes(
+                CommunicationObject::TxPdo2(3.try_into().unwrap()),
+                &[0x01, 0x02, 0x03],
+                DataLengthPolicy::Strict
+            ),
+            CanOpenFrame::new_pdo_frame(
+                Direction::Tx,
+                2,
+                3.try_into().unwrap(),
+                vec![0x01, 0x02, 0x03]
+            )
+        );
+        assert_eq!(
+            CanOpenFrame::new_with_bytes(
+                CommunicationObject::TimeStamp,
+                &[0x80, 0xEE, 0x36, 0x00, 0x11, 0x3A],
+                DataLengthPolicy::Strict
+            ),
+            Ok(CanOpenFrame::TimeStampFrame(TimeStampFrame::new(
+                3_600_000, 14865
+            )))
+        );
+        assert_eq!(
+            CanOpenFrame::new_with_bytes(
+                CommunicationObject::RxLss,
+                &[0x04, 0x01, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00],
+                DataLengthPolicy::Strict
+            ),
+            Ok(CanOpenFrame::LssFrame(LssFrame::new_request(
+                LssRequest::SwitchStateGlobal(LssMode::Configuration)
+            )))
+        );
+        assert_eq!(
+            CanOpenFrame::new_with_bytes(
+                CommunicationObject::TxLss,
+                &[0x5E, 0x05, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00],
+                DataLengthPolicy::Strict
+            ),
+            Ok(CanOpenFrame::LssFrame(LssFrame::new_response(
+                LssResponse::InquireNodeId(5)
+            )))
+        );
+        assert_eq!(
+            CanOpenFrame::new_with_bytes(
+                CommunicationObject::GlobalFailsafeCommand,
+                &[],
+                DataLengthPolicy::Strict
+            ),
+            Err(Error::NotImplemented)
+        );
+    }
+
+    #[test]
+    fn test_display() {
+        let frame = CanOpenFrame::new_sdo_write_frame(
+            3.try_into().unwrap(),
+            0x1017,
+            0x00,
+            vec![0xE8, 0x03],
+        );
+        assert_eq!(frame.to_string(), "SDO Rx node 3: write 0x1017:00 = 0x03E8");
+        assert_eq!(
+            CanOpenFrame::SyncFrame(SyncFrame::new()).to_string(),
+            "SYNC"
+        );
+        assert_eq!(
+            CanOpenFrame::SyncFrame(SyncFrame::new_with_counter(3)).to_string(),
+            "SYNC counter 3"
+        );
+    }
+
+    #[cfg(feature = "serde")]
+    #[test]
+    fn test_serde() {
+        use crate::object::ErrorRegister;
+
+        let node_id: NodeId = 3.try_into().unwrap();
+        let frames: [CanOpenFrame; 9] = [
+            CanOpenFrame::new_nmt_node_control_frame(
+                NmtCommand::ResetNode,
+                NmtNodeControlAddress::Node(node_id),
+            ),
+            SyncFrame::new_with_counter(1).into(),
+            EmergencyFrame::new(node_id, 0x8130, ErrorRegister::default()).into(),
+            TimeStampFrame::new(3_600_000, 14865).into(),
+            SdoFrame::new_sdo_write_frame(node_id, 0x1017, 0x00, vec![0xE8, 0x03]).into(),
+            CanOpenFrame::new_pdo_frame(Direction::Tx, 1, node_id, vec![0x0F, 0x00]).unwrap(),
+            NmtNodeMonitoringFrame::new(node_id, NmtState::PreOperational).into(),
+            LssFrame::new_response(LssResponse::InquireNodeId(5)).into(),
+            RemoteFrame::new_node_guarding(node_id).into(),
+        ];
+        for frame in frames {
+            let json = serde_json::to_string(&frame).unwrap();
+            let decoded: CanOpenFrame = serde_json::from_str(&json).unwrap();
+            assert_eq!(decoded.encode(), frame.encode(), "{json}");
+            assert_eq!(decoded, frame);
+        }
+        assert_eq!(
+            serde_json::to_string(&CanOpenFrame::from(NmtNodeMonitoringFrame::new(
+                node_id,
+                NmtState::Operational
+            )))
+            .unwrap(),
+            r#"{"NmtNodeMonitoringFrame":{"node_id":"3","state":"Operational","toggle":false}}"#
+        );
+
+        // The checks of the constructors apply.
+        for json in [
+            r#"{"PdoFrame":{"direction":"Tx","number":5,"node_id":"3","data":[]}}"#,
+            r#"{"PdoFrame":{"direction":"Tx","number":1,"node_id":"3","data":[0,0,0,0,0,0,0,0,0]}}"#,
+            r#"{"RemoteFrame":{"communication_object":"TxPdo1(3)","data_length":9}}"#,
+            r#"{"SdoFrame":{"direction":"Rx","node_id":"3","ccs":"InitiateDownload","index":4119,"sub_index":0,"size":5,"expedited":true,"data":[0,0,0,0,0]}}"#,
+            r#"{"NmtNodeMonitoringFrame":{"node_id":"128","state":"Operational","toggle":false}}"#,
+        ] {
+            assert!(
+                serde_json::from_str::<CanOpenFrame>(json).is_err(),
+                "{json}"
+            );
+        }
+    }
 }
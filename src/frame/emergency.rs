@@ -1,37 +1,58 @@
-use crate::error::{Error, Result};
-use crate::frame::{CanOpenFrame, ConvertibleFrame};
+use crate::error::Result;
+use crate::frame::{CanOpenFrame, ConvertibleFrame, DataLengthPolicy};
 use crate::id::{CommunicationObject, NodeId};
+use crate::object::ErrorRegister;
 
 #[derive(Clone, Copy, Debug, PartialEq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
 pub struct EmergencyFrame {
     pub node_id: NodeId,
     pub error_code: u16,
-    pub error_register: u8,
+    pub error_register: ErrorRegister,
+    pub manufacturer_specific: [u8; 5],
 }
 
 impl EmergencyFrame {
     const FRAME_DATA_SIZE: usize = 8;
+    const REQUIRED_DATA_SIZE: usize = 3;
 
-    pub fn new(node_id: NodeId, error_code: u16, error_register: u8) -> Self {
+    pub fn new(node_id: NodeId, error_code: u16, error_register: ErrorRegister) -> Self {
         Self {
             node_id,
             error_code,
             error_register,
+            manufacturer_specific: [0x00; 5],
         }
     }
 
-    pub(crate) fn new_with_bytes(node_id: NodeId, bytes: &[u8]) -> Result<Self> {
-        if bytes.len() != Self::FRAME_DATA_SIZE {
-            return Err(Error::InvalidDataLength {
-                length: bytes.len(),
-                data_type: "EmergencyFrame".to_owned(),
-            });
+    pub fn with_manufacturer_specific(self, manufacturer_specific: [u8; 5]) -> Self {
+        Self {
+            manufacturer_specific,
+            ..self
         }
+    }
+
+    pub(crate) fn new_with_bytes(
+        node_id: NodeId,
+        bytes: &[u8],
+        policy: DataLengthPolicy,
+    ) -> Result<Self> {
+        policy.check(
+            bytes,
+            Self::FRAME_DATA_SIZE,
+            Self::REQUIRED_DATA_SIZE,
+            "EmergencyFrame",
+        )?;
+        // Lenient frames may omit the manufacturer-specific bytes.
+        let mut manufacturer_specific = [0x00; 5];
+        let available = bytes.len().clamp(3, Self::FRAME_DATA_SIZE) - 3;
+        manufacturer_specific[..available].copy_from_slice(&bytes[3..3 + available]);
         Ok(Self::new(
             node_id,
             u16::from_le_bytes(bytes[0..2].try_into().unwrap()),
-            bytes[2],
-        ))
+            bytes[2].into(),
+        )
+        .with_manufacturer_specific(manufacturer_specific))
     }
 }
 
@@ -49,13 +70,30 @@ impl ConvertibleFrame for EmergencyFrame {
     fn frame_data(&self) -> std::vec::Vec<u8> {
         let mut data = std::vec::Vec::with_capacity(Self::FRAME_DATA_SIZE);
         data.extend_from_slice(&self.error_code.to_le_bytes());
-        data.push(self.error_register);
-        data.resize(Self::FRAME_DATA_SIZE, 0x00);
+        data.push(self.error_register.as_u8());
+        data.extend_from_slice(&self.manufacturer_specific);
         assert_eq!(data.len(), Self::FRAME_DATA_SIZE);
         data
     }
 }
 
+// e.g. "EMCY node 3: error 0x8130 (communication)", with the manufacturer-specific bytes
+// unless all zero
+impl std::fmt::Display for EmergencyFrame {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(
+            f,
+            "EMCY node {}: error 0x{:04X} ({})",
+            self.node_id, self.error_code, self.error_register
+        )?;
+        if self.manufacturer_specific != [0x00; 5] {
+            write!(f, ", manufacturer-specific ")?;
+            crate::frame::write_bytes(f, &self.manufacturer_specific)?;
+        }
+        Ok(())
+    }
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
@@ -65,53 +103,99 @@ mod tests {
         assert_eq!(
             EmergencyFrame::new_with_bytes(
                 1.try_into().unwrap(),
-                &[0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00]
+                &[0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00],
+                DataLengthPolicy::Strict,
             ),
             Ok(EmergencyFrame {
                 node_id: 1.try_into().unwrap(),
                 error_code: 0x0000,
-                error_register: 0x00
+                error_register: 0x00.into(),
+                manufacturer_specific: [0x00; 5]
             })
         );
         assert_eq!(
             EmergencyFrame::new_with_bytes(
                 2.try_into().unwrap(),
-                &[0x00, 0x10, 0x01, 0x00, 0x00, 0x00, 0x00, 0x00]
+                &[0x00, 0x10, 0x01, 0x00, 0x00, 0x00, 0x00, 0x00],
+                DataLengthPolicy::Strict,
             ),
             Ok(EmergencyFrame {
                 node_id: 2.try_into().unwrap(),
                 error_code: 0x1000,
-                error_register: 0x01
+                error_register: 0x01.into(),
+                manufacturer_specific: [0x00; 5]
             })
         );
         assert_eq!(
             EmergencyFrame::new_with_bytes(
                 127.try_into().unwrap(),
-                &[0x34, 0x12, 0x56, 0x00, 0x00, 0x00, 0x00, 0x00]
+                &[0x34, 0x12, 0x56, 0x00, 0x00, 0x00, 0x00, 0x00],
+                DataLengthPolicy::Strict,
             ),
             Ok(EmergencyFrame {
                 node_id: 127.try_into().unwrap(),
                 error_code: 0x1234,
-                error_register: 0x56
+                error_register: 0x56.into(),
+                manufacturer_specific: [0x00; 5]
             })
         );
-        assert!(
-            EmergencyFrame::new_with_bytes(1.try_into().unwrap(), &[0x00, 0x00, 0x00]).is_err()
+        assert!(EmergencyFrame::new_with_bytes(
+            1.try_into().unwrap(),
+            &[0x00, 0x00, 0x00],
+            DataLengthPolicy::Strict
+        )
+        .is_err());
+    }
+
+    #[test]
+    fn test_from_node_id_bytes_lenient() {
+        assert_eq!(
+            EmergencyFrame::new_with_bytes(
+                2.try_into().unwrap(),
+                &[0x00, 0x10, 0x01],
+                DataLengthPolicy::Lenient,
+            ),
+            Ok(EmergencyFrame {
+                node_id: 2.try_into().unwrap(),
+                error_code: 0x1000,
+                error_register: 0x01.into(),
+                manufacturer_specific: [0x00; 5]
+            })
+        );
+        assert_eq!(
+            EmergencyFrame::new_with_bytes(
+                127.try_into().unwrap(),
+                &[0x34, 0x12, 0x56, 0x00, 0x00, 0x00, 0x00, 0x00],
+                DataLengthPolicy::Lenient,
+            ),
+            Ok(EmergencyFrame {
+                node_id: 127.try_into().unwrap(),
+                error_code: 0x1234,
+                error_register: 0x56.into(),
+                manufacturer_specific: [0x00; 5]
+            })
         );
+        assert!(EmergencyFrame::new_with_bytes(
+            1.try_into().unwrap(),
+            &[0x00, 0x10],
+            DataLengthPolicy::Lenient
+        )
+        .is_err());
     }
 
     #[test]
     fn test_communication_object() {
         assert_eq!(
-            EmergencyFrame::new(1.try_into().unwrap(), 0x0000, 0x00).communication_object(),
+            EmergencyFrame::new(1.try_into().unwrap(), 0x0000, 0x00.into()).communication_object(),
             CommunicationObject::Emergency(1.try_into().unwrap())
         );
         assert_eq!(
-            EmergencyFrame::new(2.try_into().unwrap(), 0x1000, 0x01).communication_object(),
+            EmergencyFrame::new(2.try_into().unwrap(), 0x1000, 0x01.into()).communication_object(),
             CommunicationObject::Emergency(2.try_into().unwrap())
         );
         assert_eq!(
-            EmergencyFrame::new(127.try_into().unwrap(), 0x1234, 0x56).communication_object(),
+            EmergencyFrame::new(127.try_into().unwrap(), 0x1234, 0x56.into())
+                .communication_object(),
             CommunicationObject::Emergency(127.try_into().unwrap())
         );
     }
@@ -120,18 +204,66 @@ mod tests {
     fn test_data() {
         let mut buf = [0u8; 8];
 
-        let data = EmergencyFrame::new(1.try_into().unwrap(), 0x0000, 0x00).frame_data();
+        let data = EmergencyFrame::new(1.try_into().unwrap(), 0x0000, 0x00.into()).frame_data();
         assert_eq!(data.len(), 8);
         assert_eq!(data, &[0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00]);
 
         buf.fill(0x00);
-        let data = EmergencyFrame::new(2.try_into().unwrap(), 0x1000, 0x01).frame_data();
+        let data = EmergencyFrame::new(2.try_into().unwrap(), 0x1000, 0x01.into()).frame_data();
         assert_eq!(data.len(), 8);
         assert_eq!(data, &[0x00, 0x10, 0x01, 0x00, 0x00, 0x00, 0x00, 0x00]);
 
         buf.fill(0x00);
-        let data = EmergencyFrame::new(127.try_into().unwrap(), 0x1234, 0x56).frame_data();
+        let data = EmergencyFrame::new(127.try_into().unwrap(), 0x1234, 0x56.into()).frame_data();
         assert_eq!(data.len(), 8);
         assert_eq!(data, &[0x34, 0x12, 0x56, 0x00, 0x00, 0x00, 0x00, 0x00]);
     }
+
+    #[test]
+    fn test_manufacturer_specific() {
+        let frame = EmergencyFrame::new(1.try_into().unwrap(), 0xFF00, 0x81.into())
+            .with_manufacturer_specific([0x01, 0x02, 0x03, 0x04, 0x05]);
+        assert_eq!(
+            frame.frame_data(),
+            &[0x00, 0xFF, 0x81, 0x01, 0x02, 0x03, 0x04, 0x05]
+        );
+        assert_eq!(
+            EmergencyFrame::new_with_bytes(
+                1.try_into().unwrap(),
+                &frame.frame_data(),
+                DataLengthPolicy::Strict
+            ),
+            Ok(frame)
+        );
+        assert_eq!(
+            EmergencyFrame::new_with_bytes(
+                1.try_into().unwrap(),
+                &[0x00, 0xFF, 0x81, 0x01, 0x02],
+                DataLengthPolicy::Lenient
+            )
+            .map(|frame| frame.manufacturer_specific),
+            Ok([0x01, 0x02, 0x00, 0x00, 0x00])
+        );
+    }
+
+    #[test]
+    fn test_display() {
+        let frame =
+            EmergencyFrame::new(3.try_into().unwrap(), 0x8130, ErrorRegister::from_u8(0x11));
+        assert_eq!(
+            frame.to_string(),
+            "EMCY node 3: error 0x8130 (generic, communication)"
+        );
+        assert_eq!(
+            frame
+                .with_manufacturer_specific([0x01, 0x02, 0x03, 0x04, 0x05])
+                .to_string(),
+            "EMCY node 3: error 0x8130 (generic, communication), manufacturer-specific 01 02 03 04 05"
+        );
+        assert_eq!(
+            EmergencyFrame::new(3.try_into().unwrap(), 0x0000, ErrorRegister::default())
+                .to_string(),
+            "EMCY node 3: error 0x0000 (no error)"
+        );
+    }
 }
@@ -0,0 +1,667 @@
+use crate::error::{Error, Result};
+use crate::frame::{CanOpenFrame, ConvertibleFrame, DataLengthPolicy};
+use crate::id::CommunicationObject;
+
+#[derive(Copy, Clone, Debug, PartialEq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub enum LssMode {
+    Waiting = 0x00,
+    Configuration = 0x01,
+}
+
+impl LssMode {
+    fn as_byte(&self) -> u8 {
+        self.to_owned() as u8
+    }
+
+    fn from_byte(byte: u8) -> Result<Self> {
+        match byte {
+            0x00 => Ok(Self::Waiting),
+            0x01 => Ok(Self::Configuration),
+            _ => Err(Error::InvalidLssMode(byte)),
+        }
+    }
+}
+
+#[derive(Copy, Clone, Debug, PartialEq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub enum LssIdentityField {
+    VendorId,
+    ProductCode,
+    RevisionNumber,
+    SerialNumber,
+}
+
+impl LssIdentityField {
+    fn offset(&self) -> u8 {
+        match self {
+            Self::VendorId => 0,
+            Self::ProductCode => 1,
+            Self::RevisionNumber => 2,
+            Self::SerialNumber => 3,
+        }
+    }
+
+    fn from_offset(offset: u8) -> Self {
+        match offset {
+            0 => Self::VendorId,
+            1 => Self::ProductCode,
+            2 => Self::RevisionNumber,
+            _ => Self::SerialNumber,
+        }
+    }
+}
+
+#[derive(Copy, Clone, Debug, PartialEq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub enum LssRequest {
+    SwitchStateGlobal(LssMode),
+    SwitchStateSelective(LssIdentityField, u32),
+    ConfigureNodeId(u8),
+    ConfigureBitTiming {
+        table_selector: u8,
+        table_index: u8,
+    },
+    ActivateBitTiming {
+        switch_delay: u16,
+    },
+    StoreConfiguration,
+    InquireIdentity(LssIdentityField),
+    InquireNodeId,
+    Fastscan {
+        id_number: u32,
+        bit_checked: u8,
+        lss_sub: u8,
+        lss_next: u8,
+    },
+}
+
+#[derive(Copy, Clone, Debug, PartialEq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub enum LssResponse {
+    SwitchStateSelective,
+    ConfigureNodeId {
+        error_code: u8,
+        specific_error_code: u8,
+    },
+    ConfigureBitTiming {
+        error_code: u8,
+        specific_error_code: u8,
+    },
+    StoreConfiguration {
+        error_code: u8,
+        specific_error_code: u8,
+    },
+    InquireIdentity(LssIdentityField, u32),
+    InquireNodeId(u8),
+    IdentifySlave,
+}
+
+#[derive(Copy, Clone, Debug, PartialEq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub enum LssFrame {
+    Request(LssRequest),
+    Response(LssResponse),
+}
+
+// cf. CiA 305 command specifiers
+const SWITCH_STATE_GLOBAL: u8 = 0x04;
+const CONFIGURE_NODE_ID: u8 = 0x11;
+const CONFIGURE_BIT_TIMING: u8 = 0x13;
+const ACTIVATE_BIT_TIMING: u8 = 0x15;
+const STORE_CONFIGURATION: u8 = 0x17;
+const SWITCH_STATE_SELECTIVE: u8 = 0x40;
+const SWITCH_STATE_SELECTIVE_RESPONSE: u8 = 0x44;
+const IDENTIFY_SLAVE: u8 = 0x4F;
+const FASTSCAN: u8 = 0x51;
+const INQUIRE_IDENTITY: u8 = 0x5A;
+const INQUIRE_NODE_ID: u8 = 0x5E;
+
+impl LssFrame {
+    const FRAME_DATA_SIZE: usize = 8;
+
+    pub fn new_request(request: LssRequest) -> Self {
+        Self::Request(request)
+    }
+
+    pub fn new_response(response: LssResponse) -> Self {
+        Self::Response(response)
+    }
+
+    pub(crate) fn new_request_with_bytes(bytes: &[u8], policy: DataLengthPolicy) -> Result<Self> {
+        let required_size = match bytes.first() {
+            Some(&FASTSCAN) => Self::FRAME_DATA_SIZE,
+            _ => 5,
+        };
+        Self::check_length(bytes, policy, required_size)?;
+        let request = match bytes[0] {
+            SWITCH_STATE_GLOBAL => LssRequest::SwitchStateGlobal(LssMode::from_byte(bytes[1])?),
+            cs @ SWITCH_STATE_SELECTIVE..=0x43 => LssRequest::SwitchStateSelective(
+                LssIdentityField::from_offset(cs - SWITCH_STATE_SELECTIVE),
+                u32::from_le_bytes(bytes[1..5].try_into().unwrap()),
+            ),
+            CONFIGURE_NODE_ID => LssRequest::ConfigureNodeId(bytes[1]),
+            CONFIGURE_BIT_TIMING => LssRequest::ConfigureBitTiming {
+                table_selector: bytes[1],
+                table_index: bytes[2],
+            },
+            ACTIVATE_BIT_TIMING => LssRequest::ActivateBitTiming {
+                switch_delay: u16::from_le_bytes(bytes[1..3].try_into().unwrap()),
+            },
+            STORE_CONFIGURATION => LssRequest::StoreConfiguration,
+            cs @ INQUIRE_IDENTITY..=0x5D => {
+                LssRequest::InquireIdentity(LssIdentityField::from_offset(cs - INQUIRE_IDENTITY))
+            }
+            INQUIRE_NODE_ID => LssRequest::InquireNodeId,
+            FASTSCAN => LssRequest::Fastscan {
+                id_number: u32::from_le_bytes(bytes[1..5].try_into().unwrap()),
+                bit_checked: bytes[5],
+                lss_sub: bytes[6],
+                lss_next: bytes[7],
+            },
+            cs => return Err(Error::InvalidLssCommandSpecifier(cs)),
+        };
+        Ok(Self::Request(request))
+    }
+
+    pub(crate) fn new_response_with_bytes(bytes: &[u8], policy: DataLengthPolicy) -> Result<Self> {
+        Self::check_length(bytes, policy, 5)?;
+        let response = match bytes[0] {
+            SWITCH_STATE_SELECTIVE_RESPONSE => LssResponse::SwitchStateSelective,
+            CONFIGURE_NODE_ID => LssResponse::ConfigureNodeId {
+                error_code: bytes[1],
+                specific_error_code: bytes[2],
+            },
+            CONFIGURE_BIT_TIMING => LssResponse::ConfigureBitTiming {
+                error_code: bytes[1],
+                specific_error_code: bytes[2],
+            },
+            STORE_CONFIGURATION => LssResponse::StoreConfiguration {
+                error_code: bytes[1],
+                specific_error_code: bytes[2],
+            },
+            cs @ INQUIRE_IDENTITY..=0x5D => LssResponse::InquireIdentity(
+                LssIdentityField::from_offset(cs - INQUIRE_IDENTITY),
+                u32::from_le_bytes(bytes[1..5].try_into().unwrap()),
+            ),
+            INQUIRE_NODE_ID => LssResponse::InquireNodeId(bytes[1]),
+            IDENTIFY_SLAVE => LssResponse::IdentifySlave,
+            cs => return Err(Error::InvalidLssCommandSpecifier(cs)),
+        };
+        Ok(Self::Response(response))
+    }
+
+    fn check_length(bytes: &[u8], policy: DataLengthPolicy, required_size: usize) -> Result<()> {
+        policy.check(bytes, Self::FRAME_DATA_SIZE, required_size, "LssFrame")
+    }
+}
+
+impl From<LssFrame> for CanOpenFrame {
+    fn from(frame: LssFrame) -> Self {
+        CanOpenFrame::LssFrame(frame)
+    }
+}
+
+impl ConvertibleFrame for LssFrame {
+    fn communication_object(&self) -> CommunicationObject {
+        match self {
+            Self::Request(_) => CommunicationObject::RxLss,
+            Self::Response(_) => CommunicationObject::TxLss,
+        }
+    }
+
+    fn frame_data(&self) -> std::vec::Vec<u8> {
+        let mut data = std::vec::Vec::with_capacity(Self::FRAME_DATA_SIZE);
+        match self {
+            Self::Request(request) => match request {
+                LssRequest::SwitchStateGlobal(mode) => {
+                    data.extend_from_slice(&[SWITCH_STATE_GLOBAL, mode.as_byte()]);
+                }
+                LssRequest::SwitchStateSelective(field, value) => {
+                    data.push(SWITCH_STATE_SELECTIVE + field.offset());
+                    data.extend_from_slice(&value.to_le_bytes());
+                }
+                LssRequest::ConfigureNodeId(node_id) => {
+                    data.extend_from_slice(&[CONFIGURE_NODE_ID, *node_id]);
+                }
+                LssRequest::ConfigureBitTiming {
+                    table_selector,
+                    table_index,
+                } => {
+                    data.extend_from_slice(&[CONFIGURE_BIT_TIMING, *table_selector, *table_index]);
+                }
+                LssRequest::ActivateBitTiming { switch_delay } => {
+                    data.push(ACTIVATE_BIT_TIMING);
+                    data.extend_from_slice(&switch_delay.to_le_bytes());
+                }
+                LssRequest::StoreConfiguration => data.push(STORE_CONFIGURATION),
+                LssRequest::InquireIdentity(field) => data.push(INQUIRE_IDENTITY + field.offset()),
+                LssRequest::InquireNodeId => data.push(INQUIRE_NODE_ID),
+                LssRequest::Fastscan {
+                    id_number,
+                    bit_checked,
+                    lss_sub,
+                    lss_next,
+                } => {
+                    data.push(FASTSCAN);
+                    data.extend_from_slice(&id_number.to_le_bytes());
+                    data.extend_from_slice(&[*bit_checked, *lss_sub, *lss_next]);
+                }
+            },
+            Self::Response(response) => match response {
+                LssResponse::SwitchStateSelective => data.push(SWITCH_STATE_SELECTIVE_RESPONSE),
+                LssResponse::ConfigureNodeId {
+                    error_code,
+                    specific_error_code,
+                } => {
+                    data.extend_from_slice(&[CONFIGURE_NODE_ID, *error_code, *specific_error_code]);
+                }
+                LssResponse::ConfigureBitTiming {
+                    error_code,
+                    specific_error_code,
+                } => {
+                    data.extend_from_slice(&[
+                        CONFIGURE_BIT_TIMING,
+                        *error_code,
+                        *specific_error_code,
+                    ]);
+                }
+                LssResponse::StoreConfiguration {
+                    error_code,
+                    specific_error_code,
+                } => {
+                    data.extend_from_slice(&[
+                        STORE_CONFIGURATION,
+                        *error_code,
+                        *specific_error_code,
+                    ]);
+                }
+                LssResponse::InquireIdentity(field, value) => {
+                    data.push(INQUIRE_IDENTITY + field.offset());
+                    data.extend_from_slice(&value.to_le_bytes());
+                }
+                LssResponse::InquireNodeId(node_id) => {
+                    data.extend_from_slice(&[INQUIRE_NODE_ID, *node_id]);
+                }
+                LssResponse::IdentifySlave => data.push(IDENTIFY_SLAVE),
+            },
+        }
+        data.resize(Self::FRAME_DATA_SIZE, 0x00);
+        assert_eq!(data.len(), Self::FRAME_DATA_SIZE);
+        data
+    }
+}
+
+impl std::fmt::Display for LssMode {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            Self::Waiting => write!(f, "waiting"),
+            Self::Configuration => write!(f, "configuration"),
+        }
+    }
+}
+
+impl std::fmt::Display for LssIdentityField {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        let field = match self {
+            Self::VendorId => "vendor ID",
+            Self::ProductCode => "product code",
+            Self::RevisionNumber => "revision number",
+            Self::SerialNumber => "serial number",
+        };
+        write!(f, "{field}")
+    }
+}
+
+impl std::fmt::Display for LssRequest {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            Self::SwitchStateGlobal(mode) => write!(f, "switch state global: {mode}"),
+            Self::SwitchStateSelective(field, value) => {
+                write!(f, "switch state selective: {field} = 0x{value:08X}")
+            }
+            Self::ConfigureNodeId(node_id) => write!(f, "configure node ID {node_id}"),
+            Self::ConfigureBitTiming {
+                table_selector,
+                table_index,
+            } => write!(
+                f,
+                "configure bit timing: table {table_selector}, index {table_index}"
+            ),
+            Self::ActivateBitTiming { switch_delay } => {
+                write!(f, "activate bit timing: switch delay {switch_delay} ms")
+            }
+            Self::StoreConfiguration => write!(f, "store configuration"),
+            Self::InquireIdentity(field) => write!(f, "inquire {field}"),
+            Self::InquireNodeId => write!(f, "inquire node ID"),
+            Self::Fastscan {
+                id_number,
+                bit_checked,
+                lss_sub,
+                lss_next,
+            } => write!(
+                f,
+                "fastscan: 0x{id_number:08X}, bit {bit_checked}, sub {lss_sub}, next {lss_next}"
+            ),
+        }
+    }
+}
+
+// The result of a configuration, e.g. "ok" or "error 255 (specific 1)"
+fn write_result(
+    f: &mut std::fmt::Formatter<'_>,
+    error_code: u8,
+    specific_error_code: u8,
+) -> std::fmt::Result {
+    match error_code {
+        0 => write!(f, "ok"),
+        // cf. CiA 305: the specific error code is only meaningful for error code 255.
+        0xFF => write!(f, "error {error_code} (specific {specific_error_code})"),
+        _ => write!(f, "error {error_code}"),
+    }
+}
+
+impl std::fmt::Display for LssResponse {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            Self::SwitchStateSelective => write!(f, "switch state selective"),
+            Self::ConfigureNodeId {
+                error_code,
+                specific_error_code,
+            } => {
+                write!(f, "configure node ID: ")?;
+                write_result(f, *error_code, *specific_error_code)
+            }
+            Self::ConfigureBitTiming {
+                error_code,
+                specific_error_code,
+            } => {
+                write!(f, "configure bit timing: ")?;
+                write_result(f, *error_code, *specific_error_code)
+            }
+            Self::StoreConfiguration {
+                error_code,
+                specific_error_code,
+            } => {
+                write!(f, "store configuration: ")?;
+                write_result(f, *error_code, *specific_error_code)
+            }
+            Self::InquireIdentity(field, value) => write!(f, "{field} = 0x{value:08X}"),
+            Self::InquireNodeId(node_id) => write!(f, "node ID {node_id}"),
+            Self::IdentifySlave => write!(f, "identify slave"),
+        }
+    }
+}
+
+// e.g. "LSS request: inquire node ID"
+impl std::fmt::Display for LssFrame {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            Self::Request(request) => write!(f, "LSS request: {request}"),
+            Self::Response(response) => write!(f, "LSS response: {response}"),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn request_cases() -> std::vec::Vec<(LssRequest, [u8; 8])> {
+        vec![
+            (
+                LssRequest::SwitchStateGlobal(LssMode::Waiting),
+                [0x04, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00],
+            ),
+            (
+                LssRequest::SwitchStateGlobal(LssMode::Configuration),
+                [0x04, 0x01, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00],
+            ),
+            (
+                LssRequest::SwitchStateSelective(LssIdentityField::VendorId, 0x0000_0123),
+                [0x40, 0x23, 0x01, 0x00, 0x00, 0x00, 0x00, 0x00],
+            ),
+            (
+                LssRequest::SwitchStateSelective(LssIdentityField::ProductCode, 0x1234_5678),
+                [0x41, 0x78, 0x56, 0x34, 0x12, 0x00, 0x00, 0x00],
+            ),
+            (
+                LssRequest::SwitchStateSelective(LssIdentityField::RevisionNumber, 0x0001_0002),
+                [0x42, 0x02, 0x00, 0x01, 0x00, 0x00, 0x00, 0x00],
+            ),
+            (
+                LssRequest::SwitchStateSelective(LssIdentityField::SerialNumber, 0xDEAD_BEEF),
+                [0x43, 0xEF, 0xBE, 0xAD, 0xDE, 0x00, 0x00, 0x00],
+            ),
+            (
+                LssRequest::ConfigureNodeId(5),
+                [0x11, 0x05, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00],
+            ),
+            (
+                LssRequest::ConfigureBitTiming {
+                    table_selector: 0,
+                    table_index: 4,
+                },
+                [0x13, 0x00, 0x04, 0x00, 0x00, 0x00, 0x00, 0x00],
+            ),
+            (
+                LssRequest::ActivateBitTiming { switch_delay: 1000 },
+                [0x15, 0xE8, 0x03, 0x00, 0x00, 0x00, 0x00, 0x00],
+            ),
+            (
+                LssRequest::StoreConfiguration,
+                [0x17, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00],
+            ),
+            (
+                LssRequest::InquireIdentity(LssIdentityField::VendorId),
+                [0x5A, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00],
+            ),
+            (
+                LssRequest::InquireIdentity(LssIdentityField::SerialNumber),
+                [0x5D, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00],
+            ),
+            (
+                LssRequest::InquireNodeId,
+                [0x5E, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00],
+            ),
+            (
+                LssRequest::Fastscan {
+                    id_number: 0,
+                    bit_checked: 0x80,
+                    lss_sub: 0,
+                    lss_next: 0,
+                },
+                [0x51, 0x00, 0x00, 0x00, 0x00, 0x80, 0x00, 0x00],
+            ),
+            (
+                LssRequest::Fastscan {
+                    id_number: 0x1234_5678,
+                    bit_checked: 31,
+                    lss_sub: 2,
+                    lss_next: 3,
+                },
+                [0x51, 0x78, 0x56, 0x34, 0x12, 0x1F, 0x02, 0x03],
+            ),
+        ]
+    }
+
+    fn response_cases() -> std::vec::Vec<(LssResponse, [u8; 8])> {
+        vec![
+            (
+                LssResponse::SwitchStateSelective,
+                [0x44, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00],
+            ),
+            (
+                LssResponse::ConfigureNodeId {
+                    error_code: 0,
+                    specific_error_code: 0,
+                },
+                [0x11, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00],
+            ),
+            (
+                LssResponse::ConfigureBitTiming {
+                    error_code: 1,
+                    specific_error_code: 0,
+                },
+                [0x13, 0x01, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00],
+            ),
+            (
+                LssResponse::StoreConfiguration {
+                    error_code: 0xFF,
+                    specific_error_code: 0x12,
+                },
+                [0x17, 0xFF, 0x12, 0x00, 0x00, 0x00, 0x00, 0x00],
+            ),
+            (
+                LssResponse::InquireIdentity(LssIdentityField::ProductCode, 0x1234_5678),
+                [0x5B, 0x78, 0x56, 0x34, 0x12, 0x00, 0x00, 0x00],
+            ),
+            (
+                LssResponse::InquireIdentity(LssIdentityField::RevisionNumber, 0x0001_0002),
+                [0x5C, 0x02, 0x00, 0x01, 0x00, 0x00, 0x00, 0x00],
+            ),
+            (
+                LssResponse::InquireNodeId(0x7F),
+                [0x5E, 0x7F, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00],
+            ),
+            (
+                LssResponse::IdentifySlave,
+                [0x4F, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00],
+            ),
+        ]
+    }
+
+    #[test]
+    fn test_lss_mode_from_byte() {
+        assert_eq!(LssMode::from_byte(0x00), Ok(LssMode::Waiting));
+        assert_eq!(LssMode::from_byte(0x01), Ok(LssMode::Configuration));
+        assert_eq!(LssMode::from_byte(0x02), Err(Error::InvalidLssMode(0x02)));
+    }
+
+    #[test]
+    fn test_request_from_bytes() {
+        for (request, bytes) in request_cases() {
+            assert_eq!(
+                LssFrame::new_request_with_bytes(&bytes, DataLengthPolicy::Strict),
+                Ok(LssFrame::Request(request))
+            );
+        }
+        assert_eq!(
+            LssFrame::new_request_with_bytes(
+                &[0x44, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00],
+                DataLengthPolicy::Strict
+            ),
+            Err(Error::InvalidLssCommandSpecifier(0x44))
+        );
+        assert_eq!(
+            LssFrame::new_request_with_bytes(
+                &[0x04, 0x02, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00],
+                DataLengthPolicy::Strict
+            ),
+            Err(Error::InvalidLssMode(0x02))
+        );
+        assert_eq!(
+            LssFrame::new_request_with_bytes(&[0x04, 0x01], DataLengthPolicy::Strict),
+            Err(Error::InvalidDataLength {
+                length: 2,
+                data_type: "LssFrame".to_owned()
+            })
+        );
+        assert_eq!(
+            LssFrame::new_request_with_bytes(
+                &[0x43, 0xEF, 0xBE, 0xAD, 0xDE],
+                DataLengthPolicy::Lenient
+            ),
+            Ok(LssFrame::Request(LssRequest::SwitchStateSelective(
+                LssIdentityField::SerialNumber,
+                0xDEAD_BEEF
+            )))
+        );
+        assert_eq!(
+            LssFrame::new_request_with_bytes(
+                &[0x51, 0x00, 0x00, 0x00, 0x00, 0x80],
+                DataLengthPolicy::Lenient
+            ),
+            Err(Error::InvalidDataLength {
+                length: 6,
+                data_type: "LssFrame".to_owned()
+            })
+        );
+    }
+
+    #[test]
+    fn test_response_from_bytes() {
+        for (response, bytes) in response_cases() {
+            assert_eq!(
+                LssFrame::new_response_with_bytes(&bytes, DataLengthPolicy::Strict),
+                Ok(LssFrame::Response(response))
+            );
+        }
+        assert_eq!(
+            LssFrame::new_response_with_bytes(
+                &[0x04, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00],
+                DataLengthPolicy::Strict
+            ),
+            Err(Error::InvalidLssCommandSpecifier(0x04))
+        );
+    }
+
+    #[test]
+    fn test_communication_object() {
+        assert_eq!(
+            LssFrame::new_request(LssRequest::InquireNodeId).communication_object(),
+            CommunicationObject::RxLss
+        );
+        assert_eq!(
+            LssFrame::new_response(LssResponse::InquireNodeId(1)).communication_object(),
+            CommunicationObject::TxLss
+        );
+    }
+
+    #[test]
+    fn test_set_data() {
+        for (request, bytes) in request_cases() {
+            assert_eq!(LssFrame::new_request(request).frame_data(), &bytes);
+        }
+        for (response, bytes) in response_cases() {
+            assert_eq!(LssFrame::new_response(response).frame_data(), &bytes);
+        }
+    }
+
+    #[test]
+    fn test_display() {
+        for (frame, expected) in [
+            (
+                LssFrame::new_request(LssRequest::SwitchStateGlobal(LssMode::Configuration)),
+                "LSS request: switch state global: configuration",
+            ),
+            (
+                LssFrame::new_request(LssRequest::SwitchStateSelective(
+                    LssIdentityField::VendorId,
+                    0x0000_0123,
+                )),
+                "LSS request: switch state selective: vendor ID = 0x00000123",
+            ),
+            (
+                LssFrame::new_response(LssResponse::ConfigureNodeId {
+                    error_code: 0,
+                    specific_error_code: 0,
+                }),
+                "LSS response: configure node ID: ok",
+            ),
+            (
+                LssFrame::new_response(LssResponse::StoreConfiguration {
+                    error_code: 0xFF,
+                    specific_error_code: 1,
+                }),
+                "LSS response: store configuration: error 255 (specific 1)",
+            ),
+            (
+                LssFrame::new_response(LssResponse::InquireNodeId(5)),
+                "LSS response: node ID 5",
+            ),
+        ] {
+            assert_eq!(frame.to_string(), expected);
+        }
+    }
+}
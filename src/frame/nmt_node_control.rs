@@ -1,8 +1,9 @@
 use crate::error::{Error, Result};
-use crate::frame::{CanOpenFrame, ConvertibleFrame};
+use crate::frame::{CanOpenFrame, ConvertibleFrame, DataLengthPolicy};
 use crate::id::{CommunicationObject, NodeId};
 
 #[derive(Copy, Clone, Debug, PartialEq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
 pub enum NmtCommand {
     Operational = 0x01,
     Stopped = 0x02,
@@ -29,6 +30,7 @@ impl NmtCommand {
 }
 
 #[derive(Copy, Clone, Debug, PartialEq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
 pub enum NmtNodeControlAddress {
     AllNodes,
     Node(NodeId),
@@ -51,6 +53,7 @@ impl NmtNodeControlAddress {
 }
 
 #[derive(Copy, Clone, Debug, PartialEq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
 pub struct NmtNodeControlFrame {
     pub command: NmtCommand,
     pub address: NmtNodeControlAddress,
@@ -63,13 +66,13 @@ impl NmtNodeControlFrame {
         Self { command, address }
     }
 
-    pub(crate) fn new_with_bytes(bytes: &[u8]) -> Result<Self> {
-        if bytes.len() != Self::FRAME_DATA_SIZE {
-            return Err(Error::InvalidDataLength {
-                length: bytes.len(),
-                data_type: "NmtNodeControlFrame".to_owned(),
-            });
-        }
+    pub(crate) fn new_with_bytes(bytes: &[u8], policy: DataLengthPolicy) -> Result<Self> {
+        policy.check(
+            bytes,
+            Self::FRAME_DATA_SIZE,
+            Self::FRAME_DATA_SIZE,
+            "NmtNodeControlFrame",
+        )?;
         Ok(Self::new(
             NmtCommand::from_byte(bytes[0])?,
             NmtNodeControlAddress::from_byte(bytes[1])?,
@@ -95,6 +98,41 @@ impl ConvertibleFrame for NmtNodeControlFrame {
         assert_eq!(data.len(), Self::FRAME_DATA_SIZE);
         data
     }
+
+    fn write_frame_data(&self, buf: &mut [u8; 8]) -> usize {
+        buf[0] = self.command.as_byte();
+        buf[1] = self.address.as_byte();
+        Self::FRAME_DATA_SIZE
+    }
+}
+
+impl std::fmt::Display for NmtCommand {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        let command = match self {
+            Self::Operational => "start",
+            Self::Stopped => "stop",
+            Self::PreOperational => "enter pre-operational",
+            Self::ResetNode => "reset node",
+            Self::ResetCommunication => "reset communication",
+        };
+        write!(f, "{command}")
+    }
+}
+
+impl std::fmt::Display for NmtNodeControlAddress {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            Self::AllNodes => write!(f, "all nodes"),
+            Self::Node(node_id) => write!(f, "node {node_id}"),
+        }
+    }
+}
+
+// e.g. "NMT node 3: start"
+impl std::fmt::Display for NmtNodeControlFrame {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(f, "NMT {}: {}", self.address, self.command)
+    }
 }
 
 #[cfg(test)]
@@ -165,7 +203,7 @@ mod tests {
 
     #[test]
     fn test_from_bytes() {
-        let frame = NmtNodeControlFrame::new_with_bytes(&[0x01, 0x00]);
+        let frame = NmtNodeControlFrame::new_with_bytes(&[0x01, 0x00], DataLengthPolicy::Strict);
         assert_eq!(
             frame,
             Ok(NmtNodeControlFrame {
@@ -173,7 +211,7 @@ mod tests {
                 address: NmtNodeControlAddress::AllNodes
             })
         );
-        let frame = NmtNodeControlFrame::new_with_bytes(&[0x02, 0x01]);
+        let frame = NmtNodeControlFrame::new_with_bytes(&[0x02, 0x01], DataLengthPolicy::Strict);
         assert_eq!(
             frame,
             Ok(NmtNodeControlFrame {
@@ -181,7 +219,7 @@ mod tests {
                 address: NmtNodeControlAddress::Node(1.try_into().unwrap()),
             })
         );
-        let frame = NmtNodeControlFrame::new_with_bytes(&[0x80, 0x02]);
+        let frame = NmtNodeControlFrame::new_with_bytes(&[0x80, 0x02], DataLengthPolicy::Strict);
         assert_eq!(
             frame,
             Ok(NmtNodeControlFrame {
@@ -189,7 +227,7 @@ mod tests {
                 address: NmtNodeControlAddress::Node(2.try_into().unwrap()),
             })
         );
-        let frame = NmtNodeControlFrame::new_with_bytes(&[0x81, 0x03]);
+        let frame = NmtNodeControlFrame::new_with_bytes(&[0x81, 0x03], DataLengthPolicy::Strict);
         assert_eq!(
             frame,
             Ok(NmtNodeControlFrame {
@@ -197,7 +235,7 @@ mod tests {
                 address: NmtNodeControlAddress::Node(3.try_into().unwrap()),
             })
         );
-        let frame = NmtNodeControlFrame::new_with_bytes(&[0x82, 0x7F]);
+        let frame = NmtNodeControlFrame::new_with_bytes(&[0x82, 0x7F], DataLengthPolicy::Strict);
         assert_eq!(
             frame,
             Ok(NmtNodeControlFrame {
@@ -205,18 +243,52 @@ mod tests {
                 address: NmtNodeControlAddress::Node(127.try_into().unwrap()),
             })
         );
-        let frame = NmtNodeControlFrame::new_with_bytes(&[0x00, 0x00]);
+        let frame = NmtNodeControlFrame::new_with_bytes(&[0x00, 0x00], DataLengthPolicy::Strict);
         assert_eq!(frame, Err(Error::InvalidNmtCommand(0)));
-        let frame = NmtNodeControlFrame::new_with_bytes(&[0x03, 0x00]);
+        let frame = NmtNodeControlFrame::new_with_bytes(&[0x03, 0x00], DataLengthPolicy::Strict);
         assert_eq!(frame, Err(Error::InvalidNmtCommand(3)));
-        let frame = NmtNodeControlFrame::new_with_bytes(&[0xFF, 0x00]);
+        let frame = NmtNodeControlFrame::new_with_bytes(&[0xFF, 0x00], DataLengthPolicy::Strict);
         assert_eq!(frame, Err(Error::InvalidNmtCommand(255)));
-        let frame = NmtNodeControlFrame::new_with_bytes(&[0x01, 0x80]);
+        let frame = NmtNodeControlFrame::new_with_bytes(&[0x01, 0x80], DataLengthPolicy::Strict);
         assert_eq!(frame, Err(Error::InvalidNodeId(128)));
-        let frame = NmtNodeControlFrame::new_with_bytes(&[0x01, 0xFF]);
+        let frame = NmtNodeControlFrame::new_with_bytes(&[0x01, 0xFF], DataLengthPolicy::Strict);
         assert_eq!(frame, Err(Error::InvalidNodeId(255)));
     }
 
+    #[test]
+    fn test_from_bytes_lenient() {
+        let frame = NmtNodeControlFrame::new_with_bytes(
+            &[0x81, 0x03, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00],
+            DataLengthPolicy::Lenient,
+        );
+        assert_eq!(
+            frame,
+            Ok(NmtNodeControlFrame {
+                command: NmtCommand::ResetNode,
+                address: NmtNodeControlAddress::Node(3.try_into().unwrap()),
+            })
+        );
+        let frame = NmtNodeControlFrame::new_with_bytes(
+            &[0x81, 0x03, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00],
+            DataLengthPolicy::Strict,
+        );
+        assert_eq!(
+            frame,
+            Err(Error::InvalidDataLength {
+                length: 8,
+                data_type: "NmtNodeControlFrame".to_owned()
+            })
+        );
+        let frame = NmtNodeControlFrame::new_with_bytes(&[0x81], DataLengthPolicy::Lenient);
+        assert_eq!(
+            frame,
+            Err(Error::InvalidDataLength {
+                length: 1,
+                data_type: "NmtNodeControlFrame".to_owned()
+            })
+        );
+    }
+
     #[test]
     fn test_communication_object() {
         let frame =
@@ -306,4 +378,24 @@ mod tests {
         assert_eq!(data.len(), 2);
         assert_eq!(data, &[0x82, 0x7F]);
     }
+
+    #[test]
+    fn test_display() {
+        assert_eq!(
+            NmtNodeControlFrame::new(
+                NmtCommand::Operational,
+                NmtNodeControlAddress::Node(3.try_into().unwrap())
+            )
+            .to_string(),
+            "NMT node 3: start"
+        );
+        assert_eq!(
+            NmtNodeControlFrame::new(
+                NmtCommand::ResetCommunication,
+                NmtNodeControlAddress::AllNodes
+            )
+            .to_string(),
+            "NMT all nodes: reset communication"
+        );
+    }
 }
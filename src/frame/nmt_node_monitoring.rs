@@ -1,8 +1,9 @@
 use crate::error::{Error, Result};
-use crate::frame::{CanOpenFrame, ConvertibleFrame};
+use crate::frame::{CanOpenFrame, ConvertibleFrame, DataLengthPolicy};
 use crate::id::{CommunicationObject, NodeId};
 
 #[derive(Clone, Copy, Debug, PartialEq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
 pub enum NmtState {
     BootUp = 0x00,
     Stopped = 0x04,
@@ -27,26 +28,58 @@ impl NmtState {
 }
 
 #[derive(Clone, Copy, Debug, PartialEq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
 pub struct NmtNodeMonitoringFrame {
     pub node_id: NodeId,
     pub state: NmtState,
+    // Alternates in the responses to node guarding requests, always false in heartbeats
+    pub toggle: bool,
 }
 
 impl NmtNodeMonitoringFrame {
     const FRAME_DATA_SIZE: usize = 1;
+    const TOGGLE_BIT: u8 = 0x80;
 
     pub fn new(node_id: NodeId, state: NmtState) -> Self {
-        Self { node_id, state }
+        Self {
+            node_id,
+            state,
+            toggle: false,
+        }
+    }
+
+    // A response to a node guarding request (cf. CiA 301)
+    pub fn new_node_guarding_response(node_id: NodeId, state: NmtState, toggle: bool) -> Self {
+        Self {
+            node_id,
+            state,
+            toggle,
+        }
+    }
+
+    fn as_byte(&self) -> u8 {
+        self.state.as_byte() | if self.toggle { Self::TOGGLE_BIT } else { 0 }
     }
 
-    pub(crate) fn new_with_bytes(node_id: NodeId, bytes: &[u8]) -> Result<Self> {
-        if bytes.len() != Self::FRAME_DATA_SIZE {
-            return Err(Error::InvalidDataLength {
-                length: bytes.len(),
-                data_type: "NmtNodeMonitoringFrame".to_owned(),
-            });
+    pub(crate) fn new_with_bytes(
+        node_id: NodeId,
+        bytes: &[u8],
+        policy: DataLengthPolicy,
+    ) -> Result<Self> {
+        policy.check(
+            bytes,
+            Self::FRAME_DATA_SIZE,
+            Self::FRAME_DATA_SIZE,
+            "NmtNodeMonitoringFrame",
+        )?;
+        let toggle = bytes[0] & Self::TOGGLE_BIT != 0;
+        let state = NmtState::from_byte(bytes[0] & !Self::TOGGLE_BIT)
+            .map_err(|_| Error::InvalidNmtState(bytes[0]))?;
+        // The boot-up message is not a response to node guarding.
+        if toggle && state == NmtState::BootUp {
+            return Err(Error::InvalidNmtState(bytes[0]));
         }
-        Ok(Self::new(node_id, NmtState::from_byte(bytes[0])?))
+        Ok(Self::new_node_guarding_response(node_id, state, toggle))
     }
 }
 
@@ -63,10 +96,39 @@ impl ConvertibleFrame for NmtNodeMonitoringFrame {
 
     fn frame_data(&self) -> std::vec::Vec<u8> {
         let mut data = std::vec::Vec::with_capacity(Self::FRAME_DATA_SIZE);
-        data.push(self.state.as_byte());
+        data.push(self.as_byte());
         assert_eq!(data.len(), Self::FRAME_DATA_SIZE);
         data
     }
+
+    fn write_frame_data(&self, buf: &mut [u8; 8]) -> usize {
+        buf[0] = self.as_byte();
+        Self::FRAME_DATA_SIZE
+    }
+}
+
+impl std::fmt::Display for NmtState {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        let state = match self {
+            Self::BootUp => "boot-up",
+            Self::Stopped => "stopped",
+            Self::Operational => "operational",
+            Self::PreOperational => "pre-operational",
+        };
+        write!(f, "{state}")
+    }
+}
+
+// e.g. "Heartbeat node 3: operational". Node guarding responses share the COB, and are only
+// told apart by a set toggle bit.
+impl std::fmt::Display for NmtNodeMonitoringFrame {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(f, "Heartbeat node {}: {}", self.node_id, self.state)?;
+        if self.toggle {
+            write!(f, " (toggle)")?;
+        }
+        Ok(())
+    }
 }
 
 #[cfg(test)]
@@ -99,48 +161,143 @@ mod tests {
     #[test]
     fn test_from_node_id_bytes() {
         assert_eq!(
-            NmtNodeMonitoringFrame::new_with_bytes(1.try_into().unwrap(), &[0x00]),
+            NmtNodeMonitoringFrame::new_with_bytes(
+                1.try_into().unwrap(),
+                &[0x00],
+                DataLengthPolicy::Strict
+            ),
             Ok(NmtNodeMonitoringFrame {
                 node_id: 1.try_into().unwrap(),
-                state: NmtState::BootUp
+                state: NmtState::BootUp,
+                toggle: false,
             })
         );
         assert_eq!(
-            NmtNodeMonitoringFrame::new_with_bytes(2.try_into().unwrap(), &[0x04]),
+            NmtNodeMonitoringFrame::new_with_bytes(
+                2.try_into().unwrap(),
+                &[0x04],
+                DataLengthPolicy::Strict
+            ),
             Ok(NmtNodeMonitoringFrame {
                 node_id: 2.try_into().unwrap(),
-                state: NmtState::Stopped
+                state: NmtState::Stopped,
+                toggle: false,
             })
         );
         assert_eq!(
-            NmtNodeMonitoringFrame::new_with_bytes(3.try_into().unwrap(), &[0x05]),
+            NmtNodeMonitoringFrame::new_with_bytes(
+                3.try_into().unwrap(),
+                &[0x05],
+                DataLengthPolicy::Strict
+            ),
             Ok(NmtNodeMonitoringFrame {
                 node_id: 3.try_into().unwrap(),
-                state: NmtState::Operational
+                state: NmtState::Operational,
+                toggle: false,
             })
         );
         assert_eq!(
-            NmtNodeMonitoringFrame::new_with_bytes(4.try_into().unwrap(), &[0x7F]),
+            NmtNodeMonitoringFrame::new_with_bytes(
+                4.try_into().unwrap(),
+                &[0x7F],
+                DataLengthPolicy::Strict
+            ),
             Ok(NmtNodeMonitoringFrame {
                 node_id: 4.try_into().unwrap(),
-                state: NmtState::PreOperational
+                state: NmtState::PreOperational,
+                toggle: false,
             })
         );
 
         assert_eq!(
-            NmtNodeMonitoringFrame::new_with_bytes(5.try_into().unwrap(), &[0x01]),
+            NmtNodeMonitoringFrame::new_with_bytes(
+                5.try_into().unwrap(),
+                &[0x01],
+                DataLengthPolicy::Strict
+            ),
             Err(Error::InvalidNmtState(0x01))
         );
         assert_eq!(
-            NmtNodeMonitoringFrame::new_with_bytes(6.try_into().unwrap(), &[0x06]),
+            NmtNodeMonitoringFrame::new_with_bytes(
+                6.try_into().unwrap(),
+                &[0x06],
+                DataLengthPolicy::Strict
+            ),
             Err(Error::InvalidNmtState(0x06))
         );
         assert_eq!(
-            NmtNodeMonitoringFrame::new_with_bytes(7.try_into().unwrap(), &[0x80]),
+            NmtNodeMonitoringFrame::new_with_bytes(
+                7.try_into().unwrap(),
+                &[0x80],
+                DataLengthPolicy::Strict
+            ),
             Err(Error::InvalidNmtState(0x80))
         );
     }
 
+    #[test]
+    fn test_node_guarding_response() {
+        let node_id = 1.try_into().unwrap();
+        assert_eq!(
+            NmtNodeMonitoringFrame::new_with_bytes(node_id, &[0x85], DataLengthPolicy::Strict),
+            Ok(NmtNodeMonitoringFrame::new_node_guarding_response(
+                node_id,
+                NmtState::Operational,
+                true
+            ))
+        );
+        assert_eq!(
+            NmtNodeMonitoringFrame::new_with_bytes(node_id, &[0xFF], DataLengthPolicy::Strict),
+            Ok(NmtNodeMonitoringFrame::new_node_guarding_response(
+                node_id,
+                NmtState::PreOperational,
+                true
+            ))
+        );
+        assert_eq!(
+            NmtNodeMonitoringFrame::new_with_bytes(node_id, &[0x86], DataLengthPolicy::Strict),
+            Err(Error::InvalidNmtState(0x86))
+        );
+        assert_eq!(
+            NmtNodeMonitoringFrame::new_node_guarding_response(node_id, NmtState::Stopped, true)
+                .frame_data(),
+            vec![0x84]
+        );
+    }
+
+    #[test]
+    fn test_from_node_id_bytes_lenient() {
+        assert_eq!(
+            NmtNodeMonitoringFrame::new_with_bytes(
+                1.try_into().unwrap(),
+                &[0x05, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00],
+                DataLengthPolicy::Lenient
+            ),
+            Ok(NmtNodeMonitoringFrame {
+                node_id: 1.try_into().unwrap(),
+                state: NmtState::Operational,
+                toggle: false,
+            })
+        );
+        assert_eq!(
+            NmtNodeMonitoringFrame::new_with_bytes(
+                1.try_into().unwrap(),
+                &[0x05, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00],
+                DataLengthPolicy::Strict
+            ),
+            Err(Error::InvalidDataLength {
+                length: 8,
+                data_type: "NmtNodeMonitoringFrame".to_owned()
+            })
+        );
+        assert!(NmtNodeMonitoringFrame::new_with_bytes(
+            1.try_into().unwrap(),
+            &[],
+            DataLengthPolicy::Lenient
+        )
+        .is_err());
+    }
+
     #[test]
     fn test_communication_object() {
         assert_eq!(
@@ -192,4 +349,22 @@ mod tests {
         assert_eq!(data.len(), 1);
         assert_eq!(data, &[0x7F]);
     }
+
+    #[test]
+    fn test_display() {
+        let node_id: NodeId = 3.try_into().unwrap();
+        assert_eq!(
+            NmtNodeMonitoringFrame::new(node_id, NmtState::BootUp).to_string(),
+            "Heartbeat node 3: boot-up"
+        );
+        assert_eq!(
+            NmtNodeMonitoringFrame::new_node_guarding_response(
+                node_id,
+                NmtState::PreOperational,
+                true
+            )
+            .to_string(),
+            "Heartbeat node 3: pre-operational (toggle)"
+        );
+    }
 }
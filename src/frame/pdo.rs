@@ -0,0 +1,282 @@
+use crate::error::{Error, Result};
+use crate::frame::{CanOpenFrame, ConvertibleFrame, Direction};
+use crate::id::{CommunicationObject, NodeId};
+
+#[derive(Clone, Debug, PartialEq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+#[cfg_attr(feature = "serde", serde(try_from = "PdoFrameFields"))]
+pub struct PdoFrame {
+    pub(crate) direction: Direction,
+    pub(crate) number: u8,
+    pub(crate) node_id: NodeId,
+    pub(crate) data: std::vec::Vec<u8>,
+}
+
+impl PdoFrame {
+    const MAX_FRAME_DATA_SIZE: usize = 8;
+
+    pub fn new(
+        direction: Direction,
+        number: u8,
+        node_id: NodeId,
+        data: std::vec::Vec<u8>,
+    ) -> Result<Self> {
+        if !(1..=4).contains(&number) {
+            return Err(Error::InvalidPdoNumber(number));
+        }
+        if data.len() > Self::MAX_FRAME_DATA_SIZE {
+            return Err(Error::InvalidDataLength {
+                length: data.len(),
+                data_type: "PdoFrame".to_owned(),
+            });
+        }
+        Ok(Self {
+            direction,
+            number,
+            node_id,
+            data,
+        })
+    }
+
+    // Builds a PDO with a configured COB-ID, e.g. from 0x1800:01. Only the COB-IDs of the
+    // predefined connection set (PDO 1-4 of node 1-127) can be represented.
+    pub fn new_with_cob_id(cob_id: u16, data: std::vec::Vec<u8>) -> Result<Self> {
+        let (direction, number, node_id) = match CommunicationObject::new(cob_id) {
+            Ok(CommunicationObject::TxPdo1(node_id)) => (Direction::Tx, 1, node_id),
+            Ok(CommunicationObject::RxPdo1(node_id)) => (Direction::Rx, 1, node_id),
+            Ok(CommunicationObject::TxPdo2(node_id)) => (Direction::Tx, 2, node_id),
+            Ok(CommunicationObject::RxPdo2(node_id)) => (Direction::Rx, 2, node_id),
+            Ok(CommunicationObject::TxPdo3(node_id)) => (Direction::Tx, 3, node_id),
+            Ok(CommunicationObject::RxPdo3(node_id)) => (Direction::Rx, 3, node_id),
+            Ok(CommunicationObject::TxPdo4(node_id)) => (Direction::Tx, 4, node_id),
+            Ok(CommunicationObject::RxPdo4(node_id)) => (Direction::Rx, 4, node_id),
+            _ => return Err(Error::InvalidCobId(cob_id.into())),
+        };
+        Self::new(direction, number, node_id, data)
+    }
+
+    pub fn direction(&self) -> Direction {
+        self.direction
+    }
+
+    pub fn number(&self) -> u8 {
+        self.number
+    }
+
+    pub fn node_id(&self) -> NodeId {
+        self.node_id
+    }
+
+    pub fn data(&self) -> &[u8] {
+        &self.data
+    }
+}
+
+// Deserialized through `PdoFrame::new` to check the number and the data length
+#[cfg(feature = "serde")]
+#[derive(serde::Deserialize)]
+struct PdoFrameFields {
+    direction: Direction,
+    number: u8,
+    node_id: NodeId,
+    data: std::vec::Vec<u8>,
+}
+
+#[cfg(feature = "serde")]
+impl TryFrom<PdoFrameFields> for PdoFrame {
+    type Error = Error;
+    fn try_from(fields: PdoFrameFields) -> Result<Self> {
+        Self::new(fields.direction, fields.number, fields.node_id, fields.data)
+    }
+}
+
+impl From<PdoFrame> for CanOpenFrame {
+    fn from(frame: PdoFrame) -> Self {
+        CanOpenFrame::PdoFrame(frame)
+    }
+}
+
+impl ConvertibleFrame for PdoFrame {
+    fn communication_object(&self) -> CommunicationObject {
+        match (self.direction, self.number) {
+            (Direction::Tx, 1) => CommunicationObject::TxPdo1(self.node_id),
+            (Direction::Rx, 1) => CommunicationObject::RxPdo1(self.node_id),
+            (Direction::Tx, 2) => CommunicationObject::TxPdo2(self.node_id),
+            (Direction::Rx, 2) => CommunicationObject::RxPdo2(self.node_id),
+            (Direction::Tx, 3) => CommunicationObject::TxPdo3(self.node_id),
+            (Direction::Rx, 3) => CommunicationObject::RxPdo3(self.node_id),
+            (Direction::Tx, 4) => CommunicationObject::TxPdo4(self.node_id),
+            (Direction::Rx, 4) => CommunicationObject::RxPdo4(self.node_id),
+            _ => unreachable!("PDO number is validated on construction"),
+        }
+    }
+
+    fn frame_data(&self) -> std::vec::Vec<u8> {
+        assert!(self.data.len() <= Self::MAX_FRAME_DATA_SIZE);
+        self.data.clone()
+    }
+
+    fn write_frame_data(&self, buf: &mut [u8; 8]) -> usize {
+        buf[..self.data.len()].copy_from_slice(&self.data);
+        self.data.len()
+    }
+}
+
+// e.g. "RPDO1 node 3: 0F 00 E8 03", named from the point of view of the node
+impl std::fmt::Display for PdoFrame {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        let kind = match self.direction {
+            Direction::Tx => "TPDO",
+            Direction::Rx => "RPDO",
+        };
+        write!(f, "{kind}{} node {}:", self.number, self.node_id)?;
+        if !self.data.is_empty() {
+            write!(f, " ")?;
+            crate::frame::write_bytes(f, &self.data)?;
+        }
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_new() {
+        assert_eq!(
+            PdoFrame::new(Direction::Tx, 1, 1.try_into().unwrap(), vec![0x01, 0x02]),
+            Ok(PdoFrame {
+                direction: Direction::Tx,
+                number: 1,
+                node_id: 1.try_into().unwrap(),
+                data: vec![0x01, 0x02],
+            })
+        );
+        assert_eq!(
+            PdoFrame::new(Direction::Rx, 4, 127.try_into().unwrap(), vec![0x00; 8]),
+            Ok(PdoFrame {
+                direction: Direction::Rx,
+                number: 4,
+                node_id: 127.try_into().unwrap(),
+                data: vec![0x00; 8],
+            })
+        );
+        assert_eq!(
+            PdoFrame::new(Direction::Rx, 0, 1.try_into().unwrap(), vec![]),
+            Err(Error::InvalidPdoNumber(0))
+        );
+        assert_eq!(
+            PdoFrame::new(Direction::Rx, 5, 1.try_into().unwrap(), vec![]),
+            Err(Error::InvalidPdoNumber(5))
+        );
+        assert_eq!(
+            PdoFrame::new(Direction::Tx, 1, 1.try_into().unwrap(), vec![0x00; 9]),
+            Err(Error::InvalidDataLength {
+                length: 9,
+                data_type: "PdoFrame".to_owned()
+            })
+        );
+    }
+
+    #[test]
+    fn test_new_with_cob_id() {
+        assert_eq!(
+            PdoFrame::new_with_cob_id(0x285, vec![0x01]),
+            PdoFrame::new(Direction::Tx, 2, 5.try_into().unwrap(), vec![0x01])
+        );
+        assert_eq!(
+            PdoFrame::new_with_cob_id(0x201, vec![]),
+            PdoFrame::new(Direction::Rx, 1, 1.try_into().unwrap(), vec![])
+        );
+        assert_eq!(
+            PdoFrame::new_with_cob_id(0x180, vec![]),
+            Err(Error::InvalidCobId(0x180))
+        );
+        assert_eq!(
+            PdoFrame::new_with_cob_id(0x605, vec![]),
+            Err(Error::InvalidCobId(0x605))
+        );
+    }
+
+    #[test]
+    fn test_accessors() {
+        let frame = PdoFrame::new(Direction::Rx, 3, 5.try_into().unwrap(), vec![0xAB]).unwrap();
+        assert_eq!(frame.direction(), Direction::Rx);
+        assert_eq!(frame.number(), 3);
+        assert_eq!(frame.node_id(), 5.try_into().unwrap());
+        assert_eq!(frame.data(), &[0xAB]);
+    }
+
+    #[test]
+    fn test_communication_object() {
+        let node_id: NodeId = 10.try_into().unwrap();
+        let cases = [
+            (Direction::Tx, 1, CommunicationObject::TxPdo1(node_id)),
+            (Direction::Rx, 1, CommunicationObject::RxPdo1(node_id)),
+            (Direction::Tx, 2, CommunicationObject::TxPdo2(node_id)),
+            (Direction::Rx, 2, CommunicationObject::RxPdo2(node_id)),
+            (Direction::Tx, 3, CommunicationObject::TxPdo3(node_id)),
+            (Direction::Rx, 3, CommunicationObject::RxPdo3(node_id)),
+            (Direction::Tx, 4, CommunicationObject::TxPdo4(node_id)),
+            (Direction::Rx, 4, CommunicationObject::RxPdo4(node_id)),
+        ];
+        for (direction, number, cob) in cases {
+            assert_eq!(
+                PdoFrame::new(direction, number, node_id, vec![])
+                    .unwrap()
+                    .communication_object(),
+                cob
+            );
+        }
+    }
+
+    #[test]
+    fn test_set_data() {
+        let data = PdoFrame::new(Direction::Tx, 1, 1.try_into().unwrap(), vec![])
+            .unwrap()
+            .frame_data();
+        assert_eq!(data, &[] as &[u8]);
+
+        let data = PdoFrame::new(
+            Direction::Rx,
+            2,
+            1.try_into().unwrap(),
+            vec![0x0F, 0x00, 0xE8, 0x03, 0x00, 0x00],
+        )
+        .unwrap()
+        .frame_data();
+        assert_eq!(data, &[0x0F, 0x00, 0xE8, 0x03, 0x00, 0x00]);
+    }
+
+    #[test]
+    fn test_write_frame_data() {
+        let mut buf = [0xFF; 8];
+        let frame = PdoFrame::new(
+            Direction::Rx,
+            2,
+            1.try_into().unwrap(),
+            vec![0x0F, 0x00, 0xE8, 0x03],
+        )
+        .unwrap();
+        assert_eq!(frame.write_frame_data(&mut buf), 4);
+        assert_eq!(&buf[..4], &[0x0F, 0x00, 0xE8, 0x03]);
+    }
+
+    #[test]
+    fn test_display() {
+        let node_id: NodeId = 3.try_into().unwrap();
+        assert_eq!(
+            PdoFrame::new(Direction::Rx, 1, node_id, vec![0x0F, 0x00, 0xE8, 0x03])
+                .unwrap()
+                .to_string(),
+            "RPDO1 node 3: 0F 00 E8 03"
+        );
+        assert_eq!(
+            PdoFrame::new(Direction::Tx, 4, node_id, vec![])
+                .unwrap()
+                .to_string(),
+            "TPDO4 node 3:"
+        );
+    }
+}
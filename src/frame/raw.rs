@@ -0,0 +1,328 @@
+use crate::error::{Error, Result};
+use crate::frame::{CanOpenFrame, ConvertibleFrame, DataLengthPolicy};
+use crate::id::CommunicationObject;
+
+// A classical CAN frame with an 11-bit identifier, as exchanged with any transport. This is
+// the boundary of the codec: `CanOpenFrame::encode` and `CanOpenFrame::decode` convert from
+// and to it, and transport adapters (e.g. SocketCAN) only convert it to their own frame type.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+#[cfg_attr(
+    feature = "serde",
+    derive(serde::Serialize, serde::Deserialize),
+    serde(into = "RawFrameFields", try_from = "RawFrameFields")
+)]
+pub struct RawFrame {
+    id: u16,
+    remote: bool,
+    length: u8,
+    data: [u8; RawFrame::MAX_DATA_LENGTH],
+}
+
+impl RawFrame {
+    pub const MAX_ID: u16 = 0x7FF;
+    pub const MAX_DATA_LENGTH: usize = 8;
+
+    pub fn new(id: u16, data: &[u8]) -> Result<Self> {
+        Self::check_id(id)?;
+        if data.len() > Self::MAX_DATA_LENGTH {
+            return Err(Error::InvalidDataLength {
+                length: data.len(),
+                data_type: "RawFrame".to_owned(),
+            });
+        }
+        let mut buf = [0x00; Self::MAX_DATA_LENGTH];
+        buf[..data.len()].copy_from_slice(data);
+        Ok(Self {
+            id,
+            remote: false,
+            length: data.len() as u8,
+            data: buf,
+        })
+    }
+
+    // A remote frame carries no data; its data length code is the length of the requested
+    // data frame.
+    pub fn new_remote(id: u16, data_length: u8) -> Result<Self> {
+        Self::check_id(id)?;
+        if data_length as usize > Self::MAX_DATA_LENGTH {
+            return Err(Error::InvalidDataLength {
+                length: data_length.into(),
+                data_type: "RawFrame".to_owned(),
+            });
+        }
+        Ok(Self {
+            id,
+            remote: true,
+            length: data_length,
+            data: [0x00; Self::MAX_DATA_LENGTH],
+        })
+    }
+
+    fn check_id(id: u16) -> Result<()> {
+        if id > Self::MAX_ID {
+            return Err(Error::InvalidCobId(id.into()));
+        }
+        Ok(())
+    }
+
+    pub fn id(&self) -> u16 {
+        self.id
+    }
+
+    pub fn is_remote(&self) -> bool {
+        self.remote
+    }
+
+    pub fn data_length(&self) -> u8 {
+        self.length
+    }
+
+    // Empty for a remote frame
+    pub fn data(&self) -> &[u8] {
+        if self.remote {
+            return &[];
+        }
+        &self.data[..self.length as usize]
+    }
+}
+
+// Encodes any frame type, not only `CanOpenFrame`.
+pub fn encode<T: ConvertibleFrame>(frame: &T) -> RawFrame {
+    let mut data = [0x00; RawFrame::MAX_DATA_LENGTH];
+    let length = frame.write_frame_data(&mut data);
+    RawFrame {
+        id: frame.communication_object().as_cob_id(),
+        remote: false,
+        length: length as u8,
+        data,
+    }
+}
+
+impl CanOpenFrame {
+    pub fn encode(&self) -> RawFrame {
+        match self {
+            Self::RemoteFrame(frame) => RawFrame {
+                id: frame.communication_object().as_cob_id(),
+                remote: true,
+                length: frame.data_length(),
+                data: [0x00; RawFrame::MAX_DATA_LENGTH],
+            },
+            frame => encode(frame),
+        }
+    }
+
+    // Errors are wrapped in `Error::UndecodableFrame` with the raw identifier and data.
+    pub fn decode(frame: &RawFrame, policy: DataLengthPolicy) -> Result<Self> {
+        CommunicationObject::new(frame.id)
+            .and_then(|cob| {
+                if frame.remote {
+                    Self::new_remote_with_data_length(cob, frame.length)
+                } else {
+                    Self::new_with_bytes(cob, frame.data(), policy)
+                }
+            })
+            .map_err(|reason| Error::UndecodableFrame {
+                cob_id: frame.id.into(),
+                data: frame.data().to_vec(),
+                reason: Box::new(reason),
+            })
+    }
+}
+
+impl From<CanOpenFrame> for RawFrame {
+    fn from(frame: CanOpenFrame) -> Self {
+        frame.encode()
+    }
+}
+
+impl TryFrom<RawFrame> for CanOpenFrame {
+    type Error = Error;
+    fn try_from(frame: RawFrame) -> Result<Self> {
+        CanOpenFrame::decode(&frame, DataLengthPolicy::default())
+    }
+}
+
+// The serialized form has no padding bytes, and is deserialized through `RawFrame::new` or
+// `RawFrame::new_remote` to check the identifier and the data length.
+#[cfg(feature = "serde")]
+#[derive(serde::Serialize, serde::Deserialize)]
+struct RawFrameFields {
+    id: u16,
+    remote: bool,
+    data_length: u8,
+    data: std::vec::Vec<u8>,
+}
+
+#[cfg(feature = "serde")]
+impl From<RawFrame> for RawFrameFields {
+    fn from(frame: RawFrame) -> Self {
+        Self {
+            id: frame.id,
+            remote: frame.remote,
+            data_length: frame.length,
+            data: frame.data().to_vec(),
+        }
+    }
+}
+
+#[cfg(feature = "serde")]
+impl TryFrom<RawFrameFields> for RawFrame {
+    type Error = Error;
+    fn try_from(fields: RawFrameFields) -> Result<Self> {
+        // A remote frame carries no data, and the data length code of a data frame is the
+        // length of its data.
+        let expected = if fields.remote {
+            0
+        } else {
+            fields.data_length.into()
+        };
+        if fields.data.len() != expected {
+            return Err(Error::InvalidDataLength {
+                length: fields.data.len(),
+                data_type: "RawFrame".to_owned(),
+            });
+        }
+        if fields.remote {
+            Self::new_remote(fields.id, fields.data_length)
+        } else {
+            Self::new(fields.id, &fields.data)
+        }
+    }
+}
+
+// The compact form of candump and cansend, e.g. "603#4017100000000000", or "703#R1" for a
+// remote frame
+impl std::fmt::Display for RawFrame {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(f, "{:03X}#", self.id)?;
+        if self.remote {
+            write!(f, "R")?;
+            if self.length > 0 {
+                write!(f, "{}", self.length)?;
+            }
+            return Ok(());
+        }
+        for byte in self.data() {
+            write!(f, "{byte:02X}")?;
+        }
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    use crate::frame::{NmtNodeMonitoringFrame, NmtState, RemoteFrame, SyncFrame};
+
+    #[test]
+    fn test_new() {
+        let frame = RawFrame::new(0x701, &[0x05]).unwrap();
+        assert_eq!(frame.id(), 0x701);
+        assert_eq!(frame.data(), &[0x05]);
+        assert!(!frame.is_remote());
+        assert_eq!(RawFrame::new(0x800, &[]), Err(Error::InvalidCobId(0x800)));
+        assert!(RawFrame::new(0x701, &[0x00; 9]).is_err());
+        let frame = RawFrame::new_remote(0x701, 1).unwrap();
+        assert_eq!(frame.data(), &[] as &[u8]);
+        assert_eq!(frame.data_length(), 1);
+        assert!(RawFrame::new_remote(0x701, 9).is_err());
+    }
+
+    #[test]
+    fn test_encode() {
+        assert_eq!(
+            encode(&SyncFrame::new()),
+            RawFrame::new(0x080, &[]).unwrap()
+        );
+        let frame: CanOpenFrame =
+            NmtNodeMonitoringFrame::new(2.try_into().unwrap(), NmtState::Operational).into();
+        assert_eq!(frame.encode(), RawFrame::new(0x702, &[0x05]).unwrap());
+        let frame: CanOpenFrame = RemoteFrame::new_node_guarding(2.try_into().unwrap()).into();
+        assert_eq!(
+            RawFrame::from(frame),
+            RawFrame::new_remote(0x702, 1).unwrap()
+        );
+    }
+
+    #[test]
+    fn test_decode() {
+        assert_eq!(
+            CanOpenFrame::try_from(RawFrame::new(0x702, &[0x05]).unwrap()),
+            Ok(NmtNodeMonitoringFrame::new(2.try_into().unwrap(), NmtState::Operational).into())
+        );
+        assert_eq!(
+            CanOpenFrame::try_from(RawFrame::new_remote(0x702, 1).unwrap()),
+            Ok(RemoteFrame::new_node_guarding(2.try_into().unwrap()).into())
+        );
+        assert_eq!(
+            CanOpenFrame::decode(
+                &RawFrame::new(0x702, &[0x05, 0x00]).unwrap(),
+                DataLengthPolicy::Lenient
+            ),
+            Ok(NmtNodeMonitoringFrame::new(2.try_into().unwrap(), NmtState::Operational).into())
+        );
+        assert_eq!(
+            CanOpenFrame::try_from(RawFrame::new(0x702, &[0x06]).unwrap()),
+            Err(Error::UndecodableFrame {
+                cob_id: 0x702,
+                data: vec![0x06],
+                reason: Box::new(Error::InvalidNmtState(0x06))
+            })
+        );
+        // An SDO frame without data
+        for policy in [DataLengthPolicy::Strict, DataLengthPolicy::Lenient] {
+            assert_eq!(
+                CanOpenFrame::decode(&RawFrame::new(0x603, &[]).unwrap(), policy),
+                Err(Error::UndecodableFrame {
+                    cob_id: 0x603,
+                    data: vec![],
+                    reason: Box::new(Error::InvalidDataLength {
+                        length: 0,
+                        data_type: "SdoFrame".to_owned()
+                    })
+                })
+            );
+        }
+    }
+
+    #[cfg(feature = "serde")]
+    #[test]
+    fn test_serde() {
+        let frame = RawFrame::new(0x603, &[0x40, 0x17, 0x10, 0x00]).unwrap();
+        let json = serde_json::to_string(&frame).unwrap();
+        assert_eq!(
+            json,
+            r#"{"id":1539,"remote":false,"data_length":4,"data":[64,23,16,0]}"#
+        );
+        assert_eq!(serde_json::from_str::<RawFrame>(&json).unwrap(), frame);
+        let remote = RawFrame::new_remote(0x703, 1).unwrap();
+        let json = serde_json::to_string(&remote).unwrap();
+        assert_eq!(serde_json::from_str::<RawFrame>(&json).unwrap(), remote);
+
+        for json in [
+            r#"{"id":2048,"remote":false,"data_length":0,"data":[]}"#,
+            r#"{"id":1539,"remote":false,"data_length":9,"data":[0,0,0,0,0,0,0,0,0]}"#,
+            r#"{"id":1539,"remote":false,"data_length":1,"data":[0,0]}"#,
+            r#"{"id":1795,"remote":true,"data_length":1,"data":[0]}"#,
+        ] {
+            assert!(serde_json::from_str::<RawFrame>(json).is_err(), "{json}");
+        }
+    }
+
+    #[test]
+    fn test_display() {
+        assert_eq!(
+            RawFrame::new(0x603, &[0x40, 0x17, 0x10, 0x00])
+                .unwrap()
+                .to_string(),
+            "603#40171000"
+        );
+        assert_eq!(RawFrame::new(0x080, &[]).unwrap().to_string(), "080#");
+        assert_eq!(
+            RawFrame::new_remote(0x703, 1).unwrap().to_string(),
+            "703#R1"
+        );
+        assert_eq!(RawFrame::new_remote(0x703, 0).unwrap().to_string(), "703#R");
+    }
+}
@@ -0,0 +1,159 @@
+use crate::error::{Error, Result};
+use crate::frame::{CanOpenFrame, ConvertibleFrame};
+use crate::id::{CommunicationObject, NodeId};
+
+#[derive(Clone, Copy, Debug, PartialEq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+#[cfg_attr(feature = "serde", serde(try_from = "RemoteFrameFields"))]
+pub struct RemoteFrame {
+    pub(crate) communication_object: CommunicationObject,
+    pub(crate) data_length: u8,
+}
+
+impl RemoteFrame {
+    const MAX_DATA_LENGTH: u8 = 8;
+
+    pub fn new(communication_object: CommunicationObject, data_length: u8) -> Result<Self> {
+        if data_length > Self::MAX_DATA_LENGTH {
+            return Err(Error::InvalidDataLength {
+                length: data_length.into(),
+                data_type: "RemoteFrame".to_owned(),
+            });
+        }
+        Ok(Self {
+            communication_object,
+            data_length,
+        })
+    }
+
+    pub fn new_node_guarding(node_id: NodeId) -> Self {
+        Self {
+            communication_object: CommunicationObject::NmtNodeMonitoring(node_id),
+            data_length: 1,
+        }
+    }
+
+    pub fn new_pdo_request(number: u8, node_id: NodeId, data_length: u8) -> Result<Self> {
+        let communication_object = match number {
+            1 => CommunicationObject::TxPdo1(node_id),
+            2 => CommunicationObject::TxPdo2(node_id),
+            3 => CommunicationObject::TxPdo3(node_id),
+            4 => CommunicationObject::TxPdo4(node_id),
+            _ => return Err(Error::InvalidPdoNumber(number)),
+        };
+        Self::new(communication_object, data_length)
+    }
+
+    // The data length code of a remote frame is the length of the requested data frame.
+    pub fn data_length(&self) -> u8 {
+        self.data_length
+    }
+}
+
+// Deserialized through `RemoteFrame::new` to check the data length
+#[cfg(feature = "serde")]
+#[derive(serde::Deserialize)]
+struct RemoteFrameFields {
+    communication_object: CommunicationObject,
+    data_length: u8,
+}
+
+#[cfg(feature = "serde")]
+impl TryFrom<RemoteFrameFields> for RemoteFrame {
+    type Error = Error;
+    fn try_from(fields: RemoteFrameFields) -> Result<Self> {
+        Self::new(fields.communication_object, fields.data_length)
+    }
+}
+
+impl From<RemoteFrame> for CanOpenFrame {
+    fn from(frame: RemoteFrame) -> Self {
+        CanOpenFrame::RemoteFrame(frame)
+    }
+}
+
+impl ConvertibleFrame for RemoteFrame {
+    fn communication_object(&self) -> CommunicationObject {
+        self.communication_object
+    }
+
+    fn frame_data(&self) -> std::vec::Vec<u8> {
+        std::vec::Vec::new()
+    }
+}
+
+// e.g. "RTR NmtNodeMonitoring(3), length 1"
+impl std::fmt::Display for RemoteFrame {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(
+            f,
+            "RTR {}, length {}",
+            self.communication_object, self.data_length
+        )
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_new() {
+        let cob = CommunicationObject::TxPdo1(1.try_into().unwrap());
+        assert_eq!(
+            RemoteFrame::new(cob, 8),
+            Ok(RemoteFrame {
+                communication_object: cob,
+                data_length: 8
+            })
+        );
+        assert_eq!(
+            RemoteFrame::new(cob, 9),
+            Err(Error::InvalidDataLength {
+                length: 9,
+                data_type: "RemoteFrame".to_owned()
+            })
+        );
+    }
+
+    #[test]
+    fn test_new_node_guarding() {
+        let frame = RemoteFrame::new_node_guarding(5.try_into().unwrap());
+        assert_eq!(
+            frame.communication_object(),
+            CommunicationObject::NmtNodeMonitoring(5.try_into().unwrap())
+        );
+        assert_eq!(frame.data_length(), 1);
+        assert_eq!(frame.frame_data(), std::vec::Vec::<u8>::new());
+    }
+
+    #[test]
+    fn test_new_pdo_request() {
+        let node_id: NodeId = 3.try_into().unwrap();
+        assert_eq!(
+            RemoteFrame::new_pdo_request(1, node_id, 8)
+                .unwrap()
+                .communication_object(),
+            CommunicationObject::TxPdo1(node_id)
+        );
+        assert_eq!(
+            RemoteFrame::new_pdo_request(4, node_id, 2)
+                .unwrap()
+                .communication_object(),
+            CommunicationObject::TxPdo4(node_id)
+        );
+        assert_eq!(
+            RemoteFrame::new_pdo_request(5, node_id, 8),
+            Err(Error::InvalidPdoNumber(5))
+        );
+        assert!(RemoteFrame::new_pdo_request(1, node_id, 9).is_err());
+    }
+
+    #[test]
+    fn test_display() {
+        assert_eq!(
+            RemoteFrame::new_node_guarding(3.try_into().unwrap()).to_string(),
+            "RTR NmtNodeMonitoring(3), length 1"
+        );
+    }
+}
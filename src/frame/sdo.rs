@@ -1,14 +1,10 @@
 use crate::error::{Error, Result};
-use crate::frame::{CanOpenFrame, ConvertibleFrame};
+use crate::frame::{CanOpenFrame, ConvertibleFrame, DataLengthPolicy, Direction};
 use crate::id::{CommunicationObject, NodeId};
+use crate::sdo::SdoAbortCode;
 
 #[derive(Copy, Clone, Debug, PartialEq)]
-pub(crate) enum Direction {
-    Tx,
-    Rx,
-}
-
-#[derive(Copy, Clone, Debug, PartialEq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
 pub(crate) enum ClientCommandSpecifier {
     SegmentDownload = 0,
     InitiateDownload = 1,
@@ -35,6 +31,8 @@ impl ClientCommandSpecifier {
 }
 
 #[derive(Debug, PartialEq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+#[cfg_attr(feature = "serde", serde(try_from = "SdoFrameFields"))]
 pub struct SdoFrame {
     pub(crate) direction: Direction,
     pub(crate) node_id: NodeId,
@@ -49,6 +47,8 @@ pub struct SdoFrame {
 impl SdoFrame {
     const FRAME_DATA_SIZE: usize = 8;
     const DATA_CONTENT_SIZE: usize = 4;
+    // Command specifier and multiplexer
+    const REQUIRED_DATA_SIZE: usize = 4;
 
     pub fn new_sdo_read_frame(node_id: NodeId, index: u16, sub_index: u8) -> Self {
         Self {
@@ -81,11 +81,27 @@ impl SdoFrame {
         }
     }
 
+    pub fn abort_code(&self) -> Option<SdoAbortCode> {
+        match self.ccs {
+            ClientCommandSpecifier::AbortTransfer => Some(SdoAbortCode::from_u32(
+                u32::from_le_bytes(self.data.as_slice().try_into().ok()?),
+            )),
+            _ => None,
+        }
+    }
+
     pub(crate) fn new_with_bytes(
         direction: Direction,
         node_id: NodeId,
         bytes: &[u8],
+        policy: DataLengthPolicy,
     ) -> Result<Self> {
+        policy.check(
+            bytes,
+            Self::FRAME_DATA_SIZE,
+            Self::REQUIRED_DATA_SIZE,
+            "SdoFrame",
+        )?;
         // cf. https://en.wikipedia.org/wiki/CANopen#Service_Data_Object_(SDO)_protocol
         let ccs = ClientCommandSpecifier::from_num(bytes[0] >> 5)?;
         let expedited: bool = (bytes[0] & 0b0010) != 0;
@@ -118,6 +134,45 @@ impl SdoFrame {
     }
 }
 
+// Deserialized with the checks of `SdoFrame::new_with_bytes`, so that the frame can be encoded
+#[cfg(feature = "serde")]
+#[derive(serde::Deserialize)]
+struct SdoFrameFields {
+    direction: Direction,
+    node_id: NodeId,
+    ccs: ClientCommandSpecifier,
+    index: u16,
+    sub_index: u8,
+    size: Option<usize>,
+    expedited: bool,
+    data: std::vec::Vec<u8>,
+}
+
+#[cfg(feature = "serde")]
+impl TryFrom<SdoFrameFields> for SdoFrame {
+    type Error = Error;
+    fn try_from(fields: SdoFrameFields) -> Result<Self> {
+        if fields.size.unwrap_or(0) > Self::DATA_CONTENT_SIZE
+            || fields.data.len() > Self::DATA_CONTENT_SIZE
+        {
+            return Err(Error::InvalidDataLength {
+                length: fields.data.len().max(fields.size.unwrap_or(0)),
+                data_type: "SdoFrame".to_owned(),
+            });
+        }
+        Ok(Self {
+            direction: fields.direction,
+            node_id: fields.node_id,
+            ccs: fields.ccs,
+            index: fields.index,
+            sub_index: fields.sub_index,
+            size: fields.size,
+            expedited: fields.expedited,
+            data: fields.data,
+        })
+    }
+}
+
 impl From<SdoFrame> for CanOpenFrame {
     fn from(frame: SdoFrame) -> Self {
         CanOpenFrame::SdoFrame(frame)
@@ -153,6 +208,53 @@ impl ConvertibleFrame for SdoFrame {
     }
 }
 
+// e.g. "SDO Rx node 3: write 0x1017:00 = 0x03E8". The data of an expedited transfer is shown
+// as a little-endian integer. Tx frames are responses, whose command specifiers differ.
+impl std::fmt::Display for SdoFrame {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(f, "SDO {} node {}: ", self.direction, self.node_id)?;
+        let multiplexer = format!("0x{:04X}:{:02X}", self.index, self.sub_index);
+        let (command, with_data) = match (self.direction, self.ccs) {
+            (_, ClientCommandSpecifier::AbortTransfer) => {
+                write!(f, "abort {multiplexer}")?;
+                if let Some(abort_code) = self.abort_code() {
+                    write!(f, ": {abort_code}")?;
+                }
+                return Ok(());
+            }
+            (Direction::Rx, ClientCommandSpecifier::InitiateDownload) => ("write", true),
+            (Direction::Rx, ClientCommandSpecifier::InitiateUpload) => ("read", false),
+            (Direction::Tx, ClientCommandSpecifier::InitiateUpload) => ("read response", true),
+            // The server command specifier of the initiate download response is 3.
+            (Direction::Tx, ClientCommandSpecifier::SegmentUpload) => ("write response", false),
+            (Direction::Rx, ClientCommandSpecifier::SegmentDownload)
+            | (Direction::Tx, ClientCommandSpecifier::InitiateDownload) => {
+                return write!(f, "download segment");
+            }
+            (Direction::Rx, ClientCommandSpecifier::SegmentUpload)
+            | (Direction::Tx, ClientCommandSpecifier::SegmentDownload) => {
+                return write!(f, "upload segment");
+            }
+            (Direction::Rx, ClientCommandSpecifier::BlockUpload)
+            | (Direction::Tx, ClientCommandSpecifier::BlockDownload) => {
+                return write!(f, "block upload");
+            }
+            (Direction::Rx, ClientCommandSpecifier::BlockDownload)
+            | (Direction::Tx, ClientCommandSpecifier::BlockUpload) => {
+                return write!(f, "block download");
+            }
+        };
+        write!(f, "{command} {multiplexer}")?;
+        if with_data && self.expedited && !self.data.is_empty() {
+            write!(f, " = 0x")?;
+            for byte in self.data.iter().rev() {
+                write!(f, "{byte:02X}")?;
+            }
+        }
+        Ok(())
+    }
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
@@ -284,6 +386,7 @@ mod tests {
                 Direction::Rx,
                 1.try_into().unwrap(),
                 &[0x40, 0x18, 0x10, 0x02, 0x00, 0x00, 0x00, 0x00],
+                DataLengthPolicy::Strict,
             ),
             Ok(SdoFrame {
                 direction: Direction::Rx,
@@ -301,6 +404,7 @@ mod tests {
                 Direction::Rx,
                 1.try_into().unwrap(),
                 &[0x2F, 0x02, 0x14, 0x02, 0xFF, 0x00, 0x00, 0x00],
+                DataLengthPolicy::Strict,
             ),
             Ok(SdoFrame {
                 direction: Direction::Rx,
@@ -318,6 +422,7 @@ mod tests {
                 Direction::Rx,
                 2.try_into().unwrap(),
                 &[0x2B, 0x17, 0x10, 0x00, 0xE8, 0x03, 0x00, 0x00],
+                DataLengthPolicy::Strict,
             ),
             Ok(SdoFrame {
                 direction: Direction::Rx,
@@ -335,6 +440,7 @@ mod tests {
                 Direction::Rx,
                 3.try_into().unwrap(),
                 &[0x23, 0x00, 0x12, 0x01, 0x0A, 0x06, 0x00, 0x00],
+                DataLengthPolicy::Strict,
             ),
             Ok(SdoFrame {
                 direction: Direction::Rx,
@@ -352,6 +458,7 @@ mod tests {
                 Direction::Tx,
                 4.try_into().unwrap(),
                 &[0x43, 0x00, 0x10, 0x00, 0x92, 0x01, 0x02, 0x00],
+                DataLengthPolicy::Strict,
             ),
             Ok(SdoFrame {
                 direction: Direction::Tx,
@@ -369,6 +476,7 @@ mod tests {
                 Direction::Tx,
                 5.try_into().unwrap(),
                 &[0x80, 0x00, 0x10, 0x00, 0x02, 0x00, 0x01, 0x06],
+                DataLengthPolicy::Strict,
             ),
             Ok(SdoFrame {
                 direction: Direction::Tx,
@@ -383,6 +491,47 @@ mod tests {
         );
     }
 
+    #[test]
+    fn test_data_length() {
+        let node_id: NodeId = 1.try_into().unwrap();
+        for bytes in [&[][..], &[0x40, 0x18, 0x10]] {
+            for policy in [DataLengthPolicy::Strict, DataLengthPolicy::Lenient] {
+                assert_eq!(
+                    SdoFrame::new_with_bytes(Direction::Rx, node_id, bytes, policy),
+                    Err(Error::InvalidDataLength {
+                        length: bytes.len(),
+                        data_type: "SdoFrame".to_owned()
+                    })
+                );
+            }
+        }
+        // The unused bytes of an upload request may be omitted leniently.
+        let bytes = [0x40, 0x18, 0x10, 0x02];
+        assert!(
+            SdoFrame::new_with_bytes(Direction::Rx, node_id, &bytes, DataLengthPolicy::Strict)
+                .is_err()
+        );
+        assert_eq!(
+            SdoFrame::new_with_bytes(Direction::Rx, node_id, &bytes, DataLengthPolicy::Lenient),
+            Ok(SdoFrame::new_sdo_read_frame(node_id, 0x1018, 2))
+        );
+    }
+
+    #[test]
+    fn test_abort_code() {
+        let frame = SdoFrame::new_with_bytes(
+            Direction::Tx,
+            5.try_into().unwrap(),
+            &[0x80, 0x00, 0x10, 0x00, 0x02, 0x00, 0x01, 0x06],
+            DataLengthPolicy::Strict,
+        )
+        .unwrap();
+        assert_eq!(frame.abort_code(), Some(SdoAbortCode::ReadOnly));
+
+        let frame = SdoFrame::new_sdo_read_frame(5.try_into().unwrap(), 0x1000, 0);
+        assert_eq!(frame.abort_code(), None);
+    }
+
     #[test]
     fn test_communication_object() {
         let frame = SdoFrame {
@@ -549,4 +698,46 @@ mod tests {
         assert_eq!(data.len(), 8);
         assert_eq!(data, &[0x80, 0x00, 0x10, 0x00, 0x02, 0x00, 0x01, 0x06]);
     }
+
+    #[test]
+    fn test_display() {
+        let node_id: NodeId = 3.try_into().unwrap();
+        for (direction, bytes, expected) in [
+            (
+                Direction::Rx,
+                [0x2B, 0x17, 0x10, 0x00, 0xE8, 0x03, 0x00, 0x00],
+                "SDO Rx node 3: write 0x1017:00 = 0x03E8",
+            ),
+            (
+                Direction::Tx,
+                [0x60, 0x17, 0x10, 0x00, 0x00, 0x00, 0x00, 0x00],
+                "SDO Tx node 3: write response 0x1017:00",
+            ),
+            (
+                Direction::Rx,
+                [0x40, 0x00, 0x10, 0x00, 0x00, 0x00, 0x00, 0x00],
+                "SDO Rx node 3: read 0x1000:00",
+            ),
+            (
+                Direction::Tx,
+                [0x43, 0x00, 0x10, 0x00, 0x92, 0x01, 0x02, 0x00],
+                "SDO Tx node 3: read response 0x1000:00 = 0x00020192",
+            ),
+            (
+                Direction::Rx,
+                [0x60, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00],
+                "SDO Rx node 3: upload segment",
+            ),
+            (
+                Direction::Tx,
+                [0x80, 0x17, 0x10, 0x00, 0x02, 0x00, 0x01, 0x06],
+                "SDO Tx node 3: abort 0x1017:00: Attempt to write a read only object (0x06010002)",
+            ),
+        ] {
+            let frame =
+                SdoFrame::new_with_bytes(direction, node_id, &bytes, DataLengthPolicy::Strict)
+                    .unwrap();
+            assert_eq!(frame.to_string(), expected);
+        }
+    }
 }
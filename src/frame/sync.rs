@@ -1,18 +1,52 @@
-use crate::frame::{CanOpenFrame, ConvertibleFrame};
-use crate::id::CommunicationObject;
+use crate::error::{Error, Result};
+use crate::frame::{CanOpenFrame, ConvertibleFrame, DataLengthPolicy, SdoFrame};
+use crate::id::{CommunicationObject, NodeId};
 
-#[derive(Clone, Copy, Debug, PartialEq)]
-pub struct SyncFrame;
+// A SYNC, with the synchronous counter if the producer has a counter overflow value (0x1019)
+#[derive(Clone, Copy, Debug, Default, PartialEq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct SyncFrame {
+    counter: Option<u8>,
+}
 
 impl SyncFrame {
+    const MAX_FRAME_DATA_SIZE: usize = 1;
+
     pub fn new() -> Self {
-        Self
+        Self { counter: None }
+    }
+
+    pub fn new_with_counter(counter: u8) -> Self {
+        Self {
+            counter: Some(counter),
+        }
+    }
+
+    pub fn counter(&self) -> Option<u8> {
+        self.counter
+    }
+
+    // Lenient frames may carry bytes after the counter.
+    pub(crate) fn new_with_bytes(bytes: &[u8], policy: DataLengthPolicy) -> Result<Self> {
+        if policy == DataLengthPolicy::Strict && bytes.len() > Self::MAX_FRAME_DATA_SIZE {
+            return Err(Error::InvalidDataLength {
+                length: bytes.len(),
+                data_type: "SyncFrame".to_owned(),
+            });
+        }
+        Ok(Self {
+            counter: bytes.first().copied(),
+        })
     }
 }
 
-impl Default for SyncFrame {
-    fn default() -> Self {
-        Self::new()
+impl std::fmt::Display for SyncFrame {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(f, "SYNC")?;
+        if let Some(counter) = self.counter {
+            write!(f, " counter {counter}")?;
+        }
+        Ok(())
     }
 }
 
@@ -28,7 +62,78 @@ impl ConvertibleFrame for SyncFrame {
     }
 
     fn frame_data(&self) -> std::vec::Vec<u8> {
-        std::vec::Vec::new()
+        self.counter.into_iter().collect()
+    }
+
+    fn write_frame_data(&self, buf: &mut [u8; 8]) -> usize {
+        match self.counter {
+            Some(counter) => {
+                buf[0] = counter;
+                1
+            }
+            None => 0,
+        }
+    }
+}
+
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub struct SyncCobId {
+    cob_id: u16,
+    generate: bool,
+}
+
+impl SyncCobId {
+    pub const INDEX: u16 = 0x1005;
+    const GENERATE_BIT: u32 = 1 << 30;
+    // A 29-bit identifier, which CANopen does not use
+    const EXTENDED_FRAME_BIT: u32 = 1 << 29;
+
+    pub fn new(cob_id: u16, generate: bool) -> Result<Self> {
+        if cob_id & !0x07FF != 0 {
+            return Err(Error::InvalidCobId(cob_id.into()));
+        }
+        Ok(Self { cob_id, generate })
+    }
+
+    pub fn from_u32(value: u32) -> Result<Self> {
+        if value & Self::EXTENDED_FRAME_BIT != 0 {
+            return Err(Error::InvalidCobId(value));
+        }
+        let cob_id = value & 0x1FFF_FFFF;
+        if cob_id & !0x07FF != 0 {
+            return Err(Error::InvalidCobId(cob_id));
+        }
+        Self::new(cob_id as u16, value & Self::GENERATE_BIT != 0)
+    }
+
+    pub fn cob_id(&self) -> u16 {
+        self.cob_id
+    }
+
+    // True if the node produces the SYNC
+    pub fn generate(&self) -> bool {
+        self.generate
+    }
+
+    pub fn as_u32(&self) -> u32 {
+        (self.cob_id as u32) | if self.generate { Self::GENERATE_BIT } else { 0 }
+    }
+
+    pub fn new_sdo_read_frame(node_id: NodeId) -> SdoFrame {
+        SdoFrame::new_sdo_read_frame(node_id, Self::INDEX, 0)
+    }
+
+    pub fn new_sdo_write_frame(&self, node_id: NodeId) -> SdoFrame {
+        SdoFrame::new_sdo_write_frame(node_id, Self::INDEX, 0, self.as_u32().to_le_bytes().into())
+    }
+}
+
+impl Default for SyncCobId {
+    fn default() -> Self {
+        Self {
+            cob_id: CommunicationObject::Sync.as_cob_id(),
+            generate: false,
+        }
     }
 }
 
@@ -38,12 +143,99 @@ mod tests {
 
     #[test]
     fn test_communication_object() {
-        assert_eq!(SyncFrame.communication_object(), CommunicationObject::Sync);
+        assert_eq!(
+            SyncFrame::new().communication_object(),
+            CommunicationObject::Sync
+        );
     }
 
     #[test]
     fn test_set_data() {
         let data = SyncFrame::new().frame_data();
-        assert_eq!(data, &[]);
+        assert_eq!(data, &[] as &[u8]);
+
+        let frame = SyncFrame::new_with_counter(7);
+        assert_eq!(frame.counter(), Some(7));
+        assert_eq!(frame.frame_data(), &[7]);
+        let mut buf = [0xFF; 8];
+        assert_eq!(frame.write_frame_data(&mut buf), 1);
+        assert_eq!(buf[0], 7);
+    }
+
+    #[test]
+    fn test_sync_cob_id_new() {
+        assert_eq!(
+            SyncCobId::new(0x080, false),
+            Ok(SyncCobId {
+                cob_id: 0x080,
+                generate: false
+            })
+        );
+        assert_eq!(
+            SyncCobId::new(0x7FF, true),
+            Ok(SyncCobId {
+                cob_id: 0x7FF,
+                generate: true
+            })
+        );
+        assert_eq!(
+            SyncCobId::new(0x800, false),
+            Err(Error::InvalidCobId(0x800))
+        );
+    }
+
+    #[test]
+    fn test_sync_cob_id_from_u32() {
+        assert_eq!(SyncCobId::from_u32(0x0000_0080), Ok(SyncCobId::default()));
+        assert_eq!(
+            SyncCobId::from_u32(0x4000_0080),
+            Ok(SyncCobId {
+                cob_id: 0x080,
+                generate: true
+            })
+        );
+        assert_eq!(
+            SyncCobId::from_u32(0x8000_0081),
+            Ok(SyncCobId {
+                cob_id: 0x081,
+                generate: false
+            })
+        );
+        assert_eq!(
+            SyncCobId::from_u32(0x2000_0080),
+            Err(Error::InvalidCobId(0x2000_0080))
+        );
+        assert_eq!(
+            SyncCobId::from_u32(0x0000_0800),
+            Err(Error::InvalidCobId(0x800))
+        );
+    }
+
+    #[test]
+    fn test_sync_cob_id_as_u32() {
+        assert_eq!(SyncCobId::default().as_u32(), 0x0000_0080);
+        assert_eq!(SyncCobId::new(0x080, true).unwrap().as_u32(), 0x4000_0080);
+        assert_eq!(SyncCobId::new(0x123, false).unwrap().as_u32(), 0x0000_0123);
+    }
+
+    #[test]
+    fn test_sync_cob_id_sdo_frames() {
+        let frame = SyncCobId::new_sdo_read_frame(1.try_into().unwrap());
+        assert_eq!(
+            frame.frame_data(),
+            &[0x40, 0x05, 0x10, 0x00, 0x00, 0x00, 0x00, 0x00]
+        );
+
+        let frame = SyncCobId::new(0x080, true)
+            .unwrap()
+            .new_sdo_write_frame(2.try_into().unwrap());
+        assert_eq!(
+            frame.communication_object(),
+            CommunicationObject::RxSdo(2.try_into().unwrap())
+        );
+        assert_eq!(
+            frame.frame_data(),
+            &[0x23, 0x05, 0x10, 0x00, 0x80, 0x00, 0x00, 0x40]
+        );
     }
 }
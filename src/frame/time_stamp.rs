@@ -0,0 +1,161 @@
+use crate::error::Result;
+use crate::frame::{CanOpenFrame, ConvertibleFrame, DataLengthPolicy};
+use crate::id::CommunicationObject;
+
+#[derive(Clone, Copy, Debug, PartialEq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct TimeStampFrame {
+    // Milliseconds after midnight
+    pub milliseconds: u32,
+    // Days since January 1, 1984
+    pub days: u16,
+}
+
+impl TimeStampFrame {
+    const FRAME_DATA_SIZE: usize = 6;
+    const MILLISECONDS_MASK: u32 = 0x0FFF_FFFF;
+
+    pub fn new(milliseconds: u32, days: u16) -> Self {
+        Self { milliseconds, days }
+    }
+
+    pub(crate) fn new_with_bytes(bytes: &[u8], policy: DataLengthPolicy) -> Result<Self> {
+        policy.check(
+            bytes,
+            Self::FRAME_DATA_SIZE,
+            Self::FRAME_DATA_SIZE,
+            "TimeStampFrame",
+        )?;
+        Ok(Self::new(
+            u32::from_le_bytes(bytes[0..4].try_into().unwrap()) & Self::MILLISECONDS_MASK,
+            u16::from_le_bytes(bytes[4..6].try_into().unwrap()),
+        ))
+    }
+}
+
+impl From<TimeStampFrame> for CanOpenFrame {
+    fn from(frame: TimeStampFrame) -> Self {
+        CanOpenFrame::TimeStampFrame(frame)
+    }
+}
+
+impl ConvertibleFrame for TimeStampFrame {
+    fn communication_object(&self) -> CommunicationObject {
+        CommunicationObject::TimeStamp
+    }
+
+    fn frame_data(&self) -> std::vec::Vec<u8> {
+        let mut data = std::vec::Vec::with_capacity(Self::FRAME_DATA_SIZE);
+        data.extend_from_slice(&(self.milliseconds & Self::MILLISECONDS_MASK).to_le_bytes());
+        data.extend_from_slice(&self.days.to_le_bytes());
+        assert_eq!(data.len(), Self::FRAME_DATA_SIZE);
+        data
+    }
+}
+
+// e.g. "TIME: day 14865 01:00:00.000"
+impl std::fmt::Display for TimeStampFrame {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        let seconds = self.milliseconds / 1000;
+        write!(
+            f,
+            "TIME: day {} {:02}:{:02}:{:02}.{:03}",
+            self.days,
+            seconds / 3600,
+            seconds / 60 % 60,
+            seconds % 60,
+            self.milliseconds % 1000
+        )
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    use crate::error::Error;
+
+    #[test]
+    fn test_from_bytes() {
+        assert_eq!(
+            TimeStampFrame::new_with_bytes(
+                &[0x00, 0x00, 0x00, 0x00, 0x00, 0x00],
+                DataLengthPolicy::Strict
+            ),
+            Ok(TimeStampFrame {
+                milliseconds: 0,
+                days: 0
+            })
+        );
+        assert_eq!(
+            TimeStampFrame::new_with_bytes(
+                &[0x80, 0xEE, 0x36, 0x00, 0x11, 0x3A],
+                DataLengthPolicy::Strict
+            ),
+            Ok(TimeStampFrame {
+                milliseconds: 3_600_000,
+                days: 14865
+            })
+        );
+        // The upper 4 bits of the milliseconds are reserved.
+        assert_eq!(
+            TimeStampFrame::new_with_bytes(
+                &[0x80, 0xEE, 0x36, 0xF0, 0x11, 0x3A],
+                DataLengthPolicy::Strict
+            ),
+            Ok(TimeStampFrame {
+                milliseconds: 3_600_000,
+                days: 14865
+            })
+        );
+        assert_eq!(
+            TimeStampFrame::new_with_bytes(&[0x80, 0xEE, 0x36, 0x00], DataLengthPolicy::Strict),
+            Err(Error::InvalidDataLength {
+                length: 4,
+                data_type: "TimeStampFrame".to_owned()
+            })
+        );
+        assert_eq!(
+            TimeStampFrame::new_with_bytes(
+                &[0x80, 0xEE, 0x36, 0x00, 0x11, 0x3A, 0x00, 0x00],
+                DataLengthPolicy::Lenient
+            ),
+            Ok(TimeStampFrame {
+                milliseconds: 3_600_000,
+                days: 14865
+            })
+        );
+    }
+
+    #[test]
+    fn test_communication_object() {
+        assert_eq!(
+            TimeStampFrame::new(0, 0).communication_object(),
+            CommunicationObject::TimeStamp
+        );
+    }
+
+    #[test]
+    fn test_set_data() {
+        let data = TimeStampFrame::new(0, 0).frame_data();
+        assert_eq!(data, &[0x00, 0x00, 0x00, 0x00, 0x00, 0x00]);
+
+        let data = TimeStampFrame::new(3_600_000, 14865).frame_data();
+        assert_eq!(data, &[0x80, 0xEE, 0x36, 0x00, 0x11, 0x3A]);
+
+        let data = TimeStampFrame::new(0xFFFF_FFFF, 0xFFFF).frame_data();
+        assert_eq!(data, &[0xFF, 0xFF, 0xFF, 0x0F, 0xFF, 0xFF]);
+    }
+
+    #[test]
+    fn test_display() {
+        assert_eq!(
+            TimeStampFrame::new(3_600_000, 14865).to_string(),
+            "TIME: day 14865 01:00:00.000"
+        );
+        assert_eq!(
+            TimeStampFrame::new(86_399_999, 0).to_string(),
+            "TIME: day 0 23:59:59.999"
+        );
+    }
+}
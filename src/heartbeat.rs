@@ -0,0 +1,302 @@
+use crate::error::Result;
+use crate::frame::{NmtNodeMonitoringFrame, NmtState};
+use crate::id::NodeId;
+
+// An entry of the consumer heartbeat time (0x1016): the node to monitor and its period
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub struct ConsumerHeartbeatTime {
+    pub node_id: NodeId,
+    // Milliseconds
+    pub time: u16,
+}
+
+impl ConsumerHeartbeatTime {
+    pub const INDEX: u16 = 0x1016;
+
+    pub fn new(node_id: NodeId, time: u16) -> Self {
+        Self { node_id, time }
+    }
+
+    // Returns `None` for unused entries (node ID 0 or time 0).
+    pub fn from_u32(value: u32) -> Result<Option<Self>> {
+        let raw_id = (value >> 16) as u8;
+        let time = value as u16;
+        if raw_id == 0 || time == 0 {
+            return Ok(None);
+        }
+        Ok(Some(Self::new(raw_id.try_into()?, time)))
+    }
+
+    pub fn as_u32(&self) -> u32 {
+        ((self.node_id.as_raw() as u32) << 16) | self.time as u32
+    }
+}
+
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub enum HeartbeatEvent {
+    // The first heartbeat of a node, a boot-up message, or a heartbeat after a loss
+    NodeUp {
+        node_id: NodeId,
+        state: NmtState,
+    },
+    StateChanged {
+        node_id: NodeId,
+        from: NmtState,
+        to: NmtState,
+    },
+    // No heartbeat within the consumer heartbeat time. Reported once until the node is up
+    // again.
+    HeartbeatLost {
+        node_id: NodeId,
+        last_state: NmtState,
+    },
+}
+
+#[derive(Clone, Copy, Debug)]
+struct Consumer {
+    time: Option<std::time::Duration>,
+    last: Option<(std::time::Instant, NmtState)>,
+    lost: bool,
+}
+
+// Heartbeat consumer (cf. CiA 301). Feed it the heartbeats and boot-up messages received with
+// `on_frame`, and call `poll` periodically to detect losses. Events are returned, and also
+// sent to the channel given with `with_sender` if any.
+#[derive(Debug, Default)]
+pub struct HeartbeatMonitor {
+    consumers: std::collections::BTreeMap<u8, Consumer>,
+    sender: Option<std::sync::mpsc::Sender<HeartbeatEvent>>,
+}
+
+impl HeartbeatMonitor {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    pub fn with_sender(sender: std::sync::mpsc::Sender<HeartbeatEvent>) -> Self {
+        Self {
+            sender: Some(sender),
+            ..Self::default()
+        }
+    }
+
+    // Monitors `node_id` for losses. Heartbeats of other nodes are tracked, but never lost.
+    pub fn set_consumer_time(&mut self, node_id: NodeId, time: std::time::Duration) {
+        self.consumer(node_id).time = Some(time);
+    }
+
+    pub fn insert(&mut self, consumer_heartbeat_time: ConsumerHeartbeatTime) {
+        self.set_consumer_time(
+            consumer_heartbeat_time.node_id,
+            std::time::Duration::from_millis(consumer_heartbeat_time.time.into()),
+        );
+    }
+
+    pub fn remove(&mut self, node_id: NodeId) {
+        self.consumers.remove(&node_id.as_raw());
+    }
+
+    // The last state received from `node_id`, unless its heartbeat is lost
+    pub fn state(&self, node_id: NodeId) -> Option<NmtState> {
+        self.consumers
+            .get(&node_id.as_raw())
+            .filter(|consumer| !consumer.lost)
+            .and_then(|consumer| consumer.last)
+            .map(|(_, state)| state)
+    }
+
+    pub fn on_frame(
+        &mut self,
+        frame: &NmtNodeMonitoringFrame,
+        now: std::time::Instant,
+    ) -> Option<HeartbeatEvent> {
+        let consumer = self.consumer(frame.node_id);
+        let previous = consumer.last.filter(|_| !consumer.lost);
+        consumer.last = Some((now, frame.state));
+        consumer.lost = false;
+        let event = match previous {
+            None => HeartbeatEvent::NodeUp {
+                node_id: frame.node_id,
+                state: frame.state,
+            },
+            // A boot-up message means that the node has been reset.
+            Some(_) if frame.state == NmtState::BootUp => HeartbeatEvent::NodeUp {
+                node_id: frame.node_id,
+                state: frame.state,
+            },
+            Some((_, state)) if state == frame.state => return None,
+            Some((_, state)) => HeartbeatEvent::StateChanged {
+                node_id: frame.node_id,
+                from: state,
+                to: frame.state,
+            },
+        };
+        Some(self.emit(event))
+    }
+
+    // Reports the nodes whose heartbeat was not received within their consumer time.
+    pub fn poll(&mut self, now: std::time::Instant) -> std::vec::Vec<HeartbeatEvent> {
+        let mut events = std::vec::Vec::new();
+        for (raw_id, consumer) in self.consumers.iter_mut() {
+            let (Some(time), Some((received_at, state))) = (consumer.time, consumer.last) else {
+                continue;
+            };
+            if consumer.lost || now.saturating_duration_since(received_at) <= time {
+                continue;
+            }
+            consumer.lost = true;
+            events.push(HeartbeatEvent::HeartbeatLost {
+                node_id: (*raw_id).try_into().unwrap(),
+                last_state: state,
+            });
+        }
+        for event in &events {
+            self.emit(*event);
+        }
+        events
+    }
+
+    fn consumer(&mut self, node_id: NodeId) -> &mut Consumer {
+        self.consumers.entry(node_id.as_raw()).or_insert(Consumer {
+            time: None,
+            last: None,
+            lost: false,
+        })
+    }
+
+    fn emit(&self, event: HeartbeatEvent) -> HeartbeatEvent {
+        if let Some(sender) = &self.sender {
+            // The receiver may have been dropped; the event is returned anyway.
+            let _ = sender.send(event);
+        }
+        event
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    use crate::error::Error;
+    use std::time::Duration;
+
+    fn heartbeat(raw_id: u8, state: NmtState) -> NmtNodeMonitoringFrame {
+        NmtNodeMonitoringFrame::new(raw_id.try_into().unwrap(), state)
+    }
+
+    #[test]
+    fn test_consumer_heartbeat_time() {
+        assert_eq!(
+            ConsumerHeartbeatTime::from_u32(0x0003_01F4),
+            Ok(Some(ConsumerHeartbeatTime::new(3.try_into().unwrap(), 500)))
+        );
+        assert_eq!(ConsumerHeartbeatTime::from_u32(0x0000_01F4), Ok(None));
+        assert_eq!(ConsumerHeartbeatTime::from_u32(0x0003_0000), Ok(None));
+        assert_eq!(
+            ConsumerHeartbeatTime::from_u32(0x0080_01F4),
+            Err(Error::InvalidNodeId(0x80))
+        );
+        assert_eq!(
+            ConsumerHeartbeatTime::new(3.try_into().unwrap(), 500).as_u32(),
+            0x0003_01F4
+        );
+    }
+
+    #[test]
+    fn test_events() {
+        let now = std::time::Instant::now();
+        let node_id: NodeId = 3.try_into().unwrap();
+        let mut monitor = HeartbeatMonitor::new();
+        monitor.set_consumer_time(node_id, Duration::from_millis(100));
+        assert_eq!(monitor.poll(now + Duration::from_secs(1)), vec![]);
+
+        assert_eq!(
+            monitor.on_frame(&heartbeat(3, NmtState::BootUp), now),
+            Some(HeartbeatEvent::NodeUp {
+                node_id,
+                state: NmtState::BootUp
+            })
+        );
+        assert_eq!(
+            monitor.on_frame(&heartbeat(3, NmtState::PreOperational), now),
+            Some(HeartbeatEvent::StateChanged {
+                node_id,
+                from: NmtState::BootUp,
+                to: NmtState::PreOperational
+            })
+        );
+        assert_eq!(
+            monitor.on_frame(&heartbeat(3, NmtState::PreOperational), now),
+            None
+        );
+        assert_eq!(
+            monitor.on_frame(&heartbeat(3, NmtState::BootUp), now),
+            Some(HeartbeatEvent::NodeUp {
+                node_id,
+                state: NmtState::BootUp
+            })
+        );
+        assert_eq!(monitor.state(node_id), Some(NmtState::BootUp));
+
+        assert_eq!(monitor.poll(now + Duration::from_millis(100)), vec![]);
+        assert_eq!(
+            monitor.poll(now + Duration::from_millis(101)),
+            vec![HeartbeatEvent::HeartbeatLost {
+                node_id,
+                last_state: NmtState::BootUp
+            }]
+        );
+        assert_eq!(monitor.poll(now + Duration::from_millis(200)), vec![]);
+        assert_eq!(monitor.state(node_id), None);
+
+        assert_eq!(
+            monitor.on_frame(
+                &heartbeat(3, NmtState::Operational),
+                now + Duration::from_millis(300)
+            ),
+            Some(HeartbeatEvent::NodeUp {
+                node_id,
+                state: NmtState::Operational
+            })
+        );
+    }
+
+    #[test]
+    fn test_unmonitored_node() {
+        let now = std::time::Instant::now();
+        let mut monitor = HeartbeatMonitor::new();
+        monitor.on_frame(&heartbeat(5, NmtState::Operational), now);
+        assert_eq!(monitor.poll(now + Duration::from_secs(10)), vec![]);
+        assert_eq!(
+            monitor.state(5.try_into().unwrap()),
+            Some(NmtState::Operational)
+        );
+        monitor.remove(5.try_into().unwrap());
+        assert_eq!(monitor.state(5.try_into().unwrap()), None);
+    }
+
+    #[test]
+    fn test_sender() {
+        let now = std::time::Instant::now();
+        let (sender, receiver) = std::sync::mpsc::channel();
+        let mut monitor = HeartbeatMonitor::with_sender(sender);
+        monitor.insert(ConsumerHeartbeatTime::new(1.try_into().unwrap(), 10));
+        monitor.on_frame(&heartbeat(1, NmtState::Operational), now);
+        monitor.poll(now + Duration::from_millis(20));
+        assert_eq!(
+            receiver.try_iter().collect::<Vec<_>>(),
+            vec![
+                HeartbeatEvent::NodeUp {
+                    node_id: 1.try_into().unwrap(),
+                    state: NmtState::Operational
+                },
+                HeartbeatEvent::HeartbeatLost {
+                    node_id: 1.try_into().unwrap(),
+                    last_state: NmtState::Operational
+                },
+            ]
+        );
+        drop(receiver);
+        monitor.on_frame(&heartbeat(1, NmtState::Stopped), now);
+    }
+}
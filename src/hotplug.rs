@@ -0,0 +1,322 @@
+use crate::frame::{
+    NmtCommand, NmtNodeControlAddress, NmtNodeControlFrame, NmtNodeMonitoringFrame, NmtState,
+};
+use crate::id::NodeId;
+use crate::sdo::SdoDownload;
+
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub enum LifecycleEvent {
+    // The first heartbeat or boot-up message of a node, or the first one after it detached.
+    // `expected` tells whether the node was declared with `expect`.
+    Attached {
+        node_id: NodeId,
+        expected: bool,
+        state: NmtState,
+    },
+    // A boot-up message from an attached node: it has been reset or replugged quickly.
+    Rebooted {
+        node_id: NodeId,
+    },
+    // Nothing received from the node within the detach timeout
+    Detached {
+        node_id: NodeId,
+    },
+}
+
+// A step of a bring-up sequence, for the node it was configured for
+#[derive(Clone, Debug, PartialEq)]
+pub enum BringUpStep {
+    Write {
+        index: u16,
+        sub_index: u8,
+        data: std::vec::Vec<u8>,
+    },
+    Nmt(NmtCommand),
+}
+
+// What to do with a device when it attaches, e.g. configure the PDOs of a tool changer or a
+// battery and start it
+#[derive(Clone, Debug, Default, PartialEq)]
+pub struct BringUpSequence {
+    steps: std::vec::Vec<BringUpStep>,
+}
+
+impl BringUpSequence {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    pub fn write(mut self, index: u16, sub_index: u8, data: std::vec::Vec<u8>) -> Self {
+        self.steps.push(BringUpStep::Write {
+            index,
+            sub_index,
+            data,
+        });
+        self
+    }
+
+    pub fn nmt(mut self, command: NmtCommand) -> Self {
+        self.steps.push(BringUpStep::Nmt(command));
+        self
+    }
+
+    pub fn steps(&self) -> &[BringUpStep] {
+        &self.steps
+    }
+
+    // The transfers and frames to run in order for `node_id`
+    pub fn actions(&self, node_id: NodeId) -> std::vec::Vec<BringUpAction> {
+        self.steps
+            .iter()
+            .map(|step| match step {
+                BringUpStep::Write {
+                    index,
+                    sub_index,
+                    data,
+                } => BringUpAction::Download(SdoDownload::new(
+                    node_id,
+                    *index,
+                    *sub_index,
+                    data.clone(),
+                )),
+                BringUpStep::Nmt(command) => BringUpAction::Nmt(NmtNodeControlFrame::new(
+                    *command,
+                    NmtNodeControlAddress::Node(node_id),
+                )),
+            })
+            .collect()
+    }
+}
+
+#[derive(Clone, Debug)]
+pub enum BringUpAction {
+    Download(SdoDownload),
+    Nmt(NmtNodeControlFrame),
+}
+
+#[derive(Clone, Debug)]
+pub enum LifecycleOutput {
+    Event(LifecycleEvent),
+    // The bring-up sequence to run for a node that attached or rebooted
+    BringUp {
+        node_id: NodeId,
+        actions: std::vec::Vec<BringUpAction>,
+    },
+}
+
+// Detects nodes appearing on and disappearing from the bus from their heartbeats and boot-up
+// messages, fed with `on_frame`. `poll` reports the nodes that went silent. Nodes with a
+// bring-up sequence get it returned whenever they attach or reboot.
+#[derive(Clone, Debug)]
+pub struct NodeLifecycle {
+    detach_timeout: std::time::Duration,
+    expected: std::collections::BTreeSet<u8>,
+    bring_ups: std::collections::BTreeMap<u8, BringUpSequence>,
+    // Attached nodes and when they were last heard of
+    attached: std::collections::BTreeMap<u8, std::time::Instant>,
+}
+
+impl NodeLifecycle {
+    pub fn new(detach_timeout: std::time::Duration) -> Self {
+        Self {
+            detach_timeout,
+            expected: std::collections::BTreeSet::new(),
+            bring_ups: std::collections::BTreeMap::new(),
+            attached: std::collections::BTreeMap::new(),
+        }
+    }
+
+    pub fn expect(&mut self, node_id: NodeId) {
+        self.expected.insert(node_id.as_raw());
+    }
+
+    pub fn set_bring_up(&mut self, node_id: NodeId, sequence: BringUpSequence) {
+        self.bring_ups.insert(node_id.as_raw(), sequence);
+    }
+
+    pub fn remove_bring_up(&mut self, node_id: NodeId) {
+        self.bring_ups.remove(&node_id.as_raw());
+    }
+
+    pub fn is_attached(&self, node_id: NodeId) -> bool {
+        self.attached.contains_key(&node_id.as_raw())
+    }
+
+    pub fn attached(&self) -> impl Iterator<Item = NodeId> + '_ {
+        self.attached
+            .keys()
+            .map(|raw_id| (*raw_id).try_into().unwrap())
+    }
+
+    pub fn on_frame(
+        &mut self,
+        frame: &NmtNodeMonitoringFrame,
+        now: std::time::Instant,
+    ) -> std::vec::Vec<LifecycleOutput> {
+        let raw_id = frame.node_id.as_raw();
+        let event = match self.attached.insert(raw_id, now) {
+            None => LifecycleEvent::Attached {
+                node_id: frame.node_id,
+                expected: self.expected.contains(&raw_id),
+                state: frame.state,
+            },
+            Some(_) if frame.state == NmtState::BootUp => LifecycleEvent::Rebooted {
+                node_id: frame.node_id,
+            },
+            Some(_) => return std::vec::Vec::new(),
+        };
+        let mut outputs = vec![LifecycleOutput::Event(event)];
+        if let Some(sequence) = self.bring_ups.get(&raw_id) {
+            outputs.push(LifecycleOutput::BringUp {
+                node_id: frame.node_id,
+                actions: sequence.actions(frame.node_id),
+            });
+        }
+        outputs
+    }
+
+    pub fn poll(&mut self, now: std::time::Instant) -> std::vec::Vec<LifecycleEvent> {
+        let mut events = std::vec::Vec::new();
+        self.attached.retain(|raw_id, heard_at| {
+            if now.saturating_duration_since(*heard_at) <= self.detach_timeout {
+                return true;
+            }
+            events.push(LifecycleEvent::Detached {
+                node_id: (*raw_id).try_into().unwrap(),
+            });
+            false
+        });
+        events
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    use std::time::Duration;
+
+    fn heartbeat(raw_id: u8, state: NmtState) -> NmtNodeMonitoringFrame {
+        NmtNodeMonitoringFrame::new(raw_id.try_into().unwrap(), state)
+    }
+
+    fn events(outputs: &[LifecycleOutput]) -> std::vec::Vec<LifecycleEvent> {
+        outputs
+            .iter()
+            .filter_map(|output| match output {
+                LifecycleOutput::Event(event) => Some(*event),
+                LifecycleOutput::BringUp { .. } => None,
+            })
+            .collect()
+    }
+
+    #[test]
+    fn test_attach_detach() {
+        let now = std::time::Instant::now();
+        let mut lifecycle = NodeLifecycle::new(Duration::from_millis(500));
+        lifecycle.expect(2.try_into().unwrap());
+        assert_eq!(
+            events(&lifecycle.on_frame(&heartbeat(2, NmtState::Operational), now)),
+            vec![LifecycleEvent::Attached {
+                node_id: 2.try_into().unwrap(),
+                expected: true,
+                state: NmtState::Operational
+            }]
+        );
+        assert_eq!(
+            events(&lifecycle.on_frame(&heartbeat(9, NmtState::BootUp), now)),
+            vec![LifecycleEvent::Attached {
+                node_id: 9.try_into().unwrap(),
+                expected: false,
+                state: NmtState::BootUp
+            }]
+        );
+        assert!(lifecycle
+            .on_frame(
+                &heartbeat(2, NmtState::Operational),
+                now + Duration::from_millis(400)
+            )
+            .is_empty());
+        assert_eq!(
+            events(&lifecycle.on_frame(
+                &heartbeat(2, NmtState::BootUp),
+                now + Duration::from_millis(400)
+            )),
+            vec![LifecycleEvent::Rebooted {
+                node_id: 2.try_into().unwrap()
+            }]
+        );
+
+        assert_eq!(
+            lifecycle.poll(now + Duration::from_millis(600)),
+            vec![LifecycleEvent::Detached {
+                node_id: 9.try_into().unwrap()
+            }]
+        );
+        assert_eq!(
+            lifecycle.attached().collect::<Vec<_>>(),
+            vec![2.try_into().unwrap()]
+        );
+        assert!(!lifecycle.is_attached(9.try_into().unwrap()));
+        assert_eq!(
+            events(&lifecycle.on_frame(
+                &heartbeat(9, NmtState::PreOperational),
+                now + Duration::from_millis(700)
+            ))
+            .len(),
+            1
+        );
+    }
+
+    #[test]
+    fn test_bring_up() {
+        let now = std::time::Instant::now();
+        let node_id: NodeId = 5.try_into().unwrap();
+        let sequence = BringUpSequence::new()
+            .write(0x1800, 2, vec![0x01])
+            .nmt(NmtCommand::Operational);
+        let mut lifecycle = NodeLifecycle::new(Duration::from_millis(500));
+        lifecycle.set_bring_up(node_id, sequence.clone());
+        assert_eq!(sequence.steps().len(), 2);
+
+        let outputs = lifecycle.on_frame(&heartbeat(5, NmtState::BootUp), now);
+        let [LifecycleOutput::Event(_), LifecycleOutput::BringUp {
+            node_id: bring_up_id,
+            actions,
+        }] = outputs.as_slice()
+        else {
+            panic!("unexpected outputs: {outputs:?}");
+        };
+        assert_eq!(*bring_up_id, node_id);
+        let [BringUpAction::Download(download), BringUpAction::Nmt(frame)] = actions.as_slice()
+        else {
+            panic!("unexpected actions: {actions:?}");
+        };
+        assert_eq!(
+            download.clone().start().data(),
+            &[0x2F, 0x00, 0x18, 0x02, 0x01, 0x00, 0x00, 0x00]
+        );
+        assert_eq!(
+            *frame,
+            NmtNodeControlFrame::new(
+                NmtCommand::Operational,
+                NmtNodeControlAddress::Node(node_id)
+            )
+        );
+
+        // Again after a reboot
+        assert_eq!(
+            lifecycle
+                .on_frame(&heartbeat(5, NmtState::BootUp), now)
+                .len(),
+            2
+        );
+        lifecycle.remove_bring_up(node_id);
+        assert_eq!(
+            lifecycle
+                .on_frame(&heartbeat(5, NmtState::BootUp), now)
+                .len(),
+            1
+        );
+    }
+}
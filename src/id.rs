@@ -5,8 +5,8 @@ pub struct NodeId(u8);
 
 impl NodeId {
     pub fn new(raw_id: u8) -> Result<Self> {
-        match raw_id & 0x80 {
-            0 => Ok(Self(raw_id)),
+        match raw_id {
+            1..=127 => Ok(Self(raw_id)),
             _ => Err(Error::InvalidNodeId(raw_id)),
         }
     }
@@ -23,6 +23,40 @@ impl TryFrom<u8> for NodeId {
     }
 }
 
+impl std::fmt::Display for NodeId {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(f, "{}", self.0)
+    }
+}
+
+// Accepts "12", "0x0C" and "node 12".
+impl std::str::FromStr for NodeId {
+    type Err = Error;
+    fn from_str(s: &str) -> Result<Self> {
+        let trimmed = s.trim();
+        let number = match trimmed.get(..4) {
+            Some(prefix) if prefix.eq_ignore_ascii_case("node") => trimmed[4..].trim_start(),
+            _ => trimmed,
+        };
+        let raw_id = parse_integer(number).ok_or_else(|| parse_failed(s, "NodeId"))?;
+        NodeId::new(u8::try_from(raw_id).map_err(|_| parse_failed(s, "NodeId"))?)
+    }
+}
+
+fn parse_integer(s: &str) -> Option<u32> {
+    match s.strip_prefix("0x").or_else(|| s.strip_prefix("0X")) {
+        Some(hex) => u32::from_str_radix(hex, 16).ok(),
+        None => s.parse().ok(),
+    }
+}
+
+fn parse_failed(input: &str, data_type: &str) -> Error {
+    Error::ParseFailed {
+        input: input.to_owned(),
+        data_type: data_type.to_owned(),
+    }
+}
+
 #[derive(Copy, Clone, Debug, PartialEq)]
 pub enum CommunicationObject {
     NmtNodeControl,
@@ -46,50 +80,55 @@ pub enum CommunicationObject {
 }
 
 #[inline]
-fn get_node_id_from_cob_id(cob_id: u16) -> NodeId {
-    NodeId::new((cob_id & 0x7F) as u8)
-        .expect("Should not have failed because the passed raw ID is masked.")
+fn get_node_id_from_cob_id(cob_id: u16) -> Result<NodeId> {
+    NodeId::new((cob_id & 0x7F) as u8).map_err(|_| Error::InvalidCobId(cob_id.into()))
 }
 
 impl CommunicationObject {
-    pub(crate) fn new(id: u16) -> Result<Self> {
+    /// Classifies an 11-bit CAN identifier according to the CiA 301 predefined connection set.
+    ///
+    /// This is part of the stable public API.
+    pub fn new(id: u16) -> Result<Self> {
         match id & !0x07FF {
             0 => match id & 0b00000111_10000000 {
                 0x000 => match id {
                     0 => Ok(CommunicationObject::NmtNodeControl),
                     1 => Ok(CommunicationObject::GlobalFailsafeCommand),
-                    _ => Err(Error::InvalidCobId(id)),
+                    _ => Err(Error::InvalidCobId(id.into())),
                 },
                 0x080 => match id & 0x007F {
                     0 => Ok(CommunicationObject::Sync),
-                    _ => Ok(CommunicationObject::Emergency(get_node_id_from_cob_id(id))),
+                    _ => Ok(CommunicationObject::Emergency(get_node_id_from_cob_id(id)?)),
                 },
                 0x100 => Ok(CommunicationObject::TimeStamp),
-                0x180 => Ok(CommunicationObject::TxPdo1(get_node_id_from_cob_id(id))),
-                0x200 => Ok(CommunicationObject::RxPdo1(get_node_id_from_cob_id(id))),
-                0x280 => Ok(CommunicationObject::TxPdo2(get_node_id_from_cob_id(id))),
-                0x300 => Ok(CommunicationObject::RxPdo2(get_node_id_from_cob_id(id))),
-                0x380 => Ok(CommunicationObject::TxPdo3(get_node_id_from_cob_id(id))),
-                0x400 => Ok(CommunicationObject::RxPdo3(get_node_id_from_cob_id(id))),
-                0x480 => Ok(CommunicationObject::TxPdo4(get_node_id_from_cob_id(id))),
-                0x500 => Ok(CommunicationObject::RxPdo4(get_node_id_from_cob_id(id))),
-                0x580 => Ok(CommunicationObject::TxSdo(get_node_id_from_cob_id(id))),
-                0x600 => Ok(CommunicationObject::RxSdo(get_node_id_from_cob_id(id))),
+                0x180 => Ok(CommunicationObject::TxPdo1(get_node_id_from_cob_id(id)?)),
+                0x200 => Ok(CommunicationObject::RxPdo1(get_node_id_from_cob_id(id)?)),
+                0x280 => Ok(CommunicationObject::TxPdo2(get_node_id_from_cob_id(id)?)),
+                0x300 => Ok(CommunicationObject::RxPdo2(get_node_id_from_cob_id(id)?)),
+                0x380 => Ok(CommunicationObject::TxPdo3(get_node_id_from_cob_id(id)?)),
+                0x400 => Ok(CommunicationObject::RxPdo3(get_node_id_from_cob_id(id)?)),
+                0x480 => Ok(CommunicationObject::TxPdo4(get_node_id_from_cob_id(id)?)),
+                0x500 => Ok(CommunicationObject::RxPdo4(get_node_id_from_cob_id(id)?)),
+                0x580 => Ok(CommunicationObject::TxSdo(get_node_id_from_cob_id(id)?)),
+                0x600 => Ok(CommunicationObject::RxSdo(get_node_id_from_cob_id(id)?)),
                 0x700 => Ok(CommunicationObject::NmtNodeMonitoring(
-                    get_node_id_from_cob_id(id),
+                    get_node_id_from_cob_id(id)?,
                 )),
                 0x780 => match id {
                     0x7E4 => Ok(CommunicationObject::TxLss),
                     0x7E5 => Ok(CommunicationObject::RxLss),
-                    _ => Err(Error::InvalidCobId(id)),
+                    _ => Err(Error::InvalidCobId(id.into())),
                 },
-                _ => Err(Error::InvalidCobId(id)),
+                _ => Err(Error::InvalidCobId(id.into())),
             },
-            _ => Err(Error::InvalidCobId(id)),
+            _ => Err(Error::InvalidCobId(id.into())),
         }
     }
 
-    pub(crate) fn as_cob_id(&self) -> u16 {
+    /// Returns the 11-bit CAN identifier of this communication object.
+    ///
+    /// This is part of the stable public API.
+    pub fn as_cob_id(&self) -> u16 {
         match self {
             CommunicationObject::NmtNodeControl => 0x000,
             CommunicationObject::GlobalFailsafeCommand => 0x001,
@@ -113,12 +152,200 @@ impl CommunicationObject {
     }
 }
 
+impl CommunicationObject {
+    const NAMES: [&'static str; 18] = [
+        "NmtNodeControl",
+        "GlobalFailsafeCommand",
+        "Sync",
+        "Emergency",
+        "TimeStamp",
+        "TxPdo1",
+        "RxPdo1",
+        "TxPdo2",
+        "RxPdo2",
+        "TxPdo3",
+        "RxPdo3",
+        "TxPdo4",
+        "RxPdo4",
+        "TxSdo",
+        "RxSdo",
+        "NmtNodeMonitoring",
+        "TxLss",
+        "RxLss",
+    ];
+
+    fn name(&self) -> &'static str {
+        match self {
+            CommunicationObject::NmtNodeControl => "NmtNodeControl",
+            CommunicationObject::GlobalFailsafeCommand => "GlobalFailsafeCommand",
+            CommunicationObject::Sync => "Sync",
+            CommunicationObject::Emergency(_) => "Emergency",
+            CommunicationObject::TimeStamp => "TimeStamp",
+            CommunicationObject::TxPdo1(_) => "TxPdo1",
+            CommunicationObject::RxPdo1(_) => "RxPdo1",
+            CommunicationObject::TxPdo2(_) => "TxPdo2",
+            CommunicationObject::RxPdo2(_) => "RxPdo2",
+            CommunicationObject::TxPdo3(_) => "TxPdo3",
+            CommunicationObject::RxPdo3(_) => "RxPdo3",
+            CommunicationObject::TxPdo4(_) => "TxPdo4",
+            CommunicationObject::RxPdo4(_) => "RxPdo4",
+            CommunicationObject::TxSdo(_) => "TxSdo",
+            CommunicationObject::RxSdo(_) => "RxSdo",
+            CommunicationObject::NmtNodeMonitoring(_) => "NmtNodeMonitoring",
+            CommunicationObject::TxLss => "TxLss",
+            CommunicationObject::RxLss => "RxLss",
+        }
+    }
+
+    pub fn node_id(&self) -> Option<NodeId> {
+        match self {
+            CommunicationObject::Emergency(node_id)
+            | CommunicationObject::TxPdo1(node_id)
+            | CommunicationObject::RxPdo1(node_id)
+            | CommunicationObject::TxPdo2(node_id)
+            | CommunicationObject::RxPdo2(node_id)
+            | CommunicationObject::TxPdo3(node_id)
+            | CommunicationObject::RxPdo3(node_id)
+            | CommunicationObject::TxPdo4(node_id)
+            | CommunicationObject::RxPdo4(node_id)
+            | CommunicationObject::TxSdo(node_id)
+            | CommunicationObject::RxSdo(node_id)
+            | CommunicationObject::NmtNodeMonitoring(node_id) => Some(*node_id),
+            _ => None,
+        }
+    }
+
+    fn from_name(name: &str, node_id: Option<NodeId>) -> Option<Self> {
+        match (name, node_id) {
+            ("NmtNodeControl", None) => Some(CommunicationObject::NmtNodeControl),
+            ("GlobalFailsafeCommand", None) => Some(CommunicationObject::GlobalFailsafeCommand),
+            ("Sync", None) => Some(CommunicationObject::Sync),
+            ("Emergency", Some(node_id)) => Some(CommunicationObject::Emergency(node_id)),
+            ("TimeStamp", None) => Some(CommunicationObject::TimeStamp),
+            ("TxPdo1", Some(node_id)) => Some(CommunicationObject::TxPdo1(node_id)),
+            ("RxPdo1", Some(node_id)) => Some(CommunicationObject::RxPdo1(node_id)),
+            ("TxPdo2", Some(node_id)) => Some(CommunicationObject::TxPdo2(node_id)),
+            ("RxPdo2", Some(node_id)) => Some(CommunicationObject::RxPdo2(node_id)),
+            ("TxPdo3", Some(node_id)) => Some(CommunicationObject::TxPdo3(node_id)),
+            ("RxPdo3", Some(node_id)) => Some(CommunicationObject::RxPdo3(node_id)),
+            ("TxPdo4", Some(node_id)) => Some(CommunicationObject::TxPdo4(node_id)),
+            ("RxPdo4", Some(node_id)) => Some(CommunicationObject::RxPdo4(node_id)),
+            ("TxSdo", Some(node_id)) => Some(CommunicationObject::TxSdo(node_id)),
+            ("RxSdo", Some(node_id)) => Some(CommunicationObject::RxSdo(node_id)),
+            ("NmtNodeMonitoring", Some(node_id)) => {
+                Some(CommunicationObject::NmtNodeMonitoring(node_id))
+            }
+            ("TxLss", None) => Some(CommunicationObject::TxLss),
+            ("RxLss", None) => Some(CommunicationObject::RxLss),
+            _ => None,
+        }
+    }
+}
+
+impl std::fmt::Display for CommunicationObject {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self.node_id() {
+            Some(node_id) => write!(f, "{}({})", self.name(), node_id),
+            None => write!(f, "{}", self.name()),
+        }
+    }
+}
+
+// Accepts a COB-ID ("0x601", "1537") or a variant name as printed by `Display` ("RxSdo(1)",
+// "Sync"). Names are case-insensitive.
+impl std::str::FromStr for CommunicationObject {
+    type Err = Error;
+    fn from_str(s: &str) -> Result<Self> {
+        let trimmed = s.trim();
+        if let Some(id) = parse_integer(trimmed) {
+            return CommunicationObject::try_from(id);
+        }
+        let (name, node_id) = match trimmed.split_once('(') {
+            Some((name, rest)) => {
+                let node_id = rest
+                    .strip_suffix(')')
+                    .ok_or_else(|| parse_failed(s, "CommunicationObject"))?;
+                (name.trim(), Some(node_id.parse::<NodeId>()?))
+            }
+            None => (trimmed, None),
+        };
+        let name = Self::NAMES
+            .iter()
+            .find(|candidate| candidate.eq_ignore_ascii_case(name))
+            .ok_or_else(|| parse_failed(s, "CommunicationObject"))?;
+        Self::from_name(name, node_id).ok_or_else(|| parse_failed(s, "CommunicationObject"))
+    }
+}
+
+#[cfg(feature = "serde")]
+mod serde_impl {
+    use super::{CommunicationObject, NodeId};
+
+    macro_rules! impl_serde_as_string {
+        ($($t:ty),*) => {
+            $(
+                impl serde::Serialize for $t {
+                    fn serialize<S: serde::Serializer>(
+                        &self,
+                        serializer: S,
+                    ) -> std::result::Result<S::Ok, S::Error> {
+                        serializer.collect_str(self)
+                    }
+                }
+
+                impl<'de> serde::Deserialize<'de> for $t {
+                    fn deserialize<D: serde::Deserializer<'de>>(
+                        deserializer: D,
+                    ) -> std::result::Result<Self, D::Error> {
+                        let s = <std::string::String as serde::Deserialize>::deserialize(
+                            deserializer,
+                        )?;
+                        s.parse().map_err(serde::de::Error::custom)
+                    }
+                }
+            )*
+        };
+    }
+
+    impl_serde_as_string!(NodeId, CommunicationObject);
+}
+
+impl TryFrom<u16> for CommunicationObject {
+    type Error = Error;
+    fn try_from(id: u16) -> Result<Self> {
+        CommunicationObject::new(id)
+    }
+}
+
+impl TryFrom<u32> for CommunicationObject {
+    type Error = Error;
+    fn try_from(id: u32) -> Result<Self> {
+        match u16::try_from(id) {
+            Ok(id) if id <= 0x07FF => CommunicationObject::new(id),
+            _ => Err(Error::InvalidCobId(id)),
+        }
+    }
+}
+
+impl From<CommunicationObject> for u16 {
+    fn from(cob: CommunicationObject) -> Self {
+        cob.as_cob_id()
+    }
+}
+
+impl From<CommunicationObject> for u32 {
+    fn from(cob: CommunicationObject) -> Self {
+        cob.as_cob_id().into()
+    }
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
 
     #[test]
     fn test_node_id_new() {
+        assert!(NodeId::new(0).is_err());
         assert_eq!(NodeId::new(1), Ok(NodeId(1)));
         assert_eq!(NodeId::new(2), Ok(NodeId(2)));
         assert_eq!(NodeId::new(3), Ok(NodeId(3)));
@@ -129,6 +356,8 @@ mod tests {
 
     #[test]
     fn test_node_id_try_into() {
+        let node_id: Result<NodeId> = 0.try_into();
+        assert!(node_id.is_err());
         let node_id: Result<NodeId> = 1.try_into();
         assert_eq!(node_id, Ok(NodeId(1)));
         let node_id: Result<NodeId> = 2.try_into();
@@ -325,4 +554,150 @@ mod tests {
         let cob = CommunicationObject::new(0x7E5);
         assert_eq!(cob, Ok(CommunicationObject::RxLss));
     }
+
+    #[test]
+    fn test_try_from_u16() {
+        let cob: Result<CommunicationObject> = 0x601u16.try_into();
+        assert_eq!(cob, Ok(CommunicationObject::RxSdo(1.try_into().unwrap())));
+        let cob: Result<CommunicationObject> = 0x800u16.try_into();
+        assert_eq!(cob, Err(Error::InvalidCobId(0x800)));
+    }
+
+    #[test]
+    fn test_try_from_u32() {
+        let cob: Result<CommunicationObject> = 0x000u32.try_into();
+        assert_eq!(cob, Ok(CommunicationObject::NmtNodeControl));
+        let cob: Result<CommunicationObject> = 0x58Au32.try_into();
+        assert_eq!(cob, Ok(CommunicationObject::TxSdo(10.try_into().unwrap())));
+        let cob: Result<CommunicationObject> = 0x7E5u32.try_into();
+        assert_eq!(cob, Ok(CommunicationObject::RxLss));
+        let cob: Result<CommunicationObject> = 0x800u32.try_into();
+        assert_eq!(cob, Err(Error::InvalidCobId(0x800)));
+        let cob: Result<CommunicationObject> = 0x1_0601u32.try_into();
+        assert_eq!(cob, Err(Error::InvalidCobId(0x1_0601)));
+        let cob: Result<CommunicationObject> = 0x1FFF_FFFFu32.try_into();
+        assert_eq!(cob, Err(Error::InvalidCobId(0x1FFF_FFFF)));
+    }
+
+    #[test]
+    fn test_into_integer() {
+        let id: u16 = CommunicationObject::Sync.into();
+        assert_eq!(id, 0x080);
+        let id: u32 = CommunicationObject::TxPdo1(2.try_into().unwrap()).into();
+        assert_eq!(id, 0x182);
+        let id: u32 = CommunicationObject::RxLss.into();
+        assert_eq!(id, 0x7E5);
+    }
+
+    #[test]
+    fn test_new_without_node_id() {
+        for id in [
+            0x180, 0x200, 0x280, 0x300, 0x380, 0x400, 0x480, 0x500, 0x580, 0x600, 0x700,
+        ] {
+            assert_eq!(
+                CommunicationObject::new(id),
+                Err(Error::InvalidCobId(id.into()))
+            );
+        }
+    }
+
+    #[test]
+    fn test_new_invalid() {
+        assert_eq!(
+            CommunicationObject::new(0x002),
+            Err(Error::InvalidCobId(0x002))
+        );
+        assert_eq!(
+            CommunicationObject::new(0x680),
+            Err(Error::InvalidCobId(0x680))
+        );
+        assert_eq!(
+            CommunicationObject::new(0x7E6),
+            Err(Error::InvalidCobId(0x7E6))
+        );
+        assert_eq!(
+            CommunicationObject::new(0x800),
+            Err(Error::InvalidCobId(0x800))
+        );
+    }
+
+    #[test]
+    fn test_node_id_display() {
+        assert_eq!(NodeId(12).to_string(), "12");
+    }
+
+    #[test]
+    fn test_node_id_from_str() {
+        assert_eq!("12".parse(), Ok(NodeId(12)));
+        assert_eq!("0x7F".parse(), Ok(NodeId(127)));
+        assert_eq!("node 12".parse(), Ok(NodeId(12)));
+        assert_eq!(" Node 0x0C ".parse(), Ok(NodeId(12)));
+        assert_eq!("0".parse::<NodeId>(), Err(Error::InvalidNodeId(0)));
+        assert_eq!("128".parse::<NodeId>(), Err(Error::InvalidNodeId(128)));
+        assert_eq!(
+            "256".parse::<NodeId>(),
+            Err(Error::ParseFailed {
+                input: "256".to_owned(),
+                data_type: "NodeId".to_owned()
+            })
+        );
+        assert!("nodes".parse::<NodeId>().is_err());
+    }
+
+    #[test]
+    fn test_communication_object_display() {
+        assert_eq!(CommunicationObject::Sync.to_string(), "Sync");
+        assert_eq!(
+            CommunicationObject::RxSdo(1.try_into().unwrap()).to_string(),
+            "RxSdo(1)"
+        );
+        assert_eq!(
+            CommunicationObject::NmtNodeMonitoring(127.try_into().unwrap()).to_string(),
+            "NmtNodeMonitoring(127)"
+        );
+    }
+
+    #[test]
+    fn test_communication_object_from_str() {
+        assert_eq!(
+            "0x601".parse(),
+            Ok(CommunicationObject::RxSdo(1.try_into().unwrap()))
+        );
+        assert_eq!("128".parse(), Ok(CommunicationObject::Sync));
+        assert_eq!(
+            "RxSdo(1)".parse(),
+            Ok(CommunicationObject::RxSdo(1.try_into().unwrap()))
+        );
+        assert_eq!(
+            "txpdo3(0x10)".parse(),
+            Ok(CommunicationObject::TxPdo3(16.try_into().unwrap()))
+        );
+        assert_eq!("TimeStamp".parse(), Ok(CommunicationObject::TimeStamp));
+        assert_eq!(
+            "0x800".parse::<CommunicationObject>(),
+            Err(Error::InvalidCobId(0x800))
+        );
+        assert_eq!(
+            "RxSdo(0)".parse::<CommunicationObject>(),
+            Err(Error::InvalidNodeId(0))
+        );
+        for input in ["Sync(1)", "RxSdo", "RxSdo(1", "Foo(1)", ""] {
+            assert_eq!(
+                input.parse::<CommunicationObject>(),
+                Err(Error::ParseFailed {
+                    input: input.to_owned(),
+                    data_type: "CommunicationObject".to_owned()
+                })
+            );
+        }
+    }
+
+    #[test]
+    fn test_communication_object_round_trip() {
+        for id in 0..=0x7FF {
+            if let Ok(cob) = CommunicationObject::new(id) {
+                assert_eq!(cob.to_string().parse(), Ok(cob));
+            }
+        }
+    }
 }
@@ -0,0 +1,164 @@
+use crate::error::{Error, Result};
+
+// The state of a SocketCAN interface as reported by netlink. The bitrate and sample point are
+// not included: the netlink API of socketcan 2.1 only reports the name, index, up/down state
+// and MTU (`InterfaceDetails`), not the bit timing (`IFLA_CAN_BITTIMING`). Use
+// `ip -details link show` to check them.
+#[derive(Clone, Debug, PartialEq)]
+pub struct InterfaceState {
+    pub name: String,
+    pub is_up: bool,
+    pub fd: Option<bool>,
+}
+
+impl InterfaceState {
+    pub fn query(interface_name: &str) -> Result<Self> {
+        let details = open(interface_name)?
+            .details()
+            .map_err(|e| Error::InterfaceQueryFailed {
+                interface_name: interface_name.to_owned(),
+                message: e.to_string(),
+            })?;
+        Ok(Self {
+            name: details.name.unwrap_or_else(|| interface_name.to_owned()),
+            is_up: details.is_up,
+            fd: details.mtu.map(|mtu| mtu as usize == libc::CANFD_MTU),
+        })
+    }
+}
+
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub struct ExpectedInterfaceState {
+    pub is_up: bool,
+    pub fd: bool,
+    // Not supported yet: `check` fails with `Error::NotImplemented` when it is set, rather than
+    // passing an interface with the wrong bit timing.
+    pub bitrate: Option<u32>,
+}
+
+impl Default for ExpectedInterfaceState {
+    fn default() -> Self {
+        Self {
+            is_up: true,
+            fd: false,
+            bitrate: None,
+        }
+    }
+}
+
+impl ExpectedInterfaceState {
+    // Checks the up/down state and the classic/FD MTU. An expected bitrate cannot be checked, as
+    // `InterfaceState` cannot report it (see above), so it is an error. The sample point is not
+    // checked either.
+    pub fn check(&self, state: &InterfaceState) -> Result<()> {
+        if self.bitrate.is_some() {
+            return Err(Error::NotImplemented);
+        }
+        if state.is_up != self.is_up {
+            return Err(Error::InterfaceMismatch {
+                interface_name: state.name.clone(),
+                message: format!("expected is_up = {}, got {}", self.is_up, state.is_up),
+            });
+        }
+        match state.fd {
+            Some(fd) if fd != self.fd => Err(Error::InterfaceMismatch {
+                interface_name: state.name.clone(),
+                message: format!("expected fd = {}, got {}", self.fd, fd),
+            }),
+            Some(_) => Ok(()),
+            None => Err(Error::InterfaceMismatch {
+                interface_name: state.name.clone(),
+                message: "not a CAN interface".to_owned(),
+            }),
+        }
+    }
+}
+
+pub fn set_interface_up(interface_name: &str, up: bool) -> Result<()> {
+    let interface = open(interface_name)?;
+    let result = if up {
+        interface.bring_up()
+    } else {
+        interface.bring_down()
+    };
+    result.map_err(|e| Error::InterfaceQueryFailed {
+        interface_name: interface_name.to_owned(),
+        message: e.to_string(),
+    })
+}
+
+fn open(interface_name: &str) -> Result<socketcan::CanInterface> {
+    socketcan::CanInterface::open(interface_name).map_err(|e| Error::InterfaceQueryFailed {
+        interface_name: interface_name.to_owned(),
+        message: e.to_string(),
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_expected_interface_state_check() {
+        let expected = ExpectedInterfaceState::default();
+        let state = InterfaceState {
+            name: "can0".to_owned(),
+            is_up: true,
+            fd: Some(false),
+        };
+        assert_eq!(expected.check(&state), Ok(()));
+
+        let state = InterfaceState {
+            name: "can0".to_owned(),
+            is_up: false,
+            fd: Some(false),
+        };
+        assert_eq!(
+            expected.check(&state),
+            Err(Error::InterfaceMismatch {
+                interface_name: "can0".to_owned(),
+                message: "expected is_up = true, got false".to_owned()
+            })
+        );
+
+        let state = InterfaceState {
+            name: "can0".to_owned(),
+            is_up: true,
+            fd: Some(true),
+        };
+        assert_eq!(
+            expected.check(&state),
+            Err(Error::InterfaceMismatch {
+                interface_name: "can0".to_owned(),
+                message: "expected fd = false, got true".to_owned()
+            })
+        );
+
+        let state = InterfaceState {
+            name: "lo".to_owned(),
+            is_up: true,
+            fd: None,
+        };
+        assert!(expected.check(&state).is_err());
+
+        // The bitrate is not silently ignored
+        let expected = ExpectedInterfaceState {
+            bitrate: Some(500_000),
+            ..Default::default()
+        };
+        let state = InterfaceState {
+            name: "can0".to_owned(),
+            is_up: true,
+            fd: Some(false),
+        };
+        assert_eq!(expected.check(&state), Err(Error::NotImplemented));
+    }
+
+    #[test]
+    fn test_query_missing_interface() {
+        assert!(matches!(
+            InterfaceState::query("canopen-rs-missing"),
+            Err(Error::InterfaceQueryFailed { .. })
+        ));
+    }
+}
@@ -0,0 +1,361 @@
+use crate::frame::{
+    CanOpenFrame, ConvertibleFrame, Direction, LssFrame, NmtCommand, NmtNodeControlAddress,
+    NmtState,
+};
+use crate::heartbeat::HeartbeatEvent;
+
+// A stable JSON representation of frames and events for log pipelines, described by
+// `SCHEMA`. Field names and values only change with `SCHEMA_VERSION`, unlike `Debug`.
+pub const SCHEMA_VERSION: u32 = 1;
+
+pub const SCHEMA: &str = r##"{
+  "$schema": "https://json-schema.org/draft/2020-12/schema",
+  "$id": "https://github.com/smilerobotics/canopen-rs/schema/record-v1.json",
+  "title": "CANopen log record",
+  "type": "object",
+  "required": ["schema_version", "timestamp_us"],
+  "properties": {
+    "schema_version": { "const": 1 },
+    "timestamp_us": { "type": "integer", "description": "Microseconds since the UNIX epoch" },
+    "frame": { "$ref": "#/$defs/frame" },
+    "event": { "$ref": "#/$defs/event" }
+  },
+  "oneOf": [{ "required": ["frame"] }, { "required": ["event"] }],
+  "$defs": {
+    "node_id": { "type": "integer", "minimum": 1, "maximum": 127 },
+    "nmt_state": { "enum": ["boot_up", "stopped", "operational", "pre_operational"] },
+    "frame": {
+      "type": "object",
+      "required": ["type", "cob_id", "cob", "node_id", "remote", "data"],
+      "properties": {
+        "type": {
+          "enum": [
+            "nmt_node_control", "sync", "emergency", "time_stamp", "sdo", "pdo",
+            "nmt_node_monitoring", "lss", "remote"
+          ]
+        },
+        "cob_id": { "type": "integer", "minimum": 0, "maximum": 2047 },
+        "cob": { "type": "string", "description": "e.g. \"TxPdo1(3)\"" },
+        "node_id": { "oneOf": [{ "$ref": "#/$defs/node_id" }, { "type": "null" }] },
+        "remote": { "type": "boolean" },
+        "data": { "type": "string", "pattern": "^([0-9a-f]{2}){0,8}$" },
+        "command": {
+          "enum": ["operational", "stopped", "pre_operational", "reset_node", "reset_communication"]
+        },
+        "address": { "type": "integer", "description": "0 for all nodes" },
+        "error_code": { "type": "integer" },
+        "error_register": { "type": "integer" },
+        "milliseconds": { "type": "integer" },
+        "days": { "type": "integer" },
+        "direction": { "enum": ["tx", "rx"] },
+        "abort_code": { "type": "integer" },
+        "number": { "type": "integer", "minimum": 1, "maximum": 4 },
+        "state": { "$ref": "#/$defs/nmt_state" },
+        "toggle": { "type": "boolean" },
+        "lss": { "enum": ["request", "response"] },
+        "data_length": { "type": "integer", "minimum": 0, "maximum": 8 }
+      }
+    },
+    "event": {
+      "type": "object",
+      "required": ["type", "node_id"],
+      "properties": {
+        "type": { "enum": ["node_up", "state_changed", "heartbeat_lost"] },
+        "node_id": { "$ref": "#/$defs/node_id" },
+        "state": { "$ref": "#/$defs/nmt_state" },
+        "from": { "$ref": "#/$defs/nmt_state" },
+        "to": { "$ref": "#/$defs/nmt_state" }
+      }
+    }
+  }
+}"##;
+
+pub fn frame_to_json(frame: &CanOpenFrame) -> std::string::String {
+    let cob = frame.communication_object();
+    let (frame_type, fields) = match frame {
+        CanOpenFrame::NmtNodeControlFrame(frame) => (
+            "nmt_node_control",
+            format!(
+                ",\"command\":\"{}\",\"address\":{}",
+                nmt_command_name(frame.command),
+                match frame.address {
+                    NmtNodeControlAddress::AllNodes => 0,
+                    NmtNodeControlAddress::Node(node_id) => node_id.as_raw(),
+                }
+            ),
+        ),
+        CanOpenFrame::SyncFrame(frame) => (
+            "sync",
+            match frame.counter() {
+                Some(counter) => format!(",\"counter\":{counter}"),
+                None => std::string::String::new(),
+            },
+        ),
+        CanOpenFrame::EmergencyFrame(frame) => (
+            "emergency",
+            format!(
+                ",\"error_code\":{},\"error_register\":{}",
+                frame.error_code,
+                frame.error_register.as_u8()
+            ),
+        ),
+        CanOpenFrame::TimeStampFrame(frame) => (
+            "time_stamp",
+            format!(
+                ",\"milliseconds\":{},\"days\":{}",
+                frame.milliseconds, frame.days
+            ),
+        ),
+        CanOpenFrame::SdoFrame(frame) => (
+            "sdo",
+            format!(
+                ",\"direction\":\"{}\"{}",
+                direction_name(frame.direction),
+                frame
+                    .abort_code()
+                    .map_or(std::string::String::new(), |abort_code| format!(
+                        ",\"abort_code\":{}",
+                        abort_code.as_u32()
+                    ))
+            ),
+        ),
+        CanOpenFrame::PdoFrame(frame) => (
+            "pdo",
+            format!(
+                ",\"direction\":\"{}\",\"number\":{}",
+                direction_name(frame.direction()),
+                frame.number()
+            ),
+        ),
+        CanOpenFrame::NmtNodeMonitoringFrame(frame) => (
+            "nmt_node_monitoring",
+            format!(
+                ",\"state\":\"{}\",\"toggle\":{}",
+                nmt_state_name(frame.state),
+                frame.toggle
+            ),
+        ),
+        CanOpenFrame::LssFrame(frame) => (
+            "lss",
+            format!(
+                ",\"lss\":\"{}\"",
+                match frame {
+                    LssFrame::Request(_) => "request",
+                    LssFrame::Response(_) => "response",
+                }
+            ),
+        ),
+        CanOpenFrame::RemoteFrame(frame) => (
+            "remote",
+            format!(",\"data_length\":{}", frame.data_length()),
+        ),
+    };
+    format!(
+        "{{\"type\":\"{}\",\"cob_id\":{},\"cob\":{},\"node_id\":{},\"remote\":{},\"data\":\"{}\"{}}}",
+        frame_type,
+        cob.as_cob_id(),
+        json_string(&cob.to_string()),
+        cob.node_id()
+            .map_or("null".to_owned(), |node_id| node_id.to_string()),
+        matches!(frame, CanOpenFrame::RemoteFrame(_)),
+        hex(&frame.frame_data()),
+        fields
+    )
+}
+
+pub fn event_to_json(event: &HeartbeatEvent) -> std::string::String {
+    match event {
+        HeartbeatEvent::NodeUp { node_id, state } => format!(
+            "{{\"type\":\"node_up\",\"node_id\":{},\"state\":\"{}\"}}",
+            node_id,
+            nmt_state_name(*state)
+        ),
+        HeartbeatEvent::StateChanged { node_id, from, to } => format!(
+            "{{\"type\":\"state_changed\",\"node_id\":{},\"from\":\"{}\",\"to\":\"{}\"}}",
+            node_id,
+            nmt_state_name(*from),
+            nmt_state_name(*to)
+        ),
+        HeartbeatEvent::HeartbeatLost {
+            node_id,
+            last_state,
+        } => format!(
+            "{{\"type\":\"heartbeat_lost\",\"node_id\":{},\"state\":\"{}\"}}",
+            node_id,
+            nmt_state_name(*last_state)
+        ),
+    }
+}
+
+// A log record (one line of JSON Lines) of a frame received or transmitted at `timestamp`
+pub fn frame_record(frame: &CanOpenFrame, timestamp: std::time::SystemTime) -> std::string::String {
+    record(timestamp, "frame", &frame_to_json(frame))
+}
+
+pub fn event_record(
+    event: &HeartbeatEvent,
+    timestamp: std::time::SystemTime,
+) -> std::string::String {
+    record(timestamp, "event", &event_to_json(event))
+}
+
+fn record(timestamp: std::time::SystemTime, key: &str, value: &str) -> std::string::String {
+    // Timestamps before the UNIX epoch are clamped to it.
+    let timestamp_us = timestamp
+        .duration_since(std::time::UNIX_EPOCH)
+        .unwrap_or_default()
+        .as_micros();
+    format!(
+        "{{\"schema_version\":{},\"timestamp_us\":{},\"{}\":{}}}",
+        SCHEMA_VERSION, timestamp_us, key, value
+    )
+}
+
+fn nmt_command_name(command: NmtCommand) -> &'static str {
+    match command {
+        NmtCommand::Operational => "operational",
+        NmtCommand::Stopped => "stopped",
+        NmtCommand::PreOperational => "pre_operational",
+        NmtCommand::ResetNode => "reset_node",
+        NmtCommand::ResetCommunication => "reset_communication",
+    }
+}
+
+fn nmt_state_name(state: NmtState) -> &'static str {
+    match state {
+        NmtState::BootUp => "boot_up",
+        NmtState::Stopped => "stopped",
+        NmtState::Operational => "operational",
+        NmtState::PreOperational => "pre_operational",
+    }
+}
+
+fn direction_name(direction: Direction) -> &'static str {
+    match direction {
+        Direction::Tx => "tx",
+        Direction::Rx => "rx",
+    }
+}
+
+fn hex(data: &[u8]) -> std::string::String {
+    data.iter().map(|byte| format!("{:02x}", byte)).collect()
+}
+
+pub(crate) fn json_string(value: &str) -> std::string::String {
+    let mut json = "\"".to_owned();
+    for c in value.chars() {
+        match c {
+            '"' => json += "\\\"",
+            '\\' => json += "\\\\",
+            '\n' => json += "\\n",
+            c if (c as u32) < 0x20 => json += &format!("\\u{:04x}", c as u32),
+            c => json.push(c),
+        }
+    }
+    json.push('"');
+    json
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    use crate::frame::{
+        DataLengthPolicy, EmergencyFrame, NmtNodeMonitoringFrame, RemoteFrame, SdoFrame,
+    };
+    use crate::sdo::SdoResponse;
+
+    #[test]
+    fn test_frame_to_json() {
+        assert_eq!(
+            frame_to_json(&CanOpenFrame::new_nmt_node_control_frame(
+                NmtCommand::ResetNode,
+                NmtNodeControlAddress::AllNodes
+            )),
+            r#"{"type":"nmt_node_control","cob_id":0,"cob":"NmtNodeControl","node_id":null,"remote":false,"data":"8100","command":"reset_node","address":0}"#
+        );
+        assert_eq!(
+            frame_to_json(&CanOpenFrame::EmergencyFrame(EmergencyFrame::new(
+                2.try_into().unwrap(),
+                0x8130,
+                0x11.into()
+            ))),
+            r#"{"type":"emergency","cob_id":130,"cob":"Emergency(2)","node_id":2,"remote":false,"data":"3081110000000000","error_code":33072,"error_register":17}"#
+        );
+        assert_eq!(
+            frame_to_json(
+                &CanOpenFrame::new_pdo_frame(
+                    Direction::Tx,
+                    1,
+                    3.try_into().unwrap(),
+                    vec![0x01, 0xAB]
+                )
+                .unwrap()
+            ),
+            r#"{"type":"pdo","cob_id":387,"cob":"TxPdo1(3)","node_id":3,"remote":false,"data":"01ab","direction":"tx","number":1}"#
+        );
+        assert_eq!(
+            frame_to_json(&CanOpenFrame::NmtNodeMonitoringFrame(
+                NmtNodeMonitoringFrame::new(4.try_into().unwrap(), NmtState::PreOperational)
+            )),
+            r#"{"type":"nmt_node_monitoring","cob_id":1796,"cob":"NmtNodeMonitoring(4)","node_id":4,"remote":false,"data":"7f","state":"pre_operational","toggle":false}"#
+        );
+        assert_eq!(
+            frame_to_json(&CanOpenFrame::RemoteFrame(RemoteFrame::new_node_guarding(
+                4.try_into().unwrap()
+            ))),
+            r#"{"type":"remote","cob_id":1796,"cob":"NmtNodeMonitoring(4)","node_id":4,"remote":true,"data":"","data_length":1}"#
+        );
+    }
+
+    #[test]
+    fn test_sdo_abort_to_json() {
+        let response = SdoResponse::new_abort(
+            5.try_into().unwrap(),
+            0x1000,
+            0,
+            crate::sdo::SdoAbortCode::ObjectDoesNotExist,
+        );
+        let frame = CanOpenFrame::SdoFrame(
+            SdoFrame::new_with_bytes(
+                Direction::Tx,
+                5.try_into().unwrap(),
+                response.data(),
+                DataLengthPolicy::Strict,
+            )
+            .unwrap(),
+        );
+        assert_eq!(
+            frame_to_json(&frame),
+            r#"{"type":"sdo","cob_id":1413,"cob":"TxSdo(5)","node_id":5,"remote":false,"data":"8000100000000206","direction":"tx","abort_code":100794368}"#
+        );
+    }
+
+    #[test]
+    fn test_records() {
+        let timestamp = std::time::UNIX_EPOCH + std::time::Duration::from_micros(1_500_000);
+        assert_eq!(
+            frame_record(
+                &CanOpenFrame::SyncFrame(crate::frame::SyncFrame::new()),
+                timestamp
+            ),
+            r#"{"schema_version":1,"timestamp_us":1500000,"frame":{"type":"sync","cob_id":128,"cob":"Sync","node_id":null,"remote":false,"data":""}}"#
+        );
+        assert_eq!(
+            event_record(
+                &HeartbeatEvent::StateChanged {
+                    node_id: 3.try_into().unwrap(),
+                    from: NmtState::PreOperational,
+                    to: NmtState::Operational
+                },
+                timestamp
+            ),
+            r#"{"schema_version":1,"timestamp_us":1500000,"event":{"type":"state_changed","node_id":3,"from":"pre_operational","to":"operational"}}"#
+        );
+    }
+
+    #[test]
+    fn test_json_string() {
+        assert_eq!(json_string("a\"b\\c\n\t"), r#""a\"b\\c\n\u0009""#);
+    }
+}
@@ -0,0 +1,146 @@
+use std::os::unix::io::AsRawFd;
+
+use crate::error::{Error, Result};
+
+#[derive(Debug)]
+pub struct InterfaceLease {
+    interface_name: String,
+    path: std::path::PathBuf,
+    // The lock is held as long as the file is open.
+    _file: std::fs::File,
+}
+
+impl InterfaceLease {
+    const DEFAULT_LOCK_DIR: &'static str = "/run/lock";
+
+    pub fn acquire(interface_name: &str) -> Result<Self> {
+        Self::acquire_in(Self::DEFAULT_LOCK_DIR, interface_name)
+    }
+
+    pub fn acquire_in<P: AsRef<std::path::Path>>(
+        lock_dir: P,
+        interface_name: &str,
+    ) -> Result<Self> {
+        if !Self::is_valid_interface_name(interface_name) {
+            return Err(Error::InvalidInterfaceName(interface_name.to_owned()));
+        }
+        let path = lock_dir
+            .as_ref()
+            .join(format!("canopen-rs.{}.lock", interface_name));
+        let file = std::fs::OpenOptions::new()
+            .create(true)
+            .truncate(false)
+            .write(true)
+            .open(&path)
+            .map_err(|e| Error::InterfaceLeaseFailed {
+                interface_name: interface_name.to_owned(),
+                kind: e.kind(),
+            })?;
+        // SAFETY: `file` owns a valid file descriptor for the duration of the call.
+        if unsafe { libc::flock(file.as_raw_fd(), libc::LOCK_EX | libc::LOCK_NB) } != 0 {
+            let error = std::io::Error::last_os_error();
+            return Err(match error.kind() {
+                std::io::ErrorKind::WouldBlock => {
+                    Error::InterfaceAlreadyLeased(interface_name.to_owned())
+                }
+                kind => Error::InterfaceLeaseFailed {
+                    interface_name: interface_name.to_owned(),
+                    kind,
+                },
+            });
+        }
+        Ok(Self {
+            interface_name: interface_name.to_owned(),
+            path,
+            _file: file,
+        })
+    }
+
+    // The name becomes part of the lock file path, so it must not escape the lock directory.
+    // cf. `dev_valid_name()` in the Linux kernel
+    fn is_valid_interface_name(interface_name: &str) -> bool {
+        !interface_name.is_empty()
+            && interface_name.len() < libc::IFNAMSIZ
+            && !interface_name.contains(['/', '\0'])
+            && interface_name != "."
+            && interface_name != ".."
+    }
+
+    pub fn interface_name(&self) -> &str {
+        &self.interface_name
+    }
+
+    pub fn path(&self) -> &std::path::Path {
+        &self.path
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn lock_dir(name: &str) -> std::path::PathBuf {
+        let dir = std::env::temp_dir().join(format!(
+            "canopen-rs-lease-test-{}-{}",
+            name,
+            std::process::id()
+        ));
+        std::fs::create_dir_all(&dir).unwrap();
+        dir
+    }
+
+    #[test]
+    fn test_acquire() {
+        let dir = lock_dir("acquire");
+        let lease = InterfaceLease::acquire_in(&dir, "can0").unwrap();
+        assert_eq!(lease.interface_name(), "can0");
+        assert_eq!(lease.path(), dir.join("canopen-rs.can0.lock"));
+        assert!(lease.path().exists());
+    }
+
+    #[test]
+    fn test_acquire_twice() {
+        let dir = lock_dir("acquire-twice");
+        let lease = InterfaceLease::acquire_in(&dir, "can0").unwrap();
+        assert_eq!(
+            InterfaceLease::acquire_in(&dir, "can0").unwrap_err(),
+            Error::InterfaceAlreadyLeased("can0".to_owned())
+        );
+        assert!(InterfaceLease::acquire_in(&dir, "can1").is_ok());
+
+        drop(lease);
+        assert!(InterfaceLease::acquire_in(&dir, "can0").is_ok());
+    }
+
+    #[test]
+    fn test_acquire_in_missing_dir() {
+        let dir = lock_dir("missing-dir").join("missing");
+        assert_eq!(
+            InterfaceLease::acquire_in(dir, "can0").unwrap_err(),
+            Error::InterfaceLeaseFailed {
+                interface_name: "can0".to_owned(),
+                kind: std::io::ErrorKind::NotFound
+            }
+        );
+    }
+
+    #[test]
+    fn test_acquire_invalid_name() {
+        let dir = lock_dir("invalid-name");
+        for name in [
+            "",
+            ".",
+            "..",
+            "../can0",
+            "can/0",
+            "can0\0",
+            "can_interface_16",
+        ] {
+            assert_eq!(
+                InterfaceLease::acquire_in(&dir, name).unwrap_err(),
+                Error::InvalidInterfaceName(name.to_owned())
+            );
+        }
+        assert!(InterfaceLease::acquire_in(&dir, "can_interface15").is_ok());
+    }
+}
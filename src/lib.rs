@@ -1,7 +1,52 @@
 mod error;
 pub use error::{Error, Result};
 
+pub mod allowlist;
+pub mod cancel;
+pub mod candump;
+#[cfg(feature = "cia402")]
+pub mod cia402;
+#[cfg(feature = "cia406")]
+pub mod cia406;
+pub mod containment;
+#[cfg(feature = "dcf")]
+pub mod dcf;
+#[cfg(feature = "socketcan")]
+pub mod diagnostic;
+#[cfg(feature = "embedded-can")]
+pub mod embedded;
+pub mod emergency;
 pub mod frame;
+pub mod heartbeat;
+pub mod hotplug;
 pub mod id;
+#[cfg(feature = "netlink")]
+pub mod interface;
+pub mod json;
+#[cfg(feature = "socketcan")]
+pub mod lease;
+pub mod lss;
+#[cfg(any(test, feature = "test-util"))]
+pub mod mock;
+#[cfg(feature = "server")]
+pub mod node;
+pub mod node_guarding;
+pub mod object;
+pub mod od;
+pub mod pdo_counter;
+pub mod pdo_layout;
+#[cfg(feature = "profile")]
+pub mod profile;
+#[cfg(feature = "socketcan")]
+pub mod qos;
+pub mod sdo;
+#[cfg(all(any(test, feature = "test-util"), feature = "server"))]
+pub mod soak;
+pub mod stats;
+pub mod sync;
+pub mod time;
+pub mod topology;
+pub mod vendor_object;
 
+#[cfg(feature = "socketcan")]
 mod socketcan;
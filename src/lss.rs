@@ -0,0 +1,302 @@
+use crate::cancel::CancellationToken;
+use crate::frame::{LssFrame, LssRequest};
+
+#[derive(Clone, Copy, Debug, Default, PartialEq)]
+pub struct LssIdentity {
+    pub vendor_id: u32,
+    pub product_code: u32,
+    pub revision_number: u32,
+    pub serial_number: u32,
+}
+
+impl LssIdentity {
+    fn from_array(values: [u32; 4]) -> Self {
+        Self {
+            vendor_id: values[0],
+            product_code: values[1],
+            revision_number: values[2],
+            serial_number: values[3],
+        }
+    }
+}
+
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub enum FastscanStep {
+    // Send this frame and report whether any slave answered with `LssResponse::IdentifySlave`
+    // before the timeout.
+    Send(LssFrame),
+    // The slave with this identity is now in the LSS configuration state.
+    Found(LssIdentity),
+    // No unconfigured slave is left on the bus.
+    Finished,
+    // The cancellation token was cancelled. A new scan starts from scratch.
+    Cancelled,
+}
+
+#[derive(Clone, Copy, Debug, PartialEq)]
+enum FastscanState {
+    Reset,
+    Scanning { lss_sub: u8, bit_checked: u8 },
+    Confirming { lss_sub: u8 },
+    Done,
+}
+
+// cf. CiA 305, LSS Fastscan
+#[derive(Clone, Debug)]
+pub struct Fastscan {
+    id: [u32; 4],
+    state: FastscanState,
+    cancellation: Option<CancellationToken>,
+}
+
+impl Fastscan {
+    const RESET_BIT_CHECKED: u8 = 0x80;
+
+    pub fn new() -> Self {
+        Self {
+            id: [0; 4],
+            state: FastscanState::Reset,
+            cancellation: None,
+        }
+    }
+
+    pub fn with_cancellation(self, cancellation: CancellationToken) -> Self {
+        Self {
+            cancellation: Some(cancellation),
+            ..self
+        }
+    }
+
+    pub fn start(&mut self) -> FastscanStep {
+        self.id = [0; 4];
+        self.state = FastscanState::Reset;
+        FastscanStep::Send(Self::request(0, Self::RESET_BIT_CHECKED, 0, 0))
+    }
+
+    pub fn next(&mut self, responded: bool) -> FastscanStep {
+        if self
+            .cancellation
+            .as_ref()
+            .is_some_and(CancellationToken::is_cancelled)
+        {
+            self.state = FastscanState::Done;
+            return FastscanStep::Cancelled;
+        }
+        match self.state {
+            FastscanState::Reset => {
+                if !responded {
+                    self.state = FastscanState::Done;
+                    return FastscanStep::Finished;
+                }
+                self.scan(0, 31)
+            }
+            FastscanState::Scanning {
+                lss_sub,
+                bit_checked,
+            } => {
+                if !responded {
+                    self.id[lss_sub as usize] |= 1 << bit_checked;
+                }
+                if bit_checked == 0 {
+                    self.state = FastscanState::Confirming { lss_sub };
+                    FastscanStep::Send(Self::request(
+                        self.id[lss_sub as usize],
+                        0,
+                        lss_sub,
+                        (lss_sub + 1) % 4,
+                    ))
+                } else {
+                    self.scan(lss_sub, bit_checked - 1)
+                }
+            }
+            FastscanState::Confirming { lss_sub } => {
+                if !responded {
+                    // The slave left the bus or two slaves collided; start over.
+                    return self.start();
+                }
+                if lss_sub == 3 {
+                    self.state = FastscanState::Done;
+                    FastscanStep::Found(LssIdentity::from_array(self.id))
+                } else {
+                    self.scan(lss_sub + 1, 31)
+                }
+            }
+            FastscanState::Done => FastscanStep::Finished,
+        }
+    }
+
+    fn scan(&mut self, lss_sub: u8, bit_checked: u8) -> FastscanStep {
+        self.state = FastscanState::Scanning {
+            lss_sub,
+            bit_checked,
+        };
+        FastscanStep::Send(Self::request(
+            self.id[lss_sub as usize],
+            bit_checked,
+            lss_sub,
+            lss_sub,
+        ))
+    }
+
+    fn request(id_number: u32, bit_checked: u8, lss_sub: u8, lss_next: u8) -> LssFrame {
+        LssFrame::new_request(LssRequest::Fastscan {
+            id_number,
+            bit_checked,
+            lss_sub,
+            lss_next,
+        })
+    }
+}
+
+impl Default for Fastscan {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    // Minimal model of the slave side of Fastscan (CiA 305)
+    struct Slave {
+        identity: [u32; 4],
+        lss_pos: u8,
+        configuration: bool,
+    }
+
+    impl Slave {
+        fn new(identity: LssIdentity) -> Self {
+            Self {
+                identity: [
+                    identity.vendor_id,
+                    identity.product_code,
+                    identity.revision_number,
+                    identity.serial_number,
+                ],
+                lss_pos: 0,
+                configuration: false,
+            }
+        }
+
+        fn receive(&mut self, frame: &LssFrame) -> bool {
+            let LssFrame::Request(LssRequest::Fastscan {
+                id_number,
+                bit_checked,
+                lss_sub,
+                lss_next,
+            }) = *frame
+            else {
+                return false;
+            };
+            if self.configuration {
+                return false;
+            }
+            if bit_checked == 0x80 {
+                self.lss_pos = 0;
+                return true;
+            }
+            if lss_sub != self.lss_pos {
+                return false;
+            }
+            let mask = u32::MAX << bit_checked;
+            if (self.identity[lss_sub as usize] ^ id_number) & mask != 0 {
+                return false;
+            }
+            self.lss_pos = lss_next;
+            if bit_checked == 0 && lss_next < lss_sub {
+                self.configuration = true;
+            }
+            true
+        }
+    }
+
+    fn run(fastscan: &mut Fastscan, slaves: &mut [Slave]) -> FastscanStep {
+        let mut step = fastscan.start();
+        while let FastscanStep::Send(frame) = step {
+            let mut responded = false;
+            for slave in slaves.iter_mut() {
+                responded |= slave.receive(&frame);
+            }
+            step = fastscan.next(responded);
+        }
+        step
+    }
+
+    #[test]
+    fn test_no_slave() {
+        let mut fastscan = Fastscan::new();
+        assert_eq!(
+            fastscan.start(),
+            FastscanStep::Send(LssFrame::new_request(LssRequest::Fastscan {
+                id_number: 0,
+                bit_checked: 0x80,
+                lss_sub: 0,
+                lss_next: 0
+            }))
+        );
+        assert_eq!(fastscan.next(false), FastscanStep::Finished);
+        assert_eq!(fastscan.next(false), FastscanStep::Finished);
+    }
+
+    #[test]
+    fn test_cancel() {
+        let cancellation = CancellationToken::new();
+        let mut fastscan = Fastscan::new().with_cancellation(cancellation.clone());
+        fastscan.start();
+        assert!(matches!(fastscan.next(true), FastscanStep::Send(_)));
+        cancellation.cancel();
+        assert_eq!(fastscan.next(true), FastscanStep::Cancelled);
+        assert_eq!(fastscan.next(true), FastscanStep::Cancelled);
+    }
+
+    #[test]
+    fn test_single_slave() {
+        let identity = LssIdentity {
+            vendor_id: 0x0000_0123,
+            product_code: 0x1234_5678,
+            revision_number: 0x0001_0002,
+            serial_number: 0xDEAD_BEEF,
+        };
+        let mut slaves = [Slave::new(identity)];
+        let mut fastscan = Fastscan::new();
+        assert_eq!(
+            run(&mut fastscan, &mut slaves),
+            FastscanStep::Found(identity)
+        );
+        assert!(slaves[0].configuration);
+    }
+
+    #[test]
+    fn test_multiple_slaves() {
+        let identities = [
+            LssIdentity {
+                vendor_id: 0x0000_0123,
+                product_code: 0x0000_0001,
+                revision_number: 0x0000_0001,
+                serial_number: 0x0000_0002,
+            },
+            LssIdentity {
+                vendor_id: 0x0000_0123,
+                product_code: 0x0000_0001,
+                revision_number: 0x0000_0001,
+                serial_number: 0x0000_0001,
+            },
+            LssIdentity {
+                vendor_id: 0xFFFF_FFFF,
+                product_code: 0x0000_0000,
+                revision_number: 0x8000_0000,
+                serial_number: 0x0000_0000,
+            },
+        ];
+        let mut slaves = identities.map(Slave::new);
+        let mut fastscan = Fastscan::new();
+
+        let mut found = vec![];
+        while let FastscanStep::Found(identity) = run(&mut fastscan, &mut slaves) {
+            found.push(identity);
+        }
+        assert_eq!(found, vec![identities[1], identities[0], identities[2]]);
+        assert!(slaves.iter().all(|slave| slave.configuration));
+    }
+}
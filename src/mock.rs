@@ -0,0 +1,201 @@
+use crate::error::{Error, Result};
+use crate::frame::RawFrame;
+
+#[derive(Clone, Debug, PartialEq)]
+struct Expectation {
+    frame: RawFrame,
+    replies: std::vec::Vec<RawFrame>,
+    delay: std::time::Duration,
+}
+
+// A scripted bus for testing code built on this crate without vcan or hardware. Each expected
+// frame is answered with its replies after its delay; anything else fails the test. Like the
+// rest of the crate, it does not own the clock: the caller passes the time of each call.
+#[derive(Clone, Debug, Default, PartialEq)]
+pub struct MockCanInterface {
+    expectations: std::collections::VecDeque<Expectation>,
+    // Frames to receive with the time they are due, in order. Injected frames are due at once.
+    pending: std::collections::VecDeque<(Option<std::time::Instant>, RawFrame)>,
+    transmitted: std::vec::Vec<RawFrame>,
+}
+
+impl MockCanInterface {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    // Expects `frame` to be the next transmitted frame.
+    pub fn expect(mut self, frame: RawFrame) -> Self {
+        self.expectations.push_back(Expectation {
+            frame,
+            replies: std::vec::Vec::new(),
+            delay: std::time::Duration::ZERO,
+        });
+        self
+    }
+
+    // Answers the last expected frame with `frame`, after any other reply to it.
+    pub fn reply(mut self, frame: RawFrame) -> Self {
+        self.last_expectation().replies.push(frame);
+        self
+    }
+
+    // Delays the replies to the last expected frame, e.g. to trigger a timeout.
+    pub fn delay(mut self, delay: std::time::Duration) -> Self {
+        self.last_expectation().delay = delay;
+        self
+    }
+
+    // Receives `frame` at the start of the test, without any transmission (e.g. a boot-up).
+    pub fn inject(mut self, frame: RawFrame) -> Self {
+        self.pending.push_back((None, frame));
+        self
+    }
+
+    fn last_expectation(&mut self) -> &mut Expectation {
+        self.expectations
+            .back_mut()
+            .expect("`expect` should be called before `reply` or `delay`")
+    }
+
+    // Fails if `frame` is not the next expected frame; otherwise schedules its replies.
+    pub fn transmit(&mut self, frame: &RawFrame, now: std::time::Instant) -> Result<()> {
+        self.transmitted.push(*frame);
+        let expectation = match self.expectations.pop_front() {
+            Some(expectation) if expectation.frame == *frame => expectation,
+            Some(expectation) => {
+                return Err(Error::MockExpectationFailed(format!(
+                    "expected {:?}, transmitted {frame:?}",
+                    expectation.frame
+                )))
+            }
+            None => {
+                return Err(Error::MockExpectationFailed(format!(
+                    "unexpected {frame:?}"
+                )))
+            }
+        };
+        let due = Some(now + expectation.delay);
+        self.pending
+            .extend(expectation.replies.into_iter().map(|reply| (due, reply)));
+        Ok(())
+    }
+
+    // Returns the next reply due by `now`.
+    pub fn receive(&mut self, now: std::time::Instant) -> Option<RawFrame> {
+        let index = self
+            .pending
+            .iter()
+            .position(|(due, _)| !matches!(due, Some(due) if *due > now))?;
+        self.pending.remove(index).map(|(_, frame)| frame)
+    }
+
+    // When the next reply is due, to advance a simulated clock
+    pub fn next_deadline(&self) -> Option<std::time::Instant> {
+        self.pending.iter().filter_map(|(due, _)| *due).min()
+    }
+
+    pub fn transmitted(&self) -> &[RawFrame] {
+        &self.transmitted
+    }
+
+    // Fails unless every expected frame was transmitted and every reply received.
+    pub fn verify(&self) -> Result<()> {
+        if let Some(expectation) = self.expectations.front() {
+            return Err(Error::MockExpectationFailed(format!(
+                "{:?} was not transmitted ({} more expected)",
+                expectation.frame,
+                self.expectations.len() - 1
+            )));
+        }
+        if !self.pending.is_empty() {
+            return Err(Error::MockExpectationFailed(format!(
+                "{} frames were not received",
+                self.pending.len()
+            )));
+        }
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    use crate::frame::{encode, NmtNodeMonitoringFrame, NmtState};
+    use crate::id::NodeId;
+    use crate::sdo::{SdoTimeoutPolicy, SdoUpload, SdoUploadStep};
+
+    use std::time::Duration;
+
+    fn node_id() -> NodeId {
+        3.try_into().unwrap()
+    }
+
+    fn frame(id: u16, data: &[u8]) -> RawFrame {
+        RawFrame::new(id, data).unwrap()
+    }
+
+    #[test]
+    fn test_sdo_upload() {
+        let mut bus = MockCanInterface::new()
+            .inject(encode(&NmtNodeMonitoringFrame::new(
+                node_id(),
+                NmtState::BootUp,
+            )))
+            .expect(frame(0x603, &[0x40, 0x17, 0x10, 0x00, 0, 0, 0, 0]))
+            .reply(frame(0x583, &[0x4B, 0x17, 0x10, 0x00, 0x64, 0x00, 0, 0]))
+            .delay(Duration::from_millis(5));
+        let now = std::time::Instant::now();
+        assert_eq!(bus.receive(now), Some(frame(0x703, &[0x00])));
+
+        let mut upload = SdoUpload::new(node_id(), 0x1017, 0);
+        bus.transmit(&encode(&upload.start()), now).unwrap();
+        assert_eq!(bus.receive(now), None);
+        let due = bus.next_deadline().unwrap();
+        assert_eq!(due, now + Duration::from_millis(5));
+        let response = bus.receive(due).unwrap();
+        assert_eq!(
+            upload.on_response(response.data()),
+            Ok(SdoUploadStep::Done(vec![0x64, 0x00]))
+        );
+        assert_eq!(bus.verify(), Ok(()));
+        assert_eq!(bus.transmitted().len(), 1);
+    }
+
+    #[test]
+    fn test_timeout() {
+        let mut bus = MockCanInterface::new()
+            .expect(frame(0x603, &[0x40, 0x17, 0x10, 0x00, 0, 0, 0, 0]))
+            .reply(frame(0x583, &[0x4B, 0x17, 0x10, 0x00, 0x64, 0x00, 0, 0]))
+            .delay(Duration::from_secs(2));
+        let now = std::time::Instant::now();
+        let deadline =
+            crate::sdo::SdoDeadline::new(SdoTimeoutPolicy::default(), node_id(), 0x1017, 0, now);
+        bus.transmit(&encode(&SdoUpload::new(node_id(), 0x1017, 0).start()), now)
+            .unwrap();
+        assert!(bus.next_deadline().unwrap() > deadline.deadline());
+        assert!(deadline.is_expired(deadline.deadline()));
+        assert_eq!(bus.receive(deadline.deadline()), None);
+        assert!(bus.verify().is_err());
+    }
+
+    #[test]
+    fn test_expectation_failed() {
+        let now = std::time::Instant::now();
+        let mut bus = MockCanInterface::new().expect(frame(0x000, &[0x01, 0x03]));
+        assert!(matches!(
+            bus.transmit(&frame(0x000, &[0x01, 0x04]), now),
+            Err(Error::MockExpectationFailed(message)) if message.starts_with("expected ")
+        ));
+        assert!(bus.transmit(&frame(0x080, &[]), now).is_err());
+
+        let bus = MockCanInterface::new()
+            .expect(frame(0x000, &[0x01, 0x03]))
+            .expect(frame(0x080, &[]));
+        assert!(matches!(
+            bus.verify(),
+            Err(Error::MockExpectationFailed(message)) if message.ends_with("(1 more expected)")
+        ));
+    }
+}
@@ -0,0 +1,883 @@
+use crate::error::{Error, Result};
+use crate::frame::{
+    ConvertibleFrame, DataLengthPolicy, Direction, EmergencyFrame, NmtNodeControlFrame,
+    NmtNodeMonitoringFrame, NmtState, PdoFrame, SyncCobId,
+};
+use crate::id::{CommunicationObject, NodeId};
+use crate::object::ErrorRegister;
+use crate::od::{ObjectDictionary, Value};
+use crate::sdo::{SdoAbortCode, SdoResponse, SdoServer};
+
+mod emergency;
+pub use emergency::EmergencyProducer;
+
+mod nmt;
+pub use nmt::{NmtReset, NmtSlave};
+
+mod sync;
+pub use sync::{SyncCycle, SyncInfo};
+
+// A frame to be transmitted by a local node
+#[derive(Clone, Debug, PartialEq)]
+pub enum NodeOutput {
+    NmtNodeMonitoring(NmtNodeMonitoringFrame),
+    Emergency(EmergencyFrame),
+    Sdo(SdoResponse),
+    Pdo(PdoFrame),
+}
+
+impl ConvertibleFrame for NodeOutput {
+    fn communication_object(&self) -> CommunicationObject {
+        match self {
+            Self::NmtNodeMonitoring(frame) => frame.communication_object(),
+            Self::Emergency(frame) => frame.communication_object(),
+            Self::Sdo(frame) => frame.communication_object(),
+            Self::Pdo(frame) => frame.communication_object(),
+        }
+    }
+
+    fn frame_data(&self) -> std::vec::Vec<u8> {
+        match self {
+            Self::NmtNodeMonitoring(frame) => frame.frame_data(),
+            Self::Emergency(frame) => frame.frame_data(),
+            Self::Sdo(frame) => frame.frame_data(),
+            Self::Pdo(frame) => frame.frame_data(),
+        }
+    }
+
+    fn write_frame_data(&self, buf: &mut [u8; 8]) -> usize {
+        match self {
+            Self::NmtNodeMonitoring(frame) => frame.write_frame_data(buf),
+            Self::Emergency(frame) => frame.write_frame_data(buf),
+            Self::Sdo(frame) => frame.write_frame_data(buf),
+            Self::Pdo(frame) => frame.write_frame_data(buf),
+        }
+    }
+}
+
+// The application's work on a SYNC consumed through `LocalNode::on_frame`
+type SyncFn = dyn Fn(&mut ObjectDictionary, &SyncInfo) + Send + Sync;
+
+#[derive(Clone)]
+struct SyncCallback(std::sync::Arc<SyncFn>);
+
+impl std::fmt::Debug for SyncCallback {
+    fn fmt(&self, f: &mut std::fmt::Formatter) -> std::fmt::Result {
+        f.write_str("SyncCallback")
+    }
+}
+
+// A CANopen device implemented by this process (cf. CiA 301). It answers SDO requests from
+// its object dictionary, follows NMT commands, produces heartbeats according to 0x1017 and
+// emergencies, and transmits the TPDOs mapped in 0x1A00-0x1A03 on SYNC. The caller owns the
+// bus and the clock.
+#[derive(Clone, Debug)]
+pub struct LocalNode {
+    node_id: NodeId,
+    od: ObjectDictionary,
+    nmt: NmtSlave,
+    sdo_server: SdoServer,
+    emergency: EmergencyProducer,
+    heartbeat_sent_at: Option<std::time::Instant>,
+    sync_counters: [u8; 4],
+    sync_cycle: SyncCycle,
+    sync_info: Option<SyncInfo>,
+    sync_callback: Option<SyncCallback>,
+}
+
+impl LocalNode {
+    pub const COMMUNICATION_PROFILE_START: u16 = 0x1000;
+    pub const COMMUNICATION_PROFILE_END: u16 = 0x1FFF;
+    pub const COMMUNICATION_CYCLE_PERIOD_INDEX: u16 = 0x1006;
+    pub const PRODUCER_HEARTBEAT_TIME_INDEX: u16 = 0x1017;
+    pub const TPDO_COMMUNICATION_INDEX: u16 = 0x1800;
+    pub const TPDO_MAPPING_INDEX: u16 = 0x1A00;
+    const PDO_INVALID_BIT: u32 = 1 << 31;
+    const PDO_COB_ID_MASK: u32 = 0x7FF;
+
+    pub fn new(node_id: NodeId, od: ObjectDictionary) -> Self {
+        Self {
+            node_id,
+            od,
+            nmt: NmtSlave::new(node_id),
+            sdo_server: SdoServer::new(node_id),
+            emergency: EmergencyProducer::new(node_id),
+            heartbeat_sent_at: None,
+            sync_counters: [0; 4],
+            sync_cycle: SyncCycle::new(),
+            sync_info: None,
+            sync_callback: None,
+        }
+    }
+
+    pub fn node_id(&self) -> NodeId {
+        self.node_id
+    }
+
+    pub fn state(&self) -> NmtState {
+        self.nmt.state()
+    }
+
+    pub fn od(&self) -> &ObjectDictionary {
+        &self.od
+    }
+
+    pub fn od_mut(&mut self) -> &mut ObjectDictionary {
+        &mut self.od
+    }
+
+    pub fn sync_cycle(&self) -> &SyncCycle {
+        &self.sync_cycle
+    }
+
+    pub fn emergency(&self) -> &EmergencyProducer {
+        &self.emergency
+    }
+
+    // Records an error in 0x1001 and 0x1003. Returns its EMCY frame unless the error is already
+    // active or EMCY is not allowed in the current NMT state.
+    pub fn raise_error(
+        &mut self,
+        error_code: u16,
+        error_register: ErrorRegister,
+        manufacturer_specific: [u8; 5],
+    ) -> Option<NodeOutput> {
+        let frame = self.emergency.raise(
+            &mut self.od,
+            error_code,
+            error_register,
+            manufacturer_specific,
+        )?;
+        self.nmt
+            .is_emcy_allowed()
+            .then_some(NodeOutput::Emergency(frame))
+    }
+
+    // Returns the error reset message unless the error is not active or EMCY is not allowed in
+    // the current NMT state.
+    pub fn clear_error(&mut self, error_code: u16) -> Option<NodeOutput> {
+        let frame = self.emergency.clear(&mut self.od, error_code)?;
+        self.nmt
+            .is_emcy_allowed()
+            .then_some(NodeOutput::Emergency(frame))
+    }
+
+    // Finishes the initialisation: returns the boot-up message and enters pre-operational.
+    pub fn boot(&mut self, now: std::time::Instant) -> NodeOutput {
+        let boot_up = self.nmt.boot();
+        self.sdo_server = SdoServer::new(self.node_id);
+        self.sync_counters = [0; 4];
+        self.sync_cycle.restart();
+        self.sync_info = None;
+        self.heartbeat_sent_at = Some(now);
+        NodeOutput::NmtNodeMonitoring(boot_up)
+    }
+
+    // Feeds a frame received from the bus on `cob_id`. Returns the frames to transmit in
+    // response. The COB-ID is matched before it is classified, as SYNC may be remapped (0x1005)
+    // to any COB-ID.
+    pub fn on_frame(
+        &mut self,
+        cob_id: u16,
+        bytes: &[u8],
+        now: std::time::Instant,
+    ) -> std::vec::Vec<NodeOutput> {
+        if cob_id == self.sync_cob_id().cob_id() {
+            let callback = self.sync_callback.clone();
+            return self.on_sync(now, |od, info| {
+                if let Some(SyncCallback(callback)) = callback {
+                    callback(od, info);
+                }
+            });
+        }
+        match CommunicationObject::new(cob_id) {
+            Ok(CommunicationObject::NmtNodeControl) => {
+                match NmtNodeControlFrame::new_with_bytes(bytes, DataLengthPolicy::Lenient) {
+                    Ok(frame) => self.on_nmt_command(frame, now).into_iter().collect(),
+                    Err(_) => std::vec::Vec::new(),
+                }
+            }
+            Ok(CommunicationObject::RxSdo(node_id))
+                if node_id == self.node_id && self.nmt.is_sdo_allowed() =>
+            {
+                self.sdo_server
+                    .on_request(&mut self.od, bytes)
+                    .map(NodeOutput::Sdo)
+                    .into_iter()
+                    .collect()
+            }
+            _ => std::vec::Vec::new(),
+        }
+    }
+
+    // Runs `callback` on every SYNC consumed through `on_frame`, as `on_sync` does with its own
+    // callback.
+    pub fn set_sync_callback(
+        &mut self,
+        callback: impl Fn(&mut ObjectDictionary, &SyncInfo) + Send + Sync + 'static,
+    ) {
+        self.sync_callback = Some(SyncCallback(std::sync::Arc::new(callback)));
+    }
+
+    pub fn remove_sync_callback(&mut self) {
+        self.sync_callback = None;
+    }
+
+    // Consumes a SYNC received at `now`. `callback` runs with the cycle information before
+    // the synchronous TPDOs are sampled, so that it can update the mapped objects. Call
+    // `finish_sync` when the work of the cycle is done to record in `sync_cycle()` whether it
+    // overran the communication cycle period (0x1006).
+    pub fn on_sync(
+        &mut self,
+        now: std::time::Instant,
+        callback: impl FnOnce(&mut ObjectDictionary, &SyncInfo),
+    ) -> std::vec::Vec<NodeOutput> {
+        // SYNC is consumed with the same states as EMCY.
+        if !self.nmt.is_emcy_allowed() {
+            return std::vec::Vec::new();
+        }
+        let period = match self
+            .od
+            .variable(Self::COMMUNICATION_CYCLE_PERIOD_INDEX, 0)
+            .map(|variable| &variable.value)
+        {
+            Ok(Value::Unsigned32(microseconds)) if *microseconds > 0 => {
+                Some(std::time::Duration::from_micros((*microseconds).into()))
+            }
+            _ => None,
+        };
+        let info = self.sync_cycle.start(now, period);
+        callback(&mut self.od, &info);
+        self.sync_info = Some(info);
+        if !self.nmt.is_pdo_allowed() {
+            return std::vec::Vec::new();
+        }
+        self.transmit_synchronous_tpdos()
+    }
+
+    // Records that the work of the latest SYNC was done at `now`. Returns whether it overran the
+    // cycle, or None if no SYNC is pending.
+    pub fn finish_sync(&mut self, now: std::time::Instant) -> Option<bool> {
+        let info = self.sync_info.take()?;
+        Some(self.sync_cycle.finish_at(&info, now))
+    }
+
+    // The SYNC COB-ID consumed by the node (0x1005), or the predefined one if there is none
+    pub fn sync_cob_id(&self) -> SyncCobId {
+        match self
+            .od
+            .variable(SyncCobId::INDEX, 0)
+            .map(|variable| &variable.value)
+        {
+            Ok(Value::Unsigned32(value)) => SyncCobId::from_u32(*value).unwrap_or_default(),
+            _ => SyncCobId::default(),
+        }
+    }
+
+    // Returns the heartbeat message when the producer heartbeat time has elapsed.
+    pub fn poll(&mut self, now: std::time::Instant) -> Option<NodeOutput> {
+        if !self.nmt.is_nmt_allowed() {
+            return None;
+        }
+        let sent_at = self.heartbeat_sent_at?;
+        let period = match self
+            .od
+            .variable(Self::PRODUCER_HEARTBEAT_TIME_INDEX, 0)
+            .map(|variable| &variable.value)
+        {
+            Ok(Value::Unsigned16(milliseconds)) if *milliseconds > 0 => {
+                std::time::Duration::from_millis((*milliseconds).into())
+            }
+            _ => return None,
+        };
+        if now.duration_since(sent_at) < period {
+            return None;
+        }
+        self.heartbeat_sent_at = Some(now);
+        Some(NodeOutput::NmtNodeMonitoring(NmtNodeMonitoringFrame::new(
+            self.node_id,
+            self.nmt.state(),
+        )))
+    }
+
+    // Builds TPDO `number` (1-4) from the current values of its mapped objects. It is sent with
+    // the COB-ID configured in 0x1800-0x1803:01, or the predefined one if there is none.
+    pub fn tpdo(&self, number: u8) -> Result<PdoFrame> {
+        if !(1..=4).contains(&number) {
+            return Err(Error::InvalidPdoNumber(number));
+        }
+        let mapping = self
+            .od
+            .get(Self::TPDO_MAPPING_INDEX + (number - 1) as u16)
+            .ok_or(Error::InvalidPdoNumber(number))?;
+        let count = match mapping.get(0).map(|variable| &variable.value) {
+            Some(Value::Unsigned8(count)) => *count,
+            _ => {
+                return Err(Error::ObjectAccessFailed(
+                    SdoAbortCode::ObjectCannotBeMapped,
+                ))
+            }
+        };
+        let mut data = std::vec::Vec::new();
+        for sub_index in 1..=count {
+            let entry = match mapping.get(sub_index).map(|variable| &variable.value) {
+                Some(Value::Unsigned32(entry)) => *entry,
+                _ => {
+                    return Err(Error::ObjectAccessFailed(
+                        SdoAbortCode::ObjectCannotBeMapped,
+                    ))
+                }
+            };
+            let bytes = self
+                .od
+                .variable((entry >> 16) as u16, (entry >> 8) as u8)?
+                .value
+                .to_bytes();
+            let length = (entry & 0xFF) as usize / 8;
+            if length > bytes.len() {
+                return Err(Error::ObjectAccessFailed(
+                    SdoAbortCode::ObjectCannotBeMapped,
+                ));
+            }
+            data.extend_from_slice(&bytes[..length]);
+        }
+        let frame = match self
+            .od
+            .variable(Self::TPDO_COMMUNICATION_INDEX + (number - 1) as u16, 1)
+            .map(|variable| &variable.value)
+        {
+            Ok(Value::Unsigned32(cob_id)) => {
+                PdoFrame::new_with_cob_id((cob_id & Self::PDO_COB_ID_MASK) as u16, data)
+            }
+            _ => PdoFrame::new(Direction::Tx, number, self.node_id, data),
+        };
+        frame.map_err(|error| match error {
+            Error::InvalidDataLength { .. } => {
+                Error::ObjectAccessFailed(SdoAbortCode::PdoLengthExceeded)
+            }
+            error => error,
+        })
+    }
+
+    fn on_nmt_command(
+        &mut self,
+        frame: NmtNodeControlFrame,
+        now: std::time::Instant,
+    ) -> Option<NodeOutput> {
+        match self.nmt.on_frame(&frame)? {
+            NmtReset::Node => {
+                self.od.reset();
+                self.emergency.reset();
+            }
+            NmtReset::Communication => self
+                .od
+                .reset_range(Self::COMMUNICATION_PROFILE_START..=Self::COMMUNICATION_PROFILE_END),
+        }
+        Some(self.boot(now))
+    }
+
+    fn transmit_synchronous_tpdos(&mut self) -> std::vec::Vec<NodeOutput> {
+        let mut outputs = std::vec::Vec::new();
+        for number in 1..=4u8 {
+            let communication = Self::TPDO_COMMUNICATION_INDEX + (number - 1) as u16;
+            let valid = matches!(
+                self.od.variable(communication, 1).map(|variable| &variable.value),
+                Ok(Value::Unsigned32(cob_id)) if cob_id & Self::PDO_INVALID_BIT == 0
+            );
+            // Synchronous (cyclic) transmission types transmit every n-th SYNC.
+            let cycle = match self
+                .od
+                .variable(communication, 2)
+                .map(|variable| &variable.value)
+            {
+                Ok(Value::Unsigned8(transmission_type))
+                    if (1..=240).contains(transmission_type) =>
+                {
+                    *transmission_type
+                }
+                _ => continue,
+            };
+            if !valid {
+                continue;
+            }
+            let counter = &mut self.sync_counters[(number - 1) as usize];
+            *counter += 1;
+            if *counter < cycle {
+                continue;
+            }
+            *counter = 0;
+            if let Ok(frame) = self.tpdo(number) {
+                outputs.push(NodeOutput::Pdo(frame));
+            }
+        }
+        outputs
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    use crate::od::{AccessType, Object, Variable};
+
+    fn node_id() -> NodeId {
+        3.try_into().unwrap()
+    }
+
+    fn dictionary() -> ObjectDictionary {
+        let mut od = ObjectDictionary::new();
+        od.insert(Object::new_var(
+            0x1017,
+            Variable::new(
+                "Producer heartbeat time",
+                AccessType::ReadWrite,
+                Value::Unsigned16(100),
+            ),
+        ));
+        od.insert(Object::new_record(
+            0x1800,
+            "TPDO1 communication parameter",
+            vec![
+                (
+                    1,
+                    Variable::new("COB-ID", AccessType::ReadWrite, Value::Unsigned32(0x183)),
+                ),
+                (
+                    2,
+                    Variable::new(
+                        "Transmission type",
+                        AccessType::ReadWrite,
+                        Value::Unsigned8(2),
+                    ),
+                ),
+            ],
+        ));
+        od.insert(
+            Object::new_array(
+                0x1A00,
+                "TPDO1 mapping parameter",
+                AccessType::ReadWrite,
+                vec![
+                    Value::Unsigned32(0x6041_0010),
+                    Value::Unsigned32(0x6064_0020),
+                ],
+            )
+            .unwrap(),
+        );
+        od.insert(Object::new_var(
+            0x6041,
+            Variable::new(
+                "Statusword",
+                AccessType::ReadOnly,
+                Value::Unsigned16(0x0237),
+            ),
+        ));
+        od.insert(Object::new_var(
+            0x6064,
+            Variable::new(
+                "Position actual value",
+                AccessType::ReadOnly,
+                Value::Integer32(-2),
+            ),
+        ));
+        od
+    }
+
+    fn nmt(command: u8, address: u8) -> [u8; 2] {
+        [command, address]
+    }
+
+    #[test]
+    fn test_boot() {
+        let now = std::time::Instant::now();
+        let mut node = LocalNode::new(node_id(), dictionary());
+        assert_eq!(node.state(), NmtState::BootUp);
+        assert!(node
+            .on_frame(
+                CommunicationObject::RxSdo(node_id()).as_cob_id(),
+                &[0x40; 8],
+                now
+            )
+            .is_empty());
+        let output = node.boot(now);
+        assert_eq!(output.communication_object().as_cob_id(), 0x703);
+        assert_eq!(output.frame_data(), vec![0x00]);
+        assert_eq!(node.state(), NmtState::PreOperational);
+    }
+
+    #[test]
+    fn test_nmt_commands() {
+        let now = std::time::Instant::now();
+        let mut node = LocalNode::new(node_id(), dictionary());
+        node.boot(now);
+        node.on_frame(
+            CommunicationObject::NmtNodeControl.as_cob_id(),
+            &nmt(0x01, 0x00),
+            now,
+        );
+        assert_eq!(node.state(), NmtState::Operational);
+        node.on_frame(
+            CommunicationObject::NmtNodeControl.as_cob_id(),
+            &nmt(0x02, 0x04),
+            now,
+        );
+        assert_eq!(node.state(), NmtState::Operational);
+        node.on_frame(
+            CommunicationObject::NmtNodeControl.as_cob_id(),
+            &nmt(0x02, 0x03),
+            now,
+        );
+        assert_eq!(node.state(), NmtState::Stopped);
+        assert!(node
+            .on_frame(
+                CommunicationObject::RxSdo(node_id()).as_cob_id(),
+                &[0x40, 0x17, 0x10, 0x00, 0, 0, 0, 0],
+                now
+            )
+            .is_empty());
+
+        node.od_mut().write(0x1017, 0, &[0x00, 0x00]).unwrap();
+        let outputs = node.on_frame(
+            CommunicationObject::NmtNodeControl.as_cob_id(),
+            &nmt(0x81, 0x00),
+            now,
+        );
+        assert_eq!(
+            outputs,
+            vec![NodeOutput::NmtNodeMonitoring(NmtNodeMonitoringFrame::new(
+                node_id(),
+                NmtState::BootUp
+            ))]
+        );
+        assert_eq!(node.state(), NmtState::PreOperational);
+        assert_eq!(node.od().read(0x1017, 0), Ok(vec![100, 0]));
+    }
+
+    #[test]
+    fn test_reset_communication() {
+        let now = std::time::Instant::now();
+        let mut node = LocalNode::new(node_id(), dictionary());
+        node.boot(now);
+        node.od_mut().write(0x1017, 0, &[0x00, 0x00]).unwrap();
+        node.od_mut().variable_mut(0x6064, 0).unwrap().value = Value::Integer32(1000);
+
+        node.on_frame(
+            CommunicationObject::NmtNodeControl.as_cob_id(),
+            &nmt(0x82, 0x03),
+            now,
+        );
+        assert_eq!(node.state(), NmtState::PreOperational);
+        // Only the communication profile area is restored.
+        assert_eq!(node.od().read(0x1017, 0), Ok(vec![100, 0]));
+        assert_eq!(
+            node.od().variable(0x6064, 0).unwrap().value,
+            Value::Integer32(1000)
+        );
+    }
+
+    #[test]
+    fn test_sdo() {
+        let now = std::time::Instant::now();
+        let mut node = LocalNode::new(node_id(), dictionary());
+        node.boot(now);
+        let outputs = node.on_frame(
+            CommunicationObject::RxSdo(node_id()).as_cob_id(),
+            &[0x40, 0x41, 0x60, 0x00, 0, 0, 0, 0],
+            now,
+        );
+        assert_eq!(outputs.len(), 1);
+        assert_eq!(outputs[0].communication_object().as_cob_id(), 0x583);
+        assert_eq!(
+            outputs[0].frame_data(),
+            vec![0x4B, 0x41, 0x60, 0x00, 0x37, 0x02, 0x00, 0x00]
+        );
+        assert!(node
+            .on_frame(
+                CommunicationObject::RxSdo(4.try_into().unwrap()).as_cob_id(),
+                &[0x40, 0x41, 0x60, 0x00, 0, 0, 0, 0],
+                now
+            )
+            .is_empty());
+    }
+
+    #[test]
+    fn test_heartbeat() {
+        let now = std::time::Instant::now();
+        let mut node = LocalNode::new(node_id(), dictionary());
+        assert_eq!(node.poll(now), None);
+        node.boot(now);
+        assert_eq!(node.poll(now + std::time::Duration::from_millis(50)), None);
+        assert_eq!(
+            node.poll(now + std::time::Duration::from_millis(100)),
+            Some(NodeOutput::NmtNodeMonitoring(NmtNodeMonitoringFrame::new(
+                node_id(),
+                NmtState::PreOperational
+            )))
+        );
+        assert_eq!(node.poll(now + std::time::Duration::from_millis(150)), None);
+    }
+
+    #[test]
+    fn test_emergency() {
+        let now = std::time::Instant::now();
+        let mut node = LocalNode::new(node_id(), dictionary());
+        // Recorded, but not transmitted before boot-up
+        assert_eq!(node.raise_error(0x5000, 0x00.into(), [0x00; 5]), None);
+        assert_eq!(node.emergency().active_errors().count(), 1);
+        node.boot(now);
+        assert_eq!(
+            node.raise_error(0x8130, 0x10.into(), [0x00; 5]),
+            Some(NodeOutput::Emergency(EmergencyFrame::new(
+                node_id(),
+                0x8130,
+                0x11.into()
+            )))
+        );
+        assert_eq!(
+            node.clear_error(0x5000),
+            Some(NodeOutput::Emergency(EmergencyFrame::new(
+                node_id(),
+                0x0000,
+                0x11.into()
+            )))
+        );
+        assert_eq!(node.clear_error(0x5000), None);
+        node.on_frame(
+            CommunicationObject::NmtNodeControl.as_cob_id(),
+            &nmt(0x81, 0),
+            now,
+        );
+        assert_eq!(node.emergency().active_errors().count(), 0);
+    }
+
+    #[test]
+    fn test_tpdo() {
+        let node = LocalNode::new(node_id(), dictionary());
+        let frame = node.tpdo(1).unwrap();
+        assert_eq!(frame.communication_object().as_cob_id(), 0x183);
+        assert_eq!(frame.data(), &[0x37, 0x02, 0xFE, 0xFF, 0xFF, 0xFF]);
+        assert_eq!(node.tpdo(2), Err(Error::InvalidPdoNumber(2)));
+        assert_eq!(node.tpdo(5), Err(Error::InvalidPdoNumber(5)));
+    }
+
+    #[test]
+    fn test_tpdo_cob_id() {
+        let now = std::time::Instant::now();
+        let mut od = dictionary();
+        od.insert(Object::new_record(
+            0x1802,
+            "TPDO3 communication parameter",
+            vec![
+                (
+                    1,
+                    Variable::new("COB-ID", AccessType::ReadWrite, Value::Unsigned32(0x383)),
+                ),
+                (
+                    2,
+                    Variable::new(
+                        "Transmission type",
+                        AccessType::ReadWrite,
+                        Value::Unsigned8(1),
+                    ),
+                ),
+            ],
+        ));
+        od.insert(
+            Object::new_array(
+                0x1A02,
+                "TPDO3 mapping parameter",
+                AccessType::ReadWrite,
+                vec![Value::Unsigned32(0x6041_0010)],
+            )
+            .unwrap(),
+        );
+        let mut node = LocalNode::new(node_id(), od);
+        node.boot(now);
+        node.on_frame(
+            CommunicationObject::NmtNodeControl.as_cob_id(),
+            &nmt(0x01, 0x03),
+            now,
+        );
+
+        // Remapped to the RPDO1 of node 5
+        node.od_mut()
+            .write(0x1802, 1, &[0x05, 0x02, 0x00, 0x00])
+            .unwrap();
+        let outputs = node.on_frame(CommunicationObject::Sync.as_cob_id(), &[], now);
+        assert_eq!(outputs.len(), 1);
+        assert_eq!(outputs[0].communication_object().as_cob_id(), 0x205);
+        assert_eq!(outputs[0].frame_data(), vec![0x37, 0x02]);
+
+        // Not a PDO COB-ID of the predefined connection set
+        node.od_mut()
+            .write(0x1802, 1, &[0x05, 0x06, 0x00, 0x00])
+            .unwrap();
+        assert_eq!(node.tpdo(3), Err(Error::InvalidCobId(0x605)));
+    }
+
+    #[test]
+    fn test_tpdo_on_sync() {
+        let now = std::time::Instant::now();
+        let mut node = LocalNode::new(node_id(), dictionary());
+        node.boot(now);
+        assert!(node
+            .on_frame(CommunicationObject::Sync.as_cob_id(), &[], now)
+            .is_empty());
+        node.on_frame(
+            CommunicationObject::NmtNodeControl.as_cob_id(),
+            &nmt(0x01, 0x03),
+            now,
+        );
+        // Transmission type 2: every second SYNC
+        assert!(node
+            .on_frame(CommunicationObject::Sync.as_cob_id(), &[], now)
+            .is_empty());
+        assert_eq!(
+            node.on_frame(CommunicationObject::Sync.as_cob_id(), &[], now),
+            vec![NodeOutput::Pdo(node.tpdo(1).unwrap())]
+        );
+
+        node.od_mut().variable_mut(0x1800, 1).unwrap().value = Value::Unsigned32(0x8000_0183);
+        assert!(node
+            .on_frame(CommunicationObject::Sync.as_cob_id(), &[], now)
+            .is_empty());
+        assert!(node
+            .on_frame(CommunicationObject::Sync.as_cob_id(), &[], now)
+            .is_empty());
+    }
+
+    #[test]
+    fn test_sync_cob_id() {
+        let now = std::time::Instant::now();
+        let mut od = dictionary();
+        od.insert(Object::new_var(
+            SyncCobId::INDEX,
+            Variable::new(
+                "COB-ID SYNC",
+                AccessType::ReadWrite,
+                Value::Unsigned32(0x0000_0680),
+            ),
+        ));
+        od.variable_mut(0x1800, 2).unwrap().value = Value::Unsigned8(1);
+        let mut node = LocalNode::new(node_id(), od);
+        node.boot(now);
+        node.on_frame(
+            CommunicationObject::NmtNodeControl.as_cob_id(),
+            &nmt(0x01, 0x03),
+            now,
+        );
+        assert_eq!(node.sync_cob_id().cob_id(), 0x680);
+
+        // The predefined SYNC is not consumed, the configured COB-ID is, even though it does
+        // not classify as SYNC.
+        assert!(node
+            .on_frame(CommunicationObject::Sync.as_cob_id(), &[], now)
+            .is_empty());
+        assert_eq!(
+            node.on_frame(0x680, &[], now),
+            vec![NodeOutput::Pdo(node.tpdo(1).unwrap())]
+        );
+        assert_eq!(node.sync_cycle().cycles(), 1);
+    }
+
+    #[test]
+    fn test_sync_callback() {
+        let now = std::time::Instant::now();
+        let mut node = LocalNode::new(node_id(), dictionary());
+        node.od_mut().variable_mut(0x1800, 2).unwrap().value = Value::Unsigned8(1);
+        let cycles = std::sync::Arc::new(std::sync::Mutex::new(vec![]));
+        let received = cycles.clone();
+        node.set_sync_callback(move |od, info| {
+            received.lock().unwrap().push(info.cycle);
+            od.variable_mut(0x6041, 0).unwrap().value = Value::Unsigned16(0x0637);
+        });
+        node.boot(now);
+        node.on_frame(
+            CommunicationObject::NmtNodeControl.as_cob_id(),
+            &nmt(0x01, 0x03),
+            now,
+        );
+
+        let outputs = node.on_frame(CommunicationObject::Sync.as_cob_id(), &[], now);
+        let next_outputs = node.on_frame(
+            CommunicationObject::Sync.as_cob_id(),
+            &[],
+            now + std::time::Duration::from_millis(1),
+        );
+        assert_eq!(*cycles.lock().unwrap(), vec![0, 1]);
+        // The TPDO is sampled after the callback.
+        match (&outputs[..], &next_outputs[..]) {
+            ([NodeOutput::Pdo(frame)], [NodeOutput::Pdo(_)]) => {
+                assert_eq!(&frame.data()[..2], &[0x37, 0x06])
+            }
+            _ => panic!("unexpected outputs: {outputs:?}"),
+        }
+
+        node.remove_sync_callback();
+        node.on_frame(CommunicationObject::Sync.as_cob_id(), &[], now);
+        assert_eq!(cycles.lock().unwrap().len(), 2);
+    }
+
+    #[test]
+    fn test_on_sync() {
+        let now = std::time::Instant::now();
+        let mut od = dictionary();
+        od.insert(Object::new_var(
+            0x1006,
+            Variable::new(
+                "Communication cycle period",
+                AccessType::ReadWrite,
+                Value::Unsigned32(1000),
+            ),
+        ));
+        let mut node = LocalNode::new(node_id(), od);
+        assert!(node.on_sync(now, |_, _| panic!()).is_empty());
+        node.boot(now);
+        node.on_frame(
+            CommunicationObject::NmtNodeControl.as_cob_id(),
+            &nmt(0x01, 0x03),
+            now,
+        );
+
+        let mut cycles = vec![];
+        node.on_sync(now, |_, info| cycles.push(*info));
+        let outputs = node.on_sync(now + std::time::Duration::from_millis(1), |od, info| {
+            cycles.push(*info);
+            od.variable_mut(0x6041, 0).unwrap().value = Value::Unsigned16(0x0637);
+        });
+        assert_eq!(
+            cycles,
+            vec![
+                SyncInfo {
+                    cycle: 0,
+                    phase: None,
+                    period: Some(std::time::Duration::from_millis(1))
+                },
+                SyncInfo {
+                    cycle: 1,
+                    phase: Some(std::time::Duration::from_millis(1)),
+                    period: Some(std::time::Duration::from_millis(1))
+                },
+            ]
+        );
+        match &outputs[..] {
+            [NodeOutput::Pdo(frame)] => assert_eq!(&frame.data()[..2], &[0x37, 0x06]),
+            _ => panic!("unexpected outputs: {outputs:?}"),
+        }
+        assert_eq!(
+            node.finish_sync(now + std::time::Duration::from_micros(1500)),
+            Some(false)
+        );
+        assert_eq!(node.finish_sync(now), None);
+        assert_eq!(node.sync_cycle().overruns(), 0);
+
+        node.on_sync(now + std::time::Duration::from_millis(2), |_, _| {});
+        assert_eq!(
+            node.finish_sync(now + std::time::Duration::from_millis(7)),
+            Some(true)
+        );
+        assert!(node.sync_cycle().last_overran());
+        assert_eq!(node.sync_cycle().overruns(), 1);
+        assert_eq!(node.sync_cycle().cycles(), 3);
+    }
+}
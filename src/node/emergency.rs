@@ -0,0 +1,261 @@
+use crate::frame::EmergencyFrame;
+use crate::id::NodeId;
+use crate::object::ErrorRegister;
+use crate::od::{ObjectDictionary, Value};
+
+#[derive(Clone, Copy, Debug, PartialEq)]
+struct ActiveError {
+    error_code: u16,
+    error_register: ErrorRegister,
+}
+
+// Emergency producer of a local node (cf. CiA 301). It tracks the active errors, keeps the
+// error register (0x1001) and the pre-defined error field (0x1003) of the object dictionary up
+// to date when they exist, and builds the EMCY frames to transmit.
+#[derive(Clone, Debug)]
+pub struct EmergencyProducer {
+    node_id: NodeId,
+    active: std::vec::Vec<ActiveError>,
+}
+
+impl EmergencyProducer {
+    pub const PRE_DEFINED_ERROR_FIELD_INDEX: u16 = 0x1003;
+
+    pub fn new(node_id: NodeId) -> Self {
+        Self {
+            node_id,
+            active: std::vec::Vec::new(),
+        }
+    }
+
+    // The union of the active errors. The generic bit is set while any error is active.
+    pub fn error_register(&self) -> ErrorRegister {
+        let bits = self
+            .active
+            .iter()
+            .fold(0x00, |bits, error| bits | error.error_register.as_u8());
+        let mut error_register = ErrorRegister::from_u8(bits);
+        error_register.generic = !self.active.is_empty();
+        error_register
+    }
+
+    pub fn active_errors(&self) -> impl Iterator<Item = u16> + '_ {
+        self.active.iter().map(|error| error.error_code)
+    }
+
+    // Records the error `error_code` and returns its EMCY frame. The first two
+    // manufacturer-specific bytes are stored as the additional information of the 0x1003
+    // entry. Returns `None` if the error is already active.
+    pub fn raise(
+        &mut self,
+        od: &mut ObjectDictionary,
+        error_code: u16,
+        error_register: ErrorRegister,
+        manufacturer_specific: [u8; 5],
+    ) -> Option<EmergencyFrame> {
+        if error_code == 0x0000 || self.active_errors().any(|code| code == error_code) {
+            return None;
+        }
+        self.active.push(ActiveError {
+            error_code,
+            error_register,
+        });
+        let additional_information =
+            u16::from_le_bytes([manufacturer_specific[0], manufacturer_specific[1]]);
+        Self::push_error_field(
+            od,
+            ((additional_information as u32) << 16) | error_code as u32,
+        );
+        self.update_error_register(od);
+        Some(
+            EmergencyFrame::new(self.node_id, error_code, self.error_register())
+                .with_manufacturer_specific(manufacturer_specific),
+        )
+    }
+
+    // Removes the error `error_code` and returns the error reset message, which carries the
+    // error register of the remaining errors. Returns `None` if the error is not active.
+    pub fn clear(&mut self, od: &mut ObjectDictionary, error_code: u16) -> Option<EmergencyFrame> {
+        let position = self
+            .active
+            .iter()
+            .position(|error| error.error_code == error_code)?;
+        self.active.remove(position);
+        self.update_error_register(od);
+        Some(EmergencyFrame::new(
+            self.node_id,
+            0x0000,
+            self.error_register(),
+        ))
+    }
+
+    // Forgets the active errors without transmitting anything, e.g. on a node reset.
+    pub fn reset(&mut self) {
+        self.active.clear();
+    }
+
+    // Deletes the error history, as writing 0 to 0x1003 sub-index 0 does.
+    pub fn clear_history(od: &mut ObjectDictionary) {
+        if let Ok(variable) = od.variable_mut(Self::PRE_DEFINED_ERROR_FIELD_INDEX, 0) {
+            variable.value = Value::Unsigned8(0);
+        }
+    }
+
+    // Sub-index 1 holds the newest error. Sub-index 0 holds the number of errors recorded, up
+    // to the number of entries defined in the object dictionary.
+    fn push_error_field(od: &mut ObjectDictionary, entry: u32) {
+        let Some(object) = od.get_mut(Self::PRE_DEFINED_ERROR_FIELD_INDEX) else {
+            return;
+        };
+        let capacity = object
+            .sub_objects()
+            .filter(|(sub_index, _)| *sub_index > 0)
+            .count() as u8;
+        if capacity == 0 {
+            return;
+        }
+        for sub_index in (2..=capacity).rev() {
+            let previous = object
+                .get(sub_index - 1)
+                .map(|variable| variable.value.clone());
+            if let (Some(previous), Some(variable)) = (previous, object.get_mut(sub_index)) {
+                variable.value = previous;
+            }
+        }
+        if let Some(variable) = object.get_mut(1) {
+            variable.value = Value::Unsigned32(entry);
+        }
+        if let Some(variable) = object.get_mut(0) {
+            let count = match variable.value {
+                Value::Unsigned8(count) => count,
+                _ => 0,
+            };
+            variable.value = Value::Unsigned8(count.saturating_add(1).min(capacity));
+        }
+    }
+
+    fn update_error_register(&self, od: &mut ObjectDictionary) {
+        if let Ok(variable) = od.variable_mut(ErrorRegister::INDEX, 0) {
+            variable.value = Value::Unsigned8(self.error_register().as_u8());
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    use crate::od::{AccessType, Object, Variable};
+
+    fn dictionary() -> ObjectDictionary {
+        let mut od = ObjectDictionary::new();
+        od.insert(Object::new_var(
+            0x1001,
+            Variable::new("Error register", AccessType::ReadOnly, Value::Unsigned8(0)),
+        ));
+        od.insert(
+            Object::new_array(
+                0x1003,
+                "Pre-defined error field",
+                AccessType::ReadOnly,
+                vec![Value::Unsigned32(0); 2],
+            )
+            .unwrap(),
+        );
+        EmergencyProducer::clear_history(&mut od);
+        od
+    }
+
+    fn error_field(od: &ObjectDictionary) -> (Value, Value, Value) {
+        (
+            od.variable(0x1003, 0).unwrap().value.clone(),
+            od.variable(0x1003, 1).unwrap().value.clone(),
+            od.variable(0x1003, 2).unwrap().value.clone(),
+        )
+    }
+
+    #[test]
+    fn test_raise() {
+        let mut od = dictionary();
+        let mut producer = EmergencyProducer::new(3.try_into().unwrap());
+        let current = ErrorRegister {
+            current: true,
+            ..0x00.into()
+        };
+        assert_eq!(
+            producer.raise(&mut od, 0x2310, current, [0x34, 0x12, 0x00, 0x00, 0x01]),
+            Some(
+                EmergencyFrame::new(3.try_into().unwrap(), 0x2310, 0x03.into())
+                    .with_manufacturer_specific([0x34, 0x12, 0x00, 0x00, 0x01])
+            )
+        );
+        assert_eq!(
+            error_field(&od),
+            (
+                Value::Unsigned8(1),
+                Value::Unsigned32(0x1234_2310),
+                Value::Unsigned32(0)
+            )
+        );
+        assert_eq!(producer.raise(&mut od, 0x2310, current, [0x00; 5]), None);
+        assert_eq!(producer.raise(&mut od, 0x0000, current, [0x00; 5]), None);
+
+        producer.raise(&mut od, 0x8130, 0x10.into(), [0x00; 5]);
+        producer.raise(&mut od, 0x4210, 0x08.into(), [0x00; 5]);
+        assert_eq!(
+            error_field(&od),
+            (
+                Value::Unsigned8(2),
+                Value::Unsigned32(0x4210),
+                Value::Unsigned32(0x8130)
+            )
+        );
+        assert_eq!(producer.error_register(), 0x1B.into());
+        assert_eq!(
+            od.variable(0x1001, 0).unwrap().value,
+            Value::Unsigned8(0x1B)
+        );
+    }
+
+    #[test]
+    fn test_clear() {
+        let mut od = dictionary();
+        let mut producer = EmergencyProducer::new(3.try_into().unwrap());
+        producer.raise(&mut od, 0x2310, 0x02.into(), [0x00; 5]);
+        producer.raise(&mut od, 0x4210, 0x08.into(), [0x00; 5]);
+        assert_eq!(producer.clear(&mut od, 0x1000), None);
+        assert_eq!(
+            producer.clear(&mut od, 0x2310),
+            Some(EmergencyFrame::new(
+                3.try_into().unwrap(),
+                0x0000,
+                0x09.into()
+            ))
+        );
+        assert_eq!(producer.active_errors().collect::<Vec<_>>(), vec![0x4210]);
+        assert_eq!(
+            producer.clear(&mut od, 0x4210),
+            Some(EmergencyFrame::new(
+                3.try_into().unwrap(),
+                0x0000,
+                0x00.into()
+            ))
+        );
+        assert_eq!(od.variable(0x1001, 0).unwrap().value, Value::Unsigned8(0));
+        // The history is kept.
+        assert_eq!(od.variable(0x1003, 0).unwrap().value, Value::Unsigned8(2));
+        EmergencyProducer::clear_history(&mut od);
+        assert_eq!(od.variable(0x1003, 0).unwrap().value, Value::Unsigned8(0));
+    }
+
+    #[test]
+    fn test_without_objects() {
+        let mut od = ObjectDictionary::new();
+        let mut producer = EmergencyProducer::new(3.try_into().unwrap());
+        assert!(producer
+            .raise(&mut od, 0x5000, 0x00.into(), [0x00; 5])
+            .is_some());
+        producer.reset();
+        assert_eq!(producer.error_register(), 0x00.into());
+    }
+}
@@ -0,0 +1,185 @@
+use crate::frame::{
+    NmtCommand, NmtNodeControlAddress, NmtNodeControlFrame, NmtNodeMonitoringFrame, NmtState,
+};
+use crate::id::NodeId;
+
+// A reset requested by the NMT master. The slave is back in initialisation; the application
+// performs the reset and calls `NmtSlave::boot` afterwards.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum NmtReset {
+    // Application and communication parameters are reset to their default values.
+    Node,
+    // Communication parameters only
+    Communication,
+}
+
+// NMT state machine of a slave device (cf. CiA 301). `NmtState::BootUp` stands for the
+// initialisation state.
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub struct NmtSlave {
+    node_id: NodeId,
+    state: NmtState,
+}
+
+impl NmtSlave {
+    pub fn new(node_id: NodeId) -> Self {
+        Self {
+            node_id,
+            state: NmtState::BootUp,
+        }
+    }
+
+    pub fn node_id(&self) -> NodeId {
+        self.node_id
+    }
+
+    pub fn state(&self) -> NmtState {
+        self.state
+    }
+
+    // Finishes the initialisation: enters pre-operational and returns the boot-up message.
+    pub fn boot(&mut self) -> NmtNodeMonitoringFrame {
+        self.state = NmtState::PreOperational;
+        NmtNodeMonitoringFrame::new(self.node_id, NmtState::BootUp)
+    }
+
+    // Applies a command addressed to this node or to all nodes. Commands are ignored during
+    // the initialisation.
+    pub fn on_frame(&mut self, frame: &NmtNodeControlFrame) -> Option<NmtReset> {
+        if self.state == NmtState::BootUp {
+            return None;
+        }
+        if let NmtNodeControlAddress::Node(node_id) = frame.address {
+            if node_id != self.node_id {
+                return None;
+            }
+        }
+        match frame.command {
+            NmtCommand::Operational => self.state = NmtState::Operational,
+            NmtCommand::Stopped => self.state = NmtState::Stopped,
+            NmtCommand::PreOperational => self.state = NmtState::PreOperational,
+            NmtCommand::ResetNode => {
+                self.state = NmtState::BootUp;
+                return Some(NmtReset::Node);
+            }
+            NmtCommand::ResetCommunication => {
+                self.state = NmtState::BootUp;
+                return Some(NmtReset::Communication);
+            }
+        }
+        None
+    }
+
+    pub fn is_pdo_allowed(&self) -> bool {
+        self.state == NmtState::Operational
+    }
+
+    pub fn is_sdo_allowed(&self) -> bool {
+        matches!(self.state, NmtState::PreOperational | NmtState::Operational)
+    }
+
+    // Also applies to SYNC and TIME.
+    pub fn is_emcy_allowed(&self) -> bool {
+        matches!(self.state, NmtState::PreOperational | NmtState::Operational)
+    }
+
+    // Node control and error control (heartbeat) services
+    pub fn is_nmt_allowed(&self) -> bool {
+        self.state != NmtState::BootUp
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn command(command: NmtCommand, address: NmtNodeControlAddress) -> NmtNodeControlFrame {
+        NmtNodeControlFrame { command, address }
+    }
+
+    #[test]
+    fn test_transitions() {
+        let node_id = 5.try_into().unwrap();
+        let mut slave = NmtSlave::new(node_id);
+        assert_eq!(slave.state(), NmtState::BootUp);
+        assert_eq!(
+            slave.on_frame(&command(
+                NmtCommand::Operational,
+                NmtNodeControlAddress::AllNodes
+            )),
+            None
+        );
+        assert_eq!(slave.state(), NmtState::BootUp);
+
+        assert_eq!(
+            slave.boot(),
+            NmtNodeMonitoringFrame::new(node_id, NmtState::BootUp)
+        );
+        assert_eq!(slave.state(), NmtState::PreOperational);
+
+        slave.on_frame(&command(
+            NmtCommand::Operational,
+            NmtNodeControlAddress::Node(node_id),
+        ));
+        assert_eq!(slave.state(), NmtState::Operational);
+        slave.on_frame(&command(
+            NmtCommand::Stopped,
+            NmtNodeControlAddress::Node(6.try_into().unwrap()),
+        ));
+        assert_eq!(slave.state(), NmtState::Operational);
+        slave.on_frame(&command(
+            NmtCommand::Stopped,
+            NmtNodeControlAddress::AllNodes,
+        ));
+        assert_eq!(slave.state(), NmtState::Stopped);
+        slave.on_frame(&command(
+            NmtCommand::PreOperational,
+            NmtNodeControlAddress::AllNodes,
+        ));
+        assert_eq!(slave.state(), NmtState::PreOperational);
+
+        assert_eq!(
+            slave.on_frame(&command(
+                NmtCommand::ResetCommunication,
+                NmtNodeControlAddress::AllNodes
+            )),
+            Some(NmtReset::Communication)
+        );
+        assert_eq!(slave.state(), NmtState::BootUp);
+        slave.boot();
+        assert_eq!(
+            slave.on_frame(&command(
+                NmtCommand::ResetNode,
+                NmtNodeControlAddress::Node(node_id)
+            )),
+            Some(NmtReset::Node)
+        );
+        assert_eq!(slave.state(), NmtState::BootUp);
+    }
+
+    #[test]
+    fn test_services() {
+        let mut slave = NmtSlave::new(5.try_into().unwrap());
+        let services = |slave: &NmtSlave| {
+            [
+                slave.is_pdo_allowed(),
+                slave.is_sdo_allowed(),
+                slave.is_emcy_allowed(),
+                slave.is_nmt_allowed(),
+            ]
+        };
+        assert_eq!(services(&slave), [false, false, false, false]);
+        slave.boot();
+        assert_eq!(services(&slave), [false, true, true, true]);
+        slave.on_frame(&command(
+            NmtCommand::Operational,
+            NmtNodeControlAddress::AllNodes,
+        ));
+        assert_eq!(services(&slave), [true, true, true, true]);
+        slave.on_frame(&command(
+            NmtCommand::Stopped,
+            NmtNodeControlAddress::AllNodes,
+        ));
+        assert_eq!(services(&slave), [false, false, false, true]);
+    }
+}
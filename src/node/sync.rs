@@ -0,0 +1,157 @@
+// What an application sees on a consumed SYNC
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub struct SyncInfo {
+    // Number of SYNCs consumed before this one
+    pub cycle: u64,
+    // Time since the previous SYNC, if any
+    pub phase: Option<std::time::Duration>,
+    // Communication cycle period (0x1006), or the measured phase when it is not configured
+    pub period: Option<std::time::Duration>,
+}
+
+// Keeps track of the SYNC cycle and whether the work done on each SYNC fits in it
+#[derive(Clone, Debug, Default, PartialEq)]
+pub struct SyncCycle {
+    cycle: u64,
+    last_sync_at: Option<std::time::Instant>,
+    overruns: u64,
+    last_duration: Option<std::time::Duration>,
+    last_overran: bool,
+}
+
+impl SyncCycle {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    // Starts a cycle on a SYNC received at `now`.
+    pub fn start(
+        &mut self,
+        now: std::time::Instant,
+        period: Option<std::time::Duration>,
+    ) -> SyncInfo {
+        let phase = self
+            .last_sync_at
+            .map(|last_sync_at| now.saturating_duration_since(last_sync_at));
+        let info = SyncInfo {
+            cycle: self.cycle,
+            phase,
+            period: period.or(phase),
+        };
+        self.cycle += 1;
+        self.last_sync_at = Some(now);
+        info
+    }
+
+    // Records that the work of the cycle described by `info` took `duration`. Returns whether
+    // it overran the cycle. Nothing is an overrun while the period is unknown.
+    pub fn finish(&mut self, info: &SyncInfo, duration: std::time::Duration) -> bool {
+        let overran = info.period.is_some_and(|period| duration > period);
+        if overran {
+            self.overruns += 1;
+        }
+        self.last_duration = Some(duration);
+        self.last_overran = overran;
+        overran
+    }
+
+    // Records that the work of the cycle described by `info`, started by the latest SYNC, was
+    // done at `now`. Returns whether it overran the cycle.
+    pub fn finish_at(&mut self, info: &SyncInfo, now: std::time::Instant) -> bool {
+        let duration = self
+            .last_sync_at
+            .map_or(std::time::Duration::ZERO, |last_sync_at| {
+                now.saturating_duration_since(last_sync_at)
+            });
+        self.finish(info, duration)
+    }
+
+    // Number of SYNCs consumed
+    pub fn cycles(&self) -> u64 {
+        self.cycle
+    }
+
+    pub fn overruns(&self) -> u64 {
+        self.overruns
+    }
+
+    pub fn last_duration(&self) -> Option<std::time::Duration> {
+        self.last_duration
+    }
+
+    pub fn last_overran(&self) -> bool {
+        self.last_overran
+    }
+
+    // Forgets the previous SYNC, e.g. after a communication reset. Counters are kept.
+    pub fn restart(&mut self) {
+        self.last_sync_at = None;
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    use std::time::Duration;
+
+    #[test]
+    fn test_start() {
+        let now = std::time::Instant::now();
+        let mut sync_cycle = SyncCycle::new();
+        assert_eq!(
+            sync_cycle.start(now, None),
+            SyncInfo {
+                cycle: 0,
+                phase: None,
+                period: None
+            }
+        );
+        assert_eq!(
+            sync_cycle.start(now + Duration::from_millis(10), None),
+            SyncInfo {
+                cycle: 1,
+                phase: Some(Duration::from_millis(10)),
+                period: Some(Duration::from_millis(10))
+            }
+        );
+        assert_eq!(
+            sync_cycle.start(
+                now + Duration::from_millis(21),
+                Some(Duration::from_millis(10))
+            ),
+            SyncInfo {
+                cycle: 2,
+                phase: Some(Duration::from_millis(11)),
+                period: Some(Duration::from_millis(10))
+            }
+        );
+        assert_eq!(sync_cycle.cycles(), 3);
+    }
+
+    #[test]
+    fn test_finish() {
+        let now = std::time::Instant::now();
+        let mut sync_cycle = SyncCycle::new();
+        let info = sync_cycle.start(now, None);
+        assert!(!sync_cycle.finish(&info, Duration::from_secs(1)));
+        let info = sync_cycle.start(now, Some(Duration::from_millis(1)));
+        assert!(!sync_cycle.finish(&info, Duration::from_micros(500)));
+        assert!(!sync_cycle.last_overran());
+        assert!(sync_cycle.finish(&info, Duration::from_millis(2)));
+        assert!(sync_cycle.last_overran());
+        assert_eq!(sync_cycle.last_duration(), Some(Duration::from_millis(2)));
+        assert_eq!(sync_cycle.overruns(), 1);
+    }
+
+    #[test]
+    fn test_finish_at() {
+        let now = std::time::Instant::now();
+        let mut sync_cycle = SyncCycle::new();
+        let info = sync_cycle.start(now, Some(Duration::from_millis(1)));
+        assert!(!sync_cycle.finish_at(&info, now + Duration::from_micros(500)));
+        assert_eq!(sync_cycle.last_duration(), Some(Duration::from_micros(500)));
+        assert!(sync_cycle.finish_at(&info, now + Duration::from_millis(2)));
+        assert_eq!(sync_cycle.overruns(), 1);
+    }
+}
@@ -0,0 +1,269 @@
+use crate::frame::{NmtNodeMonitoringFrame, NmtState, RemoteFrame};
+use crate::id::NodeId;
+
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub enum NodeGuardingEvent {
+    // The first response, or a response with another state than the previous one
+    StateChanged {
+        from: Option<NmtState>,
+        to: NmtState,
+    },
+    // The toggle bit did not alternate. The response is ignored.
+    ToggleMismatch,
+    // No valid response within the node life time (guard time × life time factor)
+    LifeGuardingLost,
+    // A valid response after `LifeGuardingLost`
+    LifeGuardingResumed,
+}
+
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub enum NodeGuardingOutput {
+    // A remote frame to transmit
+    Request(RemoteFrame),
+    Event(NodeGuardingEvent),
+}
+
+// Node guarding master for one node (cf. CiA 301), for devices that do not produce
+// heartbeats. `poll` tells when to send the guarding request and reports life guarding
+// losses; responses are fed to `on_frame`. Guarding is disabled while the guard time or the
+// life time factor is 0.
+#[derive(Clone, Copy, Debug)]
+pub struct NodeGuard {
+    node_id: NodeId,
+    guard_time: std::time::Duration,
+    life_time_factor: u8,
+    requested_at: Option<std::time::Instant>,
+    responded_at: Option<std::time::Instant>,
+    started_at: Option<std::time::Instant>,
+    toggle: bool,
+    state: Option<NmtState>,
+    lost: bool,
+}
+
+impl NodeGuard {
+    pub const GUARD_TIME_INDEX: u16 = 0x100C;
+    pub const LIFE_TIME_FACTOR_INDEX: u16 = 0x100D;
+
+    pub fn new(node_id: NodeId, guard_time: std::time::Duration, life_time_factor: u8) -> Self {
+        Self {
+            node_id,
+            guard_time,
+            life_time_factor,
+            requested_at: None,
+            responded_at: None,
+            started_at: None,
+            toggle: false,
+            state: None,
+            lost: false,
+        }
+    }
+
+    // From the values of 0x100C (milliseconds) and 0x100D
+    pub fn from_objects(node_id: NodeId, guard_time: u16, life_time_factor: u8) -> Self {
+        Self::new(
+            node_id,
+            std::time::Duration::from_millis(guard_time.into()),
+            life_time_factor,
+        )
+    }
+
+    pub fn node_id(&self) -> NodeId {
+        self.node_id
+    }
+
+    pub fn life_time(&self) -> std::time::Duration {
+        self.guard_time * self.life_time_factor.into()
+    }
+
+    pub fn is_enabled(&self) -> bool {
+        !self.life_time().is_zero()
+    }
+
+    // The last state reported by the node, unless life guarding is lost
+    pub fn state(&self) -> Option<NmtState> {
+        self.state.filter(|_| !self.lost)
+    }
+
+    pub fn poll(&mut self, now: std::time::Instant) -> std::vec::Vec<NodeGuardingOutput> {
+        let mut outputs = std::vec::Vec::new();
+        if !self.is_enabled() {
+            return outputs;
+        }
+        let started_at = *self.started_at.get_or_insert(now);
+        let alive_since = self.responded_at.unwrap_or(started_at);
+        if !self.lost && now.saturating_duration_since(alive_since) > self.life_time() {
+            self.lost = true;
+            outputs.push(NodeGuardingOutput::Event(
+                NodeGuardingEvent::LifeGuardingLost,
+            ));
+        }
+        let due = self.requested_at.is_none_or(|requested_at| {
+            now.saturating_duration_since(requested_at) >= self.guard_time
+        });
+        if due {
+            self.requested_at = Some(now);
+            outputs.push(NodeGuardingOutput::Request(RemoteFrame::new_node_guarding(
+                self.node_id,
+            )));
+        }
+        outputs
+    }
+
+    pub fn on_frame(
+        &mut self,
+        frame: &NmtNodeMonitoringFrame,
+        now: std::time::Instant,
+    ) -> std::vec::Vec<NodeGuardingEvent> {
+        let mut events = std::vec::Vec::new();
+        // Heartbeats and boot-up messages are not guarding responses.
+        if frame.node_id != self.node_id
+            || self.requested_at.is_none()
+            || frame.state == NmtState::BootUp
+        {
+            return events;
+        }
+        if frame.toggle != self.toggle {
+            // Resynchronise with the node.
+            self.toggle = !frame.toggle;
+            events.push(NodeGuardingEvent::ToggleMismatch);
+            return events;
+        }
+        self.toggle = !self.toggle;
+        self.responded_at = Some(now);
+        if self.lost {
+            self.lost = false;
+            events.push(NodeGuardingEvent::LifeGuardingResumed);
+        }
+        if self.state != Some(frame.state) {
+            events.push(NodeGuardingEvent::StateChanged {
+                from: self.state,
+                to: frame.state,
+            });
+            self.state = Some(frame.state);
+        }
+        events
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    use std::time::Duration;
+
+    fn response(state: NmtState, toggle: bool) -> NmtNodeMonitoringFrame {
+        NmtNodeMonitoringFrame::new_node_guarding_response(2.try_into().unwrap(), state, toggle)
+    }
+
+    fn request() -> NodeGuardingOutput {
+        NodeGuardingOutput::Request(RemoteFrame::new_node_guarding(2.try_into().unwrap()))
+    }
+
+    #[test]
+    fn test_requests() {
+        let now = std::time::Instant::now();
+        let mut guard = NodeGuard::from_objects(2.try_into().unwrap(), 100, 3);
+        assert_eq!(guard.life_time(), Duration::from_millis(300));
+        assert_eq!(guard.poll(now), vec![request()]);
+        assert_eq!(guard.poll(now + Duration::from_millis(99)), vec![]);
+        assert_eq!(
+            guard.poll(now + Duration::from_millis(100)),
+            vec![request()]
+        );
+
+        let mut guard = NodeGuard::from_objects(2.try_into().unwrap(), 100, 0);
+        assert!(!guard.is_enabled());
+        assert_eq!(guard.poll(now), vec![]);
+    }
+
+    #[test]
+    fn test_responses() {
+        let now = std::time::Instant::now();
+        let mut guard = NodeGuard::from_objects(2.try_into().unwrap(), 100, 3);
+        assert_eq!(
+            guard.on_frame(&response(NmtState::PreOperational, false), now),
+            vec![]
+        );
+        guard.poll(now);
+        assert_eq!(
+            guard.on_frame(&response(NmtState::PreOperational, false), now),
+            vec![NodeGuardingEvent::StateChanged {
+                from: None,
+                to: NmtState::PreOperational
+            }]
+        );
+        assert_eq!(
+            guard.on_frame(&response(NmtState::PreOperational, true), now),
+            vec![]
+        );
+        assert_eq!(
+            guard.on_frame(&response(NmtState::Operational, false), now),
+            vec![NodeGuardingEvent::StateChanged {
+                from: Some(NmtState::PreOperational),
+                to: NmtState::Operational
+            }]
+        );
+        assert_eq!(
+            guard.on_frame(&response(NmtState::Operational, false), now),
+            vec![NodeGuardingEvent::ToggleMismatch]
+        );
+        assert_eq!(
+            guard.on_frame(&response(NmtState::Operational, true), now),
+            vec![]
+        );
+        assert_eq!(guard.state(), Some(NmtState::Operational));
+        assert_eq!(
+            guard.on_frame(
+                &NmtNodeMonitoringFrame::new(2.try_into().unwrap(), NmtState::BootUp),
+                now
+            ),
+            vec![]
+        );
+    }
+
+    #[test]
+    fn test_life_guarding() {
+        let now = std::time::Instant::now();
+        let mut guard = NodeGuard::from_objects(2.try_into().unwrap(), 100, 3);
+        guard.poll(now);
+        guard.on_frame(&response(NmtState::Operational, false), now);
+        assert_eq!(
+            guard.poll(now + Duration::from_millis(300)),
+            vec![request()]
+        );
+        assert_eq!(
+            guard.poll(now + Duration::from_millis(400)),
+            vec![
+                NodeGuardingOutput::Event(NodeGuardingEvent::LifeGuardingLost),
+                request()
+            ]
+        );
+        assert_eq!(guard.state(), None);
+        assert_eq!(
+            guard.poll(now + Duration::from_millis(500)),
+            vec![request()]
+        );
+        assert_eq!(
+            guard.on_frame(
+                &response(NmtState::Operational, true),
+                now + Duration::from_millis(500)
+            ),
+            vec![NodeGuardingEvent::LifeGuardingResumed]
+        );
+        assert_eq!(guard.state(), Some(NmtState::Operational));
+    }
+
+    #[test]
+    fn test_no_response() {
+        let now = std::time::Instant::now();
+        let mut guard = NodeGuard::from_objects(2.try_into().unwrap(), 10, 2);
+        guard.poll(now);
+        assert_eq!(
+            guard.poll(now + Duration::from_millis(21)),
+            vec![
+                NodeGuardingOutput::Event(NodeGuardingEvent::LifeGuardingLost),
+                request()
+            ]
+        );
+    }
+}
@@ -0,0 +1,420 @@
+use crate::error::{Error, Result};
+use crate::frame::SdoFrame;
+use crate::id::NodeId;
+
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub struct DeviceType {
+    pub device_profile_number: u16,
+    pub additional_information: u16,
+}
+
+impl DeviceType {
+    pub const INDEX: u16 = 0x1000;
+
+    pub fn new(device_profile_number: u16, additional_information: u16) -> Self {
+        Self {
+            device_profile_number,
+            additional_information,
+        }
+    }
+
+    pub fn from_u32(value: u32) -> Self {
+        Self::new(value as u16, (value >> 16) as u16)
+    }
+
+    pub fn as_u32(&self) -> u32 {
+        ((self.additional_information as u32) << 16) | self.device_profile_number as u32
+    }
+
+    pub fn from_bytes(bytes: &[u8]) -> Result<Self> {
+        Ok(Self::from_u32(u32::from_le_bytes(
+            bytes.try_into().map_err(|_| Error::InvalidDataLength {
+                length: bytes.len(),
+                data_type: "DeviceType".to_owned(),
+            })?,
+        )))
+    }
+
+    pub fn has_device_profile(&self) -> bool {
+        self.device_profile_number != 0
+    }
+
+    pub fn new_sdo_read_frame(node_id: NodeId) -> SdoFrame {
+        SdoFrame::new_sdo_read_frame(node_id, Self::INDEX, 0)
+    }
+}
+
+// Error register (0x1001), also carried by emergency messages (cf. CiA 301)
+#[derive(Clone, Copy, Debug, Default, PartialEq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct ErrorRegister {
+    pub generic: bool,
+    pub current: bool,
+    pub voltage: bool,
+    pub temperature: bool,
+    pub communication: bool,
+    pub device_profile: bool,
+    // Reserved (always 0), kept so that decoding and encoding round-trip
+    pub reserved: bool,
+    pub manufacturer: bool,
+}
+
+impl ErrorRegister {
+    pub const INDEX: u16 = 0x1001;
+
+    const NAMES: [&'static str; 8] = [
+        "generic",
+        "current",
+        "voltage",
+        "temperature",
+        "communication",
+        "device profile",
+        "reserved",
+        "manufacturer",
+    ];
+
+    pub fn from_u8(value: u8) -> Self {
+        let bit = |n: u8| value & (1 << n) != 0;
+        Self {
+            generic: bit(0),
+            current: bit(1),
+            voltage: bit(2),
+            temperature: bit(3),
+            communication: bit(4),
+            device_profile: bit(5),
+            reserved: bit(6),
+            manufacturer: bit(7),
+        }
+    }
+
+    pub fn as_u8(&self) -> u8 {
+        [
+            self.generic,
+            self.current,
+            self.voltage,
+            self.temperature,
+            self.communication,
+            self.device_profile,
+            self.reserved,
+            self.manufacturer,
+        ]
+        .iter()
+        .enumerate()
+        .fold(0, |value, (n, bit)| value | ((*bit as u8) << n))
+    }
+
+    pub fn from_bytes(bytes: &[u8]) -> Result<Self> {
+        match bytes {
+            [value] => Ok(Self::from_u8(*value)),
+            _ => Err(Error::InvalidDataLength {
+                length: bytes.len(),
+                data_type: "ErrorRegister".to_owned(),
+            }),
+        }
+    }
+
+    pub fn has_error(&self) -> bool {
+        self.as_u8() != 0
+    }
+
+    pub fn new_sdo_read_frame(node_id: NodeId) -> SdoFrame {
+        SdoFrame::new_sdo_read_frame(node_id, Self::INDEX, 0)
+    }
+}
+
+impl From<u8> for ErrorRegister {
+    fn from(value: u8) -> Self {
+        Self::from_u8(value)
+    }
+}
+
+impl From<ErrorRegister> for u8 {
+    fn from(error_register: ErrorRegister) -> Self {
+        error_register.as_u8()
+    }
+}
+
+// Lists the set bits, e.g. "generic, temperature", or "no error"
+impl std::fmt::Display for ErrorRegister {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        let value = self.as_u8();
+        if value == 0 {
+            return write!(f, "no error");
+        }
+        let mut names = Self::NAMES
+            .iter()
+            .enumerate()
+            .filter(|(n, _)| value & (1 << n) != 0)
+            .map(|(_, name)| name);
+        if let Some(name) = names.next() {
+            write!(f, "{}", name)?;
+        }
+        for name in names {
+            write!(f, ", {}", name)?;
+        }
+        Ok(())
+    }
+}
+
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub struct ConfigurationDateTime {
+    // Days since January 1, 1984
+    pub date: u32,
+    // Milliseconds after midnight
+    pub time: u32,
+}
+
+impl ConfigurationDateTime {
+    pub const INDEX: u16 = 0x1020;
+    pub const DATE_SUB_INDEX: u8 = 1;
+    pub const TIME_SUB_INDEX: u8 = 2;
+
+    pub(crate) const MILLISECONDS_PER_DAY: u128 = 24 * 60 * 60 * 1000;
+    // 1984-01-01T00:00:00Z in seconds since the UNIX epoch
+    pub(crate) const EPOCH_UNIX_SECONDS: u64 = 441_763_200;
+
+    pub fn new(date: u32, time: u32) -> Self {
+        Self { date, time }
+    }
+
+    pub fn from_system_time(system_time: std::time::SystemTime) -> Result<Self> {
+        let epoch =
+            std::time::UNIX_EPOCH + std::time::Duration::from_secs(Self::EPOCH_UNIX_SECONDS);
+        let milliseconds = system_time
+            .duration_since(epoch)
+            .map_err(|_| Error::TimeOutOfRange)?
+            .as_millis();
+        Ok(Self::new(
+            (milliseconds / Self::MILLISECONDS_PER_DAY)
+                .try_into()
+                .map_err(|_| Error::TimeOutOfRange)?,
+            (milliseconds % Self::MILLISECONDS_PER_DAY) as u32,
+        ))
+    }
+
+    pub fn now() -> Result<Self> {
+        Self::from_system_time(std::time::SystemTime::now())
+    }
+
+    pub fn from_bytes(date: &[u8], time: &[u8]) -> Result<Self> {
+        let to_u32 = |bytes: &[u8]| {
+            bytes
+                .try_into()
+                .map(u32::from_le_bytes)
+                .map_err(|_| Error::InvalidDataLength {
+                    length: bytes.len(),
+                    data_type: "ConfigurationDateTime".to_owned(),
+                })
+        };
+        Ok(Self::new(to_u32(date)?, to_u32(time)?))
+    }
+
+    pub fn is_configured(&self) -> bool {
+        self.date != 0 || self.time != 0
+    }
+
+    pub fn new_sdo_read_frames(node_id: NodeId) -> [SdoFrame; 2] {
+        [
+            SdoFrame::new_sdo_read_frame(node_id, Self::INDEX, Self::DATE_SUB_INDEX),
+            SdoFrame::new_sdo_read_frame(node_id, Self::INDEX, Self::TIME_SUB_INDEX),
+        ]
+    }
+
+    pub fn new_sdo_write_frames(&self, node_id: NodeId) -> [SdoFrame; 2] {
+        [
+            SdoFrame::new_sdo_write_frame(
+                node_id,
+                Self::INDEX,
+                Self::DATE_SUB_INDEX,
+                self.date.to_le_bytes().into(),
+            ),
+            SdoFrame::new_sdo_write_frame(
+                node_id,
+                Self::INDEX,
+                Self::TIME_SUB_INDEX,
+                self.time.to_le_bytes().into(),
+            ),
+        ]
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    use crate::frame::ConvertibleFrame;
+
+    #[test]
+    fn test_device_type_from_u32() {
+        assert_eq!(
+            DeviceType::from_u32(0x0002_0192),
+            DeviceType {
+                device_profile_number: 402,
+                additional_information: 0x0002
+            }
+        );
+        assert_eq!(
+            DeviceType::from_u32(0x0000_0000),
+            DeviceType {
+                device_profile_number: 0,
+                additional_information: 0
+            }
+        );
+    }
+
+    #[test]
+    fn test_device_type_as_u32() {
+        assert_eq!(DeviceType::new(402, 0x0002).as_u32(), 0x0002_0192);
+        assert_eq!(DeviceType::new(406, 0x0001).as_u32(), 0x0001_0196);
+    }
+
+    #[test]
+    fn test_device_type_from_bytes() {
+        assert_eq!(
+            DeviceType::from_bytes(&[0x92, 0x01, 0x02, 0x00]),
+            Ok(DeviceType::new(402, 0x0002))
+        );
+        assert_eq!(
+            DeviceType::from_bytes(&[0x92, 0x01]),
+            Err(Error::InvalidDataLength {
+                length: 2,
+                data_type: "DeviceType".to_owned()
+            })
+        );
+    }
+
+    #[test]
+    fn test_device_type_has_device_profile() {
+        assert!(DeviceType::new(402, 0).has_device_profile());
+        assert!(!DeviceType::new(0, 0x1234).has_device_profile());
+    }
+
+    #[test]
+    fn test_device_type_sdo_read_frame() {
+        assert_eq!(
+            DeviceType::new_sdo_read_frame(4.try_into().unwrap()).frame_data(),
+            &[0x40, 0x00, 0x10, 0x00, 0x00, 0x00, 0x00, 0x00]
+        );
+    }
+
+    #[test]
+    fn test_error_register() {
+        let error_register = ErrorRegister::from_u8(0x89);
+        assert_eq!(
+            error_register,
+            ErrorRegister {
+                generic: true,
+                temperature: true,
+                manufacturer: true,
+                ..Default::default()
+            }
+        );
+        assert!(error_register.has_error());
+        assert_eq!(error_register.as_u8(), 0x89);
+        assert_eq!(
+            error_register.to_string(),
+            "generic, temperature, manufacturer"
+        );
+        assert_eq!(ErrorRegister::from_u8(0x00).to_string(), "no error");
+        assert!(!ErrorRegister::default().has_error());
+        for value in 0..=u8::MAX {
+            assert_eq!(u8::from(ErrorRegister::from(value)), value);
+        }
+    }
+
+    #[test]
+    fn test_error_register_from_bytes() {
+        assert_eq!(
+            ErrorRegister::from_bytes(&[0x11]),
+            Ok(ErrorRegister {
+                generic: true,
+                communication: true,
+                ..Default::default()
+            })
+        );
+        assert_eq!(
+            ErrorRegister::from_bytes(&[]),
+            Err(Error::InvalidDataLength {
+                length: 0,
+                data_type: "ErrorRegister".to_owned()
+            })
+        );
+        assert_eq!(
+            ErrorRegister::new_sdo_read_frame(4.try_into().unwrap()).frame_data(),
+            &[0x40, 0x01, 0x10, 0x00, 0x00, 0x00, 0x00, 0x00]
+        );
+    }
+
+    #[test]
+    fn test_configuration_date_time_from_bytes() {
+        assert_eq!(
+            ConfigurationDateTime::from_bytes(&[0x10, 0x3A, 0x00, 0x00], &[0x80, 0xEE, 0x36, 0x00]),
+            Ok(ConfigurationDateTime {
+                date: 14864,
+                time: 3_600_000
+            })
+        );
+        assert_eq!(
+            ConfigurationDateTime::from_bytes(&[0x10, 0x3A], &[0x80, 0xEE, 0x36, 0x00]),
+            Err(Error::InvalidDataLength {
+                length: 2,
+                data_type: "ConfigurationDateTime".to_owned()
+            })
+        );
+    }
+
+    #[test]
+    fn test_configuration_date_time_from_system_time() {
+        let epoch = std::time::UNIX_EPOCH + std::time::Duration::from_secs(441_763_200);
+        assert_eq!(
+            ConfigurationDateTime::from_system_time(epoch),
+            Ok(ConfigurationDateTime::new(0, 0))
+        );
+        // 2024-09-12T01:00:00.250Z
+        assert_eq!(
+            ConfigurationDateTime::from_system_time(
+                std::time::UNIX_EPOCH + std::time::Duration::from_millis(1_726_102_800_250)
+            ),
+            Ok(ConfigurationDateTime::new(14865, 3_600_250))
+        );
+        assert_eq!(
+            ConfigurationDateTime::from_system_time(std::time::UNIX_EPOCH),
+            Err(Error::TimeOutOfRange)
+        );
+        assert!(ConfigurationDateTime::now().unwrap().is_configured());
+    }
+
+    #[test]
+    fn test_configuration_date_time_is_configured() {
+        assert!(!ConfigurationDateTime::new(0, 0).is_configured());
+        assert!(ConfigurationDateTime::new(14864, 0).is_configured());
+        assert!(ConfigurationDateTime::new(0, 1).is_configured());
+    }
+
+    #[test]
+    fn test_configuration_date_time_sdo_read_frames() {
+        let [date, time] = ConfigurationDateTime::new_sdo_read_frames(5.try_into().unwrap());
+        assert_eq!(
+            date.frame_data(),
+            &[0x40, 0x20, 0x10, 0x01, 0x00, 0x00, 0x00, 0x00]
+        );
+        assert_eq!(
+            time.frame_data(),
+            &[0x40, 0x20, 0x10, 0x02, 0x00, 0x00, 0x00, 0x00]
+        );
+    }
+
+    #[test]
+    fn test_configuration_date_time_sdo_write_frames() {
+        let [date, time] = ConfigurationDateTime::new(14864, 3_600_000)
+            .new_sdo_write_frames(5.try_into().unwrap());
+        assert_eq!(
+            date.frame_data(),
+            &[0x23, 0x20, 0x10, 0x01, 0x10, 0x3A, 0x00, 0x00]
+        );
+        assert_eq!(
+            time.frame_data(),
+            &[0x23, 0x20, 0x10, 0x02, 0x80, 0xEE, 0x36, 0x00]
+        );
+    }
+}
@@ -0,0 +1,691 @@
+use crate::error::{Error, Result};
+use crate::sdo::{SdoAbortCode, SdoValue};
+
+// Basic data types (cf. CiA 301)
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum DataType {
+    Boolean = 0x0001,
+    Integer8 = 0x0002,
+    Integer16 = 0x0003,
+    Integer32 = 0x0004,
+    Unsigned8 = 0x0005,
+    Unsigned16 = 0x0006,
+    Unsigned32 = 0x0007,
+    Real32 = 0x0008,
+    VisibleString = 0x0009,
+    OctetString = 0x000A,
+    Domain = 0x000F,
+    Real64 = 0x0011,
+    Integer64 = 0x0015,
+    Unsigned64 = 0x001B,
+}
+
+impl DataType {
+    pub fn from_u16(value: u16) -> Result<Self> {
+        match value {
+            0x0001 => Ok(Self::Boolean),
+            0x0002 => Ok(Self::Integer8),
+            0x0003 => Ok(Self::Integer16),
+            0x0004 => Ok(Self::Integer32),
+            0x0005 => Ok(Self::Unsigned8),
+            0x0006 => Ok(Self::Unsigned16),
+            0x0007 => Ok(Self::Unsigned32),
+            0x0008 => Ok(Self::Real32),
+            0x0009 => Ok(Self::VisibleString),
+            0x000A => Ok(Self::OctetString),
+            0x000F => Ok(Self::Domain),
+            0x0011 => Ok(Self::Real64),
+            0x0015 => Ok(Self::Integer64),
+            0x001B => Ok(Self::Unsigned64),
+            _ => Err(Error::InvalidDataType(value)),
+        }
+    }
+
+    pub fn as_u16(&self) -> u16 {
+        *self as u16
+    }
+
+    // Size in bytes, or `None` for variable-length types
+    pub fn size(&self) -> Option<usize> {
+        match self {
+            Self::Boolean | Self::Integer8 | Self::Unsigned8 => Some(1),
+            Self::Integer16 | Self::Unsigned16 => Some(2),
+            Self::Integer32 | Self::Unsigned32 | Self::Real32 => Some(4),
+            Self::Integer64 | Self::Unsigned64 | Self::Real64 => Some(8),
+            Self::VisibleString | Self::OctetString | Self::Domain => None,
+        }
+    }
+}
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum AccessType {
+    ReadOnly,
+    WriteOnly,
+    ReadWrite,
+    Constant,
+}
+
+impl AccessType {
+    pub fn is_readable(&self) -> bool {
+        !matches!(self, Self::WriteOnly)
+    }
+
+    pub fn is_writable(&self) -> bool {
+        matches!(self, Self::WriteOnly | Self::ReadWrite)
+    }
+}
+
+// Object codes (cf. CiA 301)
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum ObjectType {
+    Var = 7,
+    Array = 8,
+    Record = 9,
+}
+
+#[derive(Clone, Debug, PartialEq)]
+pub enum Value {
+    Boolean(bool),
+    Integer8(i8),
+    Integer16(i16),
+    Integer32(i32),
+    Integer64(i64),
+    Unsigned8(u8),
+    Unsigned16(u16),
+    Unsigned32(u32),
+    Unsigned64(u64),
+    Real32(f32),
+    Real64(f64),
+    VisibleString(std::string::String),
+    OctetString(std::vec::Vec<u8>),
+    Domain(std::vec::Vec<u8>),
+}
+
+impl Value {
+    pub fn data_type(&self) -> DataType {
+        match self {
+            Self::Boolean(_) => DataType::Boolean,
+            Self::Integer8(_) => DataType::Integer8,
+            Self::Integer16(_) => DataType::Integer16,
+            Self::Integer32(_) => DataType::Integer32,
+            Self::Integer64(_) => DataType::Integer64,
+            Self::Unsigned8(_) => DataType::Unsigned8,
+            Self::Unsigned16(_) => DataType::Unsigned16,
+            Self::Unsigned32(_) => DataType::Unsigned32,
+            Self::Unsigned64(_) => DataType::Unsigned64,
+            Self::Real32(_) => DataType::Real32,
+            Self::Real64(_) => DataType::Real64,
+            Self::VisibleString(_) => DataType::VisibleString,
+            Self::OctetString(_) => DataType::OctetString,
+            Self::Domain(_) => DataType::Domain,
+        }
+    }
+
+    pub fn from_bytes(data_type: DataType, bytes: &[u8]) -> Result<Self> {
+        Ok(match data_type {
+            DataType::Boolean => Self::Boolean(SdoValue::from_sdo_bytes(bytes)?),
+            DataType::Integer8 => Self::Integer8(SdoValue::from_sdo_bytes(bytes)?),
+            DataType::Integer16 => Self::Integer16(SdoValue::from_sdo_bytes(bytes)?),
+            DataType::Integer32 => Self::Integer32(SdoValue::from_sdo_bytes(bytes)?),
+            DataType::Integer64 => Self::Integer64(SdoValue::from_sdo_bytes(bytes)?),
+            DataType::Unsigned8 => Self::Unsigned8(SdoValue::from_sdo_bytes(bytes)?),
+            DataType::Unsigned16 => Self::Unsigned16(SdoValue::from_sdo_bytes(bytes)?),
+            DataType::Unsigned32 => Self::Unsigned32(SdoValue::from_sdo_bytes(bytes)?),
+            DataType::Unsigned64 => Self::Unsigned64(SdoValue::from_sdo_bytes(bytes)?),
+            DataType::Real32 => Self::Real32(SdoValue::from_sdo_bytes(bytes)?),
+            DataType::Real64 => Self::Real64(SdoValue::from_sdo_bytes(bytes)?),
+            DataType::VisibleString => Self::VisibleString(SdoValue::from_sdo_bytes(bytes)?),
+            DataType::OctetString => Self::OctetString(bytes.to_vec()),
+            DataType::Domain => Self::Domain(bytes.to_vec()),
+        })
+    }
+
+    pub fn to_bytes(&self) -> std::vec::Vec<u8> {
+        match self {
+            Self::Boolean(value) => value.to_sdo_bytes(),
+            Self::Integer8(value) => value.to_sdo_bytes(),
+            Self::Integer16(value) => value.to_sdo_bytes(),
+            Self::Integer32(value) => value.to_sdo_bytes(),
+            Self::Integer64(value) => value.to_sdo_bytes(),
+            Self::Unsigned8(value) => value.to_sdo_bytes(),
+            Self::Unsigned16(value) => value.to_sdo_bytes(),
+            Self::Unsigned32(value) => value.to_sdo_bytes(),
+            Self::Unsigned64(value) => value.to_sdo_bytes(),
+            Self::Real32(value) => value.to_sdo_bytes(),
+            Self::Real64(value) => value.to_sdo_bytes(),
+            Self::VisibleString(value) => value.to_sdo_bytes(),
+            Self::OctetString(value) | Self::Domain(value) => value.clone(),
+        }
+    }
+}
+
+// A single (sub-)entry holding a value
+#[derive(Clone, Debug, PartialEq)]
+pub struct Variable {
+    pub name: std::string::String,
+    pub access: AccessType,
+    pub pdo_mappable: bool,
+    pub default: Value,
+    pub value: Value,
+}
+
+impl Variable {
+    pub fn new(name: &str, access: AccessType, default: Value) -> Self {
+        Self {
+            name: name.to_owned(),
+            access,
+            pdo_mappable: false,
+            value: default.clone(),
+            default,
+        }
+    }
+
+    pub fn data_type(&self) -> DataType {
+        self.default.data_type()
+    }
+}
+
+#[derive(Clone, Debug, PartialEq)]
+pub struct Object {
+    pub index: u16,
+    pub name: std::string::String,
+    pub object_type: ObjectType,
+    sub_objects: std::collections::BTreeMap<u8, Variable>,
+}
+
+impl Object {
+    pub fn new_var(index: u16, variable: Variable) -> Self {
+        Self {
+            index,
+            name: variable.name.clone(),
+            object_type: ObjectType::Var,
+            sub_objects: [(0, variable)].into(),
+        }
+    }
+
+    // Sub-index 0 holds the number of elements, which start at sub-index 1.
+    pub fn new_array(
+        index: u16,
+        name: &str,
+        access: AccessType,
+        defaults: std::vec::Vec<Value>,
+    ) -> Result<Self> {
+        if let Some(value) = defaults.first() {
+            let data_type = value.data_type();
+            if let Some(value) = defaults.iter().find(|value| value.data_type() != data_type) {
+                return Err(Error::DataTypeMismatch {
+                    expected: data_type,
+                    actual: value.data_type(),
+                });
+            }
+        }
+        let length = u8::try_from(defaults.len())
+            .map_err(|_| Error::InvalidSubIndexCount(defaults.len()))?;
+        let mut sub_objects = std::collections::BTreeMap::new();
+        sub_objects.insert(
+            0,
+            Variable::new(
+                "Highest sub-index supported",
+                AccessType::ReadOnly,
+                Value::Unsigned8(length),
+            ),
+        );
+        for (sub_index, default) in (1..=length).zip(defaults) {
+            sub_objects.insert(
+                sub_index,
+                Variable::new(&format!("{}{}", name, sub_index), access, default),
+            );
+        }
+        Ok(Self {
+            index,
+            name: name.to_owned(),
+            object_type: ObjectType::Array,
+            sub_objects,
+        })
+    }
+
+    // Sub-index 0 holds the highest sub-index of `members`.
+    pub fn new_record(index: u16, name: &str, members: std::vec::Vec<(u8, Variable)>) -> Self {
+        let highest = members
+            .iter()
+            .map(|(sub_index, _)| *sub_index)
+            .max()
+            .unwrap_or(0);
+        let mut sub_objects: std::collections::BTreeMap<u8, Variable> =
+            members.into_iter().collect();
+        sub_objects.insert(
+            0,
+            Variable::new(
+                "Highest sub-index supported",
+                AccessType::ReadOnly,
+                Value::Unsigned8(highest),
+            ),
+        );
+        Self {
+            index,
+            name: name.to_owned(),
+            object_type: ObjectType::Record,
+            sub_objects,
+        }
+    }
+
+    pub fn get(&self, sub_index: u8) -> Option<&Variable> {
+        self.sub_objects.get(&sub_index)
+    }
+
+    pub fn get_mut(&mut self, sub_index: u8) -> Option<&mut Variable> {
+        self.sub_objects.get_mut(&sub_index)
+    }
+
+    pub fn sub_objects(&self) -> impl Iterator<Item = (u8, &Variable)> {
+        self.sub_objects
+            .iter()
+            .map(|(sub_index, variable)| (*sub_index, variable))
+    }
+}
+
+type ReadHook = std::sync::Arc<dyn Fn() -> Result<Value> + Send + Sync>;
+type WriteHook = std::sync::Arc<dyn Fn(&Value) -> Result<()> + Send + Sync>;
+
+// Handlers of a computed object. Two sets are equal if they share the same handlers.
+#[derive(Clone, Default)]
+struct Hooks {
+    read: Option<ReadHook>,
+    write: Option<WriteHook>,
+}
+
+impl std::fmt::Debug for Hooks {
+    fn fmt(&self, f: &mut std::fmt::Formatter) -> std::fmt::Result {
+        f.debug_struct("Hooks")
+            .field("read", &self.read.is_some())
+            .field("write", &self.write.is_some())
+            .finish()
+    }
+}
+
+impl PartialEq for Hooks {
+    fn eq(&self, other: &Self) -> bool {
+        fn same<T: ?Sized>(a: &Option<std::sync::Arc<T>>, b: &Option<std::sync::Arc<T>>) -> bool {
+            match (a, b) {
+                (Some(a), Some(b)) => std::sync::Arc::ptr_eq(a, b),
+                (None, None) => true,
+                _ => false,
+            }
+        }
+        same(&self.read, &other.read) && same(&self.write, &other.write)
+    }
+}
+
+#[derive(Clone, Debug, Default, PartialEq)]
+pub struct ObjectDictionary {
+    objects: std::collections::BTreeMap<u16, Object>,
+    hooks: std::collections::BTreeMap<(u16, u8), Hooks>,
+}
+
+impl ObjectDictionary {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    // Returns the object previously registered at the same index, if any
+    pub fn insert(&mut self, object: Object) -> Option<Object> {
+        self.objects.insert(object.index, object)
+    }
+
+    pub fn get(&self, index: u16) -> Option<&Object> {
+        self.objects.get(&index)
+    }
+
+    pub fn get_mut(&mut self, index: u16) -> Option<&mut Object> {
+        self.objects.get_mut(&index)
+    }
+
+    pub fn objects(&self) -> impl Iterator<Item = &Object> {
+        self.objects.values()
+    }
+
+    pub fn variable(&self, index: u16, sub_index: u8) -> Result<&Variable> {
+        self.get(index)
+            .ok_or(Error::ObjectAccessFailed(SdoAbortCode::ObjectDoesNotExist))?
+            .get(sub_index)
+            .ok_or(Error::ObjectAccessFailed(
+                SdoAbortCode::SubIndexDoesNotExist,
+            ))
+    }
+
+    pub fn variable_mut(&mut self, index: u16, sub_index: u8) -> Result<&mut Variable> {
+        self.get_mut(index)
+            .ok_or(Error::ObjectAccessFailed(SdoAbortCode::ObjectDoesNotExist))?
+            .get_mut(sub_index)
+            .ok_or(Error::ObjectAccessFailed(
+                SdoAbortCode::SubIndexDoesNotExist,
+            ))
+    }
+
+    // Computes the value of a variable on each `read` (e.g. an uptime or a measured bus load)
+    // instead of returning the stored one. The variable must exist: its access type and data
+    // type still apply. The value must have the data type of the variable, and errors other
+    // than `Error::ObjectAccessFailed` are reported to SDO clients as a general error.
+    pub fn set_read_hook(
+        &mut self,
+        index: u16,
+        sub_index: u8,
+        hook: impl Fn() -> Result<Value> + Send + Sync + 'static,
+    ) -> Result<()> {
+        self.variable(index, sub_index)?;
+        self.hooks.entry((index, sub_index)).or_default().read = Some(std::sync::Arc::new(hook));
+        Ok(())
+    }
+
+    // Runs `hook` on each `write` with the validated value, before it is stored. An error
+    // rejects the write.
+    pub fn set_write_hook(
+        &mut self,
+        index: u16,
+        sub_index: u8,
+        hook: impl Fn(&Value) -> Result<()> + Send + Sync + 'static,
+    ) -> Result<()> {
+        self.variable(index, sub_index)?;
+        self.hooks.entry((index, sub_index)).or_default().write = Some(std::sync::Arc::new(hook));
+        Ok(())
+    }
+
+    pub fn remove_hooks(&mut self, index: u16, sub_index: u8) {
+        self.hooks.remove(&(index, sub_index));
+    }
+
+    // Reads the current value as an SDO server would, honoring the access type.
+    pub fn read(&self, index: u16, sub_index: u8) -> Result<std::vec::Vec<u8>> {
+        let variable = self.variable(index, sub_index)?;
+        if !variable.access.is_readable() {
+            return Err(Error::ObjectAccessFailed(SdoAbortCode::WriteOnly));
+        }
+        match self
+            .hooks
+            .get(&(index, sub_index))
+            .and_then(|hooks| hooks.read.as_ref())
+        {
+            Some(hook) => {
+                let value = hook()?;
+                if value.data_type() != variable.data_type() {
+                    return Err(Error::ObjectAccessFailed(SdoAbortCode::GeneralError));
+                }
+                Ok(value.to_bytes())
+            }
+            None => Ok(variable.value.to_bytes()),
+        }
+    }
+
+    // Writes the current value as an SDO server would, honoring the access type and the size
+    // of the data type.
+    pub fn write(&mut self, index: u16, sub_index: u8, bytes: &[u8]) -> Result<()> {
+        let variable = self.variable_mut(index, sub_index)?;
+        if !variable.access.is_writable() {
+            return Err(Error::ObjectAccessFailed(SdoAbortCode::ReadOnly));
+        }
+        let data_type = variable.data_type();
+        if let Some(size) = data_type.size() {
+            if bytes.len() > size {
+                return Err(Error::ObjectAccessFailed(
+                    SdoAbortCode::DataTypeLengthTooHigh,
+                ));
+            }
+            if bytes.len() < size {
+                return Err(Error::ObjectAccessFailed(
+                    SdoAbortCode::DataTypeLengthTooLow,
+                ));
+            }
+        }
+        let value = Value::from_bytes(data_type, bytes)
+            .map_err(|_| Error::ObjectAccessFailed(SdoAbortCode::InvalidValue))?;
+        if let Some(hook) = self
+            .hooks
+            .get(&(index, sub_index))
+            .and_then(|hooks| hooks.write.as_ref())
+        {
+            hook(&value)?;
+        }
+        self.variable_mut(index, sub_index)?.value = value;
+        Ok(())
+    }
+
+    // Restores every value to its default
+    pub fn reset(&mut self) {
+        self.reset_range(..);
+    }
+
+    // Restores the values of the objects in `indices` to their defaults, e.g. 0x1000..=0x1FFF
+    // for the communication profile area
+    pub fn reset_range(&mut self, indices: impl std::ops::RangeBounds<u16>) {
+        for (_, object) in self.objects.range_mut(indices) {
+            for variable in object.sub_objects.values_mut() {
+                variable.value = variable.default.clone();
+            }
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn dictionary() -> ObjectDictionary {
+        let mut od = ObjectDictionary::new();
+        od.insert(Object::new_var(
+            0x1000,
+            Variable::new(
+                "Device type",
+                AccessType::Constant,
+                Value::Unsigned32(0x0192),
+            ),
+        ));
+        od.insert(Object::new_var(
+            0x1017,
+            Variable::new(
+                "Producer heartbeat time",
+                AccessType::ReadWrite,
+                Value::Unsigned16(0),
+            ),
+        ));
+        od.insert(
+            Object::new_array(
+                0x1016,
+                "Consumer heartbeat time",
+                AccessType::ReadWrite,
+                vec![Value::Unsigned32(0), Value::Unsigned32(0)],
+            )
+            .unwrap(),
+        );
+        od.insert(Object::new_record(
+            0x1018,
+            "Identity object",
+            vec![
+                (
+                    1,
+                    Variable::new("Vendor-ID", AccessType::ReadOnly, Value::Unsigned32(0x1234)),
+                ),
+                (
+                    4,
+                    Variable::new("Serial number", AccessType::ReadOnly, Value::Unsigned32(7)),
+                ),
+            ],
+        ));
+        od.insert(Object::new_var(
+            0x2000,
+            Variable {
+                pdo_mappable: true,
+                ..Variable::new("Command", AccessType::WriteOnly, Value::Integer16(0))
+            },
+        ));
+        od
+    }
+
+    #[test]
+    fn test_data_type() {
+        assert_eq!(DataType::from_u16(0x0007), Ok(DataType::Unsigned32));
+        assert_eq!(
+            DataType::from_u16(0x0100),
+            Err(Error::InvalidDataType(0x0100))
+        );
+        assert_eq!(DataType::Unsigned64.as_u16(), 0x001B);
+        assert_eq!(DataType::Integer16.size(), Some(2));
+        assert_eq!(DataType::VisibleString.size(), None);
+    }
+
+    #[test]
+    fn test_access_type() {
+        assert!(AccessType::Constant.is_readable());
+        assert!(!AccessType::Constant.is_writable());
+        assert!(!AccessType::WriteOnly.is_readable());
+        assert!(AccessType::ReadWrite.is_writable());
+    }
+
+    #[test]
+    fn test_value_bytes() {
+        assert_eq!(Value::Integer16(-2).to_bytes(), vec![0xFE, 0xFF]);
+        assert_eq!(
+            Value::from_bytes(DataType::Unsigned32, &[0x92, 0x01, 0x00, 0x00]),
+            Ok(Value::Unsigned32(0x0192))
+        );
+        assert_eq!(
+            Value::from_bytes(DataType::VisibleString, b"abc"),
+            Ok(Value::VisibleString("abc".to_owned()))
+        );
+        assert!(Value::from_bytes(DataType::Unsigned16, &[0x00]).is_err());
+    }
+
+    #[test]
+    fn test_array_and_record() {
+        let od = dictionary();
+        let array = od.get(0x1016).unwrap();
+        assert_eq!(array.object_type, ObjectType::Array);
+        assert_eq!(array.get(0).unwrap().value, Value::Unsigned8(2));
+        assert_eq!(array.sub_objects().count(), 3);
+
+        let record = od.get(0x1018).unwrap();
+        assert_eq!(record.object_type, ObjectType::Record);
+        assert_eq!(record.get(0).unwrap().value, Value::Unsigned8(4));
+        assert!(record.get(2).is_none());
+
+        assert_eq!(
+            Object::new_array(
+                0x1016,
+                "Mixed",
+                AccessType::ReadWrite,
+                vec![Value::Unsigned32(0), Value::Unsigned8(0)],
+            ),
+            Err(Error::DataTypeMismatch {
+                expected: DataType::Unsigned32,
+                actual: DataType::Unsigned8
+            })
+        );
+    }
+
+    #[test]
+    fn test_read() {
+        let od = dictionary();
+        assert_eq!(od.read(0x1000, 0), Ok(vec![0x92, 0x01, 0x00, 0x00]));
+        assert_eq!(od.read(0x1018, 1), Ok(vec![0x34, 0x12, 0x00, 0x00]));
+        assert_eq!(
+            od.read(0x1001, 0),
+            Err(Error::ObjectAccessFailed(SdoAbortCode::ObjectDoesNotExist))
+        );
+        assert_eq!(
+            od.read(0x1018, 2),
+            Err(Error::ObjectAccessFailed(
+                SdoAbortCode::SubIndexDoesNotExist
+            ))
+        );
+        assert_eq!(
+            od.read(0x2000, 0),
+            Err(Error::ObjectAccessFailed(SdoAbortCode::WriteOnly))
+        );
+    }
+
+    #[test]
+    fn test_write() {
+        let mut od = dictionary();
+        assert_eq!(od.write(0x1017, 0, &[0xE8, 0x03]), Ok(()));
+        assert_eq!(od.read(0x1017, 0), Ok(vec![0xE8, 0x03]));
+        assert_eq!(
+            od.write(0x1000, 0, &[0x00; 4]),
+            Err(Error::ObjectAccessFailed(SdoAbortCode::ReadOnly))
+        );
+        assert_eq!(
+            od.write(0x1017, 0, &[0x00; 4]),
+            Err(Error::ObjectAccessFailed(
+                SdoAbortCode::DataTypeLengthTooHigh
+            ))
+        );
+        assert_eq!(
+            od.write(0x1016, 1, &[0x00]),
+            Err(Error::ObjectAccessFailed(
+                SdoAbortCode::DataTypeLengthTooLow
+            ))
+        );
+        assert!(od.variable(0x2000, 0).unwrap().pdo_mappable);
+
+        od.reset();
+        assert_eq!(od.read(0x1017, 0), Ok(vec![0x00, 0x00]));
+    }
+
+    #[test]
+    fn test_reset_range() {
+        let mut od = dictionary();
+        od.write(0x1017, 0, &[0x64, 0x00]).unwrap();
+        od.write(0x2000, 0, &[0x12, 0x00]).unwrap();
+
+        od.reset_range(0x1000..=0x1FFF);
+        assert_eq!(od.read(0x1017, 0), Ok(vec![0x00, 0x00]));
+        assert_eq!(
+            od.variable(0x2000, 0).unwrap().value,
+            Value::Integer16(0x12)
+        );
+    }
+
+    #[test]
+    fn test_hooks() {
+        let mut od = dictionary();
+        let reads = std::sync::Arc::new(std::sync::atomic::AtomicU32::new(0));
+        let counter = reads.clone();
+        od.set_read_hook(0x1018, 4, move || {
+            let reads = counter.fetch_add(1, std::sync::atomic::Ordering::Relaxed) + 1;
+            Ok(Value::Unsigned32(reads))
+        })
+        .unwrap();
+        assert_eq!(od.read(0x1018, 4), Ok(vec![0x01, 0x00, 0x00, 0x00]));
+        assert_eq!(od.read(0x1018, 4), Ok(vec![0x02, 0x00, 0x00, 0x00]));
+        assert_eq!(
+            od.set_read_hook(0x1018, 2, || Ok(Value::Unsigned32(0))),
+            Err(Error::ObjectAccessFailed(
+                SdoAbortCode::SubIndexDoesNotExist
+            ))
+        );
+        od.set_read_hook(0x1000, 0, || Ok(Value::Unsigned8(0)))
+            .unwrap();
+        assert_eq!(
+            od.read(0x1000, 0),
+            Err(Error::ObjectAccessFailed(SdoAbortCode::GeneralError))
+        );
+
+        od.set_write_hook(0x1017, 0, |value| match value {
+            Value::Unsigned16(milliseconds) if *milliseconds < 10 => {
+                Err(Error::ObjectAccessFailed(SdoAbortCode::ValueTooLow))
+            }
+            _ => Ok(()),
+        })
+        .unwrap();
+        assert_eq!(
+            od.write(0x1017, 0, &[0x05, 0x00]),
+            Err(Error::ObjectAccessFailed(SdoAbortCode::ValueTooLow))
+        );
+        assert_eq!(od.read(0x1017, 0), Ok(vec![0x00, 0x00]));
+        assert_eq!(od.write(0x1017, 0, &[0x64, 0x00]), Ok(()));
+        assert_eq!(od.read(0x1017, 0), Ok(vec![0x64, 0x00]));
+
+        assert_eq!(od.clone(), od);
+        od.remove_hooks(0x1018, 4);
+        assert_eq!(od.read(0x1018, 4), Ok(vec![0x07, 0x00, 0x00, 0x00]));
+        assert_eq!(reads.load(std::sync::atomic::Ordering::Relaxed), 2);
+    }
+}
@@ -0,0 +1,250 @@
+use crate::error::{Error, Result};
+use crate::frame::{ConvertibleFrame, PdoFrame};
+
+// The position of a rolling counter in the payload of a PDO, in bits from the start of the
+// data, little-endian (cf. CiA 301 PDO mapping). The counter wraps around at 2^bit_length.
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub struct CounterField {
+    bit_offset: u8,
+    bit_length: u8,
+}
+
+impl CounterField {
+    pub fn new(bit_offset: u8, bit_length: u8) -> Result<Self> {
+        if !(1..=32).contains(&bit_length) || bit_offset as usize + bit_length as usize > 64 {
+            return Err(Error::InvalidCounterField {
+                bit_offset,
+                bit_length,
+            });
+        }
+        Ok(Self {
+            bit_offset,
+            bit_length,
+        })
+    }
+
+    pub fn bit_offset(&self) -> u8 {
+        self.bit_offset
+    }
+
+    pub fn bit_length(&self) -> u8 {
+        self.bit_length
+    }
+
+    // Returns `None` if `data` is too short to contain the counter.
+    pub fn read(&self, data: &[u8]) -> Option<u32> {
+        let end = self.bit_offset as usize + self.bit_length as usize;
+        if end > data.len() * 8 {
+            return None;
+        }
+        let mut bytes = [0x00; 8];
+        bytes[..data.len()].copy_from_slice(data);
+        Some(((u64::from_le_bytes(bytes) >> self.bit_offset) & self.mask()) as u32)
+    }
+
+    fn mask(&self) -> u64 {
+        (1 << self.bit_length) - 1
+    }
+
+    // Number of counter values skipped between `previous` and `current`
+    fn distance(&self, previous: u32, current: u32) -> u32 {
+        ((current as u64).wrapping_sub(previous as u64) & self.mask()) as u32
+    }
+}
+
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub enum CounterEvent {
+    // The counter skipped values: `missed` PDOs were lost (modulo the counter range).
+    Dropped {
+        cob_id: u16,
+        expected: u32,
+        received: u32,
+        missed: u32,
+    },
+    // The counter did not change: the producer stopped updating the data.
+    Stalled {
+        cob_id: u16,
+        value: u32,
+    },
+    // The PDO is too short to contain the counter.
+    Truncated {
+        cob_id: u16,
+        length: usize,
+    },
+}
+
+#[derive(Clone, Copy, Debug, Default, PartialEq)]
+pub struct CounterStatistics {
+    pub received: u64,
+    pub missed: u64,
+    pub stalls: u64,
+    pub truncated: u64,
+}
+
+#[derive(Clone, Copy, Debug)]
+struct Counter {
+    field: CounterField,
+    last: Option<u32>,
+    statistics: CounterStatistics,
+}
+
+// Checks that the rolling counters of received PDOs increase by one from a PDO to the next.
+// PDOs without a configured counter field are ignored.
+#[derive(Clone, Debug, Default)]
+pub struct PdoCounterMonitor {
+    counters: std::collections::BTreeMap<u16, Counter>,
+}
+
+impl PdoCounterMonitor {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    // Checks the PDOs with `cob_id`, resetting any previous state.
+    pub fn insert(&mut self, cob_id: u16, field: CounterField) {
+        self.counters.insert(
+            cob_id,
+            Counter {
+                field,
+                last: None,
+                statistics: CounterStatistics::default(),
+            },
+        );
+    }
+
+    pub fn remove(&mut self, cob_id: u16) {
+        self.counters.remove(&cob_id);
+    }
+
+    pub fn statistics(&self, cob_id: u16) -> Option<CounterStatistics> {
+        self.counters.get(&cob_id).map(|counter| counter.statistics)
+    }
+
+    // Forgets the last counter values, e.g. after the producer has been reset.
+    pub fn resync(&mut self) {
+        for counter in self.counters.values_mut() {
+            counter.last = None;
+        }
+    }
+
+    pub fn on_frame(&mut self, frame: &PdoFrame) -> Option<CounterEvent> {
+        let cob_id = frame.communication_object().as_cob_id();
+        let counter = self.counters.get_mut(&cob_id)?;
+        let Some(value) = counter.field.read(frame.data()) else {
+            counter.statistics.truncated += 1;
+            return Some(CounterEvent::Truncated {
+                cob_id,
+                length: frame.data().len(),
+            });
+        };
+        counter.statistics.received += 1;
+        let previous = counter.last.replace(value)?;
+        match counter.field.distance(previous, value) {
+            1 => None,
+            0 => {
+                counter.statistics.stalls += 1;
+                Some(CounterEvent::Stalled { cob_id, value })
+            }
+            distance => {
+                counter.statistics.missed += (distance - 1) as u64;
+                Some(CounterEvent::Dropped {
+                    cob_id,
+                    expected: ((previous as u64 + 1) & counter.field.mask()) as u32,
+                    received: value,
+                    missed: distance - 1,
+                })
+            }
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    use crate::frame::Direction;
+
+    fn pdo(data: Vec<u8>) -> PdoFrame {
+        PdoFrame::new(Direction::Tx, 1, 2.try_into().unwrap(), data).unwrap()
+    }
+
+    #[test]
+    fn test_counter_field() {
+        let field = CounterField::new(4, 4).unwrap();
+        assert_eq!(field.read(&[0xA5]), Some(0xA));
+        assert_eq!(field.read(&[]), None);
+        let field = CounterField::new(8, 16).unwrap();
+        assert_eq!(field.read(&[0x00, 0x34, 0x12]), Some(0x1234));
+        assert_eq!(field.read(&[0x00, 0x34]), None);
+        assert_eq!(
+            CounterField::new(0, 0),
+            Err(Error::InvalidCounterField {
+                bit_offset: 0,
+                bit_length: 0
+            })
+        );
+        assert_eq!(
+            CounterField::new(40, 32),
+            Err(Error::InvalidCounterField {
+                bit_offset: 40,
+                bit_length: 32
+            })
+        );
+    }
+
+    #[test]
+    fn test_on_frame() {
+        let mut monitor = PdoCounterMonitor::new();
+        monitor.insert(0x182, CounterField::new(0, 4).unwrap());
+        assert_eq!(monitor.on_frame(&pdo(vec![0x0E, 0xFF])), None);
+        assert_eq!(monitor.on_frame(&pdo(vec![0x0F, 0xFF])), None);
+        // Wraps around
+        assert_eq!(monitor.on_frame(&pdo(vec![0x00, 0xFF])), None);
+        assert_eq!(
+            monitor.on_frame(&pdo(vec![0x03])),
+            Some(CounterEvent::Dropped {
+                cob_id: 0x182,
+                expected: 0x1,
+                received: 0x3,
+                missed: 2
+            })
+        );
+        assert_eq!(
+            monitor.on_frame(&pdo(vec![0x03])),
+            Some(CounterEvent::Stalled {
+                cob_id: 0x182,
+                value: 0x3
+            })
+        );
+        assert_eq!(
+            monitor.on_frame(&pdo(vec![])),
+            Some(CounterEvent::Truncated {
+                cob_id: 0x182,
+                length: 0
+            })
+        );
+        assert_eq!(
+            monitor.statistics(0x182),
+            Some(CounterStatistics {
+                received: 5,
+                missed: 2,
+                stalls: 1,
+                truncated: 1
+            })
+        );
+
+        monitor.resync();
+        assert_eq!(monitor.on_frame(&pdo(vec![0x09])), None);
+        monitor.remove(0x182);
+        assert_eq!(monitor.on_frame(&pdo(vec![0x00])), None);
+        assert_eq!(monitor.statistics(0x182), None);
+    }
+
+    #[test]
+    fn test_unconfigured_pdo() {
+        let mut monitor = PdoCounterMonitor::new();
+        monitor.insert(0x282, CounterField::new(0, 8).unwrap());
+        assert_eq!(monitor.on_frame(&pdo(vec![0x00])), None);
+        assert_eq!(monitor.on_frame(&pdo(vec![0x05])), None);
+    }
+}
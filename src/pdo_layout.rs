@@ -0,0 +1,191 @@
+// A value that can be mapped into a PDO, encoded in little-endian (cf. CiA 301).
+pub trait PdoField: Sized {
+    const SIZE: usize;
+    fn write(&self, bytes: &mut [u8]);
+    fn read(bytes: &[u8]) -> Self;
+}
+
+macro_rules! impl_pdo_field {
+    ($($t:ty),*) => {
+        $(
+            impl PdoField for $t {
+                const SIZE: usize = std::mem::size_of::<$t>();
+
+                fn write(&self, bytes: &mut [u8]) {
+                    bytes[..Self::SIZE].copy_from_slice(&self.to_le_bytes());
+                }
+
+                fn read(bytes: &[u8]) -> Self {
+                    <$t>::from_le_bytes(bytes[..Self::SIZE].try_into().unwrap())
+                }
+            }
+        )*
+    };
+}
+
+impl_pdo_field!(u8, u16, u32, u64, i8, i16, i32, i64, f32, f64);
+
+impl PdoField for bool {
+    const SIZE: usize = 1;
+
+    fn write(&self, bytes: &mut [u8]) {
+        bytes[0] = *self as u8;
+    }
+
+    fn read(bytes: &[u8]) -> Self {
+        bytes[0] != 0
+    }
+}
+
+// Defines a struct with a fixed PDO payload layout. The fields are packed in order, and a
+// layout exceeding 64 bits fails to compile.
+//
+// pdo_layout! {
+//     pub struct MotorStatus {
+//         position: i32,
+//         status: u16,
+//     }
+// }
+#[macro_export]
+macro_rules! pdo_layout {
+    (
+        $(#[$meta:meta])*
+        $vis:vis struct $name:ident {
+            $($field:ident: $t:ty),* $(,)?
+        }
+    ) => {
+        $(#[$meta])*
+        #[derive(Clone, Copy, Debug, Default, PartialEq)]
+        $vis struct $name {
+            $(pub $field: $t,)*
+        }
+
+        const _: () = assert!(
+            $name::SIZE <= 8,
+            concat!("PDO layout ", stringify!($name), " exceeds 64 bits")
+        );
+
+        impl $name {
+            pub const SIZE: usize = 0 $(+ <$t as $crate::pdo_layout::PdoField>::SIZE)*;
+
+            pub fn to_bytes(self) -> std::vec::Vec<u8> {
+                #[allow(unused_mut)]
+                let mut bytes = std::vec![0x00; Self::SIZE];
+                let mut _offset = 0;
+                $(
+                    $crate::pdo_layout::PdoField::write(&self.$field, &mut bytes[_offset..]);
+                    _offset += <$t as $crate::pdo_layout::PdoField>::SIZE;
+                )*
+                bytes
+            }
+
+            pub fn from_bytes(bytes: &[u8]) -> $crate::Result<Self> {
+                if bytes.len() != Self::SIZE {
+                    return Err($crate::Error::InvalidDataLength {
+                        length: bytes.len(),
+                        data_type: stringify!($name).to_owned(),
+                    });
+                }
+                let mut _offset = 0;
+                $(
+                    let $field =
+                        <$t as $crate::pdo_layout::PdoField>::read(&bytes[_offset..]);
+                    _offset += <$t as $crate::pdo_layout::PdoField>::SIZE;
+                )*
+                Ok(Self { $($field,)* })
+            }
+
+            pub fn new_pdo_frame(
+                &self,
+                direction: $crate::frame::Direction,
+                number: u8,
+                node_id: $crate::id::NodeId,
+            ) -> $crate::Result<$crate::frame::PdoFrame> {
+                $crate::frame::PdoFrame::new(direction, number, node_id, self.to_bytes())
+            }
+        }
+
+        impl TryFrom<&$crate::frame::PdoFrame> for $name {
+            type Error = $crate::Error;
+            fn try_from(frame: &$crate::frame::PdoFrame) -> $crate::Result<Self> {
+                Self::from_bytes(frame.data())
+            }
+        }
+    };
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::error::Error;
+    use crate::frame::{ConvertibleFrame, Direction, PdoFrame};
+    use crate::id::CommunicationObject;
+
+    crate::pdo_layout! {
+        struct MotorStatus {
+            position: i32,
+            status: u16,
+            enabled: bool,
+            mode: i8,
+        }
+    }
+
+    #[test]
+    fn test_size() {
+        assert_eq!(MotorStatus::SIZE, 8);
+    }
+
+    #[test]
+    fn test_to_bytes() {
+        let status = MotorStatus {
+            position: -2,
+            status: 0x0637,
+            enabled: true,
+            mode: 8,
+        };
+        assert_eq!(
+            status.to_bytes(),
+            vec![0xFE, 0xFF, 0xFF, 0xFF, 0x37, 0x06, 0x01, 0x08]
+        );
+    }
+
+    #[test]
+    fn test_from_bytes() {
+        assert_eq!(
+            MotorStatus::from_bytes(&[0x10, 0x27, 0x00, 0x00, 0x37, 0x02, 0x00, 0xFF]),
+            Ok(MotorStatus {
+                position: 10000,
+                status: 0x0237,
+                enabled: false,
+                mode: -1,
+            })
+        );
+        assert_eq!(
+            MotorStatus::from_bytes(&[0x00; 7]),
+            Err(Error::InvalidDataLength {
+                length: 7,
+                data_type: "MotorStatus".to_owned()
+            })
+        );
+    }
+
+    #[test]
+    fn test_pdo_frame() {
+        let status = MotorStatus {
+            position: 1,
+            status: 2,
+            enabled: true,
+            mode: 3,
+        };
+        let frame = status
+            .new_pdo_frame(Direction::Tx, 1, 5.try_into().unwrap())
+            .unwrap();
+        assert_eq!(
+            frame.communication_object(),
+            CommunicationObject::TxPdo1(5.try_into().unwrap())
+        );
+        assert_eq!(MotorStatus::try_from(&frame), Ok(status));
+
+        let frame = PdoFrame::new(Direction::Tx, 1, 5.try_into().unwrap(), vec![0x00]).unwrap();
+        assert!(MotorStatus::try_from(&frame).is_err());
+    }
+}
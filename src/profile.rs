@@ -0,0 +1,214 @@
+use crate::error::Result;
+use crate::frame::SdoFrame;
+use crate::heartbeat::HeartbeatMonitor;
+use crate::id::NodeId;
+use crate::sdo::{SdoBlockUpload, SdoDeadline, SdoTimeoutPolicy};
+
+// Communication settings of a kind of device, so that they are tuned once per device type and
+// assigned per node
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub struct CommunicationProfile {
+    // Bit/s, for reference (e.g. to compute the bus load)
+    pub bitrate: u32,
+    pub sdo_timeout: SdoTimeoutPolicy,
+    // Minimum gap between two frames of a segmented or block transfer sent to the node, for
+    // devices that drop back-to-back frames
+    pub segment_gap: std::time::Duration,
+    pub block_size: u8,
+    // Heartbeat producer time of the node (0x1017), `None` if it does not send heartbeats
+    pub heartbeat_period: Option<std::time::Duration>,
+    // Added to the heartbeat period to get the consumer heartbeat time
+    pub heartbeat_margin: std::time::Duration,
+}
+
+impl Default for CommunicationProfile {
+    // 500 kbit/s, settings suitable for most devices
+    fn default() -> Self {
+        Self {
+            bitrate: 500_000,
+            sdo_timeout: SdoTimeoutPolicy::default(),
+            segment_gap: std::time::Duration::ZERO,
+            block_size: 32,
+            heartbeat_period: Some(std::time::Duration::from_millis(100)),
+            heartbeat_margin: std::time::Duration::from_millis(50),
+        }
+    }
+}
+
+impl CommunicationProfile {
+    // Slow sensors (e.g. I/O or encoders) on a 125 kbit/s bus: long timeouts with retries,
+    // paced segments, small blocks and a slow heartbeat
+    pub fn conservative_sensor_125k() -> Self {
+        Self {
+            bitrate: 125_000,
+            sdo_timeout: SdoTimeoutPolicy::new(std::time::Duration::from_secs(3), 2),
+            segment_gap: std::time::Duration::from_millis(2),
+            block_size: 8,
+            heartbeat_period: Some(std::time::Duration::from_secs(1)),
+            heartbeat_margin: std::time::Duration::from_millis(500),
+        }
+    }
+
+    // Servo drives on a 1 Mbit/s bus: short timeouts, large blocks sent back-to-back and a
+    // fast heartbeat, so that a lost drive is detected quickly
+    pub fn fast_servo_1m() -> Self {
+        Self {
+            bitrate: 1_000_000,
+            sdo_timeout: SdoTimeoutPolicy::new(std::time::Duration::from_millis(100), 1),
+            segment_gap: std::time::Duration::ZERO,
+            block_size: 127,
+            heartbeat_period: Some(std::time::Duration::from_millis(20)),
+            heartbeat_margin: std::time::Duration::from_millis(10),
+        }
+    }
+
+    pub fn consumer_heartbeat_time(&self) -> Option<std::time::Duration> {
+        self.heartbeat_period
+            .map(|period| period + self.heartbeat_margin)
+    }
+
+    // Configures the producer heartbeat time (0x1017) of the node, `None` if the profile has
+    // no heartbeat. Periods beyond 65535 ms are saturated.
+    pub fn new_producer_heartbeat_write_frame(&self, node_id: NodeId) -> Option<SdoFrame> {
+        let period = self.heartbeat_period?;
+        let milliseconds = period.as_millis().min(u16::MAX.into()) as u16;
+        Some(SdoFrame::new_sdo_write_frame(
+            node_id,
+            0x1017,
+            0,
+            milliseconds.to_le_bytes().into(),
+        ))
+    }
+
+    pub fn sdo_deadline(
+        &self,
+        node_id: NodeId,
+        index: u16,
+        sub_index: u8,
+        now: std::time::Instant,
+    ) -> SdoDeadline {
+        SdoDeadline::new(self.sdo_timeout, node_id, index, sub_index, now)
+    }
+
+    pub fn block_upload(
+        &self,
+        node_id: NodeId,
+        index: u16,
+        sub_index: u8,
+    ) -> Result<SdoBlockUpload> {
+        SdoBlockUpload::new(node_id, index, sub_index, self.block_size)
+    }
+}
+
+// Profiles assigned per node, with a default for the others
+#[derive(Clone, Debug, Default, PartialEq)]
+pub struct CommunicationProfiles {
+    default: CommunicationProfile,
+    nodes: std::collections::BTreeMap<u8, CommunicationProfile>,
+}
+
+impl CommunicationProfiles {
+    pub fn new(default: CommunicationProfile) -> Self {
+        Self {
+            default,
+            nodes: std::collections::BTreeMap::new(),
+        }
+    }
+
+    pub fn assign(&mut self, node_id: NodeId, profile: CommunicationProfile) {
+        self.nodes.insert(node_id.as_raw(), profile);
+    }
+
+    pub fn unassign(&mut self, node_id: NodeId) {
+        self.nodes.remove(&node_id.as_raw());
+    }
+
+    pub fn get(&self, node_id: NodeId) -> &CommunicationProfile {
+        self.nodes.get(&node_id.as_raw()).unwrap_or(&self.default)
+    }
+
+    pub fn default_profile(&self) -> &CommunicationProfile {
+        &self.default
+    }
+
+    // Sets the consumer heartbeat time of the assigned nodes whose profile has a heartbeat.
+    pub fn configure_heartbeat_monitor(&self, monitor: &mut HeartbeatMonitor) {
+        for (raw_id, profile) in &self.nodes {
+            if let Some(time) = profile.consumer_heartbeat_time() {
+                monitor.set_consumer_time((*raw_id).try_into().unwrap(), time);
+            }
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    use crate::frame::{ConvertibleFrame, NmtNodeMonitoringFrame, NmtState};
+    use crate::heartbeat::HeartbeatEvent;
+
+    use std::time::Duration;
+
+    #[test]
+    fn test_presets() {
+        let sensor = CommunicationProfile::conservative_sensor_125k();
+        assert_eq!(
+            sensor.consumer_heartbeat_time(),
+            Some(Duration::from_millis(1500))
+        );
+        assert_eq!(
+            sensor
+                .new_producer_heartbeat_write_frame(2.try_into().unwrap())
+                .unwrap()
+                .frame_data(),
+            &[0x2B, 0x17, 0x10, 0x00, 0xE8, 0x03, 0x00, 0x00]
+        );
+        let servo = CommunicationProfile::fast_servo_1m();
+        assert!(servo.block_upload(2.try_into().unwrap(), 0x1F50, 1).is_ok());
+        let now = std::time::Instant::now();
+        assert_eq!(
+            servo
+                .sdo_deadline(2.try_into().unwrap(), 0x1000, 0, now)
+                .deadline(),
+            now + Duration::from_millis(100)
+        );
+
+        let silent = CommunicationProfile {
+            heartbeat_period: None,
+            ..Default::default()
+        };
+        assert_eq!(silent.consumer_heartbeat_time(), None);
+        assert_eq!(
+            silent.new_producer_heartbeat_write_frame(2.try_into().unwrap()),
+            None
+        );
+    }
+
+    #[test]
+    fn test_profiles() {
+        let mut profiles = CommunicationProfiles::default();
+        let servo_id: NodeId = 3.try_into().unwrap();
+        profiles.assign(servo_id, CommunicationProfile::fast_servo_1m());
+        assert_eq!(profiles.get(servo_id).bitrate, 1_000_000);
+        assert_eq!(profiles.get(4.try_into().unwrap()).bitrate, 500_000);
+
+        let mut monitor = HeartbeatMonitor::new();
+        profiles.configure_heartbeat_monitor(&mut monitor);
+        let now = std::time::Instant::now();
+        monitor.on_frame(
+            &NmtNodeMonitoringFrame::new(servo_id, NmtState::Operational),
+            now,
+        );
+        assert_eq!(
+            monitor.poll(now + Duration::from_millis(31)),
+            vec![HeartbeatEvent::HeartbeatLost {
+                node_id: servo_id,
+                last_state: NmtState::Operational
+            }]
+        );
+
+        profiles.unassign(servo_id);
+        assert_eq!(profiles.get(servo_id), profiles.default_profile());
+    }
+}
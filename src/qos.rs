@@ -0,0 +1,83 @@
+use std::os::unix::io::AsRawFd;
+
+use crate::error::{Error, Result};
+
+// Priority of the frames of this process against other local processes sharing the interface.
+//
+// On the bus, the frame with the lowest identifier wins arbitration: in CANopen, NMT (0x000)
+// before SYNC (0x080) and EMCY (0x081-0x0FF), then TIME, PDOs, SDOs and finally NMT error
+// control (0x701-0x77F). Before reaching the bus, the frames of all the sockets on an interface
+// go through the queueing discipline of the kernel, where the socket priority (`SO_PRIORITY`)
+// selects the band, e.g. with `pfifo_fast` or `prio`. A socket with a higher priority is
+// dequeued first, so that its frames are not delayed by the bulk traffic of other processes.
+//
+// Priorities from 0 to 6 can be set by any process; higher ones need `CAP_NET_ADMIN`.
+pub fn set_socket_priority<S: AsRawFd>(socket: &S, priority: u32) -> Result<()> {
+    let priority = priority as libc::c_int;
+    // SAFETY: `socket` owns a valid file descriptor for the duration of the call, and the
+    // option value points to a `c_int` of the given size.
+    let result = unsafe {
+        libc::setsockopt(
+            socket.as_raw_fd(),
+            libc::SOL_SOCKET,
+            libc::SO_PRIORITY,
+            &priority as *const libc::c_int as *const libc::c_void,
+            std::mem::size_of::<libc::c_int>() as libc::socklen_t,
+        )
+    };
+    if result != 0 {
+        return Err(socket_option_failed("SO_PRIORITY"));
+    }
+    Ok(())
+}
+
+pub fn socket_priority<S: AsRawFd>(socket: &S) -> Result<u32> {
+    let mut priority: libc::c_int = 0;
+    let mut length = std::mem::size_of::<libc::c_int>() as libc::socklen_t;
+    // SAFETY: `socket` owns a valid file descriptor for the duration of the call, and the
+    // option value points to a `c_int` whose size is in `length`.
+    let result = unsafe {
+        libc::getsockopt(
+            socket.as_raw_fd(),
+            libc::SOL_SOCKET,
+            libc::SO_PRIORITY,
+            &mut priority as *mut libc::c_int as *mut libc::c_void,
+            &mut length,
+        )
+    };
+    if result != 0 {
+        return Err(socket_option_failed("SO_PRIORITY"));
+    }
+    Ok(priority as u32)
+}
+
+fn socket_option_failed(option: &str) -> Error {
+    Error::SocketOptionFailed {
+        option: option.to_owned(),
+        message: std::io::Error::last_os_error().to_string(),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_socket_priority() {
+        // Any socket has a priority, not only CAN sockets.
+        let socket = std::net::UdpSocket::bind("127.0.0.1:0").unwrap();
+        assert_eq!(socket_priority(&socket), Ok(0));
+        set_socket_priority(&socket, 6).unwrap();
+        assert_eq!(socket_priority(&socket), Ok(6));
+    }
+
+    #[test]
+    fn test_invalid_socket() {
+        let file = std::fs::File::open("/dev/null").unwrap();
+        assert!(matches!(
+            set_socket_priority(&file, 1),
+            Err(Error::SocketOptionFailed { option, .. }) if option == "SO_PRIORITY"
+        ));
+        assert!(socket_priority(&file).is_err());
+    }
+}
@@ -0,0 +1,282 @@
+use crate::cancel::CancellationToken;
+use crate::error::{Error, Result};
+use crate::frame::ConvertibleFrame;
+use crate::id::{CommunicationObject, NodeId};
+
+mod abort_code;
+pub use abort_code::SdoAbortCode;
+
+#[cfg(feature = "audit")]
+mod audit;
+#[cfg(feature = "audit")]
+pub use audit::{SdoAccess, SdoAudit, SdoAuditRecord};
+
+mod download;
+pub use download::{SdoDownload, SdoDownloadStep};
+
+mod upload;
+pub use upload::{SdoUpload, SdoUploadStep};
+
+mod block_upload;
+pub use block_upload::{SdoBlockUpload, SdoBlockUploadStep};
+
+mod block_download;
+pub use block_download::{SdoBlockDownload, SdoBlockDownloadStep};
+
+#[cfg(feature = "server")]
+mod server;
+#[cfg(feature = "server")]
+pub use server::SdoServer;
+
+mod timeout;
+pub use timeout::{SdoDeadline, SdoTimeoutPolicy};
+
+mod update;
+pub use update::{SdoUpdate, SdoUpdateStep};
+
+mod value;
+pub use value::SdoValue;
+
+// Server command specifiers (cf. CiA 301)
+const SCS_UPLOAD_SEGMENT: u8 = 0;
+const SCS_DOWNLOAD_SEGMENT: u8 = 1;
+const SCS_INITIATE_UPLOAD: u8 = 2;
+const SCS_INITIATE_DOWNLOAD: u8 = 3;
+const SCS_ABORT_TRANSFER: u8 = 4;
+const SCS_BLOCK_DOWNLOAD: u8 = 5;
+const SCS_BLOCK_UPLOAD: u8 = 6;
+
+// Client command specifiers (cf. CiA 301)
+const CCS_DOWNLOAD_SEGMENT: u8 = 0;
+const CCS_INITIATE_DOWNLOAD: u8 = 1;
+const CCS_INITIATE_UPLOAD: u8 = 2;
+const CCS_UPLOAD_SEGMENT: u8 = 3;
+const CCS_ABORT_TRANSFER: u8 = 4;
+const CCS_BLOCK_UPLOAD: u8 = 5;
+const CCS_BLOCK_DOWNLOAD: u8 = 6;
+
+const FRAME_DATA_SIZE: usize = 8;
+
+// Upper bound of the buffer allocated up front for an indicated transfer size, so that a bogus
+// size cannot make us allocate gigabytes
+const MAX_PREALLOCATED_SIZE: usize = 0x1_0000;
+
+// Default upper bound of the data a client accepts in one upload. Unlike the preallocation, this
+// bounds the buffer itself, also when the server does not indicate the size.
+const DEFAULT_MAX_UPLOAD_SIZE: usize = 0x10_0000;
+
+// A raw SDO request from the client to the server. Unlike `SdoFrame` it can carry any
+// command byte, e.g. the toggle bit of a segment request.
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub struct SdoRequest {
+    node_id: NodeId,
+    data: [u8; FRAME_DATA_SIZE],
+}
+
+impl SdoRequest {
+    pub fn new(node_id: NodeId, data: [u8; FRAME_DATA_SIZE]) -> Self {
+        Self { node_id, data }
+    }
+
+    pub fn new_abort(node_id: NodeId, index: u16, sub_index: u8, abort_code: SdoAbortCode) -> Self {
+        Self::new(
+            node_id,
+            initiate_data(
+                CCS_ABORT_TRANSFER << 5,
+                index,
+                sub_index,
+                abort_code.as_u32(),
+            ),
+        )
+    }
+
+    pub fn node_id(&self) -> NodeId {
+        self.node_id
+    }
+
+    pub fn data(&self) -> &[u8; FRAME_DATA_SIZE] {
+        &self.data
+    }
+}
+
+impl ConvertibleFrame for SdoRequest {
+    fn communication_object(&self) -> CommunicationObject {
+        CommunicationObject::RxSdo(self.node_id)
+    }
+
+    fn frame_data(&self) -> std::vec::Vec<u8> {
+        self.data.to_vec()
+    }
+
+    fn write_frame_data(&self, buf: &mut [u8; FRAME_DATA_SIZE]) -> usize {
+        *buf = self.data;
+        FRAME_DATA_SIZE
+    }
+}
+
+// A raw SDO response from the server to the client
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub struct SdoResponse {
+    node_id: NodeId,
+    data: [u8; FRAME_DATA_SIZE],
+}
+
+impl SdoResponse {
+    pub fn new(node_id: NodeId, data: [u8; FRAME_DATA_SIZE]) -> Self {
+        Self { node_id, data }
+    }
+
+    pub fn new_abort(node_id: NodeId, index: u16, sub_index: u8, abort_code: SdoAbortCode) -> Self {
+        Self::new(
+            node_id,
+            initiate_data(
+                SCS_ABORT_TRANSFER << 5,
+                index,
+                sub_index,
+                abort_code.as_u32(),
+            ),
+        )
+    }
+
+    pub fn node_id(&self) -> NodeId {
+        self.node_id
+    }
+
+    pub fn data(&self) -> &[u8; FRAME_DATA_SIZE] {
+        &self.data
+    }
+}
+
+impl ConvertibleFrame for SdoResponse {
+    fn communication_object(&self) -> CommunicationObject {
+        CommunicationObject::TxSdo(self.node_id)
+    }
+
+    fn frame_data(&self) -> std::vec::Vec<u8> {
+        self.data.to_vec()
+    }
+
+    fn write_frame_data(&self, buf: &mut [u8; FRAME_DATA_SIZE]) -> usize {
+        *buf = self.data;
+        FRAME_DATA_SIZE
+    }
+}
+
+fn initiate_data(command: u8, index: u16, sub_index: u8, value: u32) -> [u8; FRAME_DATA_SIZE] {
+    let mut data = [0x00; FRAME_DATA_SIZE];
+    data[0] = command;
+    data[1..3].copy_from_slice(&index.to_le_bytes());
+    data[3] = sub_index;
+    data[4..8].copy_from_slice(&value.to_le_bytes());
+    data
+}
+
+// CRC-16-CCITT (polynomial 0x1021, initial value 0) used by block transfers (cf. CiA 301)
+pub fn crc16(data: &[u8]) -> u16 {
+    data.iter().fold(0x0000, |crc, byte| {
+        (0..8).fold(crc ^ ((*byte as u16) << 8), |crc, _| {
+            if crc & 0x8000 != 0 {
+                (crc << 1) ^ 0x1021
+            } else {
+                crc << 1
+            }
+        })
+    })
+}
+
+// A buffer for the data of a segmented transfer, so that appending segments does not reallocate
+fn transfer_buffer(size: Option<usize>) -> std::vec::Vec<u8> {
+    std::vec::Vec::with_capacity(size.unwrap_or(0).min(MAX_PREALLOCATED_SIZE))
+}
+
+fn check_response(bytes: &[u8]) -> Result<()> {
+    if bytes.len() != FRAME_DATA_SIZE {
+        return Err(Error::InvalidDataLength {
+            length: bytes.len(),
+            data_type: "SdoResponse".to_owned(),
+        });
+    }
+    if bytes[0] >> 5 == SCS_ABORT_TRANSFER {
+        return Err(Error::SdoAborted(SdoAbortCode::from_u32(
+            u32::from_le_bytes(bytes[4..8].try_into().unwrap()),
+        )));
+    }
+    Ok(())
+}
+
+// Transfers with a cancellation token fail with `Error::Cancelled` once it is cancelled; the
+// caller then sends their `abort_request`.
+fn check_cancellation(cancellation: &Option<CancellationToken>) -> Result<()> {
+    cancellation
+        .as_ref()
+        .map_or(Ok(()), CancellationToken::check)
+}
+
+fn check_multiplexer(bytes: &[u8], index: u16, sub_index: u8) -> Result<()> {
+    if u16::from_le_bytes([bytes[1], bytes[2]]) != index || bytes[3] != sub_index {
+        return Err(Error::SdoMultiplexerMismatch { index, sub_index });
+    }
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_new_abort() {
+        let request = SdoRequest::new_abort(
+            2.try_into().unwrap(),
+            0x1008,
+            0,
+            SdoAbortCode::ProtocolTimedOut,
+        );
+        assert_eq!(
+            request.data(),
+            &[0x80, 0x08, 0x10, 0x00, 0x00, 0x00, 0x04, 0x05]
+        );
+        assert_eq!(
+            request.communication_object(),
+            CommunicationObject::RxSdo(2.try_into().unwrap())
+        );
+    }
+
+    #[test]
+    fn test_write_frame_data() {
+        let request = SdoRequest::new(2.try_into().unwrap(), [0x60, 0, 0, 0, 0, 0, 0, 0x01]);
+        let mut buf = [0xFF; FRAME_DATA_SIZE];
+        assert_eq!(request.write_frame_data(&mut buf), FRAME_DATA_SIZE);
+        assert_eq!(buf.to_vec(), request.frame_data());
+    }
+
+    #[test]
+    fn test_crc16() {
+        assert_eq!(crc16(&[]), 0x0000);
+        assert_eq!(crc16(b"123456789"), 0x31C3);
+        assert_eq!(crc16(&[0x00; 7]), 0x0000);
+        assert_eq!(crc16(&[0xFF]), 0x1EF0);
+    }
+
+    #[test]
+    fn test_transfer_buffer() {
+        assert_eq!(transfer_buffer(None).capacity(), 0);
+        assert!(transfer_buffer(Some(100)).capacity() >= 100);
+        assert!(transfer_buffer(Some(u32::MAX as usize)).capacity() < 0x2_0000);
+    }
+
+    #[test]
+    fn test_check_response() {
+        assert!(check_response(&[0x00; 8]).is_ok());
+        assert_eq!(
+            check_response(&[0x00; 7]),
+            Err(Error::InvalidDataLength {
+                length: 7,
+                data_type: "SdoResponse".to_owned()
+            })
+        );
+        assert_eq!(
+            check_response(&[0x80, 0x08, 0x10, 0x00, 0x00, 0x00, 0x02, 0x06]),
+            Err(Error::SdoAborted(SdoAbortCode::ObjectDoesNotExist))
+        );
+    }
+}
@@ -0,0 +1,139 @@
+macro_rules! sdo_abort_codes {
+    ($($variant:ident = $code:literal => $description:literal,)*) => {
+        // SDO abort codes (cf. CiA 301). Codes outside the table, e.g. manufacturer-specific
+        // ones, are kept as `Other`.
+        #[derive(Clone, Copy, Debug, PartialEq, Eq)]
+        pub enum SdoAbortCode {
+            $($variant,)*
+            Other(u32),
+        }
+
+        impl SdoAbortCode {
+            pub fn from_u32(value: u32) -> Self {
+                match value {
+                    $($code => Self::$variant,)*
+                    _ => Self::Other(value),
+                }
+            }
+
+            pub fn as_u32(&self) -> u32 {
+                match self {
+                    $(Self::$variant => $code,)*
+                    Self::Other(value) => *value,
+                }
+            }
+
+            pub fn description(&self) -> &'static str {
+                match self {
+                    $(Self::$variant => $description,)*
+                    Self::Other(_) => "Unknown abort code",
+                }
+            }
+        }
+    };
+}
+
+sdo_abort_codes! {
+    ToggleBitNotAlternated = 0x0503_0000 => "Toggle bit not alternated",
+    ProtocolTimedOut = 0x0504_0000 => "SDO protocol timed out",
+    InvalidCommandSpecifier = 0x0504_0001 => "Client/server command specifier not valid or unknown",
+    InvalidBlockSize = 0x0504_0002 => "Invalid block size",
+    InvalidSequenceNumber = 0x0504_0003 => "Invalid sequence number",
+    CrcError = 0x0504_0004 => "CRC error",
+    OutOfMemory = 0x0504_0005 => "Out of memory",
+    UnsupportedAccess = 0x0601_0000 => "Unsupported access to an object",
+    WriteOnly = 0x0601_0001 => "Attempt to read a write only object",
+    ReadOnly = 0x0601_0002 => "Attempt to write a read only object",
+    ObjectDoesNotExist = 0x0602_0000 => "Object does not exist in the object dictionary",
+    ObjectCannotBeMapped = 0x0604_0041 => "Object cannot be mapped to the PDO",
+    PdoLengthExceeded = 0x0604_0042 => "The number and length of the objects to be mapped would exceed PDO length",
+    ParameterIncompatibility = 0x0604_0043 => "General parameter incompatibility reason",
+    InternalIncompatibility = 0x0604_0047 => "General internal incompatibility in the device",
+    HardwareError = 0x0606_0000 => "Access failed due to a hardware error",
+    DataTypeMismatch = 0x0607_0010 => "Data type does not match, length of service parameter does not match",
+    DataTypeLengthTooHigh = 0x0607_0012 => "Data type does not match, length of service parameter too high",
+    DataTypeLengthTooLow = 0x0607_0013 => "Data type does not match, length of service parameter too low",
+    SubIndexDoesNotExist = 0x0609_0011 => "Sub-index does not exist",
+    InvalidValue = 0x0609_0030 => "Invalid value for parameter",
+    ValueTooHigh = 0x0609_0031 => "Value of parameter written too high",
+    ValueTooLow = 0x0609_0032 => "Value of parameter written too low",
+    MaximumLessThanMinimum = 0x0609_0036 => "Maximum value is less than minimum value",
+    ResourceNotAvailable = 0x060A_0023 => "Resource not available: SDO connection",
+    GeneralError = 0x0800_0000 => "General error",
+    DataCannotBeTransferred = 0x0800_0020 => "Data cannot be transferred or stored to the application",
+    LocalControl = 0x0800_0021 => "Data cannot be transferred or stored to the application because of local control",
+    DeviceState = 0x0800_0022 => "Data cannot be transferred or stored to the application because of the present device state",
+    NoObjectDictionary = 0x0800_0023 => "Object dictionary dynamic generation fails or no object dictionary is present",
+    NoDataAvailable = 0x0800_0024 => "No data available",
+}
+
+impl From<u32> for SdoAbortCode {
+    fn from(value: u32) -> Self {
+        Self::from_u32(value)
+    }
+}
+
+impl From<SdoAbortCode> for u32 {
+    fn from(code: SdoAbortCode) -> Self {
+        code.as_u32()
+    }
+}
+
+impl std::fmt::Display for SdoAbortCode {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(f, "{} (0x{:08X})", self.description(), self.as_u32())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_from_u32() {
+        assert_eq!(
+            SdoAbortCode::from_u32(0x0503_0000),
+            SdoAbortCode::ToggleBitNotAlternated
+        );
+        assert_eq!(
+            SdoAbortCode::from_u32(0x0504_0000),
+            SdoAbortCode::ProtocolTimedOut
+        );
+        assert_eq!(
+            SdoAbortCode::from_u32(0x0602_0000),
+            SdoAbortCode::ObjectDoesNotExist
+        );
+        assert_eq!(
+            SdoAbortCode::from_u32(0x0609_0031),
+            SdoAbortCode::ValueTooHigh
+        );
+        assert_eq!(
+            SdoAbortCode::from_u32(0x0800_0024),
+            SdoAbortCode::NoDataAvailable
+        );
+        assert_eq!(
+            SdoAbortCode::from_u32(0x1234_5678),
+            SdoAbortCode::Other(0x1234_5678)
+        );
+    }
+
+    #[test]
+    fn test_as_u32() {
+        assert_eq!(SdoAbortCode::ReadOnly.as_u32(), 0x0601_0002);
+        assert_eq!(SdoAbortCode::CrcError.as_u32(), 0x0504_0004);
+        assert_eq!(SdoAbortCode::Other(0xFFFF_0000).as_u32(), 0xFFFF_0000);
+        assert_eq!(u32::from(SdoAbortCode::GeneralError), 0x0800_0000);
+    }
+
+    #[test]
+    fn test_display() {
+        assert_eq!(
+            SdoAbortCode::ObjectDoesNotExist.to_string(),
+            "Object does not exist in the object dictionary (0x06020000)"
+        );
+        assert_eq!(
+            SdoAbortCode::Other(0x1234_5678).to_string(),
+            "Unknown abort code (0x12345678)"
+        );
+    }
+}
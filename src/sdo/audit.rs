@@ -0,0 +1,438 @@
+use crate::dcf::Dcf;
+use crate::frame::RawFrame;
+use crate::id::{CommunicationObject, NodeId};
+use crate::od::{DataType, Value};
+use crate::sdo::{
+    SdoAbortCode, CCS_ABORT_TRANSFER, CCS_DOWNLOAD_SEGMENT, CCS_INITIATE_DOWNLOAD,
+    CCS_INITIATE_UPLOAD, CCS_UPLOAD_SEGMENT, FRAME_DATA_SIZE, SCS_ABORT_TRANSFER,
+    SCS_DOWNLOAD_SEGMENT, SCS_INITIATE_DOWNLOAD, SCS_INITIATE_UPLOAD, SCS_UPLOAD_SEGMENT,
+};
+
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub enum SdoAccess {
+    Read,
+    Write,
+}
+
+// An SDO transfer observed on the bus, finished or aborted
+#[derive(Clone, Debug, PartialEq)]
+pub struct SdoAuditRecord {
+    pub node_id: NodeId,
+    pub access: SdoAccess,
+    pub index: u16,
+    pub sub_index: u8,
+    // From the EDS of the node, if loaded and describing the object
+    pub name: Option<std::string::String>,
+    pub data_type: Option<DataType>,
+    // The data transferred, partial for an aborted segmented transfer
+    pub data: std::vec::Vec<u8>,
+    // `data` decoded with `data_type`, unless the transfer was aborted or the length does not
+    // match
+    pub value: Option<Value>,
+    pub abort_code: Option<SdoAbortCode>,
+}
+
+impl std::fmt::Display for SdoAuditRecord {
+    fn fmt(&self, f: &mut std::fmt::Formatter) -> std::fmt::Result {
+        let access = match self.access {
+            SdoAccess::Read => "read",
+            SdoAccess::Write => "write",
+        };
+        write!(
+            f,
+            "node {}: {access} 0x{:04X}:{:02X}",
+            self.node_id, self.index, self.sub_index
+        )?;
+        if let Some(name) = &self.name {
+            write!(f, " ({name})")?;
+        }
+        if let Some(abort_code) = self.abort_code {
+            return write!(f, " aborted: {abort_code}");
+        }
+        write!(f, " = ")?;
+        match &self.value {
+            Some(Value::Boolean(value)) => write!(f, "{value}"),
+            Some(Value::Integer8(value)) => write!(f, "{value}"),
+            Some(Value::Integer16(value)) => write!(f, "{value}"),
+            Some(Value::Integer32(value)) => write!(f, "{value}"),
+            Some(Value::Integer64(value)) => write!(f, "{value}"),
+            Some(Value::Unsigned8(value)) => write!(f, "{value} (0x{value:02X})"),
+            Some(Value::Unsigned16(value)) => write!(f, "{value} (0x{value:04X})"),
+            Some(Value::Unsigned32(value)) => write!(f, "{value} (0x{value:08X})"),
+            Some(Value::Unsigned64(value)) => write!(f, "{value} (0x{value:016X})"),
+            Some(Value::Real32(value)) => write!(f, "{value}"),
+            Some(Value::Real64(value)) => write!(f, "{value}"),
+            Some(Value::VisibleString(value)) => write!(f, "{value:?}"),
+            Some(Value::OctetString(_)) | Some(Value::Domain(_)) | None => {
+                write!(f, "[")?;
+                crate::frame::write_bytes(f, &self.data)?;
+                write!(f, "]")
+            }
+        }
+    }
+}
+
+#[derive(Clone, Debug, PartialEq)]
+struct Transfer {
+    access: SdoAccess,
+    index: u16,
+    sub_index: u8,
+    segmented: bool,
+    // The client sent the last segment of a segmented download.
+    last_segment: bool,
+    data: std::vec::Vec<u8>,
+}
+
+// Follows the SDO transfers between a client and the servers on the default SDO channels, e.g.
+// in a bus monitor, to turn the frames into a readable audit trail of the objects read and
+// written. With the EDS of a node (which `Dcf::parse` also reads), its objects are annotated
+// with their names and values. Block transfers are not followed.
+#[derive(Clone, Debug, Default, PartialEq)]
+pub struct SdoAudit {
+    eds: std::collections::BTreeMap<u8, Dcf>,
+    transfers: std::collections::BTreeMap<u8, Transfer>,
+}
+
+impl SdoAudit {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    pub fn insert_eds(&mut self, node_id: NodeId, eds: Dcf) {
+        self.eds.insert(node_id.as_raw(), eds);
+    }
+
+    pub fn remove_eds(&mut self, node_id: NodeId) -> Option<Dcf> {
+        self.eds.remove(&node_id.as_raw())
+    }
+
+    // Feeds any observed frame; returns the record of the transfer it finishes, if any.
+    pub fn on_frame(&mut self, frame: &RawFrame) -> Option<SdoAuditRecord> {
+        if frame.is_remote() || frame.data().len() != FRAME_DATA_SIZE {
+            return None;
+        }
+        match CommunicationObject::new(frame.id()).ok()? {
+            CommunicationObject::RxSdo(node_id) => self.on_request(node_id, frame.data()),
+            CommunicationObject::TxSdo(node_id) => self.on_response(node_id, frame.data()),
+            _ => None,
+        }
+    }
+
+    fn on_request(&mut self, node_id: NodeId, bytes: &[u8]) -> Option<SdoAuditRecord> {
+        let command = bytes[0];
+        let (index, sub_index) = multiplexer(bytes);
+        match command >> 5 {
+            CCS_INITIATE_DOWNLOAD => {
+                let segmented = command & 0b0010 == 0;
+                let data = if segmented {
+                    std::vec::Vec::new()
+                } else {
+                    bytes[4..FRAME_DATA_SIZE - expedited_unused(command)].to_vec()
+                };
+                self.start(node_id, SdoAccess::Write, index, sub_index, segmented, data);
+            }
+            CCS_DOWNLOAD_SEGMENT => {
+                if let Some(transfer) = self.transfers.get_mut(&node_id.as_raw()) {
+                    if transfer.access == SdoAccess::Write && transfer.segmented {
+                        transfer.data.extend_from_slice(
+                            &bytes[1..FRAME_DATA_SIZE - segment_unused(command)],
+                        );
+                        transfer.last_segment = command & 0b0001 != 0;
+                    }
+                }
+            }
+            CCS_INITIATE_UPLOAD => {
+                let data = std::vec::Vec::new();
+                self.start(node_id, SdoAccess::Read, index, sub_index, false, data);
+            }
+            CCS_UPLOAD_SEGMENT => {}
+            CCS_ABORT_TRANSFER => return self.abort(node_id, bytes),
+            _ => {
+                self.transfers.remove(&node_id.as_raw());
+            }
+        }
+        None
+    }
+
+    fn on_response(&mut self, node_id: NodeId, bytes: &[u8]) -> Option<SdoAuditRecord> {
+        let command = bytes[0];
+        let scs = command >> 5;
+        if scs == SCS_ABORT_TRANSFER {
+            return self.abort(node_id, bytes);
+        }
+        let mut transfer = self.transfers.remove(&node_id.as_raw())?;
+        match (transfer.access, transfer.segmented, scs) {
+            (SdoAccess::Write, false, SCS_INITIATE_DOWNLOAD) => {
+                if multiplexer(bytes) != (transfer.index, transfer.sub_index) {
+                    return None;
+                }
+                return Some(self.record(node_id, transfer, None));
+            }
+            (SdoAccess::Write, true, SCS_INITIATE_DOWNLOAD) => {
+                if multiplexer(bytes) != (transfer.index, transfer.sub_index) {
+                    return None;
+                }
+            }
+            (SdoAccess::Write, true, SCS_DOWNLOAD_SEGMENT) => {
+                if transfer.last_segment {
+                    return Some(self.record(node_id, transfer, None));
+                }
+            }
+            (SdoAccess::Read, false, SCS_INITIATE_UPLOAD) => {
+                if multiplexer(bytes) != (transfer.index, transfer.sub_index) {
+                    return None;
+                }
+                if command & 0b0010 != 0 {
+                    transfer.data = bytes[4..FRAME_DATA_SIZE - expedited_unused(command)].to_vec();
+                    return Some(self.record(node_id, transfer, None));
+                }
+                transfer.segmented = true;
+            }
+            (SdoAccess::Read, true, SCS_UPLOAD_SEGMENT) => {
+                transfer
+                    .data
+                    .extend_from_slice(&bytes[1..FRAME_DATA_SIZE - segment_unused(command)]);
+                if command & 0b0001 != 0 {
+                    return Some(self.record(node_id, transfer, None));
+                }
+            }
+            // Out of sequence, e.g. the audit started in the middle of a transfer
+            _ => return None,
+        }
+        self.transfers.insert(node_id.as_raw(), transfer);
+        None
+    }
+
+    fn start(
+        &mut self,
+        node_id: NodeId,
+        access: SdoAccess,
+        index: u16,
+        sub_index: u8,
+        segmented: bool,
+        data: std::vec::Vec<u8>,
+    ) {
+        self.transfers.insert(
+            node_id.as_raw(),
+            Transfer {
+                access,
+                index,
+                sub_index,
+                segmented,
+                last_segment: false,
+                data,
+            },
+        );
+    }
+
+    // Either side may abort. The multiplexer of the abort frame is reported, as it may differ
+    // from the one of the initiate frame, e.g. for an abort caused by a malformed request.
+    fn abort(&mut self, node_id: NodeId, bytes: &[u8]) -> Option<SdoAuditRecord> {
+        let abort_code =
+            SdoAbortCode::from_u32(u32::from_le_bytes(bytes[4..8].try_into().unwrap()));
+        let (index, sub_index) = multiplexer(bytes);
+        // Without the start of the transfer, whether it was a read or a write is unknown.
+        let transfer = self.transfers.remove(&node_id.as_raw())?;
+        let transfer = Transfer {
+            index,
+            sub_index,
+            ..transfer
+        };
+        Some(self.record(node_id, transfer, Some(abort_code)))
+    }
+
+    fn record(
+        &self,
+        node_id: NodeId,
+        transfer: Transfer,
+        abort_code: Option<SdoAbortCode>,
+    ) -> SdoAuditRecord {
+        let entry = self
+            .eds
+            .get(&node_id.as_raw())
+            .and_then(|eds| eds.entry(transfer.index, transfer.sub_index));
+        let data_type = entry.map(|entry| entry.data_type);
+        let value = match (data_type, abort_code) {
+            (Some(data_type), None) => Value::from_bytes(data_type, &transfer.data).ok(),
+            _ => None,
+        };
+        SdoAuditRecord {
+            node_id,
+            access: transfer.access,
+            index: transfer.index,
+            sub_index: transfer.sub_index,
+            name: entry.map(|entry| entry.name.clone()),
+            data_type,
+            data: transfer.data,
+            value,
+            abort_code,
+        }
+    }
+}
+
+fn multiplexer(bytes: &[u8]) -> (u16, u8) {
+    (u16::from_le_bytes([bytes[1], bytes[2]]), bytes[3])
+}
+
+fn expedited_unused(command: u8) -> usize {
+    if command & 0b0001 != 0 {
+        ((command >> 2) & 0b11) as usize
+    } else {
+        0
+    }
+}
+
+fn segment_unused(command: u8) -> usize {
+    ((command >> 1) & 0b111) as usize
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const EDS: &str = "
+[1008]
+ParameterName=Manufacturer device name
+ObjectType=0x7
+DataType=0x0009
+AccessType=const
+
+[1017]
+ParameterName=Producer heartbeat time
+ObjectType=0x7
+DataType=0x0006
+AccessType=rw
+DefaultValue=0
+";
+
+    fn node_id() -> NodeId {
+        3.try_into().unwrap()
+    }
+
+    fn audit() -> SdoAudit {
+        let mut audit = SdoAudit::new();
+        audit.insert_eds(node_id(), Dcf::parse(EDS).unwrap());
+        audit
+    }
+
+    fn frame(id: u16, data: &[u8]) -> RawFrame {
+        RawFrame::new(id, data).unwrap()
+    }
+
+    #[test]
+    fn test_expedited() {
+        let mut audit = audit();
+        assert_eq!(
+            audit.on_frame(&frame(0x603, &[0x2B, 0x17, 0x10, 0x00, 0xE8, 0x03, 0, 0])),
+            None
+        );
+        let record = audit
+            .on_frame(&frame(0x583, &[0x60, 0x17, 0x10, 0x00, 0, 0, 0, 0]))
+            .unwrap();
+        assert_eq!(
+            record,
+            SdoAuditRecord {
+                node_id: node_id(),
+                access: SdoAccess::Write,
+                index: 0x1017,
+                sub_index: 0,
+                name: Some("Producer heartbeat time".to_owned()),
+                data_type: Some(DataType::Unsigned16),
+                data: vec![0xE8, 0x03],
+                value: Some(Value::Unsigned16(1000)),
+                abort_code: None,
+            }
+        );
+        assert_eq!(
+            record.to_string(),
+            "node 3: write 0x1017:00 (Producer heartbeat time) = 1000 (0x03E8)"
+        );
+
+        // Without an EDS, the raw data is reported.
+        audit.remove_eds(node_id());
+        audit.on_frame(&frame(0x603, &[0x40, 0x17, 0x10, 0x00, 0, 0, 0, 0]));
+        let record = audit
+            .on_frame(&frame(0x583, &[0x4B, 0x17, 0x10, 0x00, 0xE8, 0x03, 0, 0]))
+            .unwrap();
+        assert_eq!(record.value, None);
+        assert_eq!(record.to_string(), "node 3: read 0x1017:00 = [E8 03]");
+    }
+
+    #[test]
+    fn test_segmented() {
+        let mut audit = audit();
+        for (id, data) in [
+            (0x603, [0x40, 0x08, 0x10, 0x00, 0, 0, 0, 0]),
+            (0x583, [0x41, 0x08, 0x10, 0x00, 0x0A, 0, 0, 0]),
+            (0x603, [0x60, 0, 0, 0, 0, 0, 0, 0]),
+            (0x583, [0x00, b'c', b'a', b'n', b'o', b'p', b'e', b'n']),
+            (0x603, [0x70, 0, 0, 0, 0, 0, 0, 0]),
+        ] {
+            assert_eq!(audit.on_frame(&frame(id, &data)), None);
+        }
+        let record = audit
+            .on_frame(&frame(0x583, &[0x19, b'-', b'r', b's', 0, 0, 0, 0]))
+            .unwrap();
+        assert_eq!(
+            record.value,
+            Some(Value::VisibleString("canopen-rs".to_owned()))
+        );
+        assert_eq!(
+            record.to_string(),
+            "node 3: read 0x1008:00 (Manufacturer device name) = \"canopen-rs\""
+        );
+
+        for (id, data) in [
+            (0x603, [0x21, 0x00, 0x20, 0x01, 0x09, 0, 0, 0]),
+            (0x583, [0x60, 0x00, 0x20, 0x01, 0, 0, 0, 0]),
+            (0x603, [0x00, 1, 2, 3, 4, 5, 6, 7]),
+            (0x583, [0x20, 0, 0, 0, 0, 0, 0, 0]),
+            (0x603, [0x1B, 8, 9, 0, 0, 0, 0, 0]),
+        ] {
+            assert_eq!(audit.on_frame(&frame(id, &data)), None);
+        }
+        let record = audit
+            .on_frame(&frame(0x583, &[0x30, 0, 0, 0, 0, 0, 0, 0]))
+            .unwrap();
+        assert_eq!(record.access, SdoAccess::Write);
+        assert_eq!(record.data, vec![1, 2, 3, 4, 5, 6, 7, 8, 9]);
+        assert_eq!(
+            record.to_string(),
+            "node 3: write 0x2000:01 = [01 02 03 04 05 06 07 08 09]"
+        );
+    }
+
+    #[test]
+    fn test_abort() {
+        let mut audit = audit();
+        audit.on_frame(&frame(0x603, &[0x2B, 0x17, 0x10, 0x00, 0xE8, 0x03, 0, 0]));
+        let record = audit
+            .on_frame(&frame(
+                0x583,
+                &[0x80, 0x17, 0x10, 0x00, 0x30, 0x00, 0x09, 0x06],
+            ))
+            .unwrap();
+        assert_eq!(record.abort_code, Some(SdoAbortCode::from_u32(0x0609_0030)));
+        assert_eq!(record.value, None);
+        assert!(record
+            .to_string()
+            .starts_with("node 3: write 0x1017:00 (Producer heartbeat time) aborted: "));
+
+        // Responses without a request, other COBs and other nodes are ignored.
+        for (id, data) in [
+            (0x583, [0x60, 0x17, 0x10, 0x00, 0, 0, 0, 0]),
+            (0x583, [0x80, 0x17, 0x10, 0x00, 0x30, 0x00, 0x09, 0x06]),
+            (0x183, [0x60, 0x17, 0x10, 0x00, 0, 0, 0, 0]),
+        ] {
+            assert_eq!(audit.on_frame(&frame(id, &data)), None);
+        }
+        audit.on_frame(&frame(0x603, &[0x40, 0x17, 0x10, 0x00, 0, 0, 0, 0]));
+        assert_eq!(
+            audit.on_frame(&frame(0x584, &[0x4B, 0x17, 0x10, 0x00, 0xE8, 0x03, 0, 0])),
+            None
+        );
+        assert!(audit
+            .on_frame(&frame(0x583, &[0x4B, 0x17, 0x10, 0x00, 0xE8, 0x03, 0, 0]))
+            .is_some());
+    }
+}
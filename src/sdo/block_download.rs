@@ -0,0 +1,340 @@
+use crate::cancel::CancellationToken;
+use crate::error::{Error, Result};
+use crate::id::NodeId;
+use crate::sdo::{
+    check_cancellation, check_multiplexer, check_response, crc16, initiate_data, SdoAbortCode,
+    SdoRequest, CCS_BLOCK_DOWNLOAD, FRAME_DATA_SIZE, SCS_BLOCK_DOWNLOAD,
+};
+
+#[derive(Clone, Debug, PartialEq)]
+pub enum SdoBlockDownloadStep {
+    // Send these requests back-to-back, then wait for the next response.
+    Send(std::vec::Vec<SdoRequest>),
+    Done,
+}
+
+#[derive(Clone, Copy, Debug, PartialEq)]
+enum BlockDownloadState {
+    Initiating,
+    Downloading,
+    Ending,
+    Finished,
+}
+
+// Client side of an SDO block download (cf. CiA 301).
+#[derive(Clone, Debug)]
+pub struct SdoBlockDownload {
+    node_id: NodeId,
+    index: u16,
+    sub_index: u8,
+    data: std::vec::Vec<u8>,
+    state: BlockDownloadState,
+    crc: bool,
+    block_start: usize,
+    block_segments: u8,
+    cancellation: Option<CancellationToken>,
+}
+
+impl SdoBlockDownload {
+    const SEGMENT_DATA_SIZE: usize = 7;
+
+    // Client subcommands
+    const CS_INITIATE: u8 = 0;
+    const CS_END: u8 = 1;
+
+    // Server subcommands
+    const SS_INITIATE: u8 = 0;
+    const SS_END: u8 = 1;
+    const SS_ACK: u8 = 2;
+
+    pub fn new(node_id: NodeId, index: u16, sub_index: u8, data: std::vec::Vec<u8>) -> Self {
+        Self {
+            node_id,
+            index,
+            sub_index,
+            data,
+            state: BlockDownloadState::Initiating,
+            crc: false,
+            block_start: 0,
+            block_segments: 0,
+            cancellation: None,
+        }
+    }
+
+    pub fn with_cancellation(self, cancellation: CancellationToken) -> Self {
+        Self {
+            cancellation: Some(cancellation),
+            ..self
+        }
+    }
+
+    // Number of bytes acknowledged by the server so far
+    pub fn transferred(&self) -> usize {
+        self.block_start
+    }
+
+    pub fn abort_request(&self) -> SdoRequest {
+        SdoRequest::new_abort(
+            self.node_id,
+            self.index,
+            self.sub_index,
+            SdoAbortCode::GeneralError,
+        )
+    }
+
+    pub fn start(&mut self) -> SdoRequest {
+        self.state = BlockDownloadState::Initiating;
+        self.crc = false;
+        self.block_start = 0;
+        self.block_segments = 0;
+        // Announces CRC support and indicates the size.
+        SdoRequest::new(
+            self.node_id,
+            initiate_data(
+                (CCS_BLOCK_DOWNLOAD << 5) | 0b0110 | Self::CS_INITIATE,
+                self.index,
+                self.sub_index,
+                self.data.len() as u32,
+            ),
+        )
+    }
+
+    // Feeds the data of a frame received on the TxSDO COB of the node.
+    pub fn on_response(&mut self, bytes: &[u8]) -> Result<SdoBlockDownloadStep> {
+        check_cancellation(&self.cancellation)?;
+        check_response(bytes)?;
+        let command = bytes[0];
+        if command >> 5 != SCS_BLOCK_DOWNLOAD {
+            return Err(Error::InvalidServerCommandSpecifier(command >> 5));
+        }
+        match (self.state, command & 0b0011) {
+            (BlockDownloadState::Initiating, Self::SS_INITIATE) => {
+                check_multiplexer(bytes, self.index, self.sub_index)?;
+                self.crc = command & 0b0100 != 0;
+                self.state = BlockDownloadState::Downloading;
+                Ok(SdoBlockDownloadStep::Send(self.next_block(bytes[4])?))
+            }
+            (BlockDownloadState::Downloading, Self::SS_ACK) => {
+                let ack_sequence = bytes[1];
+                if ack_sequence > self.block_segments {
+                    return Err(Error::InvalidSdoSequenceNumber(ack_sequence));
+                }
+                // Segments after the acknowledged one are sent again in the next block.
+                let acknowledged = (self.block_start
+                    + ack_sequence as usize * Self::SEGMENT_DATA_SIZE)
+                    .min(self.data.len());
+                self.block_start = acknowledged;
+                if ack_sequence == self.block_segments && self.is_data_sent() {
+                    self.state = BlockDownloadState::Ending;
+                    return Ok(SdoBlockDownloadStep::Send(vec![self.end_request()]));
+                }
+                Ok(SdoBlockDownloadStep::Send(self.next_block(bytes[2])?))
+            }
+            (BlockDownloadState::Ending, Self::SS_END) => {
+                self.state = BlockDownloadState::Finished;
+                Ok(SdoBlockDownloadStep::Done)
+            }
+            _ => Err(Error::InvalidServerCommandSpecifier(command >> 5)),
+        }
+    }
+
+    fn is_data_sent(&self) -> bool {
+        // An empty payload is still sent as a single empty segment.
+        self.block_start >= self.data.len() && (self.block_segments > 0 || self.data.is_empty())
+    }
+
+    fn next_block(&mut self, block_size: u8) -> Result<std::vec::Vec<SdoRequest>> {
+        if !(1..=127).contains(&block_size) {
+            return Err(Error::InvalidSdoBlockSize(block_size));
+        }
+        let remaining = &self.data[self.block_start..];
+        let segment_count = remaining
+            .len()
+            .div_ceil(Self::SEGMENT_DATA_SIZE)
+            .clamp(1, block_size as usize);
+        let segments = (0..segment_count)
+            .map(|i| {
+                let start = i * Self::SEGMENT_DATA_SIZE;
+                let end = (start + Self::SEGMENT_DATA_SIZE).min(remaining.len());
+                let last = end == remaining.len();
+                let mut data = [0x00; FRAME_DATA_SIZE];
+                data[0] = ((last as u8) << 7) | (i + 1) as u8;
+                data[1..1 + end - start].copy_from_slice(&remaining[start..end]);
+                SdoRequest::new(self.node_id, data)
+            })
+            .collect();
+        self.block_segments = segment_count as u8;
+        Ok(segments)
+    }
+
+    fn end_request(&self) -> SdoRequest {
+        let last_segment_size = match self.data.len() % Self::SEGMENT_DATA_SIZE {
+            0 if !self.data.is_empty() => Self::SEGMENT_DATA_SIZE,
+            size => size,
+        };
+        let unused = (Self::SEGMENT_DATA_SIZE - last_segment_size) as u8;
+        let crc = if self.crc { crc16(&self.data) } else { 0 };
+        let mut data = [0x00; FRAME_DATA_SIZE];
+        data[0] = (CCS_BLOCK_DOWNLOAD << 5) | (unused << 2) | Self::CS_END;
+        data[1..3].copy_from_slice(&crc.to_le_bytes());
+        SdoRequest::new(self.node_id, data)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    use crate::sdo::SdoAbortCode;
+
+    fn node_id() -> NodeId {
+        1.try_into().unwrap()
+    }
+
+    fn segment(sequence: u8, last: bool, chunk: &[u8]) -> SdoRequest {
+        let mut data = [0x00; 8];
+        data[0] = sequence | ((last as u8) << 7);
+        data[1..1 + chunk.len()].copy_from_slice(chunk);
+        SdoRequest::new(node_id(), data)
+    }
+
+    #[test]
+    fn test_download() {
+        let payload: std::vec::Vec<u8> = (0..20).collect();
+        let crc = crc16(&payload).to_le_bytes();
+        let mut download = SdoBlockDownload::new(node_id(), 0x1F50, 1, payload.clone());
+        assert_eq!(
+            download.start().data(),
+            &[0xC6, 0x50, 0x1F, 0x01, 20, 0x00, 0x00, 0x00]
+        );
+        // blksize = 2
+        assert_eq!(
+            download.on_response(&[0xA4, 0x50, 0x1F, 0x01, 0x02, 0x00, 0x00, 0x00]),
+            Ok(SdoBlockDownloadStep::Send(vec![
+                segment(1, false, &payload[0..7]),
+                segment(2, false, &payload[7..14]),
+            ]))
+        );
+        assert_eq!(
+            download.on_response(&[0xA2, 0x02, 0x7F, 0x00, 0x00, 0x00, 0x00, 0x00]),
+            Ok(SdoBlockDownloadStep::Send(vec![segment(
+                1,
+                true,
+                &payload[14..20]
+            )]))
+        );
+        // 1 unused byte in the last segment
+        assert_eq!(
+            download.on_response(&[0xA2, 0x01, 0x7F, 0x00, 0x00, 0x00, 0x00, 0x00]),
+            Ok(SdoBlockDownloadStep::Send(vec![SdoRequest::new(
+                node_id(),
+                [0xC5, crc[0], crc[1], 0x00, 0x00, 0x00, 0x00, 0x00]
+            )]))
+        );
+        assert_eq!(
+            download.on_response(&[0xA1, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00]),
+            Ok(SdoBlockDownloadStep::Done)
+        );
+    }
+
+    #[test]
+    fn test_cancel() {
+        let cancellation = CancellationToken::new();
+        let payload: std::vec::Vec<u8> = (0..20).collect();
+        let mut download = SdoBlockDownload::new(node_id(), 0x1F50, 1, payload)
+            .with_cancellation(cancellation.clone());
+        download.start();
+        download
+            .on_response(&[0xA4, 0x50, 0x1F, 0x01, 0x02, 0x00, 0x00, 0x00])
+            .unwrap();
+        download
+            .on_response(&[0xA2, 0x02, 0x7F, 0x00, 0x00, 0x00, 0x00, 0x00])
+            .unwrap();
+        cancellation.cancel();
+        assert_eq!(
+            download.on_response(&[0xA2, 0x01, 0x7F, 0x00, 0x00, 0x00, 0x00, 0x00]),
+            Err(Error::Cancelled)
+        );
+        assert_eq!(download.transferred(), 14);
+        assert_eq!(
+            download.abort_request().data(),
+            &[0x80, 0x50, 0x1F, 0x01, 0x00, 0x00, 0x00, 0x08]
+        );
+    }
+
+    #[test]
+    fn test_retransmission() {
+        let payload: std::vec::Vec<u8> = (0..21).collect();
+        let mut download = SdoBlockDownload::new(node_id(), 0x1F50, 1, payload.clone());
+        download.start();
+        // The server does not support CRC.
+        assert_eq!(
+            download.on_response(&[0xA0, 0x50, 0x1F, 0x01, 0x7F, 0x00, 0x00, 0x00]),
+            Ok(SdoBlockDownloadStep::Send(vec![
+                segment(1, false, &payload[0..7]),
+                segment(2, false, &payload[7..14]),
+                segment(3, true, &payload[14..21]),
+            ]))
+        );
+        // The second segment is lost.
+        assert_eq!(
+            download.on_response(&[0xA2, 0x01, 0x7F, 0x00, 0x00, 0x00, 0x00, 0x00]),
+            Ok(SdoBlockDownloadStep::Send(vec![
+                segment(1, false, &payload[7..14]),
+                segment(2, true, &payload[14..21]),
+            ]))
+        );
+        assert_eq!(
+            download.on_response(&[0xA2, 0x02, 0x7F, 0x00, 0x00, 0x00, 0x00, 0x00]),
+            Ok(SdoBlockDownloadStep::Send(vec![SdoRequest::new(
+                node_id(),
+                [0xC1, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00]
+            )]))
+        );
+    }
+
+    #[test]
+    fn test_empty() {
+        let mut download = SdoBlockDownload::new(node_id(), 0x1F50, 1, vec![]);
+        download.start();
+        assert_eq!(
+            download.on_response(&[0xA4, 0x50, 0x1F, 0x01, 0x7F, 0x00, 0x00, 0x00]),
+            Ok(SdoBlockDownloadStep::Send(vec![segment(1, true, &[])]))
+        );
+        assert_eq!(
+            download.on_response(&[0xA2, 0x01, 0x7F, 0x00, 0x00, 0x00, 0x00, 0x00]),
+            Ok(SdoBlockDownloadStep::Send(vec![SdoRequest::new(
+                node_id(),
+                [0xDD, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00]
+            )]))
+        );
+    }
+
+    #[test]
+    fn test_errors() {
+        let mut download = SdoBlockDownload::new(node_id(), 0x1F50, 1, vec![0x00; 8]);
+        download.start();
+        assert_eq!(
+            download.on_response(&[0xA4, 0x50, 0x1F, 0x01, 0x00, 0x00, 0x00, 0x00]),
+            Err(Error::InvalidSdoBlockSize(0))
+        );
+
+        let mut download = SdoBlockDownload::new(node_id(), 0x1F50, 1, vec![0x00; 8]);
+        download.start();
+        download
+            .on_response(&[0xA4, 0x50, 0x1F, 0x01, 0x7F, 0x00, 0x00, 0x00])
+            .unwrap();
+        assert_eq!(
+            download.on_response(&[0xA2, 0x03, 0x7F, 0x00, 0x00, 0x00, 0x00, 0x00]),
+            Err(Error::InvalidSdoSequenceNumber(3))
+        );
+        assert_eq!(
+            download.on_response(&[0x80, 0x50, 0x1F, 0x01, 0x04, 0x00, 0x04, 0x05]),
+            Err(Error::SdoAborted(SdoAbortCode::CrcError))
+        );
+        assert_eq!(
+            download.on_response(&[0x60, 0x50, 0x1F, 0x01, 0x00, 0x00, 0x00, 0x00]),
+            Err(Error::InvalidServerCommandSpecifier(3))
+        );
+    }
+}
@@ -0,0 +1,451 @@
+use crate::cancel::CancellationToken;
+use crate::error::{Error, Result};
+use crate::id::NodeId;
+use crate::sdo::{
+    check_cancellation, check_multiplexer, check_response, crc16, initiate_data, SdoAbortCode,
+    SdoRequest, CCS_BLOCK_UPLOAD, DEFAULT_MAX_UPLOAD_SIZE, FRAME_DATA_SIZE, SCS_BLOCK_UPLOAD,
+};
+
+#[derive(Clone, Debug, PartialEq)]
+pub enum SdoBlockUploadStep {
+    Send(SdoRequest),
+    // Nothing to send; keep feeding the segments of the current block.
+    Receive,
+    // Send the final request; the transfer is complete.
+    Done(SdoRequest, std::vec::Vec<u8>),
+}
+
+#[derive(Clone, Copy, Debug, PartialEq)]
+enum BlockUploadState {
+    Initiating,
+    Uploading,
+    Ending,
+    Finished,
+}
+
+// Client side of an SDO block upload (cf. CiA 301).
+#[derive(Clone, Debug)]
+pub struct SdoBlockUpload {
+    node_id: NodeId,
+    index: u16,
+    sub_index: u8,
+    block_size: u8,
+    state: BlockUploadState,
+    size: Option<usize>,
+    max_size: usize,
+    crc: bool,
+    ack_sequence: u8,
+    last_received: bool,
+    data: std::vec::Vec<u8>,
+    cancellation: Option<CancellationToken>,
+}
+
+impl SdoBlockUpload {
+    pub const MAX_BLOCK_SIZE: u8 = 127;
+    pub const DEFAULT_MAX_SIZE: usize = DEFAULT_MAX_UPLOAD_SIZE;
+    const SEGMENT_DATA_SIZE: usize = 7;
+
+    // Client subcommands
+    const CS_INITIATE: u8 = 0;
+    const CS_END: u8 = 1;
+    const CS_ACK: u8 = 2;
+    const CS_START: u8 = 3;
+
+    pub fn new(node_id: NodeId, index: u16, sub_index: u8, block_size: u8) -> Result<Self> {
+        if !(1..=Self::MAX_BLOCK_SIZE).contains(&block_size) {
+            return Err(Error::InvalidSdoBlockSize(block_size));
+        }
+        Ok(Self {
+            node_id,
+            index,
+            sub_index,
+            block_size,
+            state: BlockUploadState::Initiating,
+            size: None,
+            max_size: Self::DEFAULT_MAX_SIZE,
+            crc: false,
+            ack_sequence: 0,
+            last_received: false,
+            data: std::vec::Vec::new(),
+            cancellation: None,
+        })
+    }
+
+    pub fn with_cancellation(self, cancellation: CancellationToken) -> Self {
+        Self {
+            cancellation: Some(cancellation),
+            ..self
+        }
+    }
+
+    // Limits the data accepted from the server, whether or not it indicates the size
+    pub fn with_max_size(self, max_size: usize) -> Self {
+        Self { max_size, ..self }
+    }
+
+    // The data received so far, e.g. the partial result of a cancelled transfer. Until the
+    // end of the transfer, it may include the padding of the last segment.
+    pub fn data(&self) -> &[u8] {
+        &self.data
+    }
+
+    pub fn abort_request(&self) -> SdoRequest {
+        SdoRequest::new_abort(
+            self.node_id,
+            self.index,
+            self.sub_index,
+            SdoAbortCode::GeneralError,
+        )
+    }
+
+    pub fn start(&mut self) -> SdoRequest {
+        self.state = BlockUploadState::Initiating;
+        self.size = None;
+        self.crc = false;
+        self.ack_sequence = 0;
+        self.last_received = false;
+        self.data.clear();
+        // Announces CRC support and disables the protocol switch (pst = 0).
+        SdoRequest::new(
+            self.node_id,
+            initiate_data(
+                (CCS_BLOCK_UPLOAD << 5) | 0b0100 | Self::CS_INITIATE,
+                self.index,
+                self.sub_index,
+                self.block_size.into(),
+            ),
+        )
+    }
+
+    // Feeds the data of a frame received on the TxSDO COB of the node.
+    pub fn on_response(&mut self, bytes: &[u8]) -> Result<SdoBlockUploadStep> {
+        check_cancellation(&self.cancellation)?;
+        let Some(&command) = bytes.first() else {
+            return Err(Error::InvalidDataLength {
+                length: bytes.len(),
+                data_type: "SdoResponse".to_owned(),
+            });
+        };
+        match self.state {
+            BlockUploadState::Initiating => {
+                check_response(bytes)?;
+                if command >> 5 != SCS_BLOCK_UPLOAD || command & 0b0001 != 0 {
+                    return Err(Error::InvalidServerCommandSpecifier(command >> 5));
+                }
+                check_multiplexer(bytes, self.index, self.sub_index)?;
+                self.crc = command & 0b0100 != 0;
+                if command & 0b0010 != 0 {
+                    let size = u32::from_le_bytes(bytes[4..8].try_into().unwrap()) as usize;
+                    if size > self.max_size {
+                        return Err(Error::SdoSizeLimitExceeded(self.max_size));
+                    }
+                    self.size = Some(size);
+                }
+                self.state = BlockUploadState::Uploading;
+                Ok(SdoBlockUploadStep::Send(self.request(Self::CS_START, 0, 0)))
+            }
+            BlockUploadState::Uploading => {
+                // Segments carry a sequence number instead of a command specifier,
+                // so an abort cannot be told apart from a segment here.
+                if bytes.len() != FRAME_DATA_SIZE {
+                    return Err(Error::InvalidDataLength {
+                        length: bytes.len(),
+                        data_type: "SdoResponse".to_owned(),
+                    });
+                }
+                let sequence = command & 0x7F;
+                let last = command & 0x80 != 0;
+                if sequence == self.ack_sequence + 1 {
+                    self.ack_sequence = sequence;
+                    self.data.extend_from_slice(&bytes[1..]);
+                    self.last_received = last;
+                    // The last segment may be padded, so the data may exceed the indicated
+                    // size by up to a segment, but not by more.
+                    if let Some(size) = self.size {
+                        if self.data.len() > Self::padded_length(size) {
+                            return Err(Error::SdoSizeMismatch {
+                                expected: size,
+                                actual: self.data.len(),
+                            });
+                        }
+                    }
+                    if self.data.len() > Self::padded_length(self.max_size) {
+                        return Err(Error::SdoSizeLimitExceeded(self.max_size));
+                    }
+                }
+                if !last && sequence < self.block_size {
+                    return Ok(SdoBlockUploadStep::Receive);
+                }
+                let ack = self.request(Self::CS_ACK, self.ack_sequence, self.block_size);
+                self.ack_sequence = 0;
+                if self.last_received {
+                    self.state = BlockUploadState::Ending;
+                }
+                Ok(SdoBlockUploadStep::Send(ack))
+            }
+            BlockUploadState::Ending => {
+                check_response(bytes)?;
+                if command >> 5 != SCS_BLOCK_UPLOAD || command & 0b0011 != 0b0001 {
+                    return Err(Error::InvalidServerCommandSpecifier(command >> 5));
+                }
+                let unused = ((command >> 2) & 0b111) as usize;
+                let length = self.data.len().saturating_sub(unused);
+                self.data.truncate(length);
+                if let Some(size) = self.size {
+                    if size != self.data.len() {
+                        return Err(Error::SdoSizeMismatch {
+                            expected: size,
+                            actual: self.data.len(),
+                        });
+                    }
+                }
+                if self.crc {
+                    let expected = u16::from_le_bytes([bytes[1], bytes[2]]);
+                    let actual = crc16(&self.data);
+                    if expected != actual {
+                        return Err(Error::SdoCrcMismatch { expected, actual });
+                    }
+                }
+                self.state = BlockUploadState::Finished;
+                Ok(SdoBlockUploadStep::Done(
+                    self.request(Self::CS_END, 0, 0),
+                    std::mem::take(&mut self.data),
+                ))
+            }
+            BlockUploadState::Finished => Err(Error::InvalidServerCommandSpecifier(command >> 5)),
+        }
+    }
+
+    // Length of `size` bytes of data including the padding of the last segment
+    fn padded_length(size: usize) -> usize {
+        size.div_ceil(Self::SEGMENT_DATA_SIZE) * Self::SEGMENT_DATA_SIZE
+    }
+
+    fn request(&self, subcommand: u8, byte1: u8, byte2: u8) -> SdoRequest {
+        let mut data = [0x00; FRAME_DATA_SIZE];
+        data[0] = (CCS_BLOCK_UPLOAD << 5) | subcommand;
+        data[1] = byte1;
+        data[2] = byte2;
+        SdoRequest::new(self.node_id, data)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    use crate::sdo::SdoAbortCode;
+
+    fn node_id() -> NodeId {
+        1.try_into().unwrap()
+    }
+
+    fn segment(sequence: u8, last: bool, chunk: &[u8]) -> [u8; 8] {
+        let mut segment = [0x00; 8];
+        segment[0] = sequence | ((last as u8) << 7);
+        segment[1..1 + chunk.len()].copy_from_slice(chunk);
+        segment
+    }
+
+    #[test]
+    fn test_new() {
+        assert!(SdoBlockUpload::new(node_id(), 0x1F50, 1, 127).is_ok());
+        assert_eq!(
+            SdoBlockUpload::new(node_id(), 0x1F50, 1, 0).unwrap_err(),
+            Error::InvalidSdoBlockSize(0)
+        );
+        assert_eq!(
+            SdoBlockUpload::new(node_id(), 0x1F50, 1, 128).unwrap_err(),
+            Error::InvalidSdoBlockSize(128)
+        );
+    }
+
+    #[test]
+    fn test_upload() {
+        let payload: std::vec::Vec<u8> = (0..20).collect();
+        let crc = crc16(&payload).to_le_bytes();
+        let mut upload = SdoBlockUpload::new(node_id(), 0x1F50, 1, 2).unwrap();
+        assert_eq!(
+            upload.start().data(),
+            &[0xA4, 0x50, 0x1F, 0x01, 0x02, 0x00, 0x00, 0x00]
+        );
+        assert_eq!(
+            upload.on_response(&[0xC6, 0x50, 0x1F, 0x01, 20, 0x00, 0x00, 0x00]),
+            Ok(SdoBlockUploadStep::Send(SdoRequest::new(
+                node_id(),
+                [0xA3, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00]
+            )))
+        );
+
+        // First block of 2 segments
+        assert_eq!(
+            upload.on_response(&segment(1, false, &payload[0..7])),
+            Ok(SdoBlockUploadStep::Receive)
+        );
+        assert_eq!(
+            upload.on_response(&segment(2, false, &payload[7..14])),
+            Ok(SdoBlockUploadStep::Send(SdoRequest::new(
+                node_id(),
+                [0xA2, 0x02, 0x02, 0x00, 0x00, 0x00, 0x00, 0x00]
+            )))
+        );
+        // Second block restarts the sequence numbers and ends the transfer.
+        assert_eq!(
+            upload.on_response(&segment(1, true, &payload[14..20])),
+            Ok(SdoBlockUploadStep::Send(SdoRequest::new(
+                node_id(),
+                [0xA2, 0x01, 0x02, 0x00, 0x00, 0x00, 0x00, 0x00]
+            )))
+        );
+        // 1 unused byte in the last segment
+        assert_eq!(
+            upload.on_response(&[0xC5, crc[0], crc[1], 0x00, 0x00, 0x00, 0x00, 0x00]),
+            Ok(SdoBlockUploadStep::Done(
+                SdoRequest::new(node_id(), [0xA1, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00]),
+                payload
+            ))
+        );
+    }
+
+    #[test]
+    fn test_retransmission() {
+        let payload: std::vec::Vec<u8> = (0..14).collect();
+        let mut upload = SdoBlockUpload::new(node_id(), 0x1F50, 1, 127).unwrap();
+        upload.start();
+        // No CRC, no size
+        upload
+            .on_response(&[0xC0, 0x50, 0x1F, 0x01, 0x00, 0x00, 0x00, 0x00])
+            .unwrap();
+
+        // The first segment is lost.
+        assert_eq!(
+            upload.on_response(&segment(2, true, &payload[7..14])),
+            Ok(SdoBlockUploadStep::Send(SdoRequest::new(
+                node_id(),
+                [0xA2, 0x00, 0x7F, 0x00, 0x00, 0x00, 0x00, 0x00]
+            )))
+        );
+        assert_eq!(
+            upload.on_response(&segment(1, false, &payload[0..7])),
+            Ok(SdoBlockUploadStep::Receive)
+        );
+        assert_eq!(
+            upload.on_response(&segment(2, true, &payload[7..14])),
+            Ok(SdoBlockUploadStep::Send(SdoRequest::new(
+                node_id(),
+                [0xA2, 0x02, 0x7F, 0x00, 0x00, 0x00, 0x00, 0x00]
+            )))
+        );
+        assert_eq!(
+            upload.on_response(&[0xC1, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00]),
+            Ok(SdoBlockUploadStep::Done(
+                SdoRequest::new(node_id(), [0xA1, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00]),
+                payload
+            ))
+        );
+    }
+
+    #[test]
+    fn test_size_exceeded() {
+        let mut upload = SdoBlockUpload::new(node_id(), 0x1F50, 1, 127).unwrap();
+        upload.start();
+        // 8 bytes indicated: 2 segments at most
+        upload
+            .on_response(&[0xC2, 0x50, 0x1F, 0x01, 0x08, 0x00, 0x00, 0x00])
+            .unwrap();
+        assert_eq!(
+            upload.on_response(&segment(1, false, &[0x00; 7])),
+            Ok(SdoBlockUploadStep::Receive)
+        );
+        assert_eq!(
+            upload.on_response(&segment(2, false, &[0x00; 7])),
+            Ok(SdoBlockUploadStep::Receive)
+        );
+        assert_eq!(
+            upload.on_response(&segment(3, false, &[0x00; 7])),
+            Err(Error::SdoSizeMismatch {
+                expected: 8,
+                actual: 21
+            })
+        );
+    }
+
+    #[test]
+    fn test_max_size() {
+        // Indicated size beyond the limit
+        let mut upload = SdoBlockUpload::new(node_id(), 0x1F50, 1, 127)
+            .unwrap()
+            .with_max_size(8);
+        upload.start();
+        assert_eq!(
+            upload.on_response(&[0xC2, 0x50, 0x1F, 0x01, 0x14, 0x00, 0x00, 0x00]),
+            Err(Error::SdoSizeLimitExceeded(8))
+        );
+
+        // No indicated size
+        let mut upload = SdoBlockUpload::new(node_id(), 0x1F50, 1, 127)
+            .unwrap()
+            .with_max_size(8);
+        upload.start();
+        upload
+            .on_response(&[0xC0, 0x50, 0x1F, 0x01, 0x00, 0x00, 0x00, 0x00])
+            .unwrap();
+        upload.on_response(&segment(1, false, &[0x00; 7])).unwrap();
+        upload.on_response(&segment(2, false, &[0x00; 7])).unwrap();
+        assert_eq!(
+            upload.on_response(&segment(3, false, &[0x00; 7])),
+            Err(Error::SdoSizeLimitExceeded(8))
+        );
+    }
+
+    #[test]
+    fn test_errors() {
+        let payload: std::vec::Vec<u8> = (0..7).collect();
+        let mut upload = SdoBlockUpload::new(node_id(), 0x1F50, 1, 127).unwrap();
+        upload.start();
+        upload
+            .on_response(&[0xC6, 0x50, 0x1F, 0x01, 0x07, 0x00, 0x00, 0x00])
+            .unwrap();
+        upload.on_response(&segment(1, true, &payload)).unwrap();
+        assert_eq!(
+            upload.on_response(&[0xC1, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00]),
+            Err(Error::SdoCrcMismatch {
+                expected: 0x0000,
+                actual: crc16(&payload)
+            })
+        );
+
+        let mut upload = SdoBlockUpload::new(node_id(), 0x1F50, 1, 127).unwrap();
+        upload.start();
+        assert_eq!(
+            upload.on_response(&[0x80, 0x50, 0x1F, 0x01, 0x00, 0x00, 0x04, 0x05]),
+            Err(Error::SdoAborted(SdoAbortCode::ProtocolTimedOut))
+        );
+        assert_eq!(
+            upload.on_response(&[0x41, 0x50, 0x1F, 0x01, 0x07, 0x00, 0x00, 0x00]),
+            Err(Error::InvalidServerCommandSpecifier(2))
+        );
+    }
+
+    #[test]
+    fn test_empty_response() {
+        let empty = Err(Error::InvalidDataLength {
+            length: 0,
+            data_type: "SdoResponse".to_owned(),
+        });
+        let mut upload = SdoBlockUpload::new(node_id(), 0x1F50, 1, 127).unwrap();
+        upload.start();
+        assert_eq!(upload.on_response(&[]), empty);
+        upload
+            .on_response(&[0xC2, 0x50, 0x1F, 0x01, 0x07, 0x00, 0x00, 0x00])
+            .unwrap();
+        assert_eq!(upload.on_response(&[]), empty);
+        upload.on_response(&segment(1, true, &[0x01; 7])).unwrap();
+        assert_eq!(upload.on_response(&[]), empty);
+        upload
+            .on_response(&[0xC1, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00])
+            .unwrap();
+        // Finished
+        assert_eq!(upload.on_response(&[]), empty);
+    }
+}
@@ -0,0 +1,276 @@
+use crate::cancel::CancellationToken;
+use crate::error::{Error, Result};
+use crate::id::NodeId;
+use crate::sdo::{
+    check_cancellation, check_multiplexer, check_response, initiate_data, SdoAbortCode, SdoRequest,
+    SdoValue, CCS_DOWNLOAD_SEGMENT, CCS_INITIATE_DOWNLOAD, FRAME_DATA_SIZE, SCS_DOWNLOAD_SEGMENT,
+    SCS_INITIATE_DOWNLOAD,
+};
+
+#[derive(Clone, Debug, PartialEq)]
+pub enum SdoDownloadStep {
+    Send(SdoRequest),
+    Done,
+}
+
+// Client side of an SDO download, expedited up to 4 bytes and segmented otherwise
+// (cf. CiA 301).
+#[derive(Clone, Debug)]
+pub struct SdoDownload {
+    node_id: NodeId,
+    index: u16,
+    sub_index: u8,
+    data: std::vec::Vec<u8>,
+    offset: usize,
+    toggle: bool,
+    segmented: bool,
+    cancellation: Option<CancellationToken>,
+}
+
+impl SdoDownload {
+    const EXPEDITED_DATA_SIZE: usize = 4;
+    const SEGMENT_DATA_SIZE: usize = 7;
+
+    pub fn new(node_id: NodeId, index: u16, sub_index: u8, data: std::vec::Vec<u8>) -> Self {
+        Self {
+            node_id,
+            index,
+            sub_index,
+            data,
+            offset: 0,
+            toggle: false,
+            segmented: false,
+            cancellation: None,
+        }
+    }
+
+    pub fn new_as<T: SdoValue>(node_id: NodeId, index: u16, sub_index: u8, value: &T) -> Self {
+        Self::new(node_id, index, sub_index, value.to_sdo_bytes())
+    }
+
+    pub fn with_cancellation(self, cancellation: CancellationToken) -> Self {
+        Self {
+            cancellation: Some(cancellation),
+            ..self
+        }
+    }
+
+    // Number of bytes sent so far, e.g. to report the progress of a cancelled transfer
+    pub fn transferred(&self) -> usize {
+        self.offset.min(self.data.len())
+    }
+
+    pub fn abort_request(&self) -> SdoRequest {
+        SdoRequest::new_abort(
+            self.node_id,
+            self.index,
+            self.sub_index,
+            SdoAbortCode::GeneralError,
+        )
+    }
+
+    pub fn start(&mut self) -> SdoRequest {
+        self.offset = 0;
+        self.toggle = false;
+        self.segmented = false;
+        let size = self.data.len();
+        if (1..=Self::EXPEDITED_DATA_SIZE).contains(&size) {
+            let mut value = [0x00; 4];
+            value[..size].copy_from_slice(&self.data);
+            let command = (CCS_INITIATE_DOWNLOAD << 5)
+                | (((Self::EXPEDITED_DATA_SIZE - size) as u8) << 2)
+                | 0b0011;
+            SdoRequest::new(
+                self.node_id,
+                initiate_data(
+                    command,
+                    self.index,
+                    self.sub_index,
+                    u32::from_le_bytes(value),
+                ),
+            )
+        } else {
+            self.segmented = true;
+            SdoRequest::new(
+                self.node_id,
+                initiate_data(
+                    (CCS_INITIATE_DOWNLOAD << 5) | 0b0001,
+                    self.index,
+                    self.sub_index,
+                    size as u32,
+                ),
+            )
+        }
+    }
+
+    // Feeds the data of a frame received on the TxSDO COB of the node.
+    pub fn on_response(&mut self, bytes: &[u8]) -> Result<SdoDownloadStep> {
+        check_cancellation(&self.cancellation)?;
+        check_response(bytes)?;
+        let command = bytes[0];
+        match (self.offset, command >> 5) {
+            (0, SCS_INITIATE_DOWNLOAD) => {
+                check_multiplexer(bytes, self.index, self.sub_index)?;
+                if !self.segmented {
+                    return Ok(SdoDownloadStep::Done);
+                }
+                Ok(SdoDownloadStep::Send(self.next_segment()))
+            }
+            (_, SCS_DOWNLOAD_SEGMENT) if self.segmented && self.offset > 0 => {
+                if (command & 0b1_0000 != 0) != self.toggle {
+                    return Err(Error::SdoToggleBitMismatch);
+                }
+                if self.offset >= self.data.len() {
+                    return Ok(SdoDownloadStep::Done);
+                }
+                self.toggle = !self.toggle;
+                Ok(SdoDownloadStep::Send(self.next_segment()))
+            }
+            (_, scs) => Err(Error::InvalidServerCommandSpecifier(scs)),
+        }
+    }
+
+    fn next_segment(&mut self) -> SdoRequest {
+        let end = (self.offset + Self::SEGMENT_DATA_SIZE).min(self.data.len());
+        let segment = &self.data[self.offset..end];
+        let last = end == self.data.len();
+        let mut data = [0x00; FRAME_DATA_SIZE];
+        data[0] = (CCS_DOWNLOAD_SEGMENT << 5)
+            | ((self.toggle as u8) << 4)
+            | (((Self::SEGMENT_DATA_SIZE - segment.len()) as u8) << 1)
+            | last as u8;
+        data[1..1 + segment.len()].copy_from_slice(segment);
+        // Marks the empty transfer as started as well.
+        self.offset = end.max(1);
+        SdoRequest::new(self.node_id, data)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    use crate::sdo::SdoAbortCode;
+
+    fn node_id() -> NodeId {
+        1.try_into().unwrap()
+    }
+
+    #[test]
+    fn test_expedited() {
+        let mut download = SdoDownload::new(node_id(), 0x1017, 0, 1000u16.to_le_bytes().into());
+        assert_eq!(
+            download.start().data(),
+            &[0x2B, 0x17, 0x10, 0x00, 0xE8, 0x03, 0x00, 0x00]
+        );
+        assert_eq!(
+            download.on_response(&[0x60, 0x17, 0x10, 0x00, 0x00, 0x00, 0x00, 0x00]),
+            Ok(SdoDownloadStep::Done)
+        );
+
+        let mut download = SdoDownload::new(node_id(), 0x1200, 1, vec![0x0A, 0x06, 0x00, 0x00]);
+        assert_eq!(
+            download.start().data(),
+            &[0x23, 0x00, 0x12, 0x01, 0x0A, 0x06, 0x00, 0x00]
+        );
+        assert_eq!(
+            download.on_response(&[0x60, 0x00, 0x12, 0x02, 0x00, 0x00, 0x00, 0x00]),
+            Err(Error::SdoMultiplexerMismatch {
+                index: 0x1200,
+                sub_index: 1
+            })
+        );
+    }
+
+    #[test]
+    fn test_new_as() {
+        let mut download = SdoDownload::new_as(node_id(), 0x1017, 0, &1000u16);
+        assert_eq!(
+            download.start().data(),
+            &[0x2B, 0x17, 0x10, 0x00, 0xE8, 0x03, 0x00, 0x00]
+        );
+
+        let mut download = SdoDownload::new_as(node_id(), 0x2000, 0, &-1.5f32);
+        assert_eq!(
+            download.start().data(),
+            &[0x23, 0x00, 0x20, 0x00, 0x00, 0x00, 0xC0, 0xBF]
+        );
+
+        let mut download = SdoDownload::new_as(node_id(), 0x1008, 0, &"Motor".to_owned());
+        assert_eq!(
+            download.start().data(),
+            &[0x21, 0x08, 0x10, 0x00, 0x05, 0x00, 0x00, 0x00]
+        );
+    }
+
+    #[test]
+    fn test_segmented() {
+        let mut download = SdoDownload::new(node_id(), 0x1008, 0, b"Motor driver".to_vec());
+        assert_eq!(
+            download.start().data(),
+            &[0x21, 0x08, 0x10, 0x00, 0x0C, 0x00, 0x00, 0x00]
+        );
+        assert_eq!(
+            download.on_response(&[0x60, 0x08, 0x10, 0x00, 0x00, 0x00, 0x00, 0x00]),
+            Ok(SdoDownloadStep::Send(SdoRequest::new(
+                node_id(),
+                [0x00, b'M', b'o', b't', b'o', b'r', b' ', b'd']
+            )))
+        );
+        assert_eq!(
+            download.on_response(&[0x20, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00]),
+            Ok(SdoDownloadStep::Send(SdoRequest::new(
+                node_id(),
+                [0x15, b'r', b'i', b'v', b'e', b'r', 0x00, 0x00]
+            )))
+        );
+        assert_eq!(
+            download.on_response(&[0x30, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00]),
+            Ok(SdoDownloadStep::Done)
+        );
+    }
+
+    #[test]
+    fn test_empty() {
+        let mut download = SdoDownload::new(node_id(), 0x2000, 0, vec![]);
+        assert_eq!(
+            download.start().data(),
+            &[0x21, 0x00, 0x20, 0x00, 0x00, 0x00, 0x00, 0x00]
+        );
+        assert_eq!(
+            download.on_response(&[0x60, 0x00, 0x20, 0x00, 0x00, 0x00, 0x00, 0x00]),
+            Ok(SdoDownloadStep::Send(SdoRequest::new(
+                node_id(),
+                [0x0F, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00]
+            )))
+        );
+        assert_eq!(
+            download.on_response(&[0x20, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00]),
+            Ok(SdoDownloadStep::Done)
+        );
+    }
+
+    #[test]
+    fn test_errors() {
+        let mut download = SdoDownload::new(node_id(), 0x1008, 0, b"Motor driver".to_vec());
+        download.start();
+        download
+            .on_response(&[0x60, 0x08, 0x10, 0x00, 0x00, 0x00, 0x00, 0x00])
+            .unwrap();
+        assert_eq!(
+            download.on_response(&[0x30, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00]),
+            Err(Error::SdoToggleBitMismatch)
+        );
+
+        let mut download = SdoDownload::new(node_id(), 0x1008, 0, b"Motor driver".to_vec());
+        download.start();
+        assert_eq!(
+            download.on_response(&[0x20, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00]),
+            Err(Error::InvalidServerCommandSpecifier(1))
+        );
+        assert_eq!(
+            download.on_response(&[0x80, 0x08, 0x10, 0x00, 0x02, 0x00, 0x01, 0x06]),
+            Err(Error::SdoAborted(SdoAbortCode::ReadOnly))
+        );
+    }
+}
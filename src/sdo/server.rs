@@ -0,0 +1,430 @@
+use crate::error::Error;
+use crate::id::NodeId;
+use crate::od::ObjectDictionary;
+use crate::sdo::{
+    initiate_data, transfer_buffer, SdoAbortCode, SdoResponse, CCS_ABORT_TRANSFER,
+    CCS_DOWNLOAD_SEGMENT, CCS_INITIATE_DOWNLOAD, CCS_INITIATE_UPLOAD, CCS_UPLOAD_SEGMENT,
+    FRAME_DATA_SIZE, SCS_DOWNLOAD_SEGMENT, SCS_INITIATE_DOWNLOAD, SCS_INITIATE_UPLOAD,
+    SCS_UPLOAD_SEGMENT,
+};
+
+#[derive(Clone, Debug, PartialEq)]
+enum Transfer {
+    Idle,
+    Upload {
+        index: u16,
+        sub_index: u8,
+        data: std::vec::Vec<u8>,
+        offset: usize,
+        toggle: bool,
+    },
+    Download {
+        index: u16,
+        sub_index: u8,
+        size: Option<usize>,
+        data: std::vec::Vec<u8>,
+        toggle: bool,
+    },
+}
+
+// Server side of expedited and segmented SDO transfers on an object dictionary
+// (cf. CiA 301). Block transfers are aborted.
+#[derive(Clone, Debug)]
+pub struct SdoServer {
+    node_id: NodeId,
+    transfer: Transfer,
+}
+
+impl SdoServer {
+    const EXPEDITED_DATA_SIZE: usize = 4;
+    const SEGMENT_DATA_SIZE: usize = 7;
+    // Upper bound of a segmented download, e.g. of a domain whose size is not indicated
+    const MAX_DOWNLOAD_SIZE: usize = 0x10_0000;
+
+    pub fn new(node_id: NodeId) -> Self {
+        Self {
+            node_id,
+            transfer: Transfer::Idle,
+        }
+    }
+
+    // Feeds the data of a frame received on the RxSDO COB of the node. Returns the response to
+    // transmit, if any.
+    pub fn on_request(&mut self, od: &mut ObjectDictionary, bytes: &[u8]) -> Option<SdoResponse> {
+        if bytes.len() != FRAME_DATA_SIZE {
+            return None;
+        }
+        let command = bytes[0];
+        let index = u16::from_le_bytes([bytes[1], bytes[2]]);
+        let sub_index = bytes[3];
+        match command >> 5 {
+            CCS_ABORT_TRANSFER => {
+                self.transfer = Transfer::Idle;
+                None
+            }
+            CCS_INITIATE_UPLOAD => Some(self.initiate_upload(od, index, sub_index)),
+            CCS_UPLOAD_SEGMENT => Some(self.upload_segment(command)),
+            CCS_INITIATE_DOWNLOAD => Some(self.initiate_download(od, bytes, index, sub_index)),
+            CCS_DOWNLOAD_SEGMENT => Some(self.download_segment(od, bytes)),
+            _ => Some(self.abort(index, sub_index, SdoAbortCode::InvalidCommandSpecifier)),
+        }
+    }
+
+    fn initiate_upload(&mut self, od: &ObjectDictionary, index: u16, sub_index: u8) -> SdoResponse {
+        let data = match od.read(index, sub_index) {
+            Ok(data) => data,
+            Err(error) => return self.abort(index, sub_index, abort_code(error)),
+        };
+        if (1..=Self::EXPEDITED_DATA_SIZE).contains(&data.len()) {
+            self.transfer = Transfer::Idle;
+            let mut value = [0x00; 4];
+            value[..data.len()].copy_from_slice(&data);
+            let command = (SCS_INITIATE_UPLOAD << 5)
+                | (((Self::EXPEDITED_DATA_SIZE - data.len()) as u8) << 2)
+                | 0b0011;
+            return SdoResponse::new(
+                self.node_id,
+                initiate_data(command, index, sub_index, u32::from_le_bytes(value)),
+            );
+        }
+        let size = data.len() as u32;
+        self.transfer = Transfer::Upload {
+            index,
+            sub_index,
+            data,
+            offset: 0,
+            toggle: false,
+        };
+        SdoResponse::new(
+            self.node_id,
+            initiate_data((SCS_INITIATE_UPLOAD << 5) | 0b0001, index, sub_index, size),
+        )
+    }
+
+    fn upload_segment(&mut self, command: u8) -> SdoResponse {
+        let Transfer::Upload {
+            index,
+            sub_index,
+            data,
+            offset,
+            toggle,
+        } = &mut self.transfer
+        else {
+            return self.abort(0, 0, SdoAbortCode::InvalidCommandSpecifier);
+        };
+        if (command & 0b1_0000 != 0) != *toggle {
+            let (index, sub_index) = (*index, *sub_index);
+            return self.abort(index, sub_index, SdoAbortCode::ToggleBitNotAlternated);
+        }
+        let end = (*offset + Self::SEGMENT_DATA_SIZE).min(data.len());
+        let segment = &data[*offset..end];
+        let last = end == data.len();
+        let mut response = [0x00; FRAME_DATA_SIZE];
+        response[0] = (SCS_UPLOAD_SEGMENT << 5)
+            | ((*toggle as u8) << 4)
+            | (((Self::SEGMENT_DATA_SIZE - segment.len()) as u8) << 1)
+            | last as u8;
+        response[1..1 + segment.len()].copy_from_slice(segment);
+        *offset = end;
+        *toggle = !*toggle;
+        if last {
+            self.transfer = Transfer::Idle;
+        }
+        SdoResponse::new(self.node_id, response)
+    }
+
+    fn initiate_download(
+        &mut self,
+        od: &mut ObjectDictionary,
+        bytes: &[u8],
+        index: u16,
+        sub_index: u8,
+    ) -> SdoResponse {
+        let command = bytes[0];
+        let expedited = command & 0b0010 != 0;
+        let size_indicated = command & 0b0001 != 0;
+        if !expedited {
+            let size = size_indicated
+                .then(|| u32::from_le_bytes(bytes[4..8].try_into().unwrap()) as usize);
+            self.transfer = Transfer::Download {
+                index,
+                sub_index,
+                size,
+                data: transfer_buffer(size),
+                toggle: false,
+            };
+            return self.download_acknowledge(index, sub_index);
+        }
+        self.transfer = Transfer::Idle;
+        let length = if size_indicated {
+            Self::EXPEDITED_DATA_SIZE - ((command >> 2) & 0b11) as usize
+        } else {
+            // Without a size, the data type of the object tells the length.
+            od.variable(index, sub_index)
+                .ok()
+                .and_then(|variable| variable.data_type().size())
+                .unwrap_or(Self::EXPEDITED_DATA_SIZE)
+                .min(Self::EXPEDITED_DATA_SIZE)
+        };
+        match od.write(index, sub_index, &bytes[4..4 + length]) {
+            Ok(()) => self.download_acknowledge(index, sub_index),
+            Err(error) => self.abort(index, sub_index, abort_code(error)),
+        }
+    }
+
+    fn download_segment(&mut self, od: &mut ObjectDictionary, bytes: &[u8]) -> SdoResponse {
+        let command = bytes[0];
+        let Transfer::Download {
+            index,
+            sub_index,
+            size,
+            data,
+            toggle,
+        } = &mut self.transfer
+        else {
+            return self.abort(0, 0, SdoAbortCode::InvalidCommandSpecifier);
+        };
+        let (index, sub_index) = (*index, *sub_index);
+        if (command & 0b1_0000 != 0) != *toggle {
+            return self.abort(index, sub_index, SdoAbortCode::ToggleBitNotAlternated);
+        }
+        let unused = ((command >> 1) & 0b111) as usize;
+        data.extend_from_slice(&bytes[1..FRAME_DATA_SIZE - unused]);
+        // Without an indicated size, a fixed-size data type of the object tells the limit.
+        let max_size = size
+            .or_else(|| {
+                od.variable(index, sub_index)
+                    .ok()
+                    .and_then(|variable| variable.data_type().size())
+            })
+            .unwrap_or(Self::MAX_DOWNLOAD_SIZE)
+            .min(Self::MAX_DOWNLOAD_SIZE);
+        if data.len() > max_size {
+            return self.abort(index, sub_index, SdoAbortCode::DataTypeLengthTooHigh);
+        }
+        let mut response = [0x00; FRAME_DATA_SIZE];
+        response[0] = (SCS_DOWNLOAD_SEGMENT << 5) | ((*toggle as u8) << 4);
+        *toggle = !*toggle;
+        if command & 0b0001 == 0 {
+            return SdoResponse::new(self.node_id, response);
+        }
+        let data = std::mem::take(data);
+        let size = *size;
+        self.transfer = Transfer::Idle;
+        if size.is_some_and(|size| size != data.len()) {
+            return self.abort(index, sub_index, SdoAbortCode::DataTypeMismatch);
+        }
+        match od.write(index, sub_index, &data) {
+            Ok(()) => SdoResponse::new(self.node_id, response),
+            Err(error) => self.abort(index, sub_index, abort_code(error)),
+        }
+    }
+
+    fn download_acknowledge(&self, index: u16, sub_index: u8) -> SdoResponse {
+        SdoResponse::new(
+            self.node_id,
+            initiate_data(SCS_INITIATE_DOWNLOAD << 5, index, sub_index, 0),
+        )
+    }
+
+    fn abort(&mut self, index: u16, sub_index: u8, abort_code: SdoAbortCode) -> SdoResponse {
+        self.transfer = Transfer::Idle;
+        SdoResponse::new_abort(self.node_id, index, sub_index, abort_code)
+    }
+}
+
+fn abort_code(error: Error) -> SdoAbortCode {
+    match error {
+        Error::ObjectAccessFailed(abort_code) => abort_code,
+        _ => SdoAbortCode::GeneralError,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    use crate::frame::ConvertibleFrame;
+    use crate::id::CommunicationObject;
+    use crate::od::{AccessType, Object, Value, Variable};
+    use crate::sdo::{SdoDownload, SdoDownloadStep, SdoUpload, SdoUploadStep};
+
+    fn node_id() -> NodeId {
+        2.try_into().unwrap()
+    }
+
+    fn dictionary() -> ObjectDictionary {
+        let mut od = ObjectDictionary::new();
+        od.insert(Object::new_var(
+            0x1008,
+            Variable::new(
+                "Device name",
+                AccessType::Constant,
+                Value::VisibleString("Motor controller".to_owned()),
+            ),
+        ));
+        od.insert(Object::new_var(
+            0x1017,
+            Variable::new(
+                "Producer heartbeat time",
+                AccessType::ReadWrite,
+                Value::Unsigned16(0),
+            ),
+        ));
+        od.insert(Object::new_var(
+            0x2000,
+            Variable::new(
+                "Label",
+                AccessType::ReadWrite,
+                Value::VisibleString("".to_owned()),
+            ),
+        ));
+        od
+    }
+
+    #[test]
+    fn test_expedited_upload() {
+        let mut od = dictionary();
+        let mut server = SdoServer::new(node_id());
+        let response = server
+            .on_request(&mut od, &[0x40, 0x17, 0x10, 0x00, 0, 0, 0, 0])
+            .unwrap();
+        assert_eq!(
+            response.data(),
+            &[0x4B, 0x17, 0x10, 0x00, 0x00, 0x00, 0x00, 0x00]
+        );
+        assert_eq!(
+            response.communication_object(),
+            CommunicationObject::TxSdo(node_id())
+        );
+    }
+
+    #[test]
+    fn test_segmented_upload() {
+        let mut od = dictionary();
+        let mut server = SdoServer::new(node_id());
+        let mut upload = SdoUpload::new(node_id(), 0x1008, 0);
+        let mut request = upload.start();
+        loop {
+            let response = server.on_request(&mut od, request.data()).unwrap();
+            match upload.on_response(response.data()).unwrap() {
+                SdoUploadStep::Send(next) => request = next,
+                SdoUploadStep::Done(data) => {
+                    assert_eq!(data, b"Motor controller");
+                    break;
+                }
+            }
+        }
+    }
+
+    #[test]
+    fn test_download() {
+        let mut od = dictionary();
+        let mut server = SdoServer::new(node_id());
+        for (index, data) in [
+            (0x1017, 500u16.to_le_bytes().to_vec()),
+            (0x2000, b"A longer label".to_vec()),
+        ] {
+            let mut download = SdoDownload::new(node_id(), index, 0, data.clone());
+            let mut request = download.start();
+            loop {
+                let response = server.on_request(&mut od, request.data()).unwrap();
+                match download.on_response(response.data()).unwrap() {
+                    SdoDownloadStep::Send(next) => request = next,
+                    SdoDownloadStep::Done => break,
+                }
+            }
+            assert_eq!(od.read(index, 0), Ok(data));
+        }
+    }
+
+    #[test]
+    fn test_expedited_download_without_size() {
+        let mut od = dictionary();
+        let mut server = SdoServer::new(node_id());
+        let response = server
+            .on_request(&mut od, &[0x22, 0x17, 0x10, 0x00, 0xE8, 0x03, 0x00, 0x00])
+            .unwrap();
+        assert_eq!(response.data()[0], 0x60);
+        assert_eq!(od.read(0x1017, 0), Ok(vec![0xE8, 0x03]));
+    }
+
+    #[test]
+    fn test_abort() {
+        let mut od = dictionary();
+        let mut server = SdoServer::new(node_id());
+        assert_eq!(
+            server
+                .on_request(&mut od, &[0x40, 0x00, 0x10, 0x00, 0, 0, 0, 0])
+                .unwrap(),
+            SdoResponse::new_abort(node_id(), 0x1000, 0, SdoAbortCode::ObjectDoesNotExist)
+        );
+        assert_eq!(
+            server
+                .on_request(&mut od, &[0x2F, 0x08, 0x10, 0x00, 0x01, 0, 0, 0])
+                .unwrap(),
+            SdoResponse::new_abort(node_id(), 0x1008, 0, SdoAbortCode::ReadOnly)
+        );
+        assert_eq!(
+            server
+                .on_request(&mut od, &[0x60, 0, 0, 0, 0, 0, 0, 0])
+                .unwrap(),
+            SdoResponse::new_abort(node_id(), 0, 0, SdoAbortCode::InvalidCommandSpecifier)
+        );
+        assert_eq!(
+            server
+                .on_request(&mut od, &[0xA4, 0x08, 0x10, 0x00, 0, 0, 0, 0])
+                .unwrap(),
+            SdoResponse::new_abort(node_id(), 0x1008, 0, SdoAbortCode::InvalidCommandSpecifier)
+        );
+        assert_eq!(
+            server.on_request(&mut od, &[0x80, 0x08, 0x10, 0x00, 0, 0, 0, 0]),
+            None
+        );
+        assert_eq!(server.on_request(&mut od, &[0x40, 0x08]), None);
+    }
+
+    #[test]
+    fn test_download_too_long() {
+        let mut od = dictionary();
+        let mut server = SdoServer::new(node_id());
+        // 8 bytes indicated
+        server.on_request(&mut od, &[0x21, 0x00, 0x20, 0x00, 0x08, 0, 0, 0]);
+        assert_eq!(
+            server.on_request(&mut od, &[0x00, b'L', b'a', b'b', b'e', b'l', b' ', b'o']),
+            Some(SdoResponse::new(
+                node_id(),
+                [0x20, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00]
+            ))
+        );
+        assert_eq!(
+            server
+                .on_request(&mut od, &[0x10, b'f', b' ', b'm', b'o', b't', b'o', b'r'])
+                .unwrap(),
+            SdoResponse::new_abort(node_id(), 0x2000, 0, SdoAbortCode::DataTypeLengthTooHigh)
+        );
+
+        // No size indicated: limited by the data type of the object
+        server.on_request(&mut od, &[0x20, 0x17, 0x10, 0x00, 0, 0, 0, 0]);
+        assert_eq!(
+            server
+                .on_request(&mut od, &[0x00, 0x01, 0x02, 0x03, 0x04, 0x05, 0x06, 0x07])
+                .unwrap(),
+            SdoResponse::new_abort(node_id(), 0x1017, 0, SdoAbortCode::DataTypeLengthTooHigh)
+        );
+        assert_eq!(od.read(0x1017, 0), Ok(vec![0x00, 0x00]));
+    }
+
+    #[test]
+    fn test_toggle_mismatch() {
+        let mut od = dictionary();
+        let mut server = SdoServer::new(node_id());
+        server.on_request(&mut od, &[0x40, 0x08, 0x10, 0x00, 0, 0, 0, 0]);
+        assert_eq!(
+            server
+                .on_request(&mut od, &[0x70, 0, 0, 0, 0, 0, 0, 0])
+                .unwrap(),
+            SdoResponse::new_abort(node_id(), 0x1008, 0, SdoAbortCode::ToggleBitNotAlternated)
+        );
+    }
+}
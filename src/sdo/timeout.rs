@@ -0,0 +1,171 @@
+use crate::error::{Error, Result};
+use crate::id::NodeId;
+use crate::sdo::{SdoAbortCode, SdoRequest};
+
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub struct SdoTimeoutPolicy {
+    pub timeout: std::time::Duration,
+    pub retries: u32,
+}
+
+impl Default for SdoTimeoutPolicy {
+    fn default() -> Self {
+        Self::new(std::time::Duration::from_secs(1), 0)
+    }
+}
+
+impl SdoTimeoutPolicy {
+    pub fn new(timeout: std::time::Duration, retries: u32) -> Self {
+        Self { timeout, retries }
+    }
+}
+
+// Tracks the deadline of the pending request of an SDO transfer. The caller owns the clock
+// and the bus: it calls `restart` whenever a request is sent, and `on_expired` once the
+// deadline has passed. `Ok(())` means the transfer should be restarted from its `start`.
+#[derive(Clone, Debug)]
+pub struct SdoDeadline {
+    policy: SdoTimeoutPolicy,
+    node_id: NodeId,
+    index: u16,
+    sub_index: u8,
+    sent_at: std::time::Instant,
+    retries: u32,
+}
+
+impl SdoDeadline {
+    pub fn new(
+        policy: SdoTimeoutPolicy,
+        node_id: NodeId,
+        index: u16,
+        sub_index: u8,
+        now: std::time::Instant,
+    ) -> Self {
+        Self {
+            policy,
+            node_id,
+            index,
+            sub_index,
+            sent_at: now,
+            retries: 0,
+        }
+    }
+
+    pub fn restart(&mut self, now: std::time::Instant) {
+        self.sent_at = now;
+    }
+
+    pub fn deadline(&self) -> std::time::Instant {
+        self.sent_at + self.policy.timeout
+    }
+
+    pub fn is_expired(&self, now: std::time::Instant) -> bool {
+        now >= self.deadline()
+    }
+
+    pub fn remaining(&self, now: std::time::Instant) -> std::time::Duration {
+        self.deadline().saturating_duration_since(now)
+    }
+
+    pub fn retries(&self) -> u32 {
+        self.retries
+    }
+
+    pub fn on_expired(&mut self, now: std::time::Instant) -> Result<()> {
+        if self.retries >= self.policy.retries {
+            return Err(Error::SdoTimeout {
+                node_id: self.node_id,
+                index: self.index,
+                sub_index: self.sub_index,
+            });
+        }
+        self.retries += 1;
+        self.sent_at = now;
+        Ok(())
+    }
+
+    // The request to tell the server that the transfer was given up
+    pub fn abort_request(&self) -> SdoRequest {
+        SdoRequest::new_abort(
+            self.node_id,
+            self.index,
+            self.sub_index,
+            SdoAbortCode::ProtocolTimedOut,
+        )
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn node_id() -> NodeId {
+        4.try_into().unwrap()
+    }
+
+    #[test]
+    fn test_deadline() {
+        let now = std::time::Instant::now();
+        let mut deadline = SdoDeadline::new(
+            SdoTimeoutPolicy::new(std::time::Duration::from_millis(100), 0),
+            node_id(),
+            0x1018,
+            1,
+            now,
+        );
+        assert!(!deadline.is_expired(now));
+        assert_eq!(
+            deadline.remaining(now + std::time::Duration::from_millis(40)),
+            std::time::Duration::from_millis(60)
+        );
+        assert!(deadline.is_expired(now + std::time::Duration::from_millis(100)));
+        assert_eq!(
+            deadline.remaining(now + std::time::Duration::from_millis(150)),
+            std::time::Duration::ZERO
+        );
+
+        deadline.restart(now + std::time::Duration::from_millis(80));
+        assert!(!deadline.is_expired(now + std::time::Duration::from_millis(150)));
+    }
+
+    #[test]
+    fn test_on_expired() {
+        let now = std::time::Instant::now();
+        let mut deadline = SdoDeadline::new(
+            SdoTimeoutPolicy::new(std::time::Duration::from_millis(100), 2),
+            node_id(),
+            0x1018,
+            1,
+            now,
+        );
+        let later = now + std::time::Duration::from_millis(100);
+        assert_eq!(deadline.on_expired(later), Ok(()));
+        assert_eq!(deadline.retries(), 1);
+        assert!(!deadline.is_expired(later));
+        assert_eq!(deadline.on_expired(later), Ok(()));
+        assert_eq!(
+            deadline.on_expired(later),
+            Err(Error::SdoTimeout {
+                node_id: node_id(),
+                index: 0x1018,
+                sub_index: 1
+            })
+        );
+        assert_eq!(deadline.retries(), 2);
+    }
+
+    #[test]
+    fn test_abort_request() {
+        let deadline = SdoDeadline::new(
+            SdoTimeoutPolicy::default(),
+            node_id(),
+            0x1018,
+            1,
+            std::time::Instant::now(),
+        );
+        assert_eq!(
+            deadline.abort_request().data(),
+            &[0x80, 0x18, 0x10, 0x01, 0x00, 0x00, 0x04, 0x05]
+        );
+    }
+}
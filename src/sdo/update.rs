@@ -0,0 +1,197 @@
+use crate::cancel::CancellationToken;
+use crate::error::Result;
+use crate::id::NodeId;
+use crate::sdo::{SdoDownload, SdoDownloadStep, SdoRequest, SdoUpload, SdoUploadStep, SdoValue};
+
+#[derive(Clone, Debug, PartialEq)]
+pub enum SdoUpdateStep<T> {
+    Send(SdoRequest),
+    // The value read and the value written
+    Done { old: T, new: T },
+}
+
+#[derive(Clone, Debug)]
+enum Phase {
+    Upload(SdoUpload),
+    Download(SdoDownload),
+}
+
+// Read-modify-write of an object, e.g. a controlword or a configuration register with reserved
+// bits: uploads the value, applies `update` and downloads the result. The write immediately
+// follows the read on the SDO channel of the node, so no other transfer of the caller can
+// interleave as long as it runs one transfer per node at a time.
+#[derive(Clone)]
+pub struct SdoUpdate<T, F> {
+    node_id: NodeId,
+    index: u16,
+    sub_index: u8,
+    update: F,
+    phase: Phase,
+    values: Option<(T, T)>,
+    cancellation: Option<CancellationToken>,
+}
+
+impl<T: SdoValue + Clone, F: FnMut(T) -> T> SdoUpdate<T, F> {
+    pub fn new(node_id: NodeId, index: u16, sub_index: u8, update: F) -> Self {
+        Self {
+            node_id,
+            index,
+            sub_index,
+            update,
+            phase: Phase::Upload(SdoUpload::new(node_id, index, sub_index)),
+            values: None,
+            cancellation: None,
+        }
+    }
+
+    pub fn with_cancellation(self, cancellation: CancellationToken) -> Self {
+        let phase = match self.phase {
+            Phase::Upload(upload) => Phase::Upload(upload.with_cancellation(cancellation.clone())),
+            Phase::Download(download) => {
+                Phase::Download(download.with_cancellation(cancellation.clone()))
+            }
+        };
+        Self {
+            phase,
+            cancellation: Some(cancellation),
+            ..self
+        }
+    }
+
+    pub fn abort_request(&self) -> SdoRequest {
+        match &self.phase {
+            Phase::Upload(upload) => upload.abort_request(),
+            Phase::Download(download) => download.abort_request(),
+        }
+    }
+
+    // True once the value has been read and the updated value is being written
+    pub fn is_writing(&self) -> bool {
+        matches!(self.phase, Phase::Download(_))
+    }
+
+    pub fn start(&mut self) -> SdoRequest {
+        let mut upload = SdoUpload::new(self.node_id, self.index, self.sub_index);
+        if let Some(cancellation) = &self.cancellation {
+            upload = upload.with_cancellation(cancellation.clone());
+        }
+        self.values = None;
+        let request = upload.start();
+        self.phase = Phase::Upload(upload);
+        request
+    }
+
+    // Feeds the data of a frame received on the TxSDO COB of the node.
+    pub fn on_response(&mut self, bytes: &[u8]) -> Result<SdoUpdateStep<T>> {
+        match &mut self.phase {
+            Phase::Upload(upload) => match upload.on_response(bytes)? {
+                SdoUploadStep::Send(request) => Ok(SdoUpdateStep::Send(request)),
+                SdoUploadStep::Done(data) => {
+                    let old = T::from_sdo_bytes(&data)?;
+                    let new = (self.update)(old.clone());
+                    let mut download =
+                        SdoDownload::new_as(self.node_id, self.index, self.sub_index, &new);
+                    if let Some(cancellation) = &self.cancellation {
+                        download = download.with_cancellation(cancellation.clone());
+                    }
+                    let request = download.start();
+                    self.phase = Phase::Download(download);
+                    self.values = Some((old, new));
+                    Ok(SdoUpdateStep::Send(request))
+                }
+            },
+            Phase::Download(download) => match download.on_response(bytes)? {
+                SdoDownloadStep::Send(request) => Ok(SdoUpdateStep::Send(request)),
+                SdoDownloadStep::Done => {
+                    let (old, new) = self
+                        .values
+                        .take()
+                        .expect("Should have been set when the upload finished");
+                    Ok(SdoUpdateStep::Done { old, new })
+                }
+            },
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    use crate::error::Error;
+    use crate::sdo::SdoAbortCode;
+
+    fn node_id() -> NodeId {
+        2.try_into().unwrap()
+    }
+
+    fn enable_operation() -> SdoUpdate<u16, impl FnMut(u16) -> u16> {
+        // Sets "enable operation" and keeps the other bits of the controlword.
+        SdoUpdate::new(node_id(), 0x6040, 0, |control_word: u16| {
+            control_word | 0x0008
+        })
+    }
+
+    #[test]
+    fn test_update() {
+        let mut update = enable_operation();
+        assert_eq!(
+            update.start().data(),
+            &[0x40, 0x40, 0x60, 0x00, 0x00, 0x00, 0x00, 0x00]
+        );
+        assert!(!update.is_writing());
+        let step = update
+            .on_response(&[0x4B, 0x40, 0x60, 0x00, 0x87, 0x80, 0x00, 0x00])
+            .unwrap();
+        assert_eq!(
+            step,
+            SdoUpdateStep::Send(SdoRequest::new(
+                node_id(),
+                [0x2B, 0x40, 0x60, 0x00, 0x8F, 0x80, 0x00, 0x00]
+            ))
+        );
+        assert!(update.is_writing());
+        assert_eq!(
+            update.on_response(&[0x60, 0x40, 0x60, 0x00, 0x00, 0x00, 0x00, 0x00]),
+            Ok(SdoUpdateStep::Done {
+                old: 0x8087,
+                new: 0x808F
+            })
+        );
+    }
+
+    #[test]
+    fn test_errors() {
+        let mut update = enable_operation();
+        update.start();
+        assert_eq!(
+            update.on_response(&[0x4F, 0x40, 0x60, 0x00, 0x07, 0x00, 0x00, 0x00]),
+            Err(Error::InvalidDataLength {
+                length: 1,
+                data_type: "u16".to_owned()
+            })
+        );
+
+        update.start();
+        update
+            .on_response(&[0x4B, 0x40, 0x60, 0x00, 0x07, 0x00, 0x00, 0x00])
+            .unwrap();
+        assert_eq!(
+            update.on_response(&[0x80, 0x40, 0x60, 0x00, 0x02, 0x00, 0x01, 0x06]),
+            Err(Error::SdoAborted(SdoAbortCode::ReadOnly))
+        );
+
+        let cancellation = CancellationToken::new();
+        let mut update = enable_operation().with_cancellation(cancellation.clone());
+        update.start();
+        cancellation.cancel();
+        assert_eq!(
+            update.on_response(&[0x4B, 0x40, 0x60, 0x00, 0x07, 0x00, 0x00, 0x00]),
+            Err(Error::Cancelled)
+        );
+        assert_eq!(
+            update.abort_request().data(),
+            &[0x80, 0x40, 0x60, 0x00, 0x00, 0x00, 0x00, 0x08]
+        );
+    }
+}
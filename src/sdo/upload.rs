@@ -0,0 +1,355 @@
+use crate::cancel::CancellationToken;
+use crate::error::{Error, Result};
+use crate::id::NodeId;
+use crate::sdo::{
+    check_cancellation, check_multiplexer, check_response, initiate_data, transfer_buffer,
+    SdoAbortCode, SdoRequest, SdoValue, CCS_INITIATE_UPLOAD, CCS_UPLOAD_SEGMENT,
+    DEFAULT_MAX_UPLOAD_SIZE, FRAME_DATA_SIZE, SCS_INITIATE_UPLOAD, SCS_UPLOAD_SEGMENT,
+};
+
+#[derive(Clone, Debug, PartialEq)]
+pub enum SdoUploadStep {
+    Send(SdoRequest),
+    Done(std::vec::Vec<u8>),
+}
+
+// Client side of an SDO upload, expedited or segmented (cf. CiA 301).
+#[derive(Clone, Debug)]
+pub struct SdoUpload {
+    node_id: NodeId,
+    index: u16,
+    sub_index: u8,
+    size: Option<usize>,
+    max_size: usize,
+    toggle: bool,
+    segmented: bool,
+    data: std::vec::Vec<u8>,
+    cancellation: Option<CancellationToken>,
+}
+
+impl SdoUploadStep {
+    // Decodes the uploaded data of a finished transfer
+    pub fn value_as<T: SdoValue>(&self) -> Option<Result<T>> {
+        match self {
+            Self::Send(_) => None,
+            Self::Done(data) => Some(T::from_sdo_bytes(data)),
+        }
+    }
+}
+
+impl SdoUpload {
+    pub const DEFAULT_MAX_SIZE: usize = DEFAULT_MAX_UPLOAD_SIZE;
+
+    pub fn new(node_id: NodeId, index: u16, sub_index: u8) -> Self {
+        Self {
+            node_id,
+            index,
+            sub_index,
+            size: None,
+            max_size: Self::DEFAULT_MAX_SIZE,
+            toggle: false,
+            segmented: false,
+            data: std::vec::Vec::new(),
+            cancellation: None,
+        }
+    }
+
+    pub fn with_cancellation(self, cancellation: CancellationToken) -> Self {
+        Self {
+            cancellation: Some(cancellation),
+            ..self
+        }
+    }
+
+    // Limits the data accepted from the server, whether or not it indicates the size
+    pub fn with_max_size(self, max_size: usize) -> Self {
+        Self { max_size, ..self }
+    }
+
+    // The data received so far, e.g. the partial result of a cancelled transfer
+    pub fn data(&self) -> &[u8] {
+        &self.data
+    }
+
+    pub fn abort_request(&self) -> SdoRequest {
+        SdoRequest::new_abort(
+            self.node_id,
+            self.index,
+            self.sub_index,
+            SdoAbortCode::GeneralError,
+        )
+    }
+
+    pub fn start(&mut self) -> SdoRequest {
+        self.size = None;
+        self.toggle = false;
+        self.segmented = false;
+        self.data.clear();
+        SdoRequest::new(
+            self.node_id,
+            initiate_data(CCS_INITIATE_UPLOAD << 5, self.index, self.sub_index, 0),
+        )
+    }
+
+    // Feeds the data of a frame received on the TxSDO COB of the node.
+    pub fn on_response(&mut self, bytes: &[u8]) -> Result<SdoUploadStep> {
+        check_cancellation(&self.cancellation)?;
+        check_response(bytes)?;
+        let command = bytes[0];
+        match (self.segmented, command >> 5) {
+            (false, SCS_INITIATE_UPLOAD) => {
+                check_multiplexer(bytes, self.index, self.sub_index)?;
+                let expedited = command & 0b0010 != 0;
+                let size_indicated = command & 0b0001 != 0;
+                if expedited {
+                    let unused = if size_indicated {
+                        ((command >> 2) & 0b11) as usize
+                    } else {
+                        0
+                    };
+                    return Ok(SdoUploadStep::Done(
+                        bytes[4..FRAME_DATA_SIZE - unused].to_vec(),
+                    ));
+                }
+                if size_indicated {
+                    let size = u32::from_le_bytes(bytes[4..8].try_into().unwrap()) as usize;
+                    if size > self.max_size {
+                        return Err(Error::SdoSizeLimitExceeded(self.max_size));
+                    }
+                    self.size = Some(size);
+                }
+                self.data = transfer_buffer(self.size);
+                self.segmented = true;
+                Ok(SdoUploadStep::Send(self.segment_request()))
+            }
+            (true, SCS_UPLOAD_SEGMENT) => {
+                if (command & 0b1_0000 != 0) != self.toggle {
+                    return Err(Error::SdoToggleBitMismatch);
+                }
+                let unused = ((command >> 1) & 0b111) as usize;
+                self.data
+                    .extend_from_slice(&bytes[1..FRAME_DATA_SIZE - unused]);
+                // Fails as soon as the server sends more than it indicated, rather than
+                // buffering until the last segment.
+                if let Some(size) = self.size {
+                    if self.data.len() > size {
+                        return Err(Error::SdoSizeMismatch {
+                            expected: size,
+                            actual: self.data.len(),
+                        });
+                    }
+                }
+                if self.data.len() > self.max_size {
+                    return Err(Error::SdoSizeLimitExceeded(self.max_size));
+                }
+                if command & 0b0001 == 0 {
+                    self.toggle = !self.toggle;
+                    return Ok(SdoUploadStep::Send(self.segment_request()));
+                }
+                if let Some(size) = self.size {
+                    if size != self.data.len() {
+                        return Err(Error::SdoSizeMismatch {
+                            expected: size,
+                            actual: self.data.len(),
+                        });
+                    }
+                }
+                Ok(SdoUploadStep::Done(std::mem::take(&mut self.data)))
+            }
+            (_, scs) => Err(Error::InvalidServerCommandSpecifier(scs)),
+        }
+    }
+
+    fn segment_request(&self) -> SdoRequest {
+        let mut data = [0x00; FRAME_DATA_SIZE];
+        data[0] = (CCS_UPLOAD_SEGMENT << 5) | ((self.toggle as u8) << 4);
+        SdoRequest::new(self.node_id, data)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    use crate::sdo::SdoAbortCode;
+
+    fn node_id() -> NodeId {
+        1.try_into().unwrap()
+    }
+
+    #[test]
+    fn test_start() {
+        let mut upload = SdoUpload::new(node_id(), 0x1008, 0);
+        assert_eq!(
+            upload.start().data(),
+            &[0x40, 0x08, 0x10, 0x00, 0x00, 0x00, 0x00, 0x00]
+        );
+    }
+
+    #[test]
+    fn test_expedited() {
+        let mut upload = SdoUpload::new(node_id(), 0x1000, 0);
+        upload.start();
+        assert_eq!(
+            upload.on_response(&[0x43, 0x00, 0x10, 0x00, 0x92, 0x01, 0x02, 0x00]),
+            Ok(SdoUploadStep::Done(vec![0x92, 0x01, 0x02, 0x00]))
+        );
+
+        let mut upload = SdoUpload::new(node_id(), 0x1001, 0);
+        upload.start();
+        assert_eq!(
+            upload.on_response(&[0x4F, 0x01, 0x10, 0x00, 0x05, 0x00, 0x00, 0x00]),
+            Ok(SdoUploadStep::Done(vec![0x05]))
+        );
+
+        let mut upload = SdoUpload::new(node_id(), 0x1001, 0);
+        upload.start();
+        assert_eq!(
+            upload.on_response(&[0x4F, 0x02, 0x10, 0x00, 0x05, 0x00, 0x00, 0x00]),
+            Err(Error::SdoMultiplexerMismatch {
+                index: 0x1001,
+                sub_index: 0
+            })
+        );
+    }
+
+    #[test]
+    fn test_value_as() {
+        let mut upload = SdoUpload::new(node_id(), 0x1000, 0);
+        let step = upload.start();
+        assert_eq!(SdoUploadStep::Send(step).value_as::<u32>(), None);
+        let step = upload
+            .on_response(&[0x43, 0x00, 0x10, 0x00, 0x92, 0x01, 0x02, 0x00])
+            .unwrap();
+        assert_eq!(step.value_as::<u32>(), Some(Ok(0x0002_0192)));
+        assert!(step.value_as::<u16>().unwrap().is_err());
+    }
+
+    #[test]
+    fn test_segmented() {
+        let mut upload = SdoUpload::new(node_id(), 0x1008, 0);
+        upload.start();
+        // "Motor driver" (12 bytes)
+        assert_eq!(
+            upload.on_response(&[0x41, 0x08, 0x10, 0x00, 0x0C, 0x00, 0x00, 0x00]),
+            Ok(SdoUploadStep::Send(SdoRequest::new(
+                node_id(),
+                [0x60, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00]
+            )))
+        );
+        assert_eq!(
+            upload.on_response(&[0x00, b'M', b'o', b't', b'o', b'r', b' ', b'd']),
+            Ok(SdoUploadStep::Send(SdoRequest::new(
+                node_id(),
+                [0x70, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00]
+            )))
+        );
+        assert_eq!(
+            upload.on_response(&[0x15, b'r', b'i', b'v', b'e', b'r', 0x00, 0x00]),
+            Ok(SdoUploadStep::Done(b"Motor driver".to_vec()))
+        );
+    }
+
+    #[test]
+    fn test_cancel() {
+        let cancellation = CancellationToken::new();
+        let mut upload =
+            SdoUpload::new(node_id(), 0x1008, 0).with_cancellation(cancellation.clone());
+        upload.start();
+        upload
+            .on_response(&[0x41, 0x08, 0x10, 0x00, 0x0C, 0x00, 0x00, 0x00])
+            .unwrap();
+        upload
+            .on_response(&[0x00, b'M', b'o', b't', b'o', b'r', b' ', b'd'])
+            .unwrap();
+        cancellation.cancel();
+        assert_eq!(
+            upload.on_response(&[0x15, b'r', b'i', b'v', b'e', b'r', 0x00, 0x00]),
+            Err(Error::Cancelled)
+        );
+        assert_eq!(upload.data(), b"Motor d");
+        assert_eq!(
+            upload.abort_request().data(),
+            &[0x80, 0x08, 0x10, 0x00, 0x00, 0x00, 0x00, 0x08]
+        );
+    }
+
+    #[test]
+    fn test_max_size() {
+        // Indicated size beyond the limit
+        let mut upload = SdoUpload::new(node_id(), 0x1008, 0).with_max_size(8);
+        upload.start();
+        assert_eq!(
+            upload.on_response(&[0x41, 0x08, 0x10, 0x00, 0x0C, 0x00, 0x00, 0x00]),
+            Err(Error::SdoSizeLimitExceeded(8))
+        );
+
+        // No indicated size
+        let mut upload = SdoUpload::new(node_id(), 0x1008, 0).with_max_size(8);
+        upload.start();
+        upload
+            .on_response(&[0x40, 0x08, 0x10, 0x00, 0x00, 0x00, 0x00, 0x00])
+            .unwrap();
+        upload
+            .on_response(&[0x00, b'M', b'o', b't', b'o', b'r', b' ', b'd'])
+            .unwrap();
+        assert_eq!(
+            upload.on_response(&[0x10, b'r', b'i', b'v', b'e', b'r', 0x00, 0x00]),
+            Err(Error::SdoSizeLimitExceeded(8))
+        );
+    }
+
+    #[test]
+    fn test_segmented_errors() {
+        let mut upload = SdoUpload::new(node_id(), 0x1008, 0);
+        upload.start();
+        upload
+            .on_response(&[0x41, 0x08, 0x10, 0x00, 0x0C, 0x00, 0x00, 0x00])
+            .unwrap();
+        assert_eq!(
+            upload.on_response(&[0x10, b'M', b'o', b't', b'o', b'r', b' ', b'd']),
+            Err(Error::SdoToggleBitMismatch)
+        );
+
+        let mut upload = SdoUpload::new(node_id(), 0x1008, 0);
+        upload.start();
+        upload
+            .on_response(&[0x41, 0x08, 0x10, 0x00, 0x0C, 0x00, 0x00, 0x00])
+            .unwrap();
+        assert_eq!(
+            upload.on_response(&[0x01, b'M', b'o', b't', b'o', b'r', b' ', b'd']),
+            Err(Error::SdoSizeMismatch {
+                expected: 12,
+                actual: 7
+            })
+        );
+
+        // More than the indicated size, without the last segment
+        let mut upload = SdoUpload::new(node_id(), 0x1008, 0);
+        upload.start();
+        upload
+            .on_response(&[0x41, 0x08, 0x10, 0x00, 0x0A, 0x00, 0x00, 0x00])
+            .unwrap();
+        upload
+            .on_response(&[0x00, b'M', b'o', b't', b'o', b'r', b' ', b'd'])
+            .unwrap();
+        assert_eq!(
+            upload.on_response(&[0x10, b'r', b'i', b'v', b'e', b'r', b'!', b'!']),
+            Err(Error::SdoSizeMismatch {
+                expected: 10,
+                actual: 14
+            })
+        );
+
+        let mut upload = SdoUpload::new(node_id(), 0x1008, 0);
+        upload.start();
+        assert_eq!(
+            upload.on_response(&[0x80, 0x08, 0x10, 0x00, 0x00, 0x00, 0x02, 0x06]),
+            Err(Error::SdoAborted(SdoAbortCode::ObjectDoesNotExist))
+        );
+        assert_eq!(
+            upload.on_response(&[0x60, 0x08, 0x10, 0x00, 0x00, 0x00, 0x00, 0x00]),
+            Err(Error::InvalidServerCommandSpecifier(3))
+        );
+    }
+}
@@ -0,0 +1,102 @@
+use crate::error::{Error, Result};
+use crate::pdo_layout::PdoField;
+
+// A value of a CiA 301 basic data type transferred by SDO, encoded in little-endian.
+// Fixed-size types require the exact length; strings take the whole payload.
+pub trait SdoValue: Sized {
+    fn to_sdo_bytes(&self) -> std::vec::Vec<u8>;
+    fn from_sdo_bytes(bytes: &[u8]) -> Result<Self>;
+}
+
+impl<T: PdoField> SdoValue for T {
+    fn to_sdo_bytes(&self) -> std::vec::Vec<u8> {
+        let mut bytes = std::vec![0x00; T::SIZE];
+        self.write(&mut bytes);
+        bytes
+    }
+
+    fn from_sdo_bytes(bytes: &[u8]) -> Result<Self> {
+        if bytes.len() != T::SIZE {
+            return Err(Error::InvalidDataLength {
+                length: bytes.len(),
+                data_type: std::any::type_name::<T>().to_owned(),
+            });
+        }
+        Ok(T::read(bytes))
+    }
+}
+
+// VISIBLE_STRING. Trailing NUL padding sent by some devices is dropped.
+impl SdoValue for std::string::String {
+    fn to_sdo_bytes(&self) -> std::vec::Vec<u8> {
+        self.as_bytes().to_vec()
+    }
+
+    fn from_sdo_bytes(bytes: &[u8]) -> Result<Self> {
+        let end = bytes
+            .iter()
+            .rposition(|byte| *byte != 0x00)
+            .map_or(0, |position| position + 1);
+        std::string::String::from_utf8(bytes[..end].to_vec()).map_err(|_| Error::InvalidString)
+    }
+}
+
+// OCTET_STRING
+impl SdoValue for std::vec::Vec<u8> {
+    fn to_sdo_bytes(&self) -> std::vec::Vec<u8> {
+        self.clone()
+    }
+
+    fn from_sdo_bytes(bytes: &[u8]) -> Result<Self> {
+        Ok(bytes.to_vec())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_to_sdo_bytes() {
+        assert_eq!(0x12u8.to_sdo_bytes(), vec![0x12]);
+        assert_eq!(0x1234u16.to_sdo_bytes(), vec![0x34, 0x12]);
+        assert_eq!((-2i32).to_sdo_bytes(), vec![0xFE, 0xFF, 0xFF, 0xFF]);
+        assert_eq!(1.0f32.to_sdo_bytes(), vec![0x00, 0x00, 0x80, 0x3F]);
+        assert_eq!(true.to_sdo_bytes(), vec![0x01]);
+        assert_eq!("CiA".to_owned().to_sdo_bytes(), b"CiA".to_vec());
+    }
+
+    #[test]
+    fn test_from_sdo_bytes() {
+        assert_eq!(
+            u32::from_sdo_bytes(&[0x92, 0x01, 0x02, 0x00]),
+            Ok(0x0002_0192)
+        );
+        assert_eq!(i16::from_sdo_bytes(&[0xFF, 0xFF]), Ok(-1));
+        assert_eq!(f32::from_sdo_bytes(&[0x00, 0x00, 0x80, 0x3F]), Ok(1.0));
+        assert_eq!(
+            u16::from_sdo_bytes(&[0x01, 0x02, 0x03]),
+            Err(Error::InvalidDataLength {
+                length: 3,
+                data_type: "u16".to_owned()
+            })
+        );
+    }
+
+    #[test]
+    fn test_string_from_sdo_bytes() {
+        assert_eq!(
+            String::from_sdo_bytes(b"Motor\0\0\0"),
+            Ok("Motor".to_owned())
+        );
+        assert_eq!(String::from_sdo_bytes(&[]), Ok("".to_owned()));
+        assert_eq!(
+            String::from_sdo_bytes(&[0xFF, 0xFE]),
+            Err(Error::InvalidString)
+        );
+        assert_eq!(
+            Vec::<u8>::from_sdo_bytes(&[0x00, 0x01]),
+            Ok(vec![0x00, 0x01])
+        );
+    }
+}
@@ -0,0 +1,603 @@
+use crate::error::{Error, Result};
+use crate::frame::{
+    encode, CanOpenFrame, DataLengthPolicy, NmtCommand, NmtNodeControlAddress, NmtNodeControlFrame,
+    NmtState, RawFrame, SyncFrame,
+};
+use crate::id::{CommunicationObject, NodeId};
+use crate::node::LocalNode;
+use crate::sdo::{SdoDownload, SdoDownloadStep, SdoUpload, SdoUploadStep};
+
+// A CANopen stack under a soak test, seen from the bus: frames are fed to it and the frames it
+// transmits are returned. Downstream integrators implement it for their own stack.
+pub trait SoakTarget {
+    // Feeds a frame received at `now`. Returns the frames transmitted in response.
+    fn on_frame(&mut self, frame: &RawFrame, now: std::time::Instant) -> std::vec::Vec<RawFrame>;
+
+    // Returns the frames transmitted on their own (e.g. heartbeats) by `now`.
+    fn poll(&mut self, now: std::time::Instant) -> std::vec::Vec<RawFrame>;
+
+    // Size of the state held by the target (e.g. pending transactions or buffered bytes),
+    // which must not grow over the run. `None` if it is not tracked.
+    fn resident_size(&self) -> Option<usize> {
+        None
+    }
+}
+
+impl SoakTarget for LocalNode {
+    fn on_frame(&mut self, frame: &RawFrame, now: std::time::Instant) -> std::vec::Vec<RawFrame> {
+        LocalNode::on_frame(self, frame.id(), frame.data(), now)
+            .iter()
+            .map(encode)
+            .collect()
+    }
+
+    fn poll(&mut self, now: std::time::Instant) -> std::vec::Vec<RawFrame> {
+        LocalNode::poll(self, now).iter().map(encode).collect()
+    }
+}
+
+#[derive(Clone, Debug, PartialEq)]
+pub struct SoakConfig {
+    pub node_id: NodeId,
+    // Same seed, same traffic
+    pub seed: u64,
+    // Simulated time of the run, advanced by `step` after each action
+    pub duration: std::time::Duration,
+    pub step: std::time::Duration,
+    // Objects uploaded as (index, sub-index)
+    pub read_objects: std::vec::Vec<(u16, u8)>,
+    // Objects downloaded with random data of the given size as (index, sub-index, size), then
+    // uploaded to check the data
+    pub write_objects: std::vec::Vec<(u16, u8, usize)>,
+    // Relative weights of the actions
+    pub sdo_weight: u32,
+    pub pdo_weight: u32,
+    pub nmt_weight: u32,
+    pub idle_weight: u32,
+    // Maximum number of SYNC frames in a PDO burst
+    pub max_sync_burst: u32,
+    // Frames exchanged before an SDO transfer is considered stuck
+    pub max_transfer_frames: usize,
+    // Allowed growth of `SoakTarget::resident_size` over its initial value
+    pub max_resident_growth: usize,
+}
+
+impl SoakConfig {
+    pub fn new(node_id: NodeId) -> Self {
+        Self {
+            node_id,
+            seed: 1,
+            duration: std::time::Duration::from_secs(3600),
+            step: std::time::Duration::from_millis(10),
+            read_objects: vec![(0x1017, 0)],
+            write_objects: std::vec::Vec::new(),
+            sdo_weight: 4,
+            pdo_weight: 3,
+            nmt_weight: 1,
+            idle_weight: 2,
+            max_sync_burst: 10,
+            max_transfer_frames: 1024,
+            max_resident_growth: 0,
+        }
+    }
+
+    pub fn with_seed(self, seed: u64) -> Self {
+        Self { seed, ..self }
+    }
+
+    pub fn with_duration(self, duration: std::time::Duration) -> Self {
+        Self { duration, ..self }
+    }
+
+    pub fn with_read_objects(self, read_objects: std::vec::Vec<(u16, u8)>) -> Self {
+        Self {
+            read_objects,
+            ..self
+        }
+    }
+
+    pub fn with_write_objects(self, write_objects: std::vec::Vec<(u16, u8, usize)>) -> Self {
+        Self {
+            write_objects,
+            ..self
+        }
+    }
+}
+
+#[derive(Clone, Debug, Default, PartialEq)]
+pub struct SoakReport {
+    pub actions: u64,
+    pub sdo_uploads: u64,
+    pub sdo_downloads: u64,
+    // Transfers aborted by the target, which are valid answers (e.g. a read-only object)
+    pub sdo_aborts: u64,
+    pub syncs: u64,
+    pub pdos: u64,
+    pub nmt_commands: u64,
+    pub boot_ups: u64,
+    pub heartbeats: u64,
+    pub frames_sent: u64,
+    pub frames_received: u64,
+    pub max_resident_size: Option<usize>,
+}
+
+// Drives randomized traffic against `target` for `config.duration` of simulated time from
+// `start` and checks after each frame that:
+// - every SDO transfer completes within `max_transfer_frames` frames, with the data written,
+// - a stopped node does not answer SDO requests nor transmit PDOs,
+// - PDOs are only transmitted when operational,
+// - resets are answered with a boot-up message,
+// - heartbeats report the state requested by the last NMT command,
+// - the resident size does not grow beyond `max_resident_growth`.
+//
+// The target must have booted. It is first reset to communication, so that its state is known.
+// The first violation is returned as `Error::SoakInvariantViolated`.
+pub fn run_soak<T: SoakTarget>(
+    target: &mut T,
+    config: &SoakConfig,
+    start: std::time::Instant,
+) -> Result<SoakReport> {
+    let mut runner = Runner {
+        config,
+        target,
+        random: XorShift::new(config.seed),
+        now: start,
+        state: NmtState::BootUp,
+        initial_resident_size: None,
+        report: SoakReport::default(),
+    };
+    runner.initial_resident_size = runner.target.resident_size();
+    runner.report.max_resident_size = runner.initial_resident_size;
+    runner.nmt(NmtCommand::ResetCommunication)?;
+    while runner.now.duration_since(start) < config.duration {
+        runner.act()?;
+        runner.now += config.step;
+        let frames = runner.target.poll(runner.now);
+        runner.observe(&frames)?;
+        runner.check_resident_size()?;
+    }
+    Ok(runner.report)
+}
+
+struct Runner<'a, T> {
+    config: &'a SoakConfig,
+    target: &'a mut T,
+    random: XorShift,
+    now: std::time::Instant,
+    // Expected state of the node
+    state: NmtState,
+    initial_resident_size: Option<usize>,
+    report: SoakReport,
+}
+
+impl<T: SoakTarget> Runner<'_, T> {
+    fn act(&mut self) -> Result<()> {
+        self.report.actions += 1;
+        let weights = [
+            self.config.sdo_weight,
+            self.config.pdo_weight,
+            self.config.nmt_weight,
+            self.config.idle_weight,
+        ];
+        let total = weights.iter().sum::<u32>();
+        if total == 0 {
+            return Ok(());
+        }
+        let mut choice = self.random.below(total.into()) as u32;
+        let action = weights
+            .iter()
+            .position(|weight| {
+                if choice < *weight {
+                    return true;
+                }
+                choice -= weight;
+                false
+            })
+            .unwrap();
+        match action {
+            0 => self.sdo(),
+            1 => self.sync_burst(),
+            2 => {
+                const COMMANDS: [NmtCommand; 5] = [
+                    NmtCommand::Operational,
+                    NmtCommand::Stopped,
+                    NmtCommand::PreOperational,
+                    NmtCommand::ResetNode,
+                    NmtCommand::ResetCommunication,
+                ];
+                let command = COMMANDS[self.random.below(COMMANDS.len() as u64) as usize];
+                self.nmt(command)
+            }
+            _ => Ok(()),
+        }
+    }
+
+    fn sdo(&mut self) -> Result<()> {
+        if self.state == NmtState::Stopped {
+            let (index, sub_index) = self
+                .config
+                .read_objects
+                .first()
+                .copied()
+                .unwrap_or((0x1000, 0));
+            let request = encode(&SdoUpload::new(self.config.node_id, index, sub_index).start());
+            let frames = self.exchange(&request)?;
+            if self.find_sdo_response(&frames).is_some() {
+                return Err(self.violation("stopped node answered an SDO request".to_owned()));
+            }
+            return Ok(());
+        }
+        let reads = self.config.read_objects.len();
+        let writes = self.config.write_objects.len();
+        if reads + writes == 0 {
+            return Ok(());
+        }
+        let choice = self.random.below((reads + writes) as u64) as usize;
+        if choice < reads {
+            let (index, sub_index) = self.config.read_objects[choice];
+            self.upload(index, sub_index)?;
+            return Ok(());
+        }
+        let (index, sub_index, size) = self.config.write_objects[choice - reads];
+        let data = (0..size)
+            .map(|_| self.random.next() as u8)
+            .collect::<std::vec::Vec<_>>();
+        if !self.download(index, sub_index, data.clone())? {
+            return Ok(());
+        }
+        match self.upload(index, sub_index)? {
+            Some(read) if read != data => Err(self.violation(format!(
+                "0x{index:04X}:{sub_index} reads {read:02X?} after writing {data:02X?}"
+            ))),
+            _ => Ok(()),
+        }
+    }
+
+    // Returns the data, `None` if the target aborted the transfer.
+    fn upload(&mut self, index: u16, sub_index: u8) -> Result<Option<std::vec::Vec<u8>>> {
+        self.report.sdo_uploads += 1;
+        let mut upload = SdoUpload::new(self.config.node_id, index, sub_index);
+        let mut request = upload.start();
+        for _ in 0..self.config.max_transfer_frames {
+            let response = self.sdo_exchange(&encode(&request), index, sub_index)?;
+            match upload.on_response(response.data()) {
+                Ok(SdoUploadStep::Send(next)) => request = next,
+                Ok(SdoUploadStep::Done(data)) => return Ok(Some(data)),
+                Err(Error::SdoAborted(_)) => {
+                    self.report.sdo_aborts += 1;
+                    return Ok(None);
+                }
+                Err(e) => {
+                    return Err(
+                        self.violation(format!("upload of 0x{index:04X}:{sub_index} failed ({e})"))
+                    )
+                }
+            }
+        }
+        Err(self.violation(format!("upload of 0x{index:04X}:{sub_index} is stuck")))
+    }
+
+    // Returns false if the target aborted the transfer.
+    fn download(&mut self, index: u16, sub_index: u8, data: std::vec::Vec<u8>) -> Result<bool> {
+        self.report.sdo_downloads += 1;
+        let mut download = SdoDownload::new(self.config.node_id, index, sub_index, data);
+        let mut request = download.start();
+        for _ in 0..self.config.max_transfer_frames {
+            let response = self.sdo_exchange(&encode(&request), index, sub_index)?;
+            match download.on_response(response.data()) {
+                Ok(SdoDownloadStep::Send(next)) => request = next,
+                Ok(SdoDownloadStep::Done) => return Ok(true),
+                Err(Error::SdoAborted(_)) => {
+                    self.report.sdo_aborts += 1;
+                    return Ok(false);
+                }
+                Err(e) => {
+                    return Err(self.violation(format!(
+                        "download of 0x{index:04X}:{sub_index} failed ({e})"
+                    )))
+                }
+            }
+        }
+        Err(self.violation(format!("download of 0x{index:04X}:{sub_index} is stuck")))
+    }
+
+    fn sdo_exchange(&mut self, request: &RawFrame, index: u16, sub_index: u8) -> Result<RawFrame> {
+        let frames = self.exchange(request)?;
+        match self.find_sdo_response(&frames) {
+            Some(response) => Ok(response),
+            None => Err(self.violation(format!("no SDO response for 0x{index:04X}:{sub_index}"))),
+        }
+    }
+
+    fn find_sdo_response(&self, frames: &[RawFrame]) -> Option<RawFrame> {
+        frames.iter().copied().find(|frame| {
+            CommunicationObject::new(frame.id())
+                == Ok(CommunicationObject::TxSdo(self.config.node_id))
+        })
+    }
+
+    fn sync_burst(&mut self) -> Result<()> {
+        let count = 1 + self.random.below(self.config.max_sync_burst.max(1).into());
+        for _ in 0..count {
+            self.report.syncs += 1;
+            self.exchange(&encode(&SyncFrame::new()))?;
+        }
+        Ok(())
+    }
+
+    fn nmt(&mut self, command: NmtCommand) -> Result<()> {
+        self.report.nmt_commands += 1;
+        let frame =
+            NmtNodeControlFrame::new(command, NmtNodeControlAddress::Node(self.config.node_id));
+        let boot_ups = self.report.boot_ups;
+        self.exchange(&encode(&frame))?;
+        let reset = matches!(
+            command,
+            NmtCommand::ResetNode | NmtCommand::ResetCommunication
+        );
+        if reset && self.report.boot_ups == boot_ups {
+            return Err(self.violation(format!("no boot-up message after {command:?}")));
+        }
+        self.state = match command {
+            NmtCommand::Operational => NmtState::Operational,
+            NmtCommand::Stopped => NmtState::Stopped,
+            NmtCommand::PreOperational | NmtCommand::ResetNode | NmtCommand::ResetCommunication => {
+                NmtState::PreOperational
+            }
+        };
+        Ok(())
+    }
+
+    fn exchange(&mut self, frame: &RawFrame) -> Result<std::vec::Vec<RawFrame>> {
+        self.report.frames_sent += 1;
+        let frames = self.target.on_frame(frame, self.now);
+        self.observe(&frames)?;
+        Ok(frames)
+    }
+
+    // Checks the frames transmitted by the target.
+    fn observe(&mut self, frames: &[RawFrame]) -> Result<()> {
+        self.report.frames_received += frames.len() as u64;
+        for frame in frames {
+            let decoded = match CanOpenFrame::decode(frame, DataLengthPolicy::Lenient) {
+                Ok(decoded) => decoded,
+                Err(e) => return Err(self.violation(format!("transmitted {frame:?} ({e})"))),
+            };
+            match decoded {
+                CanOpenFrame::NmtNodeMonitoringFrame(frame)
+                    if frame.node_id == self.config.node_id =>
+                {
+                    if frame.state == NmtState::BootUp {
+                        self.report.boot_ups += 1;
+                    } else if frame.state != self.state && self.state != NmtState::BootUp {
+                        return Err(self.violation(format!(
+                            "heartbeat reports {:?} instead of {:?}",
+                            frame.state, self.state
+                        )));
+                    } else {
+                        self.report.heartbeats += 1;
+                    }
+                }
+                CanOpenFrame::PdoFrame(_) => {
+                    self.report.pdos += 1;
+                    if self.state != NmtState::Operational {
+                        return Err(self
+                            .violation(format!("PDO transmitted in the {:?} state", self.state)));
+                    }
+                }
+                _ => {}
+            }
+        }
+        Ok(())
+    }
+
+    fn check_resident_size(&mut self) -> Result<()> {
+        let (Some(initial), Some(size)) = (self.initial_resident_size, self.target.resident_size())
+        else {
+            return Ok(());
+        };
+        self.report.max_resident_size = self.report.max_resident_size.max(Some(size));
+        if size > initial + self.config.max_resident_growth {
+            return Err(self.violation(format!("resident size grew from {initial} to {size}")));
+        }
+        Ok(())
+    }
+
+    fn violation(&self, message: String) -> Error {
+        Error::SoakInvariantViolated {
+            action: self.report.actions,
+            message,
+        }
+    }
+}
+
+// Deterministic pseudo-random numbers (xorshift64), good enough to vary the traffic
+#[derive(Clone, Debug)]
+struct XorShift(u64);
+
+impl XorShift {
+    fn new(seed: u64) -> Self {
+        // The state must not be zero.
+        Self(seed.max(1))
+    }
+
+    fn next(&mut self) -> u64 {
+        self.0 ^= self.0 << 13;
+        self.0 ^= self.0 >> 7;
+        self.0 ^= self.0 << 17;
+        self.0
+    }
+
+    fn below(&mut self, bound: u64) -> u64 {
+        self.next() % bound
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    use crate::od::{AccessType, Object, ObjectDictionary, Value, Variable};
+
+    fn node_id() -> NodeId {
+        3.try_into().unwrap()
+    }
+
+    fn node() -> LocalNode {
+        let mut od = ObjectDictionary::new();
+        od.insert(Object::new_var(
+            0x1008,
+            Variable::new(
+                "Manufacturer device name",
+                AccessType::Constant,
+                Value::VisibleString("Soak test node".to_owned()),
+            ),
+        ));
+        od.insert(Object::new_var(
+            0x1017,
+            Variable::new(
+                "Producer heartbeat time",
+                AccessType::ReadWrite,
+                Value::Unsigned16(100),
+            ),
+        ));
+        od.insert(Object::new_record(
+            0x1800,
+            "TPDO1 communication parameter",
+            vec![
+                (
+                    1,
+                    Variable::new("COB-ID", AccessType::ReadWrite, Value::Unsigned32(0x183)),
+                ),
+                (
+                    2,
+                    Variable::new(
+                        "Transmission type",
+                        AccessType::ReadWrite,
+                        Value::Unsigned8(1),
+                    ),
+                ),
+            ],
+        ));
+        od.insert(
+            Object::new_array(
+                0x1A00,
+                "TPDO1 mapping parameter",
+                AccessType::ReadWrite,
+                vec![Value::Unsigned32(0x2000_0020)],
+            )
+            .unwrap(),
+        );
+        od.insert(Object::new_var(
+            0x2000,
+            Variable::new("Counter", AccessType::ReadWrite, Value::Unsigned32(0)),
+        ));
+        od.insert(Object::new_var(
+            0x2001,
+            Variable::new(
+                "Label",
+                AccessType::ReadWrite,
+                Value::OctetString(vec![0; 10]),
+            ),
+        ));
+        let mut node = LocalNode::new(node_id(), od);
+        node.boot(std::time::Instant::now());
+        node
+    }
+
+    fn config() -> SoakConfig {
+        SoakConfig::new(node_id())
+            .with_duration(std::time::Duration::from_secs(20))
+            .with_read_objects(vec![(0x1008, 0), (0x1017, 0), (0x6000, 0)])
+            .with_write_objects(vec![(0x2000, 0, 4), (0x2001, 0, 10)])
+    }
+
+    #[test]
+    fn test_local_node() {
+        let report = run_soak(&mut node(), &config(), std::time::Instant::now()).unwrap();
+        assert_eq!(report.actions, 2000);
+        assert!(report.sdo_uploads > 0);
+        assert!(report.sdo_downloads > 0);
+        // 0x6000 does not exist.
+        assert!(report.sdo_aborts > 0);
+        assert!(report.pdos > 0);
+        assert!(report.boot_ups > 1);
+        assert!(report.heartbeats > 0);
+        assert_eq!(report.max_resident_size, None);
+
+        let again = run_soak(&mut node(), &config(), std::time::Instant::now()).unwrap();
+        assert_eq!(again, report);
+    }
+
+    // Answers SDO requests even when stopped
+    struct Careless(LocalNode);
+
+    impl SoakTarget for Careless {
+        fn on_frame(
+            &mut self,
+            frame: &RawFrame,
+            now: std::time::Instant,
+        ) -> std::vec::Vec<RawFrame> {
+            let stopped = self.0.state() == NmtState::Stopped;
+            if stopped
+                && CommunicationObject::new(frame.id()) == Ok(CommunicationObject::RxSdo(node_id()))
+            {
+                return vec![
+                    RawFrame::new(0x583, &[0x4B, 0x17, 0x10, 0x00, 0x64, 0x00, 0, 0]).unwrap(),
+                ];
+            }
+            SoakTarget::on_frame(&mut self.0, frame, now)
+        }
+
+        fn poll(&mut self, now: std::time::Instant) -> std::vec::Vec<RawFrame> {
+            SoakTarget::poll(&mut self.0, now)
+        }
+    }
+
+    // Keeps every frame it receives
+    struct Leaky(LocalNode, std::vec::Vec<RawFrame>);
+
+    impl SoakTarget for Leaky {
+        fn on_frame(
+            &mut self,
+            frame: &RawFrame,
+            now: std::time::Instant,
+        ) -> std::vec::Vec<RawFrame> {
+            self.1.push(*frame);
+            SoakTarget::on_frame(&mut self.0, frame, now)
+        }
+
+        fn poll(&mut self, now: std::time::Instant) -> std::vec::Vec<RawFrame> {
+            SoakTarget::poll(&mut self.0, now)
+        }
+
+        fn resident_size(&self) -> Option<usize> {
+            Some(self.1.len())
+        }
+    }
+
+    #[test]
+    fn test_violations() {
+        let result = run_soak(&mut Careless(node()), &config(), std::time::Instant::now());
+        assert!(matches!(
+            result,
+            Err(Error::SoakInvariantViolated { message, .. })
+                if message == "stopped node answered an SDO request"
+        ));
+
+        let config = SoakConfig {
+            max_resident_growth: 100,
+            ..config()
+        };
+        let result = run_soak(
+            &mut Leaky(node(), std::vec::Vec::new()),
+            &config,
+            std::time::Instant::now(),
+        );
+        assert!(matches!(
+            result,
+            Err(Error::SoakInvariantViolated { message, .. })
+                if message.starts_with("resident size grew from 0 to ")
+        ));
+    }
+}
@@ -1,62 +1,56 @@
-use libc::CAN_MAX_DLEN;
-use socketcan::EmbeddedFrame;
+use socketcan::{EmbeddedFrame, Frame};
 
 use crate::error::{Error, Result};
-use crate::frame::sdo::Direction;
-use crate::frame::ConvertibleFrame;
-use crate::frame::{
-    CanOpenFrame, EmergencyFrame, NmtNodeControlFrame, NmtNodeMonitoringFrame, SdoFrame, SyncFrame,
-};
-use crate::id::CommunicationObject;
-
-pub fn to_socketcan_frame<T: ConvertibleFrame>(frame: T) -> socketcan::CanFrame {
-    let data = frame.frame_data();
-    assert!(data.len() <= CAN_MAX_DLEN);
-    socketcan::CanFrame::new(frame.communication_object(), data.as_ref())
+use crate::frame::{CanOpenFrame, DataLengthPolicy, RawFrame};
+
+impl From<RawFrame> for socketcan::CanFrame {
+    fn from(frame: RawFrame) -> Self {
+        let id = socketcan::StandardId::new(frame.id())
+            .expect("Should have failed only when the ID was out of range (11-bit)");
+        if frame.is_remote() {
+            socketcan::CanFrame::new_remote(id, frame.data_length().into())
+        } else {
+            socketcan::CanFrame::new(id, frame.data())
+        }
         .expect("Should have failed only when the data length exceeded `CAN_MAX_DLEN`")
+    }
 }
 
 impl From<CanOpenFrame> for socketcan::CanFrame {
     fn from(frame: CanOpenFrame) -> Self {
-        match frame {
-            CanOpenFrame::NmtNodeControlFrame(frame) => to_socketcan_frame(frame),
-            CanOpenFrame::SyncFrame(frame) => to_socketcan_frame(frame),
-            CanOpenFrame::EmergencyFrame(frame) => to_socketcan_frame(frame),
-            CanOpenFrame::SdoFrame(frame) => to_socketcan_frame(frame),
-            CanOpenFrame::NmtNodeMonitoringFrame(frame) => to_socketcan_frame(frame),
-        }
+        frame.encode().into()
+    }
+}
+
+impl CanOpenFrame {
+    pub fn from_socketcan_frame(
+        frame: socketcan::CanFrame,
+        policy: DataLengthPolicy,
+    ) -> Result<Self> {
+        let raw_frame = match (&frame, frame.id()) {
+            (socketcan::CanFrame::Data(data_frame), socketcan::Id::Standard(id)) => {
+                RawFrame::new(id.as_raw(), EmbeddedFrame::data(data_frame))
+            }
+            (socketcan::CanFrame::Remote(remote_frame), socketcan::Id::Standard(id)) => {
+                RawFrame::new_remote(id.as_raw(), remote_frame.dlc() as u8)
+            }
+            (socketcan::CanFrame::Error(_), _) => Err(Error::NotImplemented),
+            (_, socketcan::Id::Extended(_)) => Err(Error::CanFdNotSupported),
+        };
+        raw_frame
+            .map_err(|reason| Error::UndecodableFrame {
+                cob_id: frame.raw_id(),
+                data: EmbeddedFrame::data(&frame).to_vec(),
+                reason: Box::new(reason),
+            })
+            .and_then(|raw_frame| CanOpenFrame::decode(&raw_frame, policy))
     }
 }
 
 impl TryFrom<socketcan::CanFrame> for CanOpenFrame {
     type Error = Error;
     fn try_from(frame: socketcan::CanFrame) -> Result<Self> {
-        match frame {
-            socketcan::CanFrame::Data(frame) => {
-                let cob: CommunicationObject = frame.id().try_into()?;
-                match cob {
-                    CommunicationObject::NmtNodeControl => {
-                        Ok(NmtNodeControlFrame::new_with_bytes(frame.data())?.into())
-                    }
-                    CommunicationObject::Sync => Ok(SyncFrame.into()),
-                    CommunicationObject::Emergency(node_id) => {
-                        Ok(EmergencyFrame::new_with_bytes(node_id, frame.data())?.into())
-                    }
-                    CommunicationObject::TxSdo(node_id) => {
-                        Ok(SdoFrame::new_with_bytes(Direction::Tx, node_id, frame.data())?.into())
-                    }
-                    CommunicationObject::RxSdo(node_id) => {
-                        Ok(SdoFrame::new_with_bytes(Direction::Rx, node_id, frame.data())?.into())
-                    }
-                    CommunicationObject::NmtNodeMonitoring(node_id) => {
-                        Ok(NmtNodeMonitoringFrame::new_with_bytes(node_id, frame.data())?.into())
-                    }
-                    _ => Err(Error::NotImplemented),
-                }
-            }
-            socketcan::CanFrame::Remote(_) => Err(Error::NotImplemented),
-            socketcan::CanFrame::Error(_) => Err(Error::NotImplemented),
-        }
+        CanOpenFrame::from_socketcan_frame(frame, DataLengthPolicy::default())
     }
 }
 
@@ -67,7 +61,17 @@ mod tests {
     use super::*;
 
     use crate::frame::sdo::ClientCommandSpecifier;
-    use crate::frame::{NmtCommand, NmtNodeControlAddress, NmtState};
+    use crate::frame::{
+        encode, ConvertibleFrame, Direction, EmergencyFrame, LssFrame, LssIdentityField,
+        LssRequest, LssResponse, NmtCommand, NmtNodeControlAddress, NmtNodeControlFrame,
+        NmtNodeMonitoringFrame, NmtState, PdoFrame, RemoteFrame, SdoFrame, SyncFrame,
+        TimeStampFrame,
+    };
+    use crate::id::CommunicationObject;
+
+    fn to_socketcan_frame<T: ConvertibleFrame>(frame: T) -> socketcan::CanFrame {
+        encode(&frame).into()
+    }
 
     #[test]
     fn test_nmt_node_control_frame_to_socketcan_frame() {
@@ -173,38 +177,65 @@ mod tests {
             socketcan::CanFrame::new(socketcan::StandardId::new(0x000).unwrap(), &[0x00, 0x00])
                 .unwrap()
                 .try_into();
-        assert_eq!(frame, Err(Error::InvalidNmtCommand(0)));
+        assert_eq!(frame.unwrap_err().reason(), &Error::InvalidNmtCommand(0));
 
         let frame: Result<CanOpenFrame> =
             socketcan::CanFrame::new(socketcan::StandardId::new(0x000).unwrap(), &[0x03, 0x00])
                 .unwrap()
                 .try_into();
-        assert_eq!(frame, Err(Error::InvalidNmtCommand(3)));
+        assert_eq!(frame.unwrap_err().reason(), &Error::InvalidNmtCommand(3));
 
         let frame: Result<CanOpenFrame> =
             socketcan::CanFrame::new(socketcan::StandardId::new(0x000).unwrap(), &[0xFF, 0x00])
                 .unwrap()
                 .try_into();
-        assert_eq!(frame, Err(Error::InvalidNmtCommand(255)));
+        assert_eq!(frame.unwrap_err().reason(), &Error::InvalidNmtCommand(255));
 
         let frame: Result<CanOpenFrame> =
             socketcan::CanFrame::new(socketcan::StandardId::new(0x000).unwrap(), &[0x01, 0x80])
                 .unwrap()
                 .try_into();
-        assert_eq!(frame, Err(Error::InvalidNodeId(128)));
+        assert_eq!(frame.unwrap_err().reason(), &Error::InvalidNodeId(128));
 
         let frame: Result<CanOpenFrame> =
             socketcan::CanFrame::new(socketcan::StandardId::new(0x000).unwrap(), &[0x01, 0xFF])
                 .unwrap()
                 .try_into();
-        assert_eq!(frame, Err(Error::InvalidNodeId(255)));
+        assert_eq!(frame.unwrap_err().reason(), &Error::InvalidNodeId(255));
+    }
+
+    #[test]
+    fn test_socketcan_frame_to_nmt_node_control_frame_lenient() {
+        let frame = CanOpenFrame::from_socketcan_frame(
+            socketcan::CanFrame::new(
+                socketcan::StandardId::new(0x000).unwrap(),
+                &[0x01, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00],
+            )
+            .unwrap(),
+            DataLengthPolicy::Lenient,
+        );
+        assert_eq!(
+            frame,
+            Ok(CanOpenFrame::NmtNodeControlFrame(NmtNodeControlFrame {
+                command: NmtCommand::Operational,
+                address: NmtNodeControlAddress::AllNodes
+            }))
+        );
+
+        let frame: Result<CanOpenFrame> = socketcan::CanFrame::new(
+            socketcan::StandardId::new(0x000).unwrap(),
+            &[0x01, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00],
+        )
+        .unwrap()
+        .try_into();
+        assert!(frame.is_err());
     }
 
     #[test]
     fn test_sync_frame_to_socketcan_frame() {
         let frame = to_socketcan_frame(SyncFrame::new());
         assert_eq!(frame.raw_id(), 0x080);
-        assert_eq!(frame.data(), &[]);
+        assert_eq!(frame.data(), &[] as &[u8]);
     }
 
     #[test]
@@ -213,26 +244,38 @@ mod tests {
             socketcan::CanFrame::new(socketcan::StandardId::new(0x080).unwrap(), &[])
                 .unwrap()
                 .try_into();
-        assert_eq!(frame, Ok(CanOpenFrame::SyncFrame(SyncFrame)));
+        assert_eq!(frame, Ok(CanOpenFrame::SyncFrame(SyncFrame::new())));
     }
 
     #[test]
     fn test_emergency_frame_to_socketcan_frame() {
-        let frame = to_socketcan_frame(EmergencyFrame::new(1.try_into().unwrap(), 0x0000, 0x00));
+        let frame = to_socketcan_frame(EmergencyFrame::new(
+            1.try_into().unwrap(),
+            0x0000,
+            0x00.into(),
+        ));
         assert_eq!(frame.raw_id(), 0x081);
         assert_eq!(
             frame.data(),
             &[0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00]
         );
 
-        let frame = to_socketcan_frame(EmergencyFrame::new(2.try_into().unwrap(), 0x1000, 0x01));
+        let frame = to_socketcan_frame(EmergencyFrame::new(
+            2.try_into().unwrap(),
+            0x1000,
+            0x01.into(),
+        ));
         assert_eq!(frame.raw_id(), 0x082);
         assert_eq!(
             frame.data(),
             &[0x00, 0x10, 0x01, 0x00, 0x00, 0x00, 0x00, 0x00]
         );
 
-        let frame = to_socketcan_frame(EmergencyFrame::new(127.try_into().unwrap(), 0x1234, 0x56));
+        let frame = to_socketcan_frame(EmergencyFrame::new(
+            127.try_into().unwrap(),
+            0x1234,
+            0x56.into(),
+        ));
         assert_eq!(frame.raw_id(), 0x0FF);
         assert_eq!(
             frame.data(),
@@ -253,7 +296,8 @@ mod tests {
             Ok(CanOpenFrame::EmergencyFrame(EmergencyFrame {
                 node_id: 1.try_into().unwrap(),
                 error_code: 0x0000,
-                error_register: 0x00
+                error_register: 0x00.into(),
+                manufacturer_specific: [0x00; 5]
             }))
         );
 
@@ -268,7 +312,8 @@ mod tests {
             Ok(CanOpenFrame::EmergencyFrame(EmergencyFrame {
                 node_id: 2.try_into().unwrap(),
                 error_code: 0x1000,
-                error_register: 0x01
+                error_register: 0x01.into(),
+                manufacturer_specific: [0x00; 5]
             }))
         );
 
@@ -283,7 +328,8 @@ mod tests {
             Ok(CanOpenFrame::EmergencyFrame(EmergencyFrame {
                 node_id: 127.try_into().unwrap(),
                 error_code: 0x1234,
-                error_register: 0x56
+                error_register: 0x56.into(),
+                manufacturer_specific: [0x00; 5]
             }))
         );
 
@@ -296,6 +342,64 @@ mod tests {
         assert!(frame.is_err());
     }
 
+    #[test]
+    fn test_socketcan_frame_to_emergency_frame_lenient() {
+        let frame = CanOpenFrame::from_socketcan_frame(
+            socketcan::CanFrame::new(
+                socketcan::StandardId::new(0x082).unwrap(),
+                &[0x00, 0x10, 0x01],
+            )
+            .unwrap(),
+            DataLengthPolicy::Lenient,
+        );
+        assert_eq!(
+            frame,
+            Ok(CanOpenFrame::EmergencyFrame(EmergencyFrame {
+                node_id: 2.try_into().unwrap(),
+                error_code: 0x1000,
+                error_register: 0x01.into(),
+                manufacturer_specific: [0x00; 5]
+            }))
+        );
+
+        let frame = CanOpenFrame::from_socketcan_frame(
+            socketcan::CanFrame::new(socketcan::StandardId::new(0x082).unwrap(), &[0x00, 0x10])
+                .unwrap(),
+            DataLengthPolicy::Lenient,
+        );
+        assert!(frame.is_err());
+    }
+
+    #[test]
+    fn test_time_stamp_frame_to_socketcan_frame() {
+        let frame = to_socketcan_frame(TimeStampFrame::new(3_600_000, 14865));
+        assert_eq!(frame.raw_id(), 0x100);
+        assert_eq!(frame.data(), &[0x80, 0xEE, 0x36, 0x00, 0x11, 0x3A]);
+    }
+
+    #[test]
+    fn test_socketcan_frame_to_time_stamp_frame() {
+        let frame: Result<CanOpenFrame> = socketcan::CanFrame::new(
+            socketcan::StandardId::new(0x100).unwrap(),
+            &[0x80, 0xEE, 0x36, 0x00, 0x11, 0x3A],
+        )
+        .unwrap()
+        .try_into();
+        assert_eq!(
+            frame,
+            Ok(CanOpenFrame::TimeStampFrame(TimeStampFrame {
+                milliseconds: 3_600_000,
+                days: 14865
+            }))
+        );
+
+        let frame: Result<CanOpenFrame> =
+            socketcan::CanFrame::new(socketcan::StandardId::new(0x100).unwrap(), &[0x80, 0xEE])
+                .unwrap()
+                .try_into();
+        assert!(frame.is_err());
+    }
+
     #[test]
     fn test_sdo_frame_to_socketcan_frame() {
         let frame = to_socketcan_frame(SdoFrame::new_sdo_read_frame(
@@ -498,6 +602,181 @@ mod tests {
         );
     }
 
+    #[test]
+    fn test_pdo_frame_to_socketcan_frame() {
+        let frame = to_socketcan_frame(
+            PdoFrame::new(Direction::Tx, 1, 1.try_into().unwrap(), vec![0x37, 0x02]).unwrap(),
+        );
+        assert_eq!(frame.raw_id(), 0x181);
+        assert_eq!(frame.data(), &[0x37, 0x02]);
+
+        let frame = to_socketcan_frame(
+            PdoFrame::new(
+                Direction::Rx,
+                4,
+                127.try_into().unwrap(),
+                vec![0x0F, 0x00, 0xE8, 0x03, 0x00, 0x00, 0x00, 0x00],
+            )
+            .unwrap(),
+        );
+        assert_eq!(frame.raw_id(), 0x57F);
+        assert_eq!(
+            frame.data(),
+            &[0x0F, 0x00, 0xE8, 0x03, 0x00, 0x00, 0x00, 0x00]
+        );
+
+        let frame: socketcan::CanFrame =
+            CanOpenFrame::new_pdo_frame(Direction::Rx, 2, 3.try_into().unwrap(), vec![0x01])
+                .unwrap()
+                .into();
+        assert_eq!(frame.raw_id(), 0x303);
+        assert_eq!(frame.data(), &[0x01]);
+    }
+
+    #[test]
+    fn test_socketcan_frame_to_pdo_frame() {
+        let frame: Result<CanOpenFrame> =
+            socketcan::CanFrame::new(socketcan::StandardId::new(0x181).unwrap(), &[0x37, 0x02])
+                .unwrap()
+                .try_into();
+        assert_eq!(
+            frame,
+            Ok(CanOpenFrame::PdoFrame(PdoFrame {
+                direction: Direction::Tx,
+                number: 1,
+                node_id: 1.try_into().unwrap(),
+                data: vec![0x37, 0x02],
+            }))
+        );
+
+        let frame: Result<CanOpenFrame> = socketcan::CanFrame::new(
+            socketcan::StandardId::new(0x4FF).unwrap(),
+            &[0x00, 0x01, 0x02, 0x03, 0x04, 0x05, 0x06, 0x07],
+        )
+        .unwrap()
+        .try_into();
+        assert_eq!(
+            frame,
+            Ok(CanOpenFrame::PdoFrame(PdoFrame {
+                direction: Direction::Tx,
+                number: 4,
+                node_id: 127.try_into().unwrap(),
+                data: vec![0x00, 0x01, 0x02, 0x03, 0x04, 0x05, 0x06, 0x07],
+            }))
+        );
+
+        let frame: Result<CanOpenFrame> =
+            socketcan::CanFrame::new(socketcan::StandardId::new(0x405).unwrap(), &[])
+                .unwrap()
+                .try_into();
+        assert_eq!(
+            frame,
+            Ok(CanOpenFrame::PdoFrame(PdoFrame {
+                direction: Direction::Rx,
+                number: 3,
+                node_id: 5.try_into().unwrap(),
+                data: vec![],
+            }))
+        );
+    }
+
+    #[test]
+    fn test_lss_frame_to_socketcan_frame() {
+        let frame = to_socketcan_frame(LssFrame::new_request(LssRequest::SwitchStateSelective(
+            LssIdentityField::ProductCode,
+            0x1234_5678,
+        )));
+        assert_eq!(frame.raw_id(), 0x7E5);
+        assert_eq!(
+            frame.data(),
+            &[0x41, 0x78, 0x56, 0x34, 0x12, 0x00, 0x00, 0x00]
+        );
+
+        let frame = to_socketcan_frame(LssFrame::new_response(LssResponse::ConfigureNodeId {
+            error_code: 0,
+            specific_error_code: 0,
+        }));
+        assert_eq!(frame.raw_id(), 0x7E4);
+        assert_eq!(
+            frame.data(),
+            &[0x11, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00]
+        );
+    }
+
+    #[test]
+    fn test_socketcan_frame_to_lss_frame() {
+        let frame: Result<CanOpenFrame> = socketcan::CanFrame::new(
+            socketcan::StandardId::new(0x7E5).unwrap(),
+            &[0x11, 0x05, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00],
+        )
+        .unwrap()
+        .try_into();
+        assert_eq!(
+            frame,
+            Ok(CanOpenFrame::LssFrame(LssFrame::Request(
+                LssRequest::ConfigureNodeId(5)
+            )))
+        );
+
+        let frame: Result<CanOpenFrame> = socketcan::CanFrame::new(
+            socketcan::StandardId::new(0x7E4).unwrap(),
+            &[0x11, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00],
+        )
+        .unwrap()
+        .try_into();
+        assert_eq!(
+            frame,
+            Ok(CanOpenFrame::LssFrame(LssFrame::Response(
+                LssResponse::ConfigureNodeId {
+                    error_code: 0,
+                    specific_error_code: 0
+                }
+            )))
+        );
+    }
+
+    #[test]
+    fn test_remote_frame_to_socketcan_frame() {
+        let frame: socketcan::CanFrame =
+            CanOpenFrame::from(RemoteFrame::new_node_guarding(4.try_into().unwrap())).into();
+        assert!(frame.is_remote_frame());
+        assert_eq!(frame.raw_id(), 0x704);
+        assert_eq!(frame.dlc(), 1);
+
+        let frame: socketcan::CanFrame =
+            CanOpenFrame::from(RemoteFrame::new_pdo_request(2, 1.try_into().unwrap(), 8).unwrap())
+                .into();
+        assert!(frame.is_remote_frame());
+        assert_eq!(frame.raw_id(), 0x281);
+        assert_eq!(frame.dlc(), 8);
+    }
+
+    #[test]
+    fn test_socketcan_frame_to_remote_frame() {
+        let frame: Result<CanOpenFrame> =
+            socketcan::CanFrame::new_remote(socketcan::StandardId::new(0x705).unwrap(), 1)
+                .unwrap()
+                .try_into();
+        assert_eq!(
+            frame,
+            Ok(CanOpenFrame::RemoteFrame(RemoteFrame::new_node_guarding(
+                5.try_into().unwrap()
+            )))
+        );
+
+        let frame: Result<CanOpenFrame> =
+            socketcan::CanFrame::new_remote(socketcan::StandardId::new(0x000).unwrap(), 0)
+                .unwrap()
+                .try_into();
+        assert_eq!(
+            frame,
+            Ok(CanOpenFrame::RemoteFrame(RemoteFrame {
+                communication_object: CommunicationObject::NmtNodeControl,
+                data_length: 0
+            }))
+        );
+    }
+
     #[test]
     fn test_nmt_node_monitoring_frame_to_socketcan_frame() {
         let frame = to_socketcan_frame(NmtNodeMonitoringFrame::new(
@@ -541,6 +820,7 @@ mod tests {
                 NmtNodeMonitoringFrame {
                     node_id: 1.try_into().unwrap(),
                     state: NmtState::BootUp,
+                    toggle: false,
                 }
             ))
         );
@@ -555,6 +835,7 @@ mod tests {
                 NmtNodeMonitoringFrame {
                     node_id: 2.try_into().unwrap(),
                     state: NmtState::Stopped,
+                    toggle: false,
                 }
             ))
         );
@@ -569,6 +850,7 @@ mod tests {
                 NmtNodeMonitoringFrame {
                     node_id: 3.try_into().unwrap(),
                     state: NmtState::Operational,
+                    toggle: false,
                 }
             ))
         );
@@ -583,6 +865,7 @@ mod tests {
                 NmtNodeMonitoringFrame {
                     node_id: 4.try_into().unwrap(),
                     state: NmtState::PreOperational,
+                    toggle: false,
                 }
             ))
         );
@@ -591,18 +874,90 @@ mod tests {
             socketcan::CanFrame::new(socketcan::StandardId::new(0x705).unwrap(), &[0x01])
                 .unwrap()
                 .try_into();
-        assert_eq!(frame, Err(Error::InvalidNmtState(0x01)));
+        assert_eq!(frame.unwrap_err().reason(), &Error::InvalidNmtState(0x01));
 
         let frame: Result<CanOpenFrame> =
             socketcan::CanFrame::new(socketcan::StandardId::new(0x706).unwrap(), &[0x06])
                 .unwrap()
                 .try_into();
-        assert_eq!(frame, Err(Error::InvalidNmtState(0x06)));
+        assert_eq!(frame.unwrap_err().reason(), &Error::InvalidNmtState(0x06));
 
         let frame: Result<CanOpenFrame> =
             socketcan::CanFrame::new(socketcan::StandardId::new(0x708).unwrap(), &[0x80])
                 .unwrap()
                 .try_into();
-        assert_eq!(frame, Err(Error::InvalidNmtState(0x80)));
+        assert_eq!(frame.unwrap_err().reason(), &Error::InvalidNmtState(0x80));
+    }
+
+    #[test]
+    fn test_socketcan_frame_to_nmt_node_monitoring_frame_lenient() {
+        let frame = CanOpenFrame::from_socketcan_frame(
+            socketcan::CanFrame::new(
+                socketcan::StandardId::new(0x703).unwrap(),
+                &[0x05, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00],
+            )
+            .unwrap(),
+            DataLengthPolicy::Lenient,
+        );
+        assert_eq!(
+            frame,
+            Ok(CanOpenFrame::NmtNodeMonitoringFrame(
+                NmtNodeMonitoringFrame {
+                    node_id: 3.try_into().unwrap(),
+                    state: NmtState::Operational,
+                    toggle: false,
+                }
+            ))
+        );
+
+        let frame = CanOpenFrame::from_socketcan_frame(
+            socketcan::CanFrame::new(
+                socketcan::StandardId::new(0x703).unwrap(),
+                &[0x05, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00],
+            )
+            .unwrap(),
+            DataLengthPolicy::Strict,
+        );
+        assert!(frame.is_err());
+    }
+
+    #[test]
+    fn test_undecodable_frame() {
+        let frame: Result<CanOpenFrame> =
+            socketcan::CanFrame::new(socketcan::StandardId::new(0x000).unwrap(), &[0x03, 0x00])
+                .unwrap()
+                .try_into();
+        assert_eq!(
+            frame,
+            Err(Error::UndecodableFrame {
+                cob_id: 0x000,
+                data: vec![0x03, 0x00],
+                reason: Box::new(Error::InvalidNmtCommand(3))
+            })
+        );
+
+        let frame: Result<CanOpenFrame> =
+            socketcan::CanFrame::new(socketcan::StandardId::new(0x7FF).unwrap(), &[0xAB])
+                .unwrap()
+                .try_into();
+        let error = frame.unwrap_err();
+        assert_eq!(error.reason(), &Error::InvalidCobId(0x7FF));
+        assert_eq!(
+            error.to_string(),
+            "Undecodable frame (COB-ID 0x7FF, data [AB]): Invalid COB ID (7FF)"
+        );
+
+        let frame: Result<CanOpenFrame> =
+            socketcan::CanFrame::new(socketcan::ExtendedId::new(0x1234_5678).unwrap(), &[])
+                .unwrap()
+                .try_into();
+        assert_eq!(
+            frame,
+            Err(Error::UndecodableFrame {
+                cob_id: 0x1234_5678,
+                data: vec![],
+                reason: Box::new(Error::CanFdNotSupported)
+            })
+        );
     }
 }
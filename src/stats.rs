@@ -0,0 +1,222 @@
+use crate::error::Result;
+
+#[derive(Clone, Copy, Debug, Default, PartialEq)]
+pub struct FrameCount {
+    pub decoded: u64,
+    pub undecodable: u64,
+}
+
+impl FrameCount {
+    pub fn total(&self) -> u64 {
+        self.decoded + self.undecodable
+    }
+}
+
+// Per-COB-ID counters are capped so that traffic with arbitrary (e.g. extended) IDs cannot
+// grow memory without bound. Frames with IDs beyond the cap are counted in `overflow`.
+#[derive(Clone, Debug, PartialEq)]
+pub struct FrameStatistics {
+    counts: std::collections::BTreeMap<u32, FrameCount>,
+    max_entries: usize,
+    overflow: FrameCount,
+}
+
+impl Default for FrameStatistics {
+    fn default() -> Self {
+        Self::with_max_entries(Self::DEFAULT_MAX_ENTRIES)
+    }
+}
+
+impl FrameStatistics {
+    // Covers every 11-bit CAN ID.
+    pub const DEFAULT_MAX_ENTRIES: usize = 0x800;
+
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    pub fn with_max_entries(max_entries: usize) -> Self {
+        Self {
+            counts: std::collections::BTreeMap::new(),
+            max_entries,
+            overflow: FrameCount::default(),
+        }
+    }
+
+    pub fn max_entries(&self) -> usize {
+        self.max_entries
+    }
+
+    pub fn record<T>(&mut self, raw_id: u32, result: &Result<T>) {
+        let count = if self.counts.len() < self.max_entries || self.counts.contains_key(&raw_id) {
+            self.counts.entry(raw_id).or_default()
+        } else {
+            &mut self.overflow
+        };
+        match result {
+            Ok(_) => count.decoded += 1,
+            Err(_) => count.undecodable += 1,
+        }
+    }
+
+    pub fn count(&self, raw_id: u32) -> FrameCount {
+        self.counts.get(&raw_id).copied().unwrap_or_default()
+    }
+
+    // Frames whose IDs were not tracked individually because the cap was reached
+    pub fn overflow(&self) -> FrameCount {
+        self.overflow
+    }
+
+    pub fn total(&self) -> FrameCount {
+        self.counts
+            .values()
+            .fold(self.overflow, |total, count| FrameCount {
+                decoded: total.decoded + count.decoded,
+                undecodable: total.undecodable + count.undecodable,
+            })
+    }
+
+    pub fn histogram(&self) -> std::collections::BTreeMap<u32, FrameCount> {
+        self.counts.clone()
+    }
+
+    pub fn undecodable_histogram(&self) -> std::collections::BTreeMap<u32, u64> {
+        self.counts
+            .iter()
+            .filter(|(_, count)| count.undecodable > 0)
+            .map(|(raw_id, count)| (*raw_id, count.undecodable))
+            .collect()
+    }
+
+    pub fn clear(&mut self) {
+        self.counts.clear();
+        self.overflow = FrameCount::default();
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    use crate::error::Error;
+
+    #[test]
+    fn test_record() {
+        let mut stats = FrameStatistics::new();
+        stats.record(0x701, &Ok(()));
+        stats.record(0x701, &Ok(()));
+        stats.record(0x181, &Err::<(), _>(Error::NotImplemented));
+        stats.record(0x701, &Err::<(), _>(Error::InvalidNmtState(0x01)));
+
+        assert_eq!(
+            stats.count(0x701),
+            FrameCount {
+                decoded: 2,
+                undecodable: 1
+            }
+        );
+        assert_eq!(
+            stats.count(0x181),
+            FrameCount {
+                decoded: 0,
+                undecodable: 1
+            }
+        );
+        assert_eq!(stats.count(0x080), FrameCount::default());
+        assert_eq!(
+            stats.total(),
+            FrameCount {
+                decoded: 2,
+                undecodable: 2
+            }
+        );
+        assert_eq!(stats.total().total(), 4);
+    }
+
+    #[test]
+    fn test_histogram() {
+        let mut stats = FrameStatistics::new();
+        stats.record(0x080, &Ok(()));
+        stats.record(0x181, &Err::<(), _>(Error::NotImplemented));
+        stats.record(0x181, &Err::<(), _>(Error::NotImplemented));
+        stats.record(0x1234_5678, &Err::<(), _>(Error::CanFdNotSupported));
+
+        assert_eq!(
+            stats.histogram().into_iter().collect::<Vec<_>>(),
+            vec![
+                (
+                    0x080,
+                    FrameCount {
+                        decoded: 1,
+                        undecodable: 0
+                    }
+                ),
+                (
+                    0x181,
+                    FrameCount {
+                        decoded: 0,
+                        undecodable: 2
+                    }
+                ),
+                (
+                    0x1234_5678,
+                    FrameCount {
+                        decoded: 0,
+                        undecodable: 1
+                    }
+                ),
+            ]
+        );
+        assert_eq!(
+            stats
+                .undecodable_histogram()
+                .into_iter()
+                .collect::<Vec<_>>(),
+            vec![(0x181, 2), (0x1234_5678, 1)]
+        );
+
+        stats.clear();
+        assert!(stats.histogram().is_empty());
+    }
+
+    #[test]
+    fn test_max_entries() {
+        let mut stats = FrameStatistics::with_max_entries(2);
+        stats.record(0x080, &Ok(()));
+        stats.record(0x181, &Ok(()));
+        stats.record(0x281, &Err::<(), _>(Error::NotImplemented));
+        stats.record(0x080, &Ok(()));
+
+        assert_eq!(stats.histogram().len(), 2);
+        assert_eq!(stats.count(0x080).decoded, 2);
+        assert_eq!(stats.count(0x281), FrameCount::default());
+        assert_eq!(
+            stats.overflow(),
+            FrameCount {
+                decoded: 0,
+                undecodable: 1
+            }
+        );
+        assert_eq!(stats.total().total(), 4);
+
+        stats.clear();
+        assert_eq!(stats.overflow(), FrameCount::default());
+    }
+
+    #[test]
+    fn test_bounded_under_arbitrary_ids() {
+        let mut stats = FrameStatistics::new();
+        for raw_id in (0..0x1FFF_FFFF).step_by(0x1F1F) {
+            stats.record(raw_id, &Err::<(), _>(Error::NotImplemented));
+        }
+        assert_eq!(
+            stats.histogram().len(),
+            FrameStatistics::DEFAULT_MAX_ENTRIES
+        );
+        assert_eq!(
+            stats.total().undecodable,
+            (0..0x1FFF_FFFF).step_by(0x1F1F).count() as u64
+        );
+    }
+}
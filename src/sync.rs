@@ -0,0 +1,258 @@
+use crate::error::{Error, Result};
+use crate::frame::{RawFrame, SyncCobId};
+use crate::id::CommunicationObject;
+
+// SYNC producer (cf. CiA 301). While started, `poll` returns a SYNC frame every `period`, and
+// `next_due` tells the caller when to poll again. The frame is sent on the COB-ID of 0x1005,
+// with the synchronous counter of 0x1019 if configured, so it is a `RawFrame` rather than a
+// `SyncFrame`. A COB-ID without the generate bit never starts the producer.
+//
+// With jitter compensation, SYNCs are scheduled on a fixed grid from the start, so that a late
+// poll does not delay the following ones. SYNCs missed by more than a period are skipped
+// rather than sent in a burst. Without it, each SYNC is due a period after the previous one
+// was sent.
+#[derive(Clone, Debug)]
+pub struct SyncProducer {
+    period: std::time::Duration,
+    cob_id: SyncCobId,
+    counter_overflow: u8,
+    counter: u8,
+    jitter_compensation: bool,
+    next_due: Option<std::time::Instant>,
+    sent: u64,
+    skipped: u64,
+}
+
+impl SyncProducer {
+    pub fn new(period: std::time::Duration) -> Self {
+        Self {
+            period,
+            // The predefined SYNC, generated by this node
+            cob_id: SyncCobId::new(CommunicationObject::Sync.as_cob_id(), true).unwrap(),
+            counter_overflow: 0,
+            counter: 1,
+            jitter_compensation: true,
+            next_due: None,
+            sent: 0,
+            skipped: 0,
+        }
+    }
+
+    // From the communication cycle period (0x1006) in microseconds
+    pub fn from_communication_cycle_period(microseconds: u32) -> Self {
+        Self::new(std::time::Duration::from_micros(microseconds.into()))
+    }
+
+    pub fn with_cob_id(self, cob_id: SyncCobId) -> Self {
+        Self { cob_id, ..self }
+    }
+
+    // From the synchronous counter overflow value (0x1019). 0 sends SYNCs without a counter;
+    // 2-240 adds a counter running from 1 to that value.
+    pub fn with_counter_overflow(self, counter_overflow: u8) -> Result<Self> {
+        if counter_overflow == 1 || counter_overflow > 240 {
+            return Err(Error::InvalidSyncCounterOverflow(counter_overflow));
+        }
+        Ok(Self {
+            counter_overflow,
+            ..self
+        })
+    }
+
+    pub fn with_jitter_compensation(self, jitter_compensation: bool) -> Self {
+        Self {
+            jitter_compensation,
+            ..self
+        }
+    }
+
+    pub fn period(&self) -> std::time::Duration {
+        self.period
+    }
+
+    pub fn cob_id(&self) -> SyncCobId {
+        self.cob_id
+    }
+
+    // Takes effect from the next SYNC. A zero period stops the producer.
+    pub fn set_period(&mut self, period: std::time::Duration) {
+        self.period = period;
+        if period.is_zero() {
+            self.stop();
+        }
+    }
+
+    pub fn is_running(&self) -> bool {
+        self.next_due.is_some()
+    }
+
+    // The first SYNC is due immediately and the counter restarts from 1. A zero period or a
+    // COB-ID without the generate bit never starts the producer.
+    pub fn start(&mut self, now: std::time::Instant) {
+        if !self.period.is_zero() && self.cob_id.generate() && self.next_due.is_none() {
+            self.next_due = Some(now);
+            self.counter = 1;
+        }
+    }
+
+    pub fn stop(&mut self) {
+        self.next_due = None;
+    }
+
+    pub fn next_due(&self) -> Option<std::time::Instant> {
+        self.next_due
+    }
+
+    pub fn sent(&self) -> u64 {
+        self.sent
+    }
+
+    // Number of SYNCs skipped because `poll` was called too late
+    pub fn skipped(&self) -> u64 {
+        self.skipped
+    }
+
+    pub fn poll(&mut self, now: std::time::Instant) -> Option<RawFrame> {
+        let due = self.next_due?;
+        if now < due {
+            return None;
+        }
+        let next_due = if self.jitter_compensation {
+            let late = now.duration_since(due);
+            let missed = (late.as_nanos() / self.period.as_nanos()) as u32;
+            self.skipped += missed as u64;
+            due + self.period * (missed + 1)
+        } else {
+            now + self.period
+        };
+        self.next_due = Some(next_due);
+        self.sent += 1;
+        if self.counter_overflow == 0 {
+            return RawFrame::new(self.cob_id.cob_id(), &[]).ok();
+        }
+        let counter = self.counter;
+        self.counter = if counter >= self.counter_overflow {
+            1
+        } else {
+            counter + 1
+        };
+        RawFrame::new(self.cob_id.cob_id(), &[counter]).ok()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    use std::time::Duration;
+
+    fn sync(cob_id: u16, data: &[u8]) -> Option<RawFrame> {
+        Some(RawFrame::new(cob_id, data).unwrap())
+    }
+
+    #[test]
+    fn test_start_stop() {
+        let now = std::time::Instant::now();
+        let mut producer = SyncProducer::new(Duration::from_millis(10));
+        assert_eq!(producer.poll(now), None);
+        producer.start(now);
+        assert!(producer.is_running());
+        assert_eq!(producer.poll(now), sync(0x080, &[]));
+        assert_eq!(producer.poll(now + Duration::from_millis(9)), None);
+        assert_eq!(
+            producer.poll(now + Duration::from_millis(10)),
+            sync(0x080, &[])
+        );
+        producer.stop();
+        assert_eq!(producer.next_due(), None);
+        assert_eq!(producer.poll(now + Duration::from_millis(20)), None);
+        assert_eq!(producer.sent(), 2);
+
+        let mut producer = SyncProducer::from_communication_cycle_period(0);
+        producer.start(now);
+        assert!(!producer.is_running());
+    }
+
+    #[test]
+    fn test_cob_id() {
+        let now = std::time::Instant::now();
+        let mut producer = SyncProducer::new(Duration::from_millis(10))
+            .with_cob_id(SyncCobId::new(0x081, true).unwrap());
+        producer.start(now);
+        assert_eq!(producer.poll(now), sync(0x081, &[]));
+
+        // Not the SYNC producer
+        let mut producer = SyncProducer::new(Duration::from_millis(10))
+            .with_cob_id(SyncCobId::new(0x081, false).unwrap());
+        producer.start(now);
+        assert!(!producer.is_running());
+        assert_eq!(producer.poll(now), None);
+    }
+
+    #[test]
+    fn test_counter() {
+        let now = std::time::Instant::now();
+        let mut producer = SyncProducer::new(Duration::from_millis(10))
+            .with_counter_overflow(3)
+            .unwrap();
+        producer.start(now);
+        let counters = (0..5)
+            .map(|i| {
+                producer
+                    .poll(now + Duration::from_millis(10) * i)
+                    .unwrap()
+                    .data()
+                    .to_vec()
+            })
+            .collect::<Vec<_>>();
+        assert_eq!(counters, vec![vec![1], vec![2], vec![3], vec![1], vec![2]]);
+
+        // Restarted from 1
+        producer.stop();
+        producer.start(now + Duration::from_millis(100));
+        assert_eq!(
+            producer.poll(now + Duration::from_millis(100)),
+            sync(0x080, &[1])
+        );
+
+        for counter_overflow in [1, 241] {
+            assert!(matches!(
+                SyncProducer::new(Duration::from_millis(10)).with_counter_overflow(counter_overflow),
+                Err(Error::InvalidSyncCounterOverflow(value)) if value == counter_overflow
+            ));
+        }
+    }
+
+    #[test]
+    fn test_jitter_compensation() {
+        let now = std::time::Instant::now();
+        let mut producer = SyncProducer::new(Duration::from_millis(10));
+        producer.start(now);
+        producer.poll(now);
+        // A late poll does not shift the grid.
+        producer.poll(now + Duration::from_millis(13));
+        assert_eq!(producer.next_due(), Some(now + Duration::from_millis(20)));
+        // Missed SYNCs are skipped.
+        producer.poll(now + Duration::from_millis(45));
+        assert_eq!(producer.next_due(), Some(now + Duration::from_millis(50)));
+        assert_eq!(producer.skipped(), 2);
+        assert_eq!(producer.sent(), 3);
+    }
+
+    #[test]
+    fn test_without_jitter_compensation() {
+        let now = std::time::Instant::now();
+        let mut producer =
+            SyncProducer::new(Duration::from_millis(10)).with_jitter_compensation(false);
+        producer.start(now);
+        producer.poll(now);
+        producer.poll(now + Duration::from_millis(13));
+        assert_eq!(producer.next_due(), Some(now + Duration::from_millis(23)));
+        producer.set_period(Duration::from_millis(5));
+        producer.poll(now + Duration::from_millis(23));
+        assert_eq!(producer.next_due(), Some(now + Duration::from_millis(28)));
+        assert_eq!(producer.skipped(), 0);
+        producer.set_period(Duration::ZERO);
+        assert!(!producer.is_running());
+    }
+}
@@ -0,0 +1,225 @@
+use crate::error::{Error, Result};
+use crate::frame::TimeStampFrame;
+use crate::object::ConfigurationDateTime;
+
+// A point in time read from a time source
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub struct TimeReading {
+    // UTC since the UNIX epoch, without leap seconds
+    pub utc: std::time::Duration,
+    // Whether the reading is within an inserted leap second (23:59:60), which `utc` cannot
+    // represent: it repeats 23:59:59 meanwhile.
+    pub leap_second: bool,
+}
+
+impl TimeReading {
+    // 1980-01-06T00:00:00Z in seconds since the UNIX epoch
+    const GPS_EPOCH_UNIX_SECONDS: u64 = 315_964_800;
+    // TAI − GPS time
+    const TAI_GPS_OFFSET_SECONDS: u16 = 19;
+
+    pub fn from_utc(utc: std::time::Duration) -> Self {
+        Self {
+            utc,
+            leap_second: false,
+        }
+    }
+
+    // From TAI since the UNIX epoch, e.g. a PTP clock. `utc_offset` is TAI − UTC in seconds (the
+    // current UTC offset announced by the grandmaster).
+    pub fn from_tai(tai: std::time::Duration, utc_offset: u16) -> Result<Self> {
+        tai.checked_sub(std::time::Duration::from_secs(utc_offset.into()))
+            .map(Self::from_utc)
+            .ok_or(Error::TimeOutOfRange)
+    }
+
+    // From GPS time since the GPS epoch (1980-01-06). `utc_offset` is GPS time − UTC in seconds
+    // (the leap seconds broadcast in the navigation message).
+    pub fn from_gps(gps: std::time::Duration, utc_offset: u16) -> Result<Self> {
+        Self::from_tai(
+            gps + std::time::Duration::from_secs(
+                Self::GPS_EPOCH_UNIX_SECONDS + Self::TAI_GPS_OFFSET_SECONDS as u64,
+            ),
+            utc_offset + Self::TAI_GPS_OFFSET_SECONDS,
+        )
+    }
+
+    pub fn with_leap_second(self, leap_second: bool) -> Self {
+        Self {
+            leap_second,
+            ..self
+        }
+    }
+
+    // CANopen time has no 61st second: an inserted leap second is reported as the last
+    // millisecond of the day.
+    pub fn to_time_stamp(&self) -> Result<TimeStampFrame> {
+        let since_epoch = self
+            .utc
+            .checked_sub(std::time::Duration::from_secs(
+                ConfigurationDateTime::EPOCH_UNIX_SECONDS,
+            ))
+            .ok_or(Error::TimeOutOfRange)?
+            .as_millis();
+        let days = since_epoch / ConfigurationDateTime::MILLISECONDS_PER_DAY;
+        let milliseconds = if self.leap_second {
+            ConfigurationDateTime::MILLISECONDS_PER_DAY - 1
+        } else {
+            since_epoch % ConfigurationDateTime::MILLISECONDS_PER_DAY
+        };
+        Ok(TimeStampFrame::new(
+            milliseconds as u32,
+            days.try_into().map_err(|_| Error::TimeOutOfRange)?,
+        ))
+    }
+}
+
+// The clock the TIME producer distributes. Implement it for PTP-disciplined clocks or GPS
+// receivers to match the plant-wide time.
+pub trait TimeSource {
+    fn read(&mut self) -> Result<TimeReading>;
+}
+
+// The system clock. It knows nothing of leap seconds.
+#[derive(Clone, Copy, Debug, Default)]
+pub struct SystemClock;
+
+impl TimeSource for SystemClock {
+    fn read(&mut self) -> Result<TimeReading> {
+        std::time::SystemTime::now()
+            .duration_since(std::time::UNIX_EPOCH)
+            .map(TimeReading::from_utc)
+            .map_err(|_| Error::TimeOutOfRange)
+    }
+}
+
+// TIME producer (cf. CiA 301). `poll` returns the TIME frame to transmit every `period`, read
+// from the time source when it is due.
+#[derive(Clone, Debug)]
+pub struct TimeProducer<S: TimeSource = SystemClock> {
+    source: S,
+    period: std::time::Duration,
+    sent_at: Option<std::time::Instant>,
+}
+
+impl<S: TimeSource> TimeProducer<S> {
+    pub fn new(source: S, period: std::time::Duration) -> Self {
+        Self {
+            source,
+            period,
+            sent_at: None,
+        }
+    }
+
+    pub fn source(&self) -> &S {
+        &self.source
+    }
+
+    pub fn source_mut(&mut self) -> &mut S {
+        &mut self.source
+    }
+
+    // The TIME frame for the current time, regardless of the period
+    pub fn frame(&mut self) -> Result<TimeStampFrame> {
+        self.source.read()?.to_time_stamp()
+    }
+
+    pub fn poll(&mut self, now: std::time::Instant) -> Result<Option<TimeStampFrame>> {
+        let due = self
+            .sent_at
+            .is_none_or(|sent_at| now.saturating_duration_since(sent_at) >= self.period);
+        if !due {
+            return Ok(None);
+        }
+        let frame = self.frame()?;
+        self.sent_at = Some(now);
+        Ok(Some(frame))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    use std::time::Duration;
+
+    // 1985-01-01T01:00:00Z
+    const UNIX_SECONDS: u64 = 473_389_200;
+
+    struct FixedClock(TimeReading);
+
+    impl TimeSource for FixedClock {
+        fn read(&mut self) -> Result<TimeReading> {
+            Ok(self.0)
+        }
+    }
+
+    #[test]
+    fn test_to_time_stamp() {
+        assert_eq!(
+            TimeReading::from_utc(Duration::from_millis(UNIX_SECONDS * 1000 + 250)).to_time_stamp(),
+            Ok(TimeStampFrame::new(3_600_250, 366))
+        );
+        assert_eq!(
+            TimeReading::from_utc(Duration::from_secs(UNIX_SECONDS))
+                .with_leap_second(true)
+                .to_time_stamp(),
+            Ok(TimeStampFrame::new(86_399_999, 366))
+        );
+        assert_eq!(
+            TimeReading::from_utc(Duration::from_secs(0)).to_time_stamp(),
+            Err(Error::TimeOutOfRange)
+        );
+        assert_eq!(
+            TimeReading::from_utc(Duration::from_secs(
+                ConfigurationDateTime::EPOCH_UNIX_SECONDS + 65_536 * 86_400
+            ))
+            .to_time_stamp(),
+            Err(Error::TimeOutOfRange)
+        );
+    }
+
+    #[test]
+    fn test_from_tai_gps() {
+        assert_eq!(
+            TimeReading::from_tai(Duration::from_secs(UNIX_SECONDS + 37), 37),
+            Ok(TimeReading::from_utc(Duration::from_secs(UNIX_SECONDS)))
+        );
+        assert_eq!(
+            TimeReading::from_tai(Duration::from_secs(1), 37),
+            Err(Error::TimeOutOfRange)
+        );
+        assert_eq!(
+            TimeReading::from_gps(
+                Duration::from_secs(UNIX_SECONDS - TimeReading::GPS_EPOCH_UNIX_SECONDS + 18),
+                18
+            ),
+            Ok(TimeReading::from_utc(Duration::from_secs(UNIX_SECONDS)))
+        );
+    }
+
+    #[test]
+    fn test_poll() {
+        let now = std::time::Instant::now();
+        let mut producer = TimeProducer::new(
+            FixedClock(TimeReading::from_utc(Duration::from_secs(UNIX_SECONDS))),
+            Duration::from_secs(1),
+        );
+        assert_eq!(
+            producer.poll(now),
+            Ok(Some(TimeStampFrame::new(3_600_000, 366)))
+        );
+        assert_eq!(producer.poll(now + Duration::from_millis(999)), Ok(None));
+        producer.source_mut().0.leap_second = true;
+        assert_eq!(
+            producer.poll(now + Duration::from_secs(1)),
+            Ok(Some(TimeStampFrame::new(86_399_999, 366)))
+        );
+    }
+
+    #[test]
+    fn test_system_clock() {
+        let mut producer = TimeProducer::new(SystemClock, Duration::from_secs(1));
+        assert!(producer.frame().unwrap().days > 14000);
+    }
+}
@@ -0,0 +1,515 @@
+#[cfg(feature = "dcf")]
+use crate::dcf::Dcf;
+use crate::error::{Error, Result};
+use crate::frame::PdoFrame;
+use crate::id::NodeId;
+use crate::json::json_string;
+use crate::lss::LssIdentity;
+use crate::object::DeviceType;
+#[cfg(feature = "dcf")]
+use crate::od::Value;
+use crate::sdo::SdoDownload;
+
+const RPDO_COMMUNICATION_INDEX: u16 = 0x1400;
+const TPDO_COMMUNICATION_INDEX: u16 = 0x1800;
+#[cfg(feature = "dcf")]
+const PDO_COUNT: u16 = 512;
+const PDO_INVALID_BIT: u32 = 1 << 31;
+#[cfg(feature = "dcf")]
+const PDO_COB_ID_MASK: u32 = 0x07FF;
+
+// What is known about a node of the network, from a scan and/or its configuration
+#[derive(Clone, Debug, PartialEq)]
+pub struct NodeDescription {
+    pub node_id: NodeId,
+    pub name: Option<std::string::String>,
+    pub device_type: Option<DeviceType>,
+    pub identity: Option<LssIdentity>,
+    // (PDO number, COB-ID) of the valid PDOs
+    pub tpdos: std::vec::Vec<(u16, u16)>,
+    pub rpdos: std::vec::Vec<(u16, u16)>,
+}
+
+impl NodeDescription {
+    pub fn new(node_id: NodeId) -> Self {
+        Self {
+            node_id,
+            name: None,
+            device_type: None,
+            identity: None,
+            tpdos: std::vec::Vec::new(),
+            rpdos: std::vec::Vec::new(),
+        }
+    }
+
+    // Takes the PDO COB-IDs (sub-index 1 of 0x1400-0x15FF and 0x1800-0x19FF) from `dcf`.
+    #[cfg(feature = "dcf")]
+    pub fn from_dcf(dcf: &Dcf, node_id: NodeId) -> Result<Self> {
+        Ok(Self {
+            rpdos: pdo_cob_ids(dcf, node_id, RPDO_COMMUNICATION_INDEX)?,
+            tpdos: pdo_cob_ids(dcf, node_id, TPDO_COMMUNICATION_INDEX)?,
+            ..Self::new(node_id)
+        })
+    }
+}
+
+impl NodeDescription {
+    fn set_pdo(&mut self, transmit: bool, number: u16, cob_id: u16) {
+        let pdos = if transmit {
+            &mut self.tpdos
+        } else {
+            &mut self.rpdos
+        };
+        match pdos.binary_search_by_key(&number, |(number, _)| *number) {
+            Ok(position) => pdos[position].1 = cob_id,
+            Err(position) => pdos.insert(position, (number, cob_id)),
+        }
+    }
+}
+
+// COB-IDs restricted by CiA 301, and those of SYNC, EMCY and TIME in the predefined connection
+// set
+// PDO frames are only represented with the COB-IDs of the predefined connection set, so links
+// are limited to those, which also keeps them clear of the COB-IDs reserved by CiA 301.
+fn is_linkable_cob_id(cob_id: u16) -> bool {
+    PdoFrame::new_with_cob_id(cob_id, std::vec::Vec::new()).is_ok()
+}
+
+#[cfg(feature = "dcf")]
+fn pdo_cob_ids(dcf: &Dcf, node_id: NodeId, base: u16) -> Result<std::vec::Vec<(u16, u16)>> {
+    let mut cob_ids = std::vec::Vec::new();
+    for offset in 0..PDO_COUNT {
+        let Some(entry) = dcf.entry(base + offset, 1) else {
+            continue;
+        };
+        if let Some(Value::Unsigned32(value)) = entry.value(node_id)? {
+            if value & PDO_INVALID_BIT == 0 {
+                cob_ids.push((offset + 1, (value & PDO_COB_ID_MASK) as u16));
+            }
+        }
+    }
+    Ok(cob_ids)
+}
+
+// A TPDO of one node received as an RPDO by another
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub struct PdoLink {
+    pub cob_id: u16,
+    pub producer: NodeId,
+    pub tpdo: u16,
+    pub consumer: NodeId,
+    pub rpdo: u16,
+}
+
+// A description of a network, exportable as JSON or Graphviz (DOT)
+#[derive(Clone, Debug, Default, PartialEq)]
+pub struct Topology {
+    nodes: std::vec::Vec<NodeDescription>,
+}
+
+impl Topology {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    // Adds a node, replacing any with the same node ID. Nodes are kept sorted by node ID.
+    pub fn insert(&mut self, node: NodeDescription) {
+        let raw_id = node.node_id.as_raw();
+        match self
+            .nodes
+            .binary_search_by_key(&raw_id, |node| node.node_id.as_raw())
+        {
+            Ok(position) => self.nodes[position] = node,
+            Err(position) => self.nodes.insert(position, node),
+        }
+    }
+
+    pub fn nodes(&self) -> &[NodeDescription] {
+        &self.nodes
+    }
+
+    // Matches the TPDO COB-IDs of every node with the RPDO COB-IDs of the others.
+    pub fn pdo_links(&self) -> std::vec::Vec<PdoLink> {
+        let mut links = std::vec::Vec::new();
+        for producer in &self.nodes {
+            for (tpdo, cob_id) in &producer.tpdos {
+                for consumer in &self.nodes {
+                    if consumer.node_id == producer.node_id {
+                        continue;
+                    }
+                    for (rpdo, _) in consumer.rpdos.iter().filter(|(_, id)| id == cob_id) {
+                        links.push(PdoLink {
+                            cob_id: *cob_id,
+                            producer: producer.node_id,
+                            tpdo: *tpdo,
+                            consumer: consumer.node_id,
+                            rpdo: *rpdo,
+                        });
+                    }
+                }
+            }
+        }
+        links
+    }
+
+    // Links TPDO `tpdo` of `producer` to RPDO `rpdo` of `consumer` on `cob_id`, so that the
+    // consumer receives the producer's data directly. Fails if the COB-ID is not a PDO COB-ID
+    // of the predefined connection set (0x181-0x57F except 0x200, 0x280, ...), which is all
+    // `PdoFrame` can represent, or if it is transmitted by another TPDO. Returns the SDO
+    // downloads that configure both nodes; each COB-ID is invalidated before it is changed.
+    pub fn link_pdo(
+        &mut self,
+        producer: NodeId,
+        tpdo: u8,
+        consumer: NodeId,
+        rpdo: u8,
+        cob_id: u16,
+    ) -> Result<std::vec::Vec<SdoDownload>> {
+        for number in [tpdo, rpdo] {
+            if number == 0 {
+                return Err(Error::InvalidPdoNumber(number));
+            }
+        }
+        if producer == consumer || !is_linkable_cob_id(cob_id) {
+            return Err(Error::InvalidCobId(cob_id.into()));
+        }
+        for node in &self.nodes {
+            for (number, used) in &node.tpdos {
+                if *used == cob_id && !(node.node_id == producer && *number == tpdo as u16) {
+                    return Err(Error::PdoCobIdCollision {
+                        cob_id,
+                        node_id: node.node_id,
+                        pdo: *number,
+                    });
+                }
+            }
+        }
+
+        self.node_mut(producer).set_pdo(true, tpdo.into(), cob_id);
+        self.node_mut(consumer).set_pdo(false, rpdo.into(), cob_id);
+        let mut downloads = std::vec::Vec::new();
+        for (node_id, index) in [
+            (producer, TPDO_COMMUNICATION_INDEX + tpdo as u16 - 1),
+            (consumer, RPDO_COMMUNICATION_INDEX + rpdo as u16 - 1),
+        ] {
+            for value in [PDO_INVALID_BIT | cob_id as u32, cob_id as u32] {
+                downloads.push(SdoDownload::new(
+                    node_id,
+                    index,
+                    1,
+                    value.to_le_bytes().into(),
+                ));
+            }
+        }
+        Ok(downloads)
+    }
+
+    fn node_mut(&mut self, node_id: NodeId) -> &mut NodeDescription {
+        let raw_id = node_id.as_raw();
+        let position = match self
+            .nodes
+            .binary_search_by_key(&raw_id, |node| node.node_id.as_raw())
+        {
+            Ok(position) => position,
+            Err(position) => {
+                self.nodes.insert(position, NodeDescription::new(node_id));
+                position
+            }
+        };
+        &mut self.nodes[position]
+    }
+
+    pub fn to_json(&self) -> std::string::String {
+        let nodes = self
+            .nodes
+            .iter()
+            .map(|node| {
+                format!(
+                    "{{\"node_id\":{},\"name\":{},\"device_profile_number\":{},\"identity\":{},\"tpdos\":{},\"rpdos\":{}}}",
+                    node.node_id,
+                    node.name
+                        .as_deref()
+                        .map_or("null".to_owned(), json_string),
+                    node.device_type
+                        .map_or("null".to_owned(), |device_type| {
+                            device_type.device_profile_number.to_string()
+                        }),
+                    node.identity.map_or("null".to_owned(), |identity| {
+                        format!(
+                            "{{\"vendor_id\":{},\"product_code\":{},\"revision_number\":{},\"serial_number\":{}}}",
+                            identity.vendor_id,
+                            identity.product_code,
+                            identity.revision_number,
+                            identity.serial_number
+                        )
+                    }),
+                    json_pdos(&node.tpdos),
+                    json_pdos(&node.rpdos),
+                )
+            })
+            .collect::<std::vec::Vec<_>>();
+        let links = self
+            .pdo_links()
+            .iter()
+            .map(|link| {
+                format!(
+                    "{{\"cob_id\":{},\"producer\":{},\"tpdo\":{},\"consumer\":{},\"rpdo\":{}}}",
+                    link.cob_id, link.producer, link.tpdo, link.consumer, link.rpdo
+                )
+            })
+            .collect::<std::vec::Vec<_>>();
+        format!(
+            "{{\"nodes\":[{}],\"pdo_links\":[{}]}}",
+            nodes.join(","),
+            links.join(",")
+        )
+    }
+
+    pub fn to_dot(&self) -> std::string::String {
+        let mut dot = "digraph canopen {\n".to_owned();
+        for node in &self.nodes {
+            let mut label = format!("{}", node.node_id);
+            if let Some(name) = &node.name {
+                label += &format!(": {}", name);
+            }
+            if let Some(device_type) = node.device_type.filter(DeviceType::has_device_profile) {
+                label += &format!("\\nCiA {}", device_type.device_profile_number);
+            }
+            dot += &format!(
+                "    node{} [label=\"{}\"];\n",
+                node.node_id,
+                label.replace('"', "\\\"")
+            );
+        }
+        for link in self.pdo_links() {
+            dot += &format!(
+                "    node{} -> node{} [label=\"TPDO{} -> RPDO{} (0x{:03X})\"];\n",
+                link.producer, link.consumer, link.tpdo, link.rpdo, link.cob_id
+            );
+        }
+        dot += "}\n";
+        dot
+    }
+}
+
+fn json_pdos(pdos: &[(u16, u16)]) -> std::string::String {
+    let pdos = pdos
+        .iter()
+        .map(|(number, cob_id)| format!("{{\"number\":{},\"cob_id\":{}}}", number, cob_id))
+        .collect::<std::vec::Vec<_>>();
+    format!("[{}]", pdos.join(","))
+}
+
+// The fixtures are DCFs.
+#[cfg(all(test, feature = "dcf"))]
+mod tests {
+    use super::*;
+
+    use crate::frame::{CanOpenFrame, ConvertibleFrame, DataLengthPolicy};
+
+    const PRODUCER_DCF: &str = "
+[DeviceComissioning]
+NodeID=1
+
+[1800sub1]
+ParameterName=COB-ID used by TPDO 1
+DataType=0x0007
+AccessType=rw
+ParameterValue=$NODEID+0x180
+
+[1801sub1]
+ParameterName=COB-ID used by TPDO 2
+DataType=0x0007
+AccessType=rw
+ParameterValue=0x80000280
+";
+
+    const CONSUMER_DCF: &str = "
+[1400sub1]
+ParameterName=COB-ID used by RPDO 1
+DataType=0x0007
+AccessType=rw
+ParameterValue=0x181
+";
+
+    fn topology() -> Topology {
+        let mut topology = Topology::new();
+        let consumer = NodeDescription {
+            name: Some("Drive \"A\"".to_owned()),
+            device_type: Some(DeviceType::new(402, 0x0002)),
+            ..NodeDescription::from_dcf(&Dcf::parse(CONSUMER_DCF).unwrap(), 2.try_into().unwrap())
+                .unwrap()
+        };
+        topology.insert(consumer);
+        let producer = NodeDescription {
+            identity: Some(LssIdentity {
+                vendor_id: 1,
+                product_code: 2,
+                revision_number: 3,
+                serial_number: 4,
+            }),
+            ..NodeDescription::from_dcf(&Dcf::parse(PRODUCER_DCF).unwrap(), 1.try_into().unwrap())
+                .unwrap()
+        };
+        topology.insert(producer);
+        topology
+    }
+
+    #[test]
+    fn test_from_dcf() {
+        let node =
+            NodeDescription::from_dcf(&Dcf::parse(PRODUCER_DCF).unwrap(), 1.try_into().unwrap())
+                .unwrap();
+        assert_eq!(node.tpdos, vec![(1, 0x181)]);
+        assert!(node.rpdos.is_empty());
+    }
+
+    #[test]
+    fn test_insert() {
+        let mut topology = topology();
+        assert_eq!(
+            topology
+                .nodes()
+                .iter()
+                .map(|node| node.node_id.as_raw())
+                .collect::<Vec<_>>(),
+            vec![1, 2]
+        );
+        topology.insert(NodeDescription::new(2.try_into().unwrap()));
+        assert_eq!(topology.nodes().len(), 2);
+        assert_eq!(topology.nodes()[1].name, None);
+    }
+
+    #[test]
+    fn test_pdo_links() {
+        assert_eq!(
+            topology().pdo_links(),
+            vec![PdoLink {
+                cob_id: 0x181,
+                producer: 1.try_into().unwrap(),
+                tpdo: 1,
+                consumer: 2.try_into().unwrap(),
+                rpdo: 1,
+            }]
+        );
+    }
+
+    #[test]
+    fn test_link_pdo() {
+        let mut topology = topology();
+        let downloads = topology
+            .link_pdo(2.try_into().unwrap(), 1, 1.try_into().unwrap(), 2, 0x182)
+            .unwrap();
+        let requests = downloads
+            .into_iter()
+            .map(|mut download| download.start())
+            .collect::<Vec<_>>();
+        assert_eq!(
+            requests
+                .iter()
+                .map(|request| (request.node_id().as_raw(), request.data().to_vec()))
+                .collect::<Vec<_>>(),
+            vec![
+                (2, vec![0x23, 0x00, 0x18, 0x01, 0x82, 0x01, 0x00, 0x80]),
+                (2, vec![0x23, 0x00, 0x18, 0x01, 0x82, 0x01, 0x00, 0x00]),
+                (1, vec![0x23, 0x01, 0x14, 0x01, 0x82, 0x01, 0x00, 0x80]),
+                (1, vec![0x23, 0x01, 0x14, 0x01, 0x82, 0x01, 0x00, 0x00]),
+            ]
+        );
+        assert_eq!(topology.nodes()[1].tpdos, vec![(1, 0x182)]);
+        assert_eq!(topology.nodes()[0].rpdos, vec![(2, 0x182)]);
+        assert_eq!(topology.pdo_links().len(), 2);
+
+        // Relinking the same TPDO is not a collision.
+        assert!(topology
+            .link_pdo(2.try_into().unwrap(), 1, 3.try_into().unwrap(), 1, 0x182)
+            .is_ok());
+        assert_eq!(topology.nodes().len(), 3);
+    }
+
+    #[test]
+    fn test_link_pdo_round_trip() {
+        let mut topology = topology();
+        for cob_id in [0x182, 0x2A5, 0x57F] {
+            topology
+                .link_pdo(2.try_into().unwrap(), 2, 3.try_into().unwrap(), 1, cob_id)
+                .unwrap();
+            // What the producer transmits on the linked COB-ID decodes to the same frame.
+            let frame: CanOpenFrame = PdoFrame::new_with_cob_id(cob_id, vec![0x01, 0x02])
+                .unwrap()
+                .into();
+            let cob = frame.communication_object();
+            assert_eq!(cob.as_cob_id(), cob_id);
+            assert_eq!(
+                CanOpenFrame::new_with_bytes(cob, &frame.frame_data(), DataLengthPolicy::Strict),
+                Ok(frame)
+            );
+        }
+    }
+
+    #[test]
+    fn test_link_pdo_errors() {
+        let mut topology = topology();
+        assert_eq!(
+            topology
+                .link_pdo(2.try_into().unwrap(), 1, 3.try_into().unwrap(), 1, 0x181)
+                .err(),
+            Some(Error::PdoCobIdCollision {
+                cob_id: 0x181,
+                node_id: 1.try_into().unwrap(),
+                pdo: 1
+            })
+        );
+        for cob_id in [0x000, 0x080, 0x200, 0x581, 0x601, 0x680, 0x701, 0x800] {
+            assert_eq!(
+                topology
+                    .link_pdo(2.try_into().unwrap(), 1, 3.try_into().unwrap(), 1, cob_id)
+                    .err(),
+                Some(Error::InvalidCobId(cob_id.into()))
+            );
+        }
+        assert_eq!(
+            topology
+                .link_pdo(2.try_into().unwrap(), 1, 2.try_into().unwrap(), 1, 0x182)
+                .err(),
+            Some(Error::InvalidCobId(0x182))
+        );
+        assert_eq!(
+            topology
+                .link_pdo(2.try_into().unwrap(), 0, 3.try_into().unwrap(), 1, 0x182)
+                .err(),
+            Some(Error::InvalidPdoNumber(0))
+        );
+        assert_eq!(topology.nodes().len(), 2);
+    }
+
+    #[test]
+    fn test_to_json() {
+        assert_eq!(
+            topology().to_json(),
+            concat!(
+                r#"{"nodes":["#,
+                r#"{"node_id":1,"name":null,"device_profile_number":null,"#,
+                r#""identity":{"vendor_id":1,"product_code":2,"revision_number":3,"serial_number":4},"#,
+                r#""tpdos":[{"number":1,"cob_id":385}],"rpdos":[]},"#,
+                r#"{"node_id":2,"name":"Drive \"A\"","device_profile_number":402,"identity":null,"#,
+                r#""tpdos":[],"rpdos":[{"number":1,"cob_id":385}]}],"#,
+                r#""pdo_links":[{"cob_id":385,"producer":1,"tpdo":1,"consumer":2,"rpdo":1}]}"#
+            )
+        );
+    }
+
+    #[test]
+    fn test_to_dot() {
+        assert_eq!(
+            topology().to_dot(),
+            concat!(
+                "digraph canopen {\n",
+                "    node1 [label=\"1\"];\n",
+                "    node2 [label=\"2: Drive \\\"A\\\"\\nCiA 402\"];\n",
+                "    node1 -> node2 [label=\"TPDO1 -> RPDO1 (0x181)\"];\n",
+                "}\n"
+            )
+        );
+    }
+}
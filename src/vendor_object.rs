@@ -0,0 +1,169 @@
+// Manufacturer-specific profile area of the object dictionary (cf. CiA 301).
+pub const INDEX_RANGE: std::ops::RangeInclusive<u16> = 0x2000..=0x5FFF;
+
+pub const fn is_vendor_index(index: u16) -> bool {
+    index >= *INDEX_RANGE.start() && index <= *INDEX_RANGE.end()
+}
+
+// Defines a namespace (module) of manufacturer-specific objects. Each entry becomes a unit
+// struct with INDEX/SUB_INDEX constants, SDO frame builders according to its access type
+// (ro, wo or rw), and little-endian value decoding. An index outside 0x2000-0x5FFF fails to
+// compile.
+//
+// vendor_objects! {
+//     pub mod acme {
+//         MaxCurrent: 0x2000, 0, u16, rw;
+//         Temperature: 0x2001, 1, i16, ro;
+//     }
+// }
+#[macro_export]
+macro_rules! vendor_objects {
+    (
+        $(#[$meta:meta])*
+        $vis:vis mod $namespace:ident {
+            $(
+                $(#[$object_meta:meta])*
+                $object:ident: $index:literal, $sub_index:literal, $t:ty, $access:ident;
+            )*
+        }
+    ) => {
+        $(#[$meta])*
+        $vis mod $namespace {
+            $(
+                $(#[$object_meta])*
+                #[derive(Clone, Copy, Debug, PartialEq)]
+                pub struct $object;
+
+                const _: () = assert!(
+                    $crate::vendor_object::is_vendor_index($index),
+                    concat!(
+                        "Object ",
+                        stringify!($object),
+                        " is outside the manufacturer-specific area"
+                    )
+                );
+
+                impl $object {
+                    pub const INDEX: u16 = $index;
+                    pub const SUB_INDEX: u8 = $sub_index;
+                    pub const NAME: &'static str =
+                        concat!(stringify!($namespace), "::", stringify!($object));
+
+                    pub fn from_bytes(bytes: &[u8]) -> $crate::Result<$t> {
+                        if bytes.len() != <$t as $crate::pdo_layout::PdoField>::SIZE {
+                            return Err($crate::Error::InvalidDataLength {
+                                length: bytes.len(),
+                                data_type: Self::NAME.to_owned(),
+                            });
+                        }
+                        Ok(<$t as $crate::pdo_layout::PdoField>::read(bytes))
+                    }
+                }
+
+                $crate::vendor_objects!(@access $access, $object, $t);
+            )*
+        }
+    };
+    (@access ro, $object:ident, $t:ty) => {
+        $crate::vendor_objects!(@read $object);
+    };
+    (@access wo, $object:ident, $t:ty) => {
+        $crate::vendor_objects!(@write $object, $t);
+    };
+    (@access rw, $object:ident, $t:ty) => {
+        $crate::vendor_objects!(@read $object);
+        $crate::vendor_objects!(@write $object, $t);
+    };
+    (@read $object:ident) => {
+        impl $object {
+            pub fn new_sdo_read_frame(node_id: $crate::id::NodeId) -> $crate::frame::SdoFrame {
+                $crate::frame::SdoFrame::new_sdo_read_frame(node_id, Self::INDEX, Self::SUB_INDEX)
+            }
+        }
+    };
+    (@write $object:ident, $t:ty) => {
+        impl $object {
+            pub fn new_sdo_write_frame(
+                node_id: $crate::id::NodeId,
+                value: $t,
+            ) -> $crate::frame::SdoFrame {
+                let mut data = std::vec![0x00; <$t as $crate::pdo_layout::PdoField>::SIZE];
+                $crate::pdo_layout::PdoField::write(&value, &mut data);
+                $crate::frame::SdoFrame::new_sdo_write_frame(
+                    node_id,
+                    Self::INDEX,
+                    Self::SUB_INDEX,
+                    data,
+                )
+            }
+        }
+    };
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    use crate::error::Error;
+    use crate::frame::ConvertibleFrame;
+
+    crate::vendor_objects! {
+        mod acme {
+            MaxCurrent: 0x2000, 0, u16, rw;
+            Temperature: 0x2001, 1, i16, ro;
+            Trigger: 0x5FFF, 2, bool, wo;
+        }
+    }
+
+    #[test]
+    fn test_is_vendor_index() {
+        assert!(!is_vendor_index(0x1FFF));
+        assert!(is_vendor_index(0x2000));
+        assert!(is_vendor_index(0x5FFF));
+        assert!(!is_vendor_index(0x6000));
+    }
+
+    #[test]
+    fn test_constants() {
+        assert_eq!(acme::MaxCurrent::INDEX, 0x2000);
+        assert_eq!(acme::Temperature::SUB_INDEX, 1);
+        assert_eq!(acme::Trigger::NAME, "acme::Trigger");
+    }
+
+    #[test]
+    fn test_sdo_read_frame() {
+        assert_eq!(
+            acme::Temperature::new_sdo_read_frame(3.try_into().unwrap()).frame_data(),
+            &[0x40, 0x01, 0x20, 0x01, 0x00, 0x00, 0x00, 0x00]
+        );
+        assert_eq!(
+            acme::MaxCurrent::new_sdo_read_frame(3.try_into().unwrap()).frame_data(),
+            &[0x40, 0x00, 0x20, 0x00, 0x00, 0x00, 0x00, 0x00]
+        );
+    }
+
+    #[test]
+    fn test_sdo_write_frame() {
+        assert_eq!(
+            acme::MaxCurrent::new_sdo_write_frame(3.try_into().unwrap(), 0x1234).frame_data(),
+            &[0x2B, 0x00, 0x20, 0x00, 0x34, 0x12, 0x00, 0x00]
+        );
+        assert_eq!(
+            acme::Trigger::new_sdo_write_frame(3.try_into().unwrap(), true).frame_data(),
+            &[0x2F, 0xFF, 0x5F, 0x02, 0x01, 0x00, 0x00, 0x00]
+        );
+    }
+
+    #[test]
+    fn test_from_bytes() {
+        assert_eq!(acme::Temperature::from_bytes(&[0xFE, 0xFF]), Ok(-2));
+        assert_eq!(acme::Trigger::from_bytes(&[0x01]), Ok(true));
+        assert_eq!(
+            acme::MaxCurrent::from_bytes(&[0x00]),
+            Err(Error::InvalidDataLength {
+                length: 1,
+                data_type: "acme::MaxCurrent".to_owned()
+            })
+        );
+    }
+}